@@ -0,0 +1,183 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Conversions between the wrappers' requests and glide-core, which are shared by the FFI crates.
+
+use glide_core::client::Client as GlideClient;
+use glide_core::command_request::{Routes, SimpleRoutes, SlotTypes};
+use glide_core::connection_request;
+use glide_core::errors;
+use glide_core::ConnectionRequest;
+use protobuf::Message;
+use redis::cluster_routing::{
+    MultipleNodeRoutingInfo, ResponsePolicy, Routable, Route, RoutingInfo, SingleNodeRoutingInfo,
+    SlotAddr,
+};
+use redis::{Cmd, Value};
+use tokio::runtime::{Builder, Runtime};
+
+/// Creates a client from a serialized Protobuf `ConnectionRequest`, along with the runtime that drives its requests,
+/// whose thread is named `thread_name`.
+pub fn create_client(
+    connection_request_bytes: &[u8],
+    thread_name: &str,
+) -> Result<(GlideClient, Runtime), String> {
+    let request = connection_request::ConnectionRequest::parse_from_bytes(connection_request_bytes)
+        .map_err(|err| err.to_string())?;
+    let runtime = Builder::new_multi_thread()
+        .enable_all()
+        .worker_threads(1)
+        .thread_name(thread_name)
+        .build()
+        .map_err(|err| {
+            let redis_error = err.into();
+            errors::error_message(&redis_error)
+        })?;
+    let client = runtime
+        .block_on(GlideClient::new(ConnectionRequest::from(request), None))
+        .map_err(|err| err.to_string())?;
+    Ok((client, runtime))
+}
+
+/// Converts a Protobuf slot type into the address of the slot's node.
+pub fn get_slot_addr(slot_type: &protobuf::EnumOrUnknown<SlotTypes>) -> Result<SlotAddr, String> {
+    slot_type
+        .enum_value()
+        .map(|slot_type| match slot_type {
+            SlotTypes::Primary => SlotAddr::Master,
+            SlotTypes::Replica => SlotAddr::ReplicaRequired,
+        })
+        .map_err(|id| format!("Received unexpected slot id type {id}"))
+}
+
+/// Converts a Protobuf route of `cmd` into its routing info, or returns `None` if the route is empty, so that the route
+/// is derived from the command.
+pub fn get_route(route: Routes, cmd: &Cmd) -> Result<Option<RoutingInfo>, String> {
+    use glide_core::command_request::routes::Value;
+    let Some(route) = route.value else {
+        return Ok(None);
+    };
+    let response_policy = cmd
+        .command()
+        .and_then(|cmd| ResponsePolicy::for_command(&cmd));
+    match route {
+        Value::SimpleRoutes(simple_route) => {
+            let simple_route = simple_route
+                .enum_value()
+                .map_err(|id| format!("Received unexpected simple route type {id}"))?;
+            Ok(Some(match simple_route {
+                SimpleRoutes::AllNodes => {
+                    RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllNodes, response_policy))
+                }
+                SimpleRoutes::AllPrimaries => {
+                    RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllMasters, response_policy))
+                }
+                SimpleRoutes::Random => RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random),
+            }))
+        }
+        Value::SlotKeyRoute(slot_key_route) => Ok(Some(RoutingInfo::SingleNode(
+            SingleNodeRoutingInfo::SpecificNode(Route::new(
+                redis::cluster_topology::get_slot(slot_key_route.slot_key.as_bytes()),
+                get_slot_addr(&slot_key_route.slot_type)?,
+            )),
+        ))),
+        Value::SlotIdRoute(slot_id_route) => Ok(Some(RoutingInfo::SingleNode(
+            SingleNodeRoutingInfo::SpecificNode(Route::new(
+                slot_id_route.slot_id as u16,
+                get_slot_addr(&slot_id_route.slot_type)?,
+            )),
+        ))),
+        Value::ByAddressRoute(by_address_route) => {
+            let port = u16::try_from(by_address_route.port)
+                .map_err(|err| format!("Failed to parse port: {err:?}"))?;
+            Ok(Some(RoutingInfo::SingleNode(
+                SingleNodeRoutingInfo::ByAddress {
+                    host: by_address_route.host.to_string(),
+                    port,
+                },
+            )))
+        }
+    }
+}
+
+/// Replaces values that can't be borrowed through the accessors with an equivalent representation.
+pub fn normalize_value(value: Value) -> Value {
+    let normalize_all =
+        |values: Vec<Value>| -> Vec<Value> { values.into_iter().map(normalize_value).collect() };
+    match value {
+        Value::BigNumber(number) => Value::SimpleString(number.to_string()),
+        Value::Array(values) => Value::Array(normalize_all(values)),
+        Value::Set(values) => Value::Set(normalize_all(values)),
+        Value::Push { kind, data } => Value::Push {
+            kind,
+            data: normalize_all(data),
+        },
+        Value::Map(map) => Value::Map(
+            map.into_iter()
+                .map(|(key, value)| (normalize_value(key), normalize_value(value)))
+                .collect(),
+        ),
+        Value::Attribute { data, attributes } => Value::Attribute {
+            data: Box::new(normalize_value(*data)),
+            attributes,
+        },
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glide_core::command_request::{ByAddressRoute, SlotIdRoute};
+
+    #[test]
+    fn test_routes_are_converted() {
+        let cmd = redis::cmd("DBSIZE");
+        assert_eq!(get_route(Routes::new(), &cmd), Ok(None));
+
+        let mut route = Routes::new();
+        route.set_simple_routes(SimpleRoutes::AllPrimaries);
+        assert_eq!(
+            get_route(route, &cmd),
+            Ok(Some(RoutingInfo::MultiNode((
+                MultipleNodeRoutingInfo::AllMasters,
+                Some(ResponsePolicy::Aggregate(
+                    redis::cluster_routing::AggregateOp::Sum
+                )),
+            ))))
+        );
+
+        let mut slot_id_route = SlotIdRoute::new();
+        slot_id_route.slot_id = 42;
+        slot_id_route.slot_type = SlotTypes::Replica.into();
+        let mut route = Routes::new();
+        route.set_slot_id_route(slot_id_route);
+        assert_eq!(
+            get_route(route, &cmd),
+            Ok(Some(RoutingInfo::SingleNode(
+                SingleNodeRoutingInfo::SpecificNode(Route::new(42, SlotAddr::ReplicaRequired))
+            )))
+        );
+
+        let mut by_address_route = ByAddressRoute::new();
+        by_address_route.host = "node".into();
+        by_address_route.port = 70000;
+        let mut route = Routes::new();
+        route.set_by_address_route(by_address_route);
+        assert!(get_route(route, &cmd).is_err());
+    }
+
+    #[test]
+    fn test_big_numbers_are_normalized_to_strings() {
+        let value = Value::Array(vec![Value::Map(vec![(
+            Value::BigNumber(12.into()),
+            Value::Int(1),
+        )])]);
+        assert_eq!(
+            normalize_value(value),
+            Value::Array(vec![Value::Map(vec![(
+                Value::SimpleString("12".to_string()),
+                Value::Int(1),
+            )])])
+        );
+    }
+}
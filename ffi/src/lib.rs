@@ -0,0 +1,539 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! A language-agnostic C ABI over glide-core.
+//!
+//! Unlike the per-language FFI crates, this layer doesn't expose the layout of response values. Values are handed to the
+//! caller as opaque [`GlideValue`] pointers, which are inspected through the `glide_value_*` accessors and released with
+//! [`glide_value_free`]. This keeps the ABI stable when new value types are added to the core. The accessors see through
+//! the attributes that the server attaches to values, and treat a null value pointer as a value of another type.
+
+#![deny(unsafe_op_in_unsafe_fn)]
+pub mod conversions;
+
+use conversions::{create_client, get_route};
+use glide_core::client::Client as GlideClient;
+use glide_core::command_request::Routes;
+use glide_core::errors;
+use glide_core::errors::RequestErrorType;
+use glide_core::request_type::RequestType;
+use protobuf::Message;
+use redis::{RedisError, Value};
+use std::ffi::{c_void, CString};
+use std::os::raw::c_char;
+use std::slice::from_raw_parts;
+use tokio::runtime::Runtime;
+
+/// An opaque response value.
+///
+/// Values received in [`SuccessCallback`] are owned by the caller and must be released with [`glide_value_free`].
+/// Values returned by [`glide_value_element`], [`glide_value_map_key`] and [`glide_value_map_value`] are borrowed from
+/// their parent and must not be freed separately.
+#[repr(transparent)]
+pub struct GlideValue(Value);
+
+/// The type of a [`GlideValue`], as returned by [`glide_value_type`].
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq)]
+pub enum GlideValueType {
+    Nil = 0,
+    Okay = 1,
+    Int = 2,
+    Double = 3,
+    Boolean = 4,
+    /// Bulk, simple and verbatim strings, and big numbers in their textual representation.
+    String = 5,
+    Array = 6,
+    Set = 7,
+    Map = 8,
+    /// A push notification. Its elements are the notification's data.
+    Push = 9,
+    /// Returned for a null value pointer.
+    Invalid = -1,
+}
+
+/// Success callback that is called when a command succeeds.
+///
+/// `callback_index` is the value passed to [`glide_command`], to let the caller identify the request.
+/// `value` is owned by the caller, and must be released by calling [`glide_value_free`].
+pub type SuccessCallback =
+    unsafe extern "C" fn(callback_index: usize, value: *mut GlideValue) -> ();
+
+/// Failure callback that is called when a command fails.
+///
+/// `callback_index` is the value passed to [`glide_command`], to let the caller identify the request.
+/// `error_message` is owned by the caller, and must be released by calling [`glide_free_error_message`].
+/// `error_type` is the type of error returned by glide-core, depending on the `RedisError` returned.
+pub type FailureCallback = unsafe extern "C" fn(
+    callback_index: usize,
+    error_message: *mut c_char,
+    error_type: RequestErrorType,
+) -> ();
+
+/// The result of [`glide_client_create`]. Exactly one of the fields is set.
+///
+/// The struct must be released with [`glide_free_connection_response`].
+#[repr(C)]
+pub struct GlideConnectionResponse {
+    client: *const c_void,
+    error_message: *const c_char,
+}
+
+struct ClientAdapter {
+    client: GlideClient,
+    success_callback: SuccessCallback,
+    failure_callback: FailureCallback,
+    runtime: Runtime,
+}
+
+fn create_client_internal(
+    connection_request_bytes: &[u8],
+    success_callback: SuccessCallback,
+    failure_callback: FailureCallback,
+) -> Result<ClientAdapter, String> {
+    let (client, runtime) = create_client(connection_request_bytes, "Valkey-GLIDE FFI thread")?;
+    Ok(ClientAdapter {
+        client,
+        success_callback,
+        failure_callback,
+        runtime,
+    })
+}
+
+fn to_c_string(message: String) -> *mut c_char {
+    // Interior nul bytes can't be represented in a C string, so they're dropped.
+    let message = message.replace('\0', "");
+    CString::new(message)
+        .expect("Couldn't convert error message to CString")
+        .into_raw()
+}
+
+/// Creates a new client, configured using a serialized Protobuf `ConnectionRequest`.
+///
+/// The returned `GlideConnectionResponse` must be released by calling [`glide_free_connection_response`].
+///
+/// # Safety
+///
+/// * `connection_request_bytes` must point to `connection_request_len` consecutive properly initialized bytes, which are owned by the caller.
+/// * The `client` pointer in the returned `GlideConnectionResponse` must be released by calling [`glide_client_close`].
+/// * `success_callback` and `failure_callback` must remain valid while the client is open.
+#[no_mangle]
+pub unsafe extern "C" fn glide_client_create(
+    connection_request_bytes: *const u8,
+    connection_request_len: usize,
+    success_callback: SuccessCallback,
+    failure_callback: FailureCallback,
+) -> *mut GlideConnectionResponse {
+    let request_bytes = unsafe { from_raw_parts(connection_request_bytes, connection_request_len) };
+    let response = match create_client_internal(request_bytes, success_callback, failure_callback) {
+        Ok(client) => GlideConnectionResponse {
+            client: Box::into_raw(Box::new(client)) as *const c_void,
+            error_message: std::ptr::null(),
+        },
+        Err(err) => GlideConnectionResponse {
+            client: std::ptr::null(),
+            error_message: to_c_string(err),
+        },
+    };
+    Box::into_raw(Box::new(response))
+}
+
+/// Releases a `GlideConnectionResponse`, including its error message. The client itself isn't closed.
+///
+/// # Safety
+///
+/// * `response` must be obtained from [`glide_client_create`], and can only be released once.
+#[no_mangle]
+pub unsafe extern "C" fn glide_free_connection_response(response: *mut GlideConnectionResponse) {
+    if response.is_null() {
+        return;
+    }
+    let response = unsafe { Box::from_raw(response) };
+    if !response.error_message.is_null() {
+        drop(unsafe { CString::from_raw(response.error_message as *mut c_char) });
+    }
+}
+
+/// Closes the client and releases its resources.
+///
+/// # Safety
+///
+/// * `client` must be obtained from the `GlideConnectionResponse` returned by [`glide_client_create`], and can only be closed once.
+/// * No other function may be called with `client` after this function was called.
+#[no_mangle]
+pub unsafe extern "C" fn glide_client_close(client: *const c_void) {
+    if client.is_null() {
+        return;
+    }
+    drop(unsafe { Box::from_raw(client as *mut ClientAdapter) });
+}
+
+/// Releases an error message received in [`FailureCallback`].
+///
+/// # Safety
+///
+/// * `error_message` must be obtained from [`FailureCallback`], and can only be released once.
+#[no_mangle]
+pub unsafe extern "C" fn glide_free_error_message(error_message: *mut c_char) {
+    if error_message.is_null() {
+        return;
+    }
+    drop(unsafe { CString::from_raw(error_message) });
+}
+
+/// Converts the caller's argument arrays into a `Vec` of slices.
+///
+/// # Safety
+///
+/// The returned slices borrow the caller's memory, and must be copied before this call returns.
+unsafe fn convert_args<'a>(
+    arg_count: usize,
+    args: *const *const u8,
+    args_len: *const usize,
+) -> Vec<&'a [u8]> {
+    if arg_count == 0 {
+        return Vec::new();
+    }
+    let arg_ptrs = unsafe { from_raw_parts(args, arg_count) };
+    let arg_lengths = unsafe { from_raw_parts(args_len, arg_count) };
+    arg_ptrs
+        .iter()
+        .zip(arg_lengths)
+        .map(|(&ptr, &len)| unsafe { from_raw_parts(ptr, len) })
+        .collect()
+}
+
+/// Sends a command. The result is delivered through the client's success or failure callback, with `callback_index`.
+///
+/// `route_bytes` is an optional serialized Protobuf `Routes` object. If `route_bytes_len` is 0, the route is derived from the command.
+///
+/// # Safety
+///
+/// * `client` must be obtained from [`glide_client_create`] and not yet closed.
+/// * `args` and `args_len` must point to `arg_count` consecutive elements each, where `args[i]` points to `args_len[i]` bytes.
+///   The arguments are copied before this function returns.
+/// * `route_bytes` must point to `route_bytes_len` consecutive bytes, if `route_bytes_len` isn't 0.
+#[no_mangle]
+pub unsafe extern "C" fn glide_command(
+    client: *const c_void,
+    callback_index: usize,
+    request_type: RequestType,
+    arg_count: usize,
+    args: *const *const u8,
+    args_len: *const usize,
+    route_bytes: *const u8,
+    route_bytes_len: usize,
+) {
+    let client_adapter = unsafe { &*(client as *const ClientAdapter) };
+    let success_callback = client_adapter.success_callback;
+    let failure_callback = client_adapter.failure_callback;
+    let report_error = move |err: RedisError| {
+        let error_message = to_c_string(errors::error_message(&err));
+        unsafe { failure_callback(callback_index, error_message, errors::error_type(&err)) };
+    };
+
+    let Some(mut cmd) = request_type.get_command() else {
+        report_error(RedisError::from((
+            redis::ErrorKind::ClientError,
+            "Received invalid request type",
+        )));
+        return;
+    };
+    // The arguments are copied into the command before spawning the task, since they're owned by the caller.
+    for arg in unsafe { convert_args(arg_count, args, args_len) } {
+        cmd.arg(arg);
+    }
+
+    let routing = if route_bytes_len == 0 {
+        None
+    } else {
+        let route_bytes = unsafe { from_raw_parts(route_bytes, route_bytes_len) };
+        match Routes::parse_from_bytes(route_bytes)
+            .map_err(|err| err.to_string())
+            .and_then(|route| get_route(route, &cmd))
+        {
+            Ok(routing) => routing,
+            Err(message) => {
+                report_error(RedisError::from((
+                    redis::ErrorKind::ClientError,
+                    "Received invalid route",
+                    message,
+                )));
+                return;
+            }
+        }
+    };
+
+    let mut glide_client = client_adapter.client.clone();
+    client_adapter.runtime.spawn(async move {
+        match glide_client.send_command(&cmd, routing).await {
+            Ok(value) => {
+                let value =
+                    Box::into_raw(Box::new(GlideValue(conversions::normalize_value(value))));
+                unsafe { success_callback(callback_index, value) };
+            }
+            Err(err) => report_error(err),
+        }
+    });
+}
+
+/// Returns the value behind `value`, without the attributes that the server attached to it, or `None` if `value` is
+/// null, since panics can't unwind across the C ABI.
+fn as_value<'a>(value: *const GlideValue) -> Option<&'a Value> {
+    // Safety: `GlideValue` is a transparent wrapper, and non-null pointers were created by this crate.
+    let mut value = unsafe { &value.as_ref()?.0 };
+    while let Value::Attribute { data, .. } = value {
+        value = data;
+    }
+    Some(value)
+}
+
+fn as_glide_value(value: &Value) -> *const GlideValue {
+    value as *const Value as *const GlideValue
+}
+
+/// Returns the type of `value`, or [`GlideValueType::Invalid`] if `value` is null.
+///
+/// # Safety
+///
+/// * `value` must be a valid `GlideValue` pointer, or null.
+#[no_mangle]
+pub unsafe extern "C" fn glide_value_type(value: *const GlideValue) -> GlideValueType {
+    let Some(value) = as_value(value) else {
+        return GlideValueType::Invalid;
+    };
+    match value {
+        Value::Nil => GlideValueType::Nil,
+        Value::Okay => GlideValueType::Okay,
+        Value::Int(_) => GlideValueType::Int,
+        Value::Double(_) => GlideValueType::Double,
+        Value::Boolean(_) => GlideValueType::Boolean,
+        Value::BulkString(_)
+        | Value::SimpleString(_)
+        | Value::VerbatimString { .. }
+        | Value::BigNumber(_) => GlideValueType::String,
+        Value::Array(_) => GlideValueType::Array,
+        Value::Set(_) => GlideValueType::Set,
+        Value::Map(_) => GlideValueType::Map,
+        Value::Push { .. } => GlideValueType::Push,
+        Value::Attribute { data, .. } => unsafe { glide_value_type(as_glide_value(data)) },
+    }
+}
+
+/// Returns the integer held by `value`, or 0 if `value` isn't an integer.
+///
+/// # Safety
+///
+/// * `value` must be a valid `GlideValue` pointer, or null.
+#[no_mangle]
+pub unsafe extern "C" fn glide_value_int(value: *const GlideValue) -> i64 {
+    match as_value(value) {
+        Some(Value::Int(int)) => *int,
+        _ => 0,
+    }
+}
+
+/// Returns the double held by `value`, or 0 if `value` isn't a double.
+///
+/// # Safety
+///
+/// * `value` must be a valid `GlideValue` pointer, or null.
+#[no_mangle]
+pub unsafe extern "C" fn glide_value_double(value: *const GlideValue) -> f64 {
+    match as_value(value) {
+        Some(Value::Double(double)) => *double,
+        _ => 0.0,
+    }
+}
+
+/// Returns the boolean held by `value`, or false if `value` isn't a boolean.
+///
+/// # Safety
+///
+/// * `value` must be a valid `GlideValue` pointer, or null.
+#[no_mangle]
+pub unsafe extern "C" fn glide_value_bool(value: *const GlideValue) -> bool {
+    matches!(as_value(value), Some(Value::Boolean(true)))
+}
+
+/// Returns a pointer to the bytes of a string value, and writes their length to `len`.
+/// The bytes aren't nul-terminated, and are borrowed from `value`. Returns null if `value` isn't a string.
+///
+/// # Safety
+///
+/// * `value` must be a valid `GlideValue` pointer, or null.
+/// * `len` must be a valid pointer to a `usize`, or null.
+#[no_mangle]
+pub unsafe extern "C" fn glide_value_string(
+    value: *const GlideValue,
+    len: *mut usize,
+) -> *const u8 {
+    let bytes: Option<&[u8]> = match as_value(value) {
+        Some(Value::BulkString(bytes)) => Some(bytes),
+        Some(Value::SimpleString(text)) => Some(text.as_bytes()),
+        Some(Value::VerbatimString { text, .. }) => Some(text.as_bytes()),
+        Some(Value::Okay) => Some(b"OK"),
+        _ => None,
+    };
+    if let Some(len) = unsafe { len.as_mut() } {
+        *len = bytes.map_or(0, <[u8]>::len);
+    }
+    bytes.map_or(std::ptr::null(), <[u8]>::as_ptr)
+}
+
+/// Returns the number of elements in an array, set, push or map value (where each key-value pair is one element),
+/// or 0 for other values.
+///
+/// # Safety
+///
+/// * `value` must be a valid `GlideValue` pointer, or null.
+#[no_mangle]
+pub unsafe extern "C" fn glide_value_len(value: *const GlideValue) -> usize {
+    match as_value(value) {
+        Some(Value::Array(values) | Value::Set(values)) => values.len(),
+        Some(Value::Push { data, .. }) => data.len(),
+        Some(Value::Map(map)) => map.len(),
+        _ => 0,
+    }
+}
+
+/// Returns the element at `index` of an array, set or push value, or null if there's no such element.
+/// The returned value is borrowed from `value`, and must not be freed.
+///
+/// # Safety
+///
+/// * `value` must be a valid `GlideValue` pointer, or null.
+#[no_mangle]
+pub unsafe extern "C" fn glide_value_element(
+    value: *const GlideValue,
+    index: usize,
+) -> *const GlideValue {
+    let element = match as_value(value) {
+        Some(Value::Array(values) | Value::Set(values)) => values.get(index),
+        Some(Value::Push { data, .. }) => data.get(index),
+        _ => None,
+    };
+    element.map_or(std::ptr::null(), as_glide_value)
+}
+
+/// Returns the key of the map entry at `index`, or null if there's no such entry.
+/// The returned value is borrowed from `value`, and must not be freed.
+///
+/// # Safety
+///
+/// * `value` must be a valid `GlideValue` pointer, or null.
+#[no_mangle]
+pub unsafe extern "C" fn glide_value_map_key(
+    value: *const GlideValue,
+    index: usize,
+) -> *const GlideValue {
+    match as_value(value) {
+        Some(Value::Map(map)) => map
+            .get(index)
+            .map_or(std::ptr::null(), |(key, _)| as_glide_value(key)),
+        _ => std::ptr::null(),
+    }
+}
+
+/// Returns the value of the map entry at `index`, or null if there's no such entry.
+/// The returned value is borrowed from `value`, and must not be freed.
+///
+/// # Safety
+///
+/// * `value` must be a valid `GlideValue` pointer, or null.
+#[no_mangle]
+pub unsafe extern "C" fn glide_value_map_value(
+    value: *const GlideValue,
+    index: usize,
+) -> *const GlideValue {
+    match as_value(value) {
+        Some(Value::Map(map)) => map
+            .get(index)
+            .map_or(std::ptr::null(), |(_, map_value)| as_glide_value(map_value)),
+        _ => std::ptr::null(),
+    }
+}
+
+/// Releases a value received in [`SuccessCallback`], including all of its elements.
+///
+/// # Safety
+///
+/// * `value` must be obtained from [`SuccessCallback`], and can only be released once.
+/// * Pointers borrowed from `value` must not be used after this call.
+#[no_mangle]
+pub unsafe extern "C" fn glide_value_free(value: *mut GlideValue) {
+    if value.is_null() {
+        return;
+    }
+    drop(unsafe { Box::from_raw(value) });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glide_value(value: Value) -> *mut GlideValue {
+        Box::into_raw(Box::new(GlideValue(value)))
+    }
+
+    fn string(value: *const GlideValue) -> Option<Vec<u8>> {
+        let mut len = 0;
+        let bytes = unsafe { glide_value_string(value, &mut len) };
+        (!bytes.is_null()).then(|| unsafe { from_raw_parts(bytes, len) }.to_vec())
+    }
+
+    #[test]
+    fn test_nested_values_are_borrowed_from_their_parent() {
+        let value = glide_value(Value::Array(vec![
+            Value::Int(5),
+            Value::Map(vec![(
+                Value::BulkString(b"key".to_vec()),
+                Value::Double(1.5),
+            )]),
+        ]));
+        unsafe {
+            assert_eq!(glide_value_type(value), GlideValueType::Array);
+            assert_eq!(glide_value_len(value), 2);
+            assert_eq!(glide_value_int(glide_value_element(value, 0)), 5);
+            let map = glide_value_element(value, 1);
+            assert_eq!(glide_value_type(map), GlideValueType::Map);
+            assert_eq!(string(glide_value_map_key(map, 0)), Some(b"key".to_vec()));
+            assert_eq!(glide_value_double(glide_value_map_value(map, 0)), 1.5);
+            assert!(glide_value_map_key(map, 1).is_null());
+            assert!(glide_value_element(value, 2).is_null());
+            glide_value_free(value);
+        }
+    }
+
+    #[test]
+    fn test_attributes_are_skipped() {
+        let value = glide_value(Value::Attribute {
+            data: Box::new(Value::Map(vec![(Value::Okay, Value::Boolean(true))])),
+            attributes: vec![(Value::SimpleString("ttl".to_string()), Value::Int(3))],
+        });
+        unsafe {
+            assert_eq!(glide_value_type(value), GlideValueType::Map);
+            assert_eq!(glide_value_len(value), 1);
+            assert_eq!(string(glide_value_map_key(value, 0)), Some(b"OK".to_vec()));
+            assert!(glide_value_bool(glide_value_map_value(value, 0)));
+            glide_value_free(value);
+        }
+    }
+
+    #[test]
+    fn test_null_values_dont_panic() {
+        let null = std::ptr::null();
+        unsafe {
+            assert_eq!(glide_value_type(null), GlideValueType::Invalid);
+            assert_eq!(glide_value_int(null), 0);
+            assert_eq!(glide_value_double(null), 0.0);
+            assert!(!glide_value_bool(null));
+            assert_eq!(string(null), None);
+            assert!(glide_value_string(null, std::ptr::null_mut()).is_null());
+            assert_eq!(glide_value_len(null), 0);
+            assert!(glide_value_element(null, 0).is_null());
+            assert!(glide_value_map_key(null, 0).is_null());
+            assert!(glide_value_map_value(null, 0).is_null());
+            glide_value_free(std::ptr::null_mut());
+        }
+    }
+}
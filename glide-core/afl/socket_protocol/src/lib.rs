@@ -0,0 +1,18 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use glide_core::command_request::CommandRequest;
+use glide_core::connection_request;
+use glide_core::rotating_buffer::RotatingBuffer;
+use protobuf::Message;
+
+/// Decodes `data` the way the socket listener does - as a stream of length-delimited command requests, and as the
+/// connection request that opens a socket.
+pub fn decode(data: &[u8]) {
+    let mut rotating_buffer = RotatingBuffer::new(data.len());
+    rotating_buffer.current_buffer().extend_from_slice(data);
+    let _ = rotating_buffer.get_requests::<CommandRequest>();
+
+    if let Ok(request) = connection_request::ConnectionRequest::parse_from_bytes(data) {
+        let _ = glide_core::ConnectionRequest::from(request);
+    }
+}
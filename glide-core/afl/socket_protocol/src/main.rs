@@ -0,0 +1,9 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use afl::fuzz;
+
+fn main() {
+    fuzz!(|data: &[u8]| {
+        fuzz_target_socket_protocol::decode(data);
+    });
+}
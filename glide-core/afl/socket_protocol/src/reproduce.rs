@@ -0,0 +1,14 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 2 {
+        println!("Usage: {} <path-to-crash>", args[0]);
+        std::process::exit(1);
+    }
+
+    let data = std::fs::read(&args[1])
+        .unwrap_or_else(|err| panic!("Could not open file {}: {err}", args[1]));
+    fuzz_target_socket_protocol::decode(&data);
+    println!("Decoded without panicking");
+}
@@ -0,0 +1,83 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use glide_core::client::PreparedTransaction;
+use redis::{Pipeline, Value};
+
+const REPEATS: usize = 25;
+
+/// A transaction that mixes commands whose replies are converted with commands whose replies are passed as-is, and
+/// the replies that the server would send for it.
+fn transaction_and_replies() -> (Pipeline, Vec<Value>) {
+    let mut pipeline = redis::pipe();
+    pipeline.atomic();
+    let mut replies = Vec::new();
+    let field_and_value = || {
+        vec![
+            Value::BulkString(b"field".to_vec()),
+            Value::BulkString(b"value".to_vec()),
+        ]
+    };
+    for _ in 0..REPEATS {
+        pipeline.cmd("GET").arg("key");
+        replies.push(Value::BulkString(b"value".to_vec()));
+        pipeline.cmd("HGETALL").arg("hash");
+        replies.push(Value::Array(field_and_value()));
+        pipeline
+            .cmd("ZRANGE")
+            .arg("zset")
+            .arg(0)
+            .arg(-1)
+            .arg("WITHSCORES");
+        replies.push(Value::Array(vec![
+            Value::BulkString(b"member".to_vec()),
+            Value::BulkString(b"1.5".to_vec()),
+        ]));
+        pipeline
+            .cmd("XREAD")
+            .arg("COUNT")
+            .arg(1)
+            .arg("STREAMS")
+            .arg("stream")
+            .arg(0);
+        replies.push(Value::Array(vec![Value::Array(vec![
+            Value::BulkString(b"stream".to_vec()),
+            Value::Array(vec![Value::Array(vec![
+                Value::BulkString(b"1-0".to_vec()),
+                Value::Array(field_and_value()),
+            ])]),
+        ])]));
+    }
+    (pipeline, replies)
+}
+
+fn pipeline_conversion_benchmark(c: &mut Criterion) {
+    let (pipeline, replies) = transaction_and_replies();
+    let mut group = c.benchmark_group("transaction_reply_conversion");
+
+    // What `Client::send_transaction` does on every call.
+    group.bench_function("unprepared", |b| {
+        b.iter_batched(
+            || (pipeline.clone(), replies.clone()),
+            |(pipeline, replies)| {
+                PreparedTransaction::new(black_box(pipeline), false, false).convert_replies(replies)
+            },
+            BatchSize::SmallInput,
+        );
+    });
+
+    // What `Client::send_prepared_transaction` does on every call.
+    let prepared = PreparedTransaction::new(pipeline.clone(), false, false);
+    group.bench_function("prepared", |b| {
+        b.iter_batched(
+            || replies.clone(),
+            |replies| black_box(&prepared).convert_replies(replies),
+            BatchSize::SmallInput,
+        );
+    });
+    group.finish();
+}
+
+criterion_group!(benches, pipeline_conversion_benchmark);
+
+criterion_main!(benches);
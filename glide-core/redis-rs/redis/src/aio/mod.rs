@@ -64,6 +64,16 @@ pub trait ConnectionLike {
     /// reads the single response from it.
     fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value>;
 
+    /// Sends an already encoded (packed) command without returning its reply, for writes that may be lost. By default
+    /// the reply is awaited and discarded, see [`MultiplexedConnection::send_packed_command_without_reply`] for a
+    /// connection that skips it.
+    fn req_packed_command_without_reply<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, ()>
+    where
+        Self: Send,
+    {
+        Box::pin(async move { self.req_packed_command(cmd).await.map(|_| ()) })
+    }
+
     /// Sends multiple already encoded (packed) command into the TCP socket
     /// and reads `count` responses from it.  This is used to implement
     /// pipelining.
@@ -116,6 +126,73 @@ impl Clone for Box<dyn DisconnectNotifier> {
     }
 }
 
+/// A scheme that authenticates new connections, in place of authenticating with the username and password of
+/// [`RedisConnectionInfo`]. This lets embedders plug in other schemes, such as tokens that are fetched from an external
+/// service or passed in the username field, or challenge-response exchanges, for both standalone and cluster
+/// connections.
+#[async_trait]
+pub trait Authenticator: Send + Sync {
+    /// Authenticates `con`, which was just established. It's called before any other command is sent, including
+    /// `HELLO`, so the connection still uses RESP2. An error fails the connection attempt, and is returned as is.
+    async fn authenticate(&self, con: &mut (dyn ConnectionLike + Send)) -> RedisResult<()>;
+}
+
+impl std::fmt::Debug for dyn Authenticator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Authenticator")
+    }
+}
+
+/// Authenticates `con` with `AUTH`, retrying with the password alone if the server predates ACLs and rejects the
+/// username. This is how connections are authenticated without an [`Authenticator`], and authenticators that only
+/// obtain the credentials can use it too.
+pub async fn authenticate_with_password<C>(
+    con: &mut C,
+    username: Option<&str>,
+    password: &str,
+) -> RedisResult<()>
+where
+    C: ConnectionLike + ?Sized,
+{
+    let mut command = cmd("AUTH");
+    if let Some(username) = username {
+        command.arg(username);
+    }
+    match con.req_packed_command(command.arg(password)).await {
+        Ok(Value::Okay) => Ok(()),
+        Err(e) => {
+            let err_msg = e.detail().ok_or((
+                ErrorKind::AuthenticationFailed,
+                "Password authentication failed",
+            ))?;
+
+            if !err_msg.contains("wrong number of arguments for 'auth' command") {
+                fail!((
+                    ErrorKind::AuthenticationFailed,
+                    "Password authentication failed",
+                ));
+            }
+
+            let mut command = cmd("AUTH");
+            match con.req_packed_command(command.arg(password)).await {
+                Ok(Value::Okay) => Ok(()),
+                _ => {
+                    fail!((
+                        ErrorKind::AuthenticationFailed,
+                        "Password authentication failed"
+                    ));
+                }
+            }
+        }
+        _ => {
+            fail!((
+                ErrorKind::AuthenticationFailed,
+                "Password authentication failed"
+            ));
+        }
+    }
+}
+
 // Helper function to extract and update availability zone from INFO command
 async fn update_az_from_info<C>(con: &mut C) -> RedisResult<()>
 where
@@ -151,52 +228,25 @@ async fn setup_connection<C>(
     discover_az: bool,
 ) -> RedisResult<()>
 where
-    C: ConnectionLike,
+    C: ConnectionLike + Send,
 {
-    if connection_info.protocol != ProtocolVersion::RESP2 {
+    if let Some(authenticator) = &connection_info.authenticator {
+        authenticator.authenticate(con).await?;
+        if connection_info.protocol != ProtocolVersion::RESP2 {
+            // The connection is already authenticated, so HELLO only switches the protocol.
+            let val: RedisResult<Value> = cmd("HELLO").arg("3").query_async(con).await;
+            if let Err(err) = val {
+                return Err(get_resp3_hello_command_error(err));
+            }
+        }
+    } else if connection_info.protocol != ProtocolVersion::RESP2 {
         let hello_cmd = resp3_hello(connection_info);
         let val: RedisResult<Value> = hello_cmd.query_async(con).await;
         if let Err(err) = val {
             return Err(get_resp3_hello_command_error(err));
         }
     } else if let Some(password) = &connection_info.password {
-        let mut command = cmd("AUTH");
-        if let Some(username) = &connection_info.username {
-            command.arg(username);
-        }
-        match command.arg(password).query_async(con).await {
-            Ok(Value::Okay) => (),
-            Err(e) => {
-                let err_msg = e.detail().ok_or((
-                    ErrorKind::AuthenticationFailed,
-                    "Password authentication failed",
-                ))?;
-
-                if !err_msg.contains("wrong number of arguments for 'auth' command") {
-                    fail!((
-                        ErrorKind::AuthenticationFailed,
-                        "Password authentication failed",
-                    ));
-                }
-
-                let mut command = cmd("AUTH");
-                match command.arg(password).query_async(con).await {
-                    Ok(Value::Okay) => (),
-                    _ => {
-                        fail!((
-                            ErrorKind::AuthenticationFailed,
-                            "Password authentication failed"
-                        ));
-                    }
-                }
-            }
-            _ => {
-                fail!((
-                    ErrorKind::AuthenticationFailed,
-                    "Password authentication failed"
-                ));
-            }
-        }
+        authenticate_with_password(con, connection_info.username.as_deref(), password).await?;
     }
 
     if connection_info.db != 0 {
@@ -224,6 +274,17 @@ where
         }
     }
 
+    for (flag, client_flag_cmd) in crate::connection::client_flag_cmds(connection_info) {
+        match client_flag_cmd.query_async(con).await {
+            Ok(Value::Okay) => {}
+            _ => fail!((
+                ErrorKind::ResponseError,
+                "Redis server refused to set client flag",
+                flag.to_string()
+            )),
+        }
+    }
+
     if discover_az {
         update_az_from_info(con).await?;
     }
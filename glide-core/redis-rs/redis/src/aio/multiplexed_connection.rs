@@ -71,6 +71,7 @@ struct PipelineMessage<S> {
     input: S,
     output: PipelineOutput,
     // If `None`, this is a single request, not a pipeline of multiple requests.
+    // If `Some(0)`, the server doesn't reply, and the output is sent as soon as the request is written.
     pipeline_response_count: Option<usize>,
 }
 
@@ -258,6 +259,11 @@ where
         }
 
         match self_.sink_stream.start_send(input) {
+            Ok(()) if pipeline_response_count == Some(0) => {
+                // Nothing is added to `in_flight`, so the replies of the following requests are matched to them.
+                let _ = output.send(Ok(Value::Nil));
+                Ok(())
+            }
             Ok(()) => {
                 let response_aggregate = ResponseAggregate::new(pipeline_response_count);
                 let entry = InFlight {
@@ -419,6 +425,8 @@ pub struct MultiplexedConnection {
     push_manager: PushManager,
     availability_zone: Option<String>,
     password: Option<String>,
+    // Whether the server accepted `CLIENT REPLY` when the connection was set up, so that replies can be skipped.
+    client_reply_allowed: bool,
 }
 
 impl Debug for MultiplexedConnection {
@@ -467,7 +475,7 @@ impl MultiplexedConnection {
         let (mut pipeline, driver) =
             Pipeline::new(codec, glide_connection_options.disconnect_notifier);
         let driver = Box::pin(driver);
-        let pm = PushManager::default();
+        let pm = PushManager::default().with_address(connection_info.addr.to_string());
         if let Some(sender) = glide_connection_options.push_sender {
             pm.replace_sender(sender);
         }
@@ -485,11 +493,23 @@ impl MultiplexedConnection {
             .await?;
 
         let driver = {
-            let auth = setup_connection(
-                &connection_info.redis,
-                &mut con,
-                glide_connection_options.discover_az,
-            );
+            let auth = async {
+                setup_connection(
+                    &connection_info.redis,
+                    &mut con,
+                    glide_connection_options.discover_az,
+                )
+                .await?;
+                // `CLIENT REPLY ON` doesn't change anything, but tells whether the server lets the connection skip
+                // replies - ACLs, proxies and old servers might reject `CLIENT REPLY`.
+                con.client_reply_allowed = cmd("CLIENT")
+                    .arg("REPLY")
+                    .arg("ON")
+                    .query_async::<_, ()>(&mut con)
+                    .await
+                    .is_ok();
+                Ok::<_, RedisError>(())
+            };
 
             futures_util::pin_mut!(auth);
 
@@ -536,6 +556,27 @@ impl MultiplexedConnection {
         result
     }
 
+    /// Sends `cmd` preceded by `CLIENT REPLY SKIP`, so that the server doesn't reply to it, and returns once it was
+    /// written. Since no reply is expected, the replies of the other requests on the connection stay matched to them.
+    /// Errors of the command are lost along with its reply.
+    ///
+    /// If the server rejected `CLIENT REPLY` when the connection was set up, it would reply to both the rejected
+    /// `CLIENT REPLY SKIP` and `cmd`, so instead the reply is awaited and discarded, and its error is returned.
+    pub async fn send_packed_command_without_reply(&mut self, cmd: &Cmd) -> RedisResult<()> {
+        if !self.client_reply_allowed {
+            return self.send_packed_command(cmd).await.map(|_| ());
+        }
+        let mut packed = crate::cmd("CLIENT")
+            .arg("REPLY")
+            .arg("SKIP")
+            .get_packed_command();
+        cmd.write_packed_command(&mut packed);
+        self.pipeline
+            .send_recv(packed, Some(0), self.response_timeout)
+            .await
+            .map(|_| ())
+    }
+
     /// Sends multiple already encoded (packed) command into the TCP socket
     /// and reads `count` responses from it.  This is used to implement
     /// pipelining.
@@ -682,6 +723,7 @@ impl MultiplexedConnectionBuilder {
             protocol,
             password,
             availability_zone: self.availability_zone,
+            client_reply_allowed: false,
         };
 
         Ok(con)
@@ -693,6 +735,10 @@ impl ConnectionLike for MultiplexedConnection {
         (async move { self.send_packed_command(cmd).await }).boxed()
     }
 
+    fn req_packed_command_without_reply<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, ()> {
+        (async move { self.send_packed_command_without_reply(cmd).await }).boxed()
+    }
+
     fn req_packed_commands<'a>(
         &'a mut self,
         cmd: &'a crate::Pipeline,
@@ -0,0 +1,162 @@
+//! Approximate accounting of the bytes that are sent and received per command and per node.
+//!
+//! The sizes are derived from the RESP encoding of the commands and responses, rather than measured on the socket,
+//! so that they can be attributed to commands even when requests are multiplexed.
+
+use crate::cmd::{Arg, Cmd};
+use crate::types::Value;
+use crate::Pipeline;
+use std::fmt::{self, Write};
+use telemetrylib::Telemetry;
+
+/// The length of the RESP header of an element - type byte, decimal length, and CRLF.
+fn header_size(len: usize) -> usize {
+    1 + decimal_digits(len as u64) + 2
+}
+
+fn decimal_digits(mut number: u64) -> usize {
+    let mut digits = 1;
+    while number >= 10 {
+        number /= 10;
+        digits += 1;
+    }
+    digits
+}
+
+/// Returns the number of bytes that `cmd` takes on the wire.
+pub fn packed_command_size(cmd: &Cmd) -> usize {
+    let args = cmd.args_iter();
+    let mut size = header_size(args.len());
+    for arg in args {
+        let len = match arg {
+            Arg::Simple(arg) => arg.len(),
+            // The cursor is replaced by its decimal value, which is usually short.
+            Arg::Cursor => 1,
+        };
+        size += header_size(len) + len + 2;
+    }
+    size
+}
+
+/// The number of elements of an aggregate reply that are measured. The size of a larger aggregate is extrapolated from
+/// its first elements, so that accounting for a large reply doesn't walk all of it.
+const SAMPLED_ELEMENTS: usize = 32;
+
+/// Counts the bytes that are written to it, so that numbers are measured without being formatted into a string.
+struct LengthCounter(usize);
+
+impl fmt::Write for LengthCounter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
+fn display_len(value: impl fmt::Display) -> usize {
+    let mut counter = LengthCounter(0);
+    let _ = write!(counter, "{value}");
+    counter.0
+}
+
+fn sampled_size<T>(elements: &[T], size: impl Fn(&T) -> usize) -> usize {
+    let sampled = elements.len().min(SAMPLED_ELEMENTS);
+    if sampled == 0 {
+        return 0;
+    }
+    let sampled_size: usize = elements[..sampled].iter().map(size).sum();
+    sampled_size.saturating_mul(elements.len()) / sampled
+}
+
+/// Returns the approximate number of bytes that `value` took on the wire.
+pub fn response_size(value: &Value) -> usize {
+    let pair_size = |(key, value): &(Value, Value)| response_size(key) + response_size(value);
+    match value {
+        Value::Nil => 3,
+        Value::Okay => 5,
+        Value::Int(int) => 1 + display_len(int) + 2,
+        Value::Double(double) => 1 + display_len(double) + 2,
+        Value::Boolean(_) => 4,
+        Value::SimpleString(string) => 1 + string.len() + 2,
+        Value::BigNumber(number) => 1 + display_len(number) + 2,
+        Value::BulkString(bytes) => header_size(bytes.len()) + bytes.len() + 2,
+        Value::VerbatimString { text, .. } => header_size(text.len() + 4) + text.len() + 4 + 2,
+        Value::Array(values) | Value::Set(values) | Value::Push { data: values, .. } => {
+            header_size(values.len()) + sampled_size(values, response_size)
+        }
+        Value::Map(pairs) => header_size(pairs.len()) + sampled_size(pairs, pair_size),
+        Value::Attribute { data, attributes } => {
+            header_size(attributes.len())
+                + sampled_size(attributes, pair_size)
+                + response_size(data)
+        }
+    }
+}
+
+fn command_name(cmd: &Cmd) -> String {
+    match cmd.args_iter().next() {
+        Some(Arg::Simple(name)) => String::from_utf8_lossy(name).to_ascii_uppercase(),
+        _ => String::new(),
+    }
+}
+
+/// Records the bytes of `cmd`, which was sent to `node`, and of its response, if one was received.
+pub fn record_command(cmd: &Cmd, node: &str, response: Option<&Value>) {
+    Telemetry::record_bytes(
+        &command_name(cmd),
+        node,
+        packed_command_size(cmd),
+        response.map(response_size).unwrap_or(0),
+    );
+}
+
+/// Records the bytes of `pipeline`, which was sent to `node`, and of its responses, if they were received.
+/// Responses are attributed to their commands when they match one to one, and to `EXEC` otherwise.
+pub fn record_pipeline(pipeline: &Pipeline, node: &str, responses: Option<&[Value]>) {
+    let commands_count = pipeline.cmd_iter().count();
+    let responses_match_commands =
+        responses.is_some_and(|responses| responses.len() == commands_count);
+    for (index, cmd) in pipeline.cmd_iter().enumerate() {
+        let read = match responses {
+            Some(responses) if responses_match_commands => response_size(&responses[index]),
+            _ => 0,
+        };
+        Telemetry::record_bytes(&command_name(cmd), node, packed_command_size(cmd), read);
+    }
+    if let Some(responses) = responses.filter(|_| !responses_match_commands) {
+        Telemetry::record_bytes("EXEC", node, 0, responses.iter().map(response_size).sum());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packed_command_size_matches_encoding() {
+        let mut cmd = crate::cmd("SET");
+        cmd.arg("key").arg("a value that is longer than ten bytes");
+        assert_eq!(packed_command_size(&cmd), cmd.get_packed_command().len());
+    }
+
+    #[test]
+    fn test_response_size_matches_encoding() {
+        assert_eq!(response_size(&Value::BulkString(b"hello".to_vec())), 11); // $5\r\nhello\r\n
+        assert_eq!(response_size(&Value::Int(-42)), 6); // :-42\r\n
+        assert_eq!(
+            response_size(&Value::Array(vec![Value::Okay, Value::Nil])),
+            12 // *2\r\n+OK\r\n_\r\n
+        );
+    }
+
+    #[test]
+    fn test_response_size_of_large_arrays_is_extrapolated() {
+        let values = vec![Value::Int(7); SAMPLED_ELEMENTS * 10];
+        // Each element is :7\r\n, after the *320\r\n header.
+        assert_eq!(
+            response_size(&Value::Array(values)),
+            6 + SAMPLED_ELEMENTS * 10 * 4
+        );
+        assert_eq!(response_size(&Value::Int(1234567)), 10);
+        assert_eq!(response_size(&Value::Array(vec![])), 4);
+    }
+}
@@ -35,6 +35,8 @@
 //!     .expire(key, 60).ignore()
 //!     .query(&mut connection).unwrap();
 //! ```
+#[cfg(feature = "cluster-async")]
+pub use crate::cluster_client::ConnectionsCheck;
 pub use crate::cluster_client::{ClusterClient, ClusterClientBuilder};
 use crate::cluster_pipeline::UNROUTABLE_ERROR;
 pub use crate::cluster_pipeline::{cluster_pipe, ClusterPipeline};
@@ -998,6 +1000,10 @@ pub(crate) fn get_connection_info(
             protocol: cluster_params.protocol,
             db: 0,
             pubsub_subscriptions: cluster_params.pubsub_subscriptions,
+            no_evict: cluster_params.no_evict,
+            no_touch: cluster_params.no_touch,
+            #[cfg(feature = "aio")]
+            authenticator: cluster_params.authenticator,
         },
     })
 }
@@ -1031,6 +1037,12 @@ pub(crate) fn slot_cmd() -> Cmd {
     cmd
 }
 
+pub(crate) fn shards_cmd() -> Cmd {
+    let mut cmd = Cmd::new();
+    cmd.arg("CLUSTER").arg("SHARDS");
+    cmd
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,6 +1,6 @@
 use crate::cluster_async::ConnectionFuture;
 use crate::cluster_routing::{Route, ShardAddrs, SlotAddr};
-use crate::cluster_slotmap::{ReadFromReplicaStrategy, SlotMap, SlotMapValue};
+use crate::cluster_slotmap::{ReadFromReplicaStrategy, RoutingWeights, SlotMap, SlotMapValue};
 use crate::cluster_topology::TopologyHash;
 use dashmap::DashMap;
 use futures::FutureExt;
@@ -138,6 +138,7 @@ pub(crate) struct ConnectionsContainer<Connection> {
     connection_map: DashMap<String, ClusterNode<Connection>>,
     pub(crate) slot_map: SlotMap,
     read_from_replica_strategy: ReadFromReplicaStrategy,
+    routing_weights: RoutingWeights,
     topology_hash: TopologyHash,
 }
 
@@ -154,6 +155,7 @@ impl<Connection> Default for ConnectionsContainer<Connection> {
             connection_map: Default::default(),
             slot_map: Default::default(),
             read_from_replica_strategy: ReadFromReplicaStrategy::AlwaysFromPrimary,
+            routing_weights: Default::default(),
             topology_hash: 0,
         }
     }
@@ -169,6 +171,7 @@ where
         slot_map: SlotMap,
         connection_map: ConnectionsMap<Connection>,
         read_from_replica_strategy: ReadFromReplicaStrategy,
+        routing_weights: RoutingWeights,
         topology_hash: TopologyHash,
     ) -> Self {
         let connection_map = connection_map.0;
@@ -181,6 +184,7 @@ where
             connection_map,
             slot_map,
             read_from_replica_strategy,
+            routing_weights,
             topology_hash,
         }
     }
@@ -223,6 +227,9 @@ where
         &self,
         slot_map_value: &SlotMapValue,
     ) -> Option<ConnectionAndAddress<Connection>> {
+        if !self.routing_weights.is_empty() {
+            return self.weighted_read_from_replica(slot_map_value);
+        }
         let addrs = &slot_map_value.addrs;
         let initial_index = slot_map_value.last_used_replica.load(Ordering::Relaxed);
         let mut check_count = 0;
@@ -247,6 +254,28 @@ where
         }
     }
 
+    /// Chooses a connected replica in proportion to the routing weights, falling back to the primary if all of the
+    /// connected replicas have weight 0.
+    fn weighted_read_from_replica(
+        &self,
+        slot_map_value: &SlotMapValue,
+    ) -> Option<ConnectionAndAddress<Connection>> {
+        let addrs = &slot_map_value.addrs;
+        let chosen = self
+            .routing_weights
+            .choose(addrs.replicas().iter().filter_map(|replica| {
+                self.connection_for_address(replica.as_str())
+                    .map(|connection| (replica.as_str(), connection))
+            }));
+        match chosen {
+            Some((connection, weight)) => {
+                Telemetry::incr_weighted_reads(RoutingWeights::weight_class(weight));
+                Some(connection)
+            }
+            None => self.connection_for_address(addrs.primary().as_str()),
+        }
+    }
+
     /// Returns the node's connection in the same availability zone as `client_az` in round robin strategy if exits,
     /// if not, will fall back to any available replica or primary.
     pub(crate) fn round_robin_read_from_replica_with_az_awareness(
@@ -447,7 +476,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use std::collections::{HashMap, HashSet};
 
     use crate::cluster_routing::Slot;
 
@@ -571,6 +600,7 @@ mod tests {
             slot_map,
             connection_map,
             read_from_replica_strategy: ReadFromReplicaStrategy::AZAffinity("use-1a".to_string()),
+            routing_weights: Default::default(),
             topology_hash: 0,
         }
     }
@@ -627,6 +657,7 @@ mod tests {
             slot_map,
             connection_map,
             read_from_replica_strategy: strategy,
+            routing_weights: Default::default(),
             topology_hash: 0,
         }
     }
@@ -718,6 +749,34 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn get_connection_for_replica_route_by_weight() {
+        let container = create_container_with_strategy(ReadFromReplicaStrategy::RoundRobin, false);
+        container.routing_weights.set(HashMap::from([
+            ("replica3-1".to_string(), 0),
+            ("replica2-1".to_string(), 0),
+        ]));
+
+        for _ in 0..10 {
+            assert_eq!(
+                32,
+                container
+                    .connection_for_route(&Route::new(2001, SlotAddr::ReplicaOptional))
+                    .unwrap()
+                    .1
+            );
+        }
+
+        // all of the replicas have weight 0, so the primary is used.
+        assert_eq!(
+            2,
+            container
+                .connection_for_route(&Route::new(1002, SlotAddr::ReplicaOptional))
+                .unwrap()
+                .1
+        );
+    }
+
     #[test]
     fn get_primary_connection_for_replica_route_if_no_replicas_were_added() {
         let container = create_container();
@@ -381,7 +381,7 @@ where
     Ok(())
 }
 
-async fn create_connection<C>(
+pub(crate) async fn create_connection<C>(
     node: &str,
     mut params: ClusterParams,
     socket_addr: Option<SocketAddr>,
@@ -402,6 +402,7 @@ where
     if is_management {
         glide_connection_options.disconnect_notifier = None;
     }
+    crate::connection_throttle::wait_for_connection_attempt(node).await;
     C::connect(
         info,
         response_timeout,
@@ -33,8 +33,9 @@ use crate::{
     client::GlideConnectionOptions,
     cluster_routing::{Routable, RoutingInfo, ShardUpdateResult},
     cluster_topology::{
-        calculate_topology, get_slot, SlotRefreshState, DEFAULT_NUMBER_OF_REFRESH_SLOTS_RETRIES,
-        DEFAULT_REFRESH_SLOTS_RETRY_BASE_DURATION_MILLIS, DEFAULT_REFRESH_SLOTS_RETRY_BASE_FACTOR,
+        calculate_topology, get_slot, shards_to_slots_view, SlotRefreshState, TopologySource,
+        DEFAULT_NUMBER_OF_REFRESH_SLOTS_RETRIES, DEFAULT_REFRESH_SLOTS_RETRY_BASE_DURATION_MILLIS,
+        DEFAULT_REFRESH_SLOTS_RETRY_BASE_FACTOR,
     },
     cmd,
     commands::cluster_scan::{cluster_scan, ClusterScanArgs, ScanStateRC},
@@ -47,7 +48,7 @@ use std::{
     net::{IpAddr, SocketAddr},
     pin::Pin,
     sync::{
-        atomic::{self, AtomicUsize, Ordering},
+        atomic::{self, AtomicBool, AtomicUsize, Ordering},
         Arc, Mutex,
     },
     task::{self, Poll},
@@ -59,11 +60,11 @@ use tokio::task::JoinHandle;
 
 #[cfg(feature = "tokio-comp")]
 use crate::aio::DisconnectNotifier;
-use telemetrylib::Telemetry;
+use telemetrylib::{NodeEvent, Telemetry};
 
 use crate::{
     aio::{get_socket_addrs, ConnectionLike, MultiplexedConnection, Runtime},
-    cluster::slot_cmd,
+    cluster::{shards_cmd, slot_cmd, TlsMode},
     cluster_async::connections_logic::{
         get_host_and_port_from_addr, get_or_create_conn, ConnectionFuture, RefreshConnectionType,
     },
@@ -113,7 +114,11 @@ const MUTEX_WRITE_ERR: &str = "Failed to obtain write lock. Poisoned mutex?";
 /// underlying connections maintained for each node in the cluster, as well
 /// as common parameters for connecting to nodes and executing commands.
 #[derive(Clone)]
-pub struct ClusterConnection<C = MultiplexedConnection>(mpsc::Sender<Message<C>>);
+pub struct ClusterConnection<C = MultiplexedConnection> {
+    sender: mpsc::Sender<Message<C>>,
+    /// Read without sending a message to the connection's task, e.g. to find the node that serves a request.
+    core: Core<C>,
+}
 
 impl<C> ClusterConnection<C>
 where
@@ -127,6 +132,7 @@ where
         ClusterConnInner::new(initial_nodes, cluster_params, push_sender)
             .await
             .map(|inner| {
+                let core = inner.inner.clone();
                 let (tx, mut rx) = mpsc::channel::<Message<_>>(100);
                 let stream = async move {
                     let _ = stream::poll_fn(move |cx| rx.poll_recv(cx))
@@ -136,7 +142,7 @@ where
                 };
                 #[cfg(feature = "tokio-comp")]
                 tokio::spawn(stream);
-                ClusterConnection(tx)
+                ClusterConnection { sender: tx, core }
             })
     }
 
@@ -204,7 +210,7 @@ where
         cluster_scan_args: ClusterScanArgs,
     ) -> RedisResult<(ScanStateRC, Vec<Value>)> {
         let (sender, receiver) = oneshot::channel();
-        self.0
+        self.sender
             .send(Message {
                 cmd: CmdArg::ClusterScan { cluster_scan_args },
                 sender,
@@ -226,7 +232,9 @@ where
             })
             .map(|response| match response {
                 Response::ClusterScanResult(new_scan_state_ref, key) => (new_scan_state_ref, key),
-                Response::Single(_) | Response::Multiple(_) => unreachable!(),
+                Response::Single(_) | Response::Multiple(_) | Response::NodeConnectionInfo(_) => {
+                    unreachable!()
+                }
             })
     }
 
@@ -237,12 +245,22 @@ where
         routing: cluster_routing::RoutingInfo,
     ) -> RedisResult<Value> {
         trace!("route_command");
+        self.send_cmd(cmd, routing, false).await
+    }
+
+    async fn send_cmd(
+        &mut self,
+        cmd: &Cmd,
+        routing: cluster_routing::RoutingInfo,
+        without_reply: bool,
+    ) -> RedisResult<Value> {
         let (sender, receiver) = oneshot::channel();
-        self.0
+        self.sender
             .send(Message {
                 cmd: CmdArg::Cmd {
                     cmd: Arc::new(cmd.clone()),
                     routing: routing.into(),
+                    without_reply,
                 },
                 sender,
             })
@@ -265,7 +283,9 @@ where
             })
             .map(|response| match response {
                 Response::Single(value) => value,
-                Response::ClusterScanResult(..) | Response::Multiple(_) => unreachable!(),
+                Response::ClusterScanResult(..)
+                | Response::Multiple(_)
+                | Response::NodeConnectionInfo(_) => unreachable!(),
             })
     }
 
@@ -278,7 +298,7 @@ where
         route: SingleNodeRoutingInfo,
     ) -> RedisResult<Vec<Value>> {
         let (sender, receiver) = oneshot::channel();
-        self.0
+        self.sender
             .send(Message {
                 cmd: CmdArg::Pipeline {
                     pipeline: Arc::new(pipeline.clone()),
@@ -303,9 +323,24 @@ where
             })
             .map(|response| match response {
                 Response::Multiple(values) => values,
-                Response::ClusterScanResult(..) | Response::Single(_) => unreachable!(),
+                Response::ClusterScanResult(..)
+                | Response::Single(_)
+                | Response::NodeConnectionInfo(_) => unreachable!(),
             })
     }
+    /// Sends `cmd` to the node of `route` without awaiting its reply, see
+    /// [`MultiplexedConnection::send_packed_command_without_reply`]. The command is sent on the node's connection, after
+    /// the requests that were sent to the node before it.
+    pub async fn route_command_without_reply(
+        &mut self,
+        cmd: &Cmd,
+        route: SingleNodeRoutingInfo,
+    ) -> RedisResult<()> {
+        self.send_cmd(cmd, cluster_routing::RoutingInfo::SingleNode(route), true)
+            .await
+            .map(|_| ())
+    }
+
     /// Update the password used to authenticate with all cluster servers
     pub async fn update_connection_password(
         &mut self,
@@ -315,13 +350,93 @@ where
             .await
     }
 
+    /// Returns the command that the topology was last read with, for debugging. `None` until the topology was read
+    /// from the servers.
+    pub async fn topology_source(&mut self) -> RedisResult<Option<TopologySource>> {
+        let source = self
+            .route_operation_request(Operation::GetTopologySource)
+            .await?;
+        Ok(match source {
+            Value::SimpleString(source) if source == TopologySource::ClusterShards.as_str() => {
+                Some(TopologySource::ClusterShards)
+            }
+            Value::SimpleString(source) if source == TopologySource::ClusterSlots.as_str() => {
+                Some(TopologySource::ClusterSlots)
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns the number of connections that are currently open to the cluster's nodes, including the management
+    /// connections.
+    pub async fn connections_count(&mut self) -> RedisResult<usize> {
+        let count = self
+            .route_operation_request(Operation::GetConnectionsCount)
+            .await?;
+        crate::from_owned_redis_value(count)
+    }
+
+    /// Closes the connections to all the nodes, e.g. while the client is idle. The topology is kept, and the next
+    /// request reconnects to the initial nodes before it's sent. The periodic checks are paused until then.
+    pub async fn suspend(&mut self) -> RedisResult<()> {
+        self.route_operation_request(Operation::Suspend).await?;
+        Ok(())
+    }
+
+    /// Returns the address of the primary that serves `route` according to the current slot map, or `None` if the
+    /// route may be served by a replica or no node serves it. The request isn't routed, so the replicas' round robin
+    /// isn't affected.
+    pub fn primary_for_route(&self, route: &Route) -> Option<String> {
+        self.core
+            .conn_lock
+            .read()
+            .expect(MUTEX_READ_ERR)
+            .slot_map
+            .primary_for_route(route)
+            .map(|address| address.to_string())
+    }
+
+    /// Opens a connection to the node that serves `route`, which isn't shared with other requests - e.g. for `WATCH`
+    /// and the transaction that follows it, which would otherwise be affected by the other requests on the node's
+    /// connection. The connection is opened like the cluster's connections, with the same connection options and rate
+    /// limit, but it isn't managed by the cluster: it doesn't serve subscriptions, isn't reconnected, and is closed
+    /// once it's dropped.
+    pub async fn dedicated_connection(&mut self, route: Route) -> RedisResult<C> {
+        let (sender, receiver) = oneshot::channel();
+        self.sender
+            .send(Message {
+                cmd: CmdArg::OperationRequest(Operation::GetNodeConnectionInfo(route)),
+                sender,
+            })
+            .await
+            .map_err(|_| RedisError::from(io::Error::from(io::ErrorKind::BrokenPipe)))?;
+        let response = receiver.await.unwrap_or_else(|err| {
+            Err(RedisError::from(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                err.to_string(),
+            )))
+        })?;
+        let Response::NodeConnectionInfo(node) = response else {
+            unreachable!()
+        };
+        let connection = connections_logic::create_connection(
+            &node.address,
+            node.params,
+            None,
+            false,
+            node.glide_connection_options,
+        )
+        .await?;
+        Ok(connection.conn)
+    }
+
     /// Routes an operation request to the appropriate handler.
     async fn route_operation_request(
         &mut self,
         operation_request: Operation,
     ) -> RedisResult<Value> {
         let (sender, receiver) = oneshot::channel();
-        self.0
+        self.sender
             .send(Message {
                 cmd: CmdArg::OperationRequest(operation_request),
                 sender,
@@ -339,7 +454,9 @@ where
             })
             .map(|response| match response {
                 Response::Single(values) => values,
-                Response::ClusterScanResult(..) | Response::Multiple(_) => unreachable!(),
+                Response::ClusterScanResult(..)
+                | Response::Multiple(_)
+                | Response::NodeConnectionInfo(_) => unreachable!(),
             })
     }
 }
@@ -391,6 +508,11 @@ pub(crate) struct InnerCore<C> {
     subscriptions_by_address: TokioRwLock<HashMap<String, PubSubSubscriptionInfo>>,
     unassigned_subscriptions: TokioRwLock<PubSubSubscriptionInfo>,
     glide_connection_options: GlideConnectionOptions,
+    /// The command that the topology was last read with. Once a server doesn't support CLUSTER SHARDS, CLUSTER SLOTS
+    /// is used for the rest of the connection's life.
+    topology_source: StdRwLock<Option<TopologySource>>,
+    /// Set while the connections are closed by a call to `suspend`, until the next request.
+    suspended: AtomicBool,
 }
 
 pub(crate) type Core<C> = Arc<InnerCore<C>>;
@@ -600,6 +722,8 @@ enum CmdArg<C> {
     Cmd {
         cmd: Arc<Cmd>,
         routing: InternalRoutingInfo<C>,
+        // Whether the command is sent without a reply, see `ConnectionLike::req_packed_command_without_reply`.
+        without_reply: bool,
     },
     Pipeline {
         pipeline: Arc<crate::Pipeline>,
@@ -619,6 +743,10 @@ enum CmdArg<C> {
 #[derive(Clone)]
 enum Operation {
     UpdateConnectionPassword(Option<String>),
+    GetTopologySource,
+    GetConnectionsCount,
+    Suspend,
+    GetNodeConnectionInfo(Route),
 }
 
 fn route_for_pipeline(pipeline: &crate::Pipeline) -> RedisResult<Option<Route>> {
@@ -668,6 +796,22 @@ pub(crate) enum Response {
     Single(Value),
     ClusterScanResult(ScanStateRC, Vec<Value>),
     Multiple(Vec<Value>),
+    NodeConnectionInfo(Box<NodeConnectionInfo>),
+}
+
+/// What is needed in order to open a connection to a node, outside of the connections that the cluster manages.
+pub(crate) struct NodeConnectionInfo {
+    address: String,
+    params: ClusterParams,
+    glide_connection_options: GlideConnectionOptions,
+}
+
+impl fmt::Debug for NodeConnectionInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NodeConnectionInfo")
+            .field("address", &self.address)
+            .finish()
+    }
 }
 
 #[derive(Debug)]
@@ -1111,6 +1255,7 @@ where
                 Default::default(),
                 connections,
                 cluster_params.read_from_replicas.clone(),
+                cluster_params.routing_weights.clone(),
                 0,
             )),
             cluster_params: StdRwLock::new(cluster_params.clone()),
@@ -1126,6 +1271,8 @@ where
             ),
             subscriptions_by_address: TokioRwLock::new(Default::default()),
             glide_connection_options,
+            topology_source: StdRwLock::new(None),
+            suspended: AtomicBool::new(false),
         });
         let mut connection = ClusterConnInner {
             inner,
@@ -1142,8 +1289,11 @@ where
         .await?;
 
         if let Some(duration) = topology_checks_interval {
-            let periodic_task =
-                ClusterConnInner::periodic_topology_check(connection.inner.clone(), duration);
+            let periodic_task = ClusterConnInner::supervise_periodic_task(
+                connection.inner.clone(),
+                "periodic topology check",
+                move |inner| ClusterConnInner::periodic_topology_check(inner, duration),
+            );
             #[cfg(feature = "tokio-comp")]
             {
                 connection.periodic_checks_handler = Some(tokio::spawn(periodic_task));
@@ -1152,8 +1302,11 @@ where
 
         let connections_validation_interval = cluster_params.connections_validation_interval;
         if let Some(duration) = connections_validation_interval {
-            let connections_validation_handler =
-                ClusterConnInner::connections_validation_task(connection.inner.clone(), duration);
+            let connections_validation_handler = ClusterConnInner::supervise_periodic_task(
+                connection.inner.clone(),
+                "connections validation",
+                move |inner| ClusterConnInner::connections_validation_task(inner, duration),
+            );
             #[cfg(feature = "tokio-comp")]
             {
                 connection.connections_validation_handler =
@@ -1415,6 +1568,7 @@ where
         while let Some(result) = tasks.next().await {
             match result {
                 (address, Ok(node)) => {
+                    Telemetry::record_node_event(&address, NodeEvent::Reconnect);
                     let connections_container = inner.conn_lock.read().expect(MUTEX_READ_ERR);
                     connections_container.replace_or_add_connection_for_address(address, node);
                 }
@@ -1438,6 +1592,7 @@ where
             Response::Single(value) => value,
             Response::Multiple(_) => unreachable!(),
             Response::ClusterScanResult(_, _) => unreachable!(),
+            Response::NodeConnectionInfo(_) => unreachable!(),
         };
 
         let convert_result = |res: Result<RedisResult<Response>, _>| {
@@ -1607,7 +1762,10 @@ where
                 Self::refresh_slots(inner.clone(), curr_retry)
                     .await
                     .map_err(|err| {
-                        if err.kind() == ErrorKind::AllConnectionsUnavailable {
+                        // A topology that the connections check rejected won't be accepted on a retry.
+                        if err.kind() == ErrorKind::AllConnectionsUnavailable
+                            || err.kind() == ErrorKind::InvalidClientConfig
+                        {
                             RetryError::permanent(err)
                         } else {
                             RetryError::transient(err)
@@ -1637,9 +1795,36 @@ where
         Ok(topology_changed)
     }
 
+    /// Runs the background task created by `make_task`. If the task panics, a `FatalClientError` notification is sent
+    /// and the task is restarted. This is safe for periodic tasks, since they don't hold state between iterations.
+    async fn supervise_periodic_task<F, Fut>(
+        inner: Arc<InnerCore<C>>,
+        task_name: &'static str,
+        make_task: F,
+    ) where
+        F: Fn(Arc<InnerCore<C>>) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let push_sender = inner.glide_connection_options.push_sender.clone();
+        crate::run_supervised(
+            task_name,
+            move || make_task(inner.clone()),
+            move |push_info| {
+                if let Some(push_sender) = &push_sender {
+                    let _ = push_sender.send(push_info);
+                }
+                async {}
+            },
+        )
+        .await
+    }
+
     async fn periodic_topology_check(inner: Arc<InnerCore<C>>, interval_duration: Duration) {
         loop {
             let _ = boxed_sleep(interval_duration).await;
+            if inner.suspended.load(Ordering::Relaxed) {
+                continue;
+            }
             // Check and refresh topology if needed
             let should_refresh_pubsub = match Self::check_topology_and_refresh_if_diff(
                 inner.clone(),
@@ -1680,6 +1865,9 @@ where
                 let _ = boxed_sleep(interval_duration).await;
             }
 
+            if inner.suspended.load(Ordering::Relaxed) {
+                continue;
+            }
             Self::validate_all_user_connections(inner.clone()).await;
         }
     }
@@ -1829,6 +2017,13 @@ where
         // Create a new connection vector of the found nodes
         let nodes = new_slots.all_node_addresses();
         let nodes_len = nodes.len();
+        if let Some(check) = inner
+            .get_cluster_param(|params| params.connections_check.clone())
+            .expect(MUTEX_READ_ERR)
+        {
+            // Each node has a user and a management connection.
+            check(nodes_len * 2)?;
+        }
         let addresses_and_connections_iter = stream::iter(nodes)
             .fold(
                 Vec::with_capacity(nodes_len),
@@ -1897,15 +2092,24 @@ where
             .await;
 
         info!("refresh_slots found nodes:\n{new_connections}");
+        for node in new_connections.0.iter() {
+            Telemetry::record_node_event(node.key(), NodeEvent::TopologyRefresh);
+        }
         // Reset the current slot map and connection vector with the new ones
         let mut write_guard = inner.conn_lock.write().expect(MUTEX_WRITE_ERR);
-        let read_from_replicas = inner
-            .get_cluster_param(|params| params.read_from_replicas.clone())
+        let (read_from_replicas, routing_weights) = inner
+            .get_cluster_param(|params| {
+                (
+                    params.read_from_replicas.clone(),
+                    params.routing_weights.clone(),
+                )
+            })
             .expect(MUTEX_READ_ERR);
         *write_guard = ConnectionsContainer::new(
             new_slots,
             new_connections,
             read_from_replicas,
+            routing_weights,
             topology_hash,
         );
         Ok(())
@@ -1936,6 +2140,18 @@ where
         slot: u16,
         new_primary: Arc<String>,
     ) -> RedisResult<()> {
+        let is_pinned = inner
+            .get_cluster_param(|params| {
+                params
+                    .pinned_slots
+                    .iter()
+                    .any(|pinned| pinned.contains(slot))
+            })
+            .expect(MUTEX_READ_ERR);
+        if is_pinned {
+            // The redirection is followed by the request, but the slot stays routed to its pinned node.
+            return Ok(());
+        }
         let curr_shard_addrs = inner
             .conn_lock
             .read()
@@ -2026,6 +2242,7 @@ where
                                             conn,
                                         }
                                         .into(),
+                                        without_reply: false,
                                     },
                                 },
                             }),
@@ -2096,6 +2313,7 @@ where
     pub(crate) async fn try_cmd_request(
         cmd: Arc<Cmd>,
         routing: InternalRoutingInfo<C>,
+        without_reply: bool,
         core: Core<C>,
     ) -> OperationResult {
         let routing = match routing {
@@ -2119,29 +2337,125 @@ where
         let (address, mut conn) = Self::get_connection(routing, core, Some(cmd.clone()))
             .await
             .map_err(|err| (OperationTarget::NotFound, err))?;
-        conn.req_packed_command(&cmd)
-            .await
+        let result = if without_reply {
+            conn.req_packed_command_without_reply(&cmd)
+                .await
+                .map(|()| Value::Nil)
+        } else {
+            conn.req_packed_command(&cmd).await
+        };
+        crate::byte_counters::record_command(&cmd, &address, result.as_ref().ok());
+        Telemetry::record_node_event(&address, NodeEvent::for_request(result.is_ok()));
+        if let Err(err) = &result {
+            if matches!(err.retry_method(), RetryMethod::MovedRedirect)
+                && cluster_routing::is_readonly(cmd.as_ref())
+            {
+                Self::reconnect_replica_after_moved_read(&core, &address, err).await;
+            }
+        }
+        result
             .map(Response::Single)
             .map_err(|err| (address.into(), err))
     }
 
+    /// Called when a read was rejected with MOVED. If the read was sent to a replica, the connection might have lost its
+    /// READONLY state, so the replica's user connection is recreated, which sends READONLY as part of its setup. This
+    /// covers single commands, pipelines and transactions alike, since they all share the connection.
+    ///
+    /// A replica also returns MOVED for slots that were migrated to another shard, e.g. during resharding, so the
+    /// connection is only recreated if the redirect points to the replica's own primary. Otherwise the redirect is just
+    /// followed.
+    async fn reconnect_replica_after_moved_read(core: &Core<C>, address: &str, err: &RedisError) {
+        let reads_from_replicas = core
+            .get_cluster_param(|params| {
+                params.read_from_replicas
+                    != crate::cluster_slotmap::ReadFromReplicaStrategy::AlwaysFromPrimary
+            })
+            .unwrap_or(false);
+        if !reads_from_replicas {
+            return;
+        }
+        let Some((redirect_address, _)) = err.redirect_node() else {
+            return;
+        };
+        let address_string = address.to_string();
+        let node = {
+            let connections_container = core.conn_lock.read().expect(MUTEX_READ_ERR);
+            if connections_container.is_primary(&address_string) {
+                return;
+            }
+            let redirected_to_own_primary = connections_container
+                .slot_map
+                .nodes_map()
+                .get(&address_string)
+                .is_some_and(|shard_addrs| shard_addrs.primary().as_str() == redirect_address);
+            if !redirected_to_own_primary {
+                return;
+            }
+            connections_container.node_for_address(address)
+        };
+        Telemetry::incr_replica_moved_reads();
+
+        let mut cluster_params = core.cluster_params.read().expect(MUTEX_READ_ERR).clone();
+        let subs_guard = core.subscriptions_by_address.read().await;
+        cluster_params.pubsub_subscriptions = subs_guard.get(address).cloned();
+        drop(subs_guard);
+        match connect_and_check(
+            address,
+            cluster_params,
+            None,
+            RefreshConnectionType::OnlyUserConnection,
+            node,
+            core.glide_connection_options.clone(),
+        )
+        .await
+        .get_node()
+        {
+            Ok(node) => {
+                Telemetry::record_node_event(address, NodeEvent::Reconnect);
+                core.conn_lock
+                    .read()
+                    .expect(MUTEX_READ_ERR)
+                    .replace_or_add_connection_for_address(address, node);
+            }
+            Err(err) => warn!(
+                "Failed to reconnect to replica `{}` after a read was rejected with MOVED. Error: `{:?}`",
+                address, err
+            ),
+        }
+    }
+
     async fn try_pipeline_request(
         pipeline: Arc<crate::Pipeline>,
         offset: usize,
         count: usize,
         conn: impl Future<Output = RedisResult<(String, C)>>,
+        core: Core<C>,
     ) -> OperationResult {
         trace!("try_pipeline_request");
         let (address, mut conn) = conn.await.map_err(|err| (OperationTarget::NotFound, err))?;
-        conn.req_packed_commands(&pipeline, offset, count)
-            .await
+        let result = conn.req_packed_commands(&pipeline, offset, count).await;
+        crate::byte_counters::record_pipeline(&pipeline, &address, result.as_deref().ok());
+        Telemetry::record_node_event(&address, NodeEvent::for_request(result.is_ok()));
+        if let Err(err) = &result {
+            if matches!(err.retry_method(), RetryMethod::MovedRedirect)
+                && pipeline.cmd_iter().all(cluster_routing::is_readonly)
+            {
+                Self::reconnect_replica_after_moved_read(&core, &address, err).await;
+            }
+        }
+        result
             .map(Response::Multiple)
             .map_err(|err| (OperationTarget::Node { address }, err))
     }
 
     async fn try_request(info: RequestInfo<C>, core: Core<C>) -> OperationResult {
         match info.cmd {
-            CmdArg::Cmd { cmd, routing } => Self::try_cmd_request(cmd, routing, core).await,
+            CmdArg::Cmd {
+                cmd,
+                routing,
+                without_reply,
+            } => Self::try_cmd_request(cmd, routing, without_reply, core).await,
             CmdArg::Pipeline {
                 pipeline,
                 offset,
@@ -2152,7 +2466,8 @@ where
                     pipeline,
                     offset,
                     count,
-                    Self::get_connection(route, core, None),
+                    Self::get_connection(route, core.clone(), None),
+                    core,
                 )
                 .await
             }
@@ -2176,6 +2491,67 @@ where
                         .expect(MUTEX_WRITE_ERR);
                     Ok(Response::Single(Value::Okay))
                 }
+                Operation::GetTopologySource => {
+                    let source = *core.topology_source.read().expect(MUTEX_READ_ERR);
+                    Ok(Response::Single(source.map_or(Value::Nil, |source| {
+                        Value::SimpleString(source.as_str().to_string())
+                    })))
+                }
+                Operation::GetConnectionsCount => {
+                    let count: usize = core
+                        .conn_lock
+                        .read()
+                        .expect(MUTEX_READ_ERR)
+                        .connection_map()
+                        .iter()
+                        .map(|node| node.connections_count())
+                        .sum();
+                    Ok(Response::Single(Value::Int(count as i64)))
+                }
+                Operation::Suspend => {
+                    core.suspended.store(true, Ordering::Relaxed);
+                    let connections_container = core.conn_lock.read().expect(MUTEX_READ_ERR);
+                    let addresses: Vec<String> = connections_container
+                        .connection_map()
+                        .iter()
+                        .map(|node| node.key().clone())
+                        .collect();
+                    for address in addresses.iter() {
+                        connections_container.remove_node(address);
+                    }
+                    info!("Suspended the connections to {} nodes", addresses.len());
+                    Ok(Response::Single(Value::Okay))
+                }
+                Operation::GetNodeConnectionInfo(route) => {
+                    let address = core
+                        .conn_lock
+                        .read()
+                        .expect(MUTEX_READ_ERR)
+                        .slot_map
+                        .slot_addr_for_route(&route)
+                        .ok_or_else(|| {
+                            (
+                                OperationTarget::NotFound,
+                                RedisError::from((
+                                    ErrorKind::ConnectionNotFoundForRoute,
+                                    "No node serves the requested slot",
+                                )),
+                            )
+                        })?;
+                    let mut params = core
+                        .get_cluster_param(|params| params.clone())
+                        .map_err(|err| (OperationTarget::NotFound, err))?;
+                    // The subscriptions are served by the cluster's connections.
+                    params.pubsub_subscriptions = None;
+                    let mut glide_connection_options = core.glide_connection_options.clone();
+                    // The cluster isn't notified of disconnections, since it doesn't manage the connection.
+                    glide_connection_options.disconnect_notifier = None;
+                    Ok(Response::NodeConnectionInfo(Box::new(NodeConnectionInfo {
+                        address: address.to_string(),
+                        params,
+                        glide_connection_options,
+                    })))
+                }
             },
         }
     }
@@ -2579,9 +2955,21 @@ where
         Poll::Ready(Ok(()))
     }
 
-    fn start_send(self: Pin<&mut Self>, msg: Message<C>) -> Result<(), Self::Error> {
+    fn start_send(mut self: Pin<&mut Self>, msg: Message<C>) -> Result<(), Self::Error> {
         let Message { cmd, sender } = msg;
 
+        // Operations don't use the connections, so they don't resume a suspended client. The state is only replaced if
+        // no recovery is in progress, otherwise the flag is kept for the next request.
+        if !matches!(cmd, CmdArg::OperationRequest(_))
+            && matches!(self.state, ConnectionState::PollComplete)
+            && self.inner.suspended.swap(false, Ordering::Relaxed)
+        {
+            debug!("Resuming the suspended connections");
+            self.state = ConnectionState::Recover(RecoverFuture::Reconnect(Box::pin(
+                ClusterConnInner::reconnect_to_initial_nodes(self.inner.clone()),
+            )));
+        }
+
         let info = RequestInfo { cmd };
 
         self.inner
@@ -2696,32 +3084,57 @@ where
             vec![],
         );
     };
+    let tls_mode = inner
+        .get_cluster_param(|params| params.tls)
+        .expect(MUTEX_READ_ERR);
+    let try_shards =
+        *inner.topology_source.read().expect(MUTEX_READ_ERR) != Some(TopologySource::ClusterSlots);
     let topology_join_results =
         futures::future::join_all(requested_nodes.into_iter().map(|(addr, conn)| async move {
             let mut conn: C = conn.await;
-            let res = conn.req_packed_command(&slot_cmd()).await;
-            (addr, res)
+            let (res, source) = query_topology(&mut conn, try_shards, tls_mode).await;
+            (addr, res, source)
         }))
         .await;
+    let sources: HashSet<TopologySource> = topology_join_results
+        .iter()
+        .filter(|(_, res, _)| res.is_ok())
+        .map(|(_, _, source)| *source)
+        .collect();
+    let source = if sources.contains(&TopologySource::ClusterSlots) {
+        Some(TopologySource::ClusterSlots)
+    } else {
+        sources.into_iter().next()
+    };
+    if let Some(source) = source {
+        let previous = inner
+            .topology_source
+            .write()
+            .expect(MUTEX_WRITE_ERR)
+            .replace(source);
+        if previous != Some(source) {
+            info!("Reading the cluster topology with {}", source.as_str());
+        }
+    }
     let failed_addresses = topology_join_results
         .iter()
-        .filter_map(|(address, res)| match res {
+        .filter_map(|(address, res, _)| match res {
             Err(err) if err.is_unrecoverable_error() => Some(address.clone()),
             _ => None,
         })
         .collect();
-    let topology_values = topology_join_results.iter().filter_map(|(addr, res)| {
+    let topology_values = topology_join_results.iter().filter_map(|(addr, res, _)| {
         res.as_ref()
             .ok()
             .and_then(|value| get_host_and_port_from_addr(addr).map(|(host, _)| (host, value)))
     });
-    let tls_mode = inner
-        .get_cluster_param(|params| params.tls)
-        .expect(MUTEX_READ_ERR);
 
     let read_from_replicas = inner
         .get_cluster_param(|params| params.read_from_replicas.clone())
         .expect(MUTEX_READ_ERR);
+    let pinned_slots = inner
+        .get_cluster_param(|params| params.pinned_slots.clone())
+        .expect(MUTEX_READ_ERR);
     (
         calculate_topology(
             topology_values,
@@ -2729,11 +3142,41 @@ where
             tls_mode,
             num_of_nodes_to_query,
             read_from_replicas,
+            &pinned_slots,
         ),
         failed_addresses,
     )
 }
 
+/// Reads the topology from `conn`, in the layout of a CLUSTER SLOTS reply. CLUSTER SHARDS is preferred if
+/// `try_shards` is set, since unlike CLUSTER SLOTS it reports the nodes' roles, and falls back to CLUSTER SLOTS if the
+/// server doesn't support it.
+async fn query_topology<C>(
+    conn: &mut C,
+    try_shards: bool,
+    tls_mode: Option<TlsMode>,
+) -> (RedisResult<Value>, TopologySource)
+where
+    C: ConnectionLike,
+{
+    if try_shards {
+        match conn.req_packed_command(&shards_cmd()).await {
+            Ok(shards) => {
+                if let Some(slots_view) = shards_to_slots_view(&shards, tls_mode) {
+                    return (Ok(slots_view), TopologySource::ClusterShards);
+                }
+            }
+            // Servers older than 7.0 reply with an unknown subcommand error.
+            Err(err) if err.kind() == ErrorKind::ResponseError => {}
+            Err(err) => return (Err(err), TopologySource::ClusterShards),
+        }
+    }
+    (
+        conn.req_packed_command(&slot_cmd()).await,
+        TopologySource::ClusterSlots,
+    )
+}
+
 impl<C> ConnectionLike for ClusterConnection<C>
 where
     C: ConnectionLike + Send + Clone + Unpin + Sync + Connect + 'static,
@@ -1,4 +1,7 @@
-use crate::cluster_slotmap::ReadFromReplicaStrategy;
+#[cfg(feature = "aio")]
+use crate::aio::Authenticator;
+use crate::cluster_slotmap::{ReadFromReplicaStrategy, RoutingWeights};
+use crate::cluster_topology::PinnedSlots;
 #[cfg(feature = "cluster-async")]
 use crate::cluster_topology::{
     DEFAULT_SLOTS_REFRESH_MAX_JITTER_MILLI, DEFAULT_SLOTS_REFRESH_WAIT_DURATION,
@@ -10,6 +13,8 @@ use crate::{PubSubSubscriptionInfo, PushInfo};
 use rand::Rng;
 #[cfg(feature = "cluster-async")]
 use std::ops::Add;
+#[cfg(any(feature = "aio", feature = "cluster-async"))]
+use std::sync::Arc;
 use std::time::Duration;
 
 #[cfg(feature = "tls-rustls")]
@@ -26,6 +31,11 @@ use crate::tls::{retrieve_tls_certificates, TlsCertificates};
 
 use tokio::sync::mpsc;
 
+/// A check of the number of connections that a cluster client is about to open, see
+/// [`ClusterClientBuilder::connections_check`].
+#[cfg(feature = "cluster-async")]
+pub type ConnectionsCheck = Arc<dyn Fn(usize) -> RedisResult<()> + Send + Sync>;
+
 /// Parameters specific to builder, so that
 /// builder parameters may have different types
 /// than final ClusterParams
@@ -34,9 +44,13 @@ struct BuilderParams {
     password: Option<String>,
     username: Option<String>,
     read_from_replicas: ReadFromReplicaStrategy,
+    routing_weights: RoutingWeights,
+    pinned_slots: Vec<PinnedSlots>,
     tls: Option<TlsMode>,
     #[cfg(feature = "tls-rustls")]
     certs: Option<TlsCertificates>,
+    #[cfg(feature = "tls-rustls")]
+    tls_server_name: Option<String>,
     retries_configuration: RetryParams,
     connection_timeout: Option<Duration>,
     #[cfg(feature = "cluster-async")]
@@ -49,6 +63,12 @@ struct BuilderParams {
     response_timeout: Option<Duration>,
     protocol: ProtocolVersion,
     pubsub_subscriptions: Option<PubSubSubscriptionInfo>,
+    no_evict: bool,
+    no_touch: bool,
+    #[cfg(feature = "aio")]
+    authenticator: Option<Arc<dyn Authenticator>>,
+    #[cfg(feature = "cluster-async")]
+    connections_check: Option<ConnectionsCheck>,
 }
 
 #[derive(Clone)]
@@ -131,6 +151,9 @@ pub struct ClusterParams {
     pub(crate) password: Option<String>,
     pub(crate) username: Option<String>,
     pub(crate) read_from_replicas: ReadFromReplicaStrategy,
+    pub(crate) routing_weights: RoutingWeights,
+    /// Slot ranges that are routed to a set node, regardless of the topology that is read from the cluster.
+    pub(crate) pinned_slots: Vec<PinnedSlots>,
     /// tls indicates tls behavior of connections.
     /// When Some(TlsMode), connections use tls and verify certification depends on TlsMode.
     /// When None, connections do not use tls.
@@ -148,6 +171,13 @@ pub struct ClusterParams {
     pub(crate) response_timeout: Duration,
     pub(crate) protocol: ProtocolVersion,
     pub(crate) pubsub_subscriptions: Option<PubSubSubscriptionInfo>,
+    pub(crate) no_evict: bool,
+    pub(crate) no_touch: bool,
+    #[cfg(feature = "aio")]
+    pub(crate) authenticator: Option<Arc<dyn Authenticator>>,
+    /// Called with the number of connections that a newly discovered topology needs, before they are opened.
+    #[cfg(feature = "cluster-async")]
+    pub(crate) connections_check: Option<ConnectionsCheck>,
 }
 
 impl ClusterParams {
@@ -159,13 +189,19 @@ impl ClusterParams {
         let tls_params = {
             let retrieved_tls_params = value.certs.clone().map(retrieve_tls_certificates);
 
-            retrieved_tls_params.transpose()?
+            let mut tls_params = retrieved_tls_params.transpose()?;
+            if let Some(server_name) = value.tls_server_name.clone() {
+                tls_params.get_or_insert_with(Default::default).server_name = Some(server_name);
+            }
+            tls_params
         };
 
         Ok(Self {
             password: value.password,
             username: value.username,
             read_from_replicas: value.read_from_replicas,
+            routing_weights: value.routing_weights,
+            pinned_slots: value.pinned_slots,
             tls: value.tls,
             retry_params: value.retries_configuration,
             connection_timeout: value.connection_timeout.unwrap_or(Duration::MAX),
@@ -180,6 +216,12 @@ impl ClusterParams {
             response_timeout: value.response_timeout.unwrap_or(Duration::MAX),
             protocol: value.protocol,
             pubsub_subscriptions: value.pubsub_subscriptions,
+            no_evict: value.no_evict,
+            no_touch: value.no_touch,
+            #[cfg(feature = "aio")]
+            authenticator: value.authenticator,
+            #[cfg(feature = "cluster-async")]
+            connections_check: value.connections_check,
         })
     }
 }
@@ -248,6 +290,12 @@ impl ClusterClientBuilder {
         } else {
             &None
         };
+        #[cfg(feature = "aio")]
+        if cluster_params.authenticator.is_none() {
+            cluster_params
+                .authenticator
+                .clone_from(&first_node.redis.authenticator);
+        }
         if cluster_params.tls.is_none() {
             cluster_params.tls = match first_node.addr {
                 ConnectionAddr::TcpTls {
@@ -294,6 +342,10 @@ impl ClusterClientBuilder {
             }
 
             node.redis.protocol = cluster_params.protocol;
+            #[cfg(feature = "aio")]
+            node.redis
+                .authenticator
+                .clone_from(&cluster_params.authenticator);
             nodes.push(node);
         }
 
@@ -309,6 +361,37 @@ impl ClusterClientBuilder {
         self
     }
 
+    /// Sends `CLIENT NO-EVICT ON` on every connection of the new ClusterClient, including reconnections.
+    pub fn no_evict(mut self, no_evict: bool) -> ClusterClientBuilder {
+        self.builder_params.no_evict = no_evict;
+        self
+    }
+
+    /// Sends `CLIENT NO-TOUCH ON` on every connection of the new ClusterClient, including reconnections.
+    pub fn no_touch(mut self, no_touch: bool) -> ClusterClientBuilder {
+        self.builder_params.no_touch = no_touch;
+        self
+    }
+
+    /// Sets the scheme that authenticates the connections of the new ClusterClient, including reconnections. It
+    /// replaces the password and username authentication.
+    #[cfg(feature = "aio")]
+    pub fn authenticator(mut self, authenticator: Arc<dyn Authenticator>) -> ClusterClientBuilder {
+        self.builder_params.authenticator = Some(authenticator);
+        self
+    }
+
+    /// Sets a check that is called with the number of connections that a newly discovered topology needs, before
+    /// they are opened. The topology isn't connected to if the check fails, e.g. because the process can't hold that
+    /// many connections. Each node of the topology needs a user and a management connection. The check should fail
+    /// with [`ErrorKind::InvalidClientConfig`](crate::ErrorKind::InvalidClientConfig), so that the topology refresh
+    /// isn't retried.
+    #[cfg(feature = "cluster-async")]
+    pub fn connections_check(mut self, check: ConnectionsCheck) -> ClusterClientBuilder {
+        self.builder_params.connections_check = Some(check);
+        self
+    }
+
     /// Sets password for the new ClusterClient.
     pub fn password(mut self, password: String) -> ClusterClientBuilder {
         self.builder_params.password = Some(password);
@@ -378,6 +461,26 @@ impl ClusterClientBuilder {
         self
     }
 
+    /// Sets the name that is sent as SNI and that the nodes' certificates are verified against, instead of the
+    /// addresses that the nodes are dialed at.
+    ///
+    /// The same name is used for all the nodes of the cluster, so their certificates must all be valid for it.
+    #[cfg(feature = "tls-rustls")]
+    pub fn tls_server_name(mut self, server_name: String) -> ClusterClientBuilder {
+        self.builder_params.tls_server_name = Some(server_name);
+        self
+    }
+
+    /// Routes the given slot ranges to the given nodes, overriding the owners that are read from the cluster.
+    ///
+    /// The pins are reapplied after every topology refresh, and MOVED errors for pinned slots are followed without
+    /// updating the slot map. This is meant for tests that need deterministic routing, and for controlled cutovers,
+    /// without changing the cluster's configuration. Only the async cluster connection applies the pins.
+    pub fn pinned_slots(mut self, pinned_slots: Vec<PinnedSlots>) -> ClusterClientBuilder {
+        self.builder_params.pinned_slots = pinned_slots;
+        self
+    }
+
     /// Enables reading from replicas for all new connections (default is disabled).
     ///
     /// If enabled, then read queries will go to the replica nodes & write queries will go to the
@@ -402,6 +505,15 @@ impl ClusterClientBuilder {
         self
     }
 
+    /// Sets the weights used to choose the replica that serves a read request.
+    ///
+    /// The weights are shared with the given `routing_weights`, so they can be replaced at runtime.
+    /// They apply to the `RoundRobin` strategy and to requests that are routed to replicas explicitly.
+    pub fn routing_weights(mut self, routing_weights: RoutingWeights) -> ClusterClientBuilder {
+        self.builder_params.routing_weights = routing_weights;
+        self
+    }
+
     /// Enables periodic topology checks for this client.
     ///
     /// If enabled, periodic topology checks will be executed at the configured intervals to examine whether there
@@ -873,6 +873,147 @@ pub fn is_readonly(routable: &impl Routable) -> bool {
     }
 }
 
+/// Returns the keys of `routable`, as far as they can be told from the command's arguments.
+///
+/// Unlike routing, which only needs a single key of most commands, this returns all of the command's keys, so it can
+/// be used for reporting which keys a command accessed.
+pub fn command_keys<R>(routable: &R) -> Vec<&[u8]>
+where
+    R: Routable + ?Sized,
+{
+    let Some(cmd) = routable.command() else {
+        return Vec::new();
+    };
+    let keys_in_range = |start: usize, end: usize, step: usize| -> Vec<&[u8]> {
+        (start..end)
+            .step_by(step)
+            .map_while(|idx| routable.arg_idx(idx))
+            .collect()
+    };
+    let key_count = |idx: usize| {
+        routable
+            .arg_idx(idx)
+            .and_then(|x| std::str::from_utf8(x).ok())
+            .and_then(|x| x.parse::<usize>().ok())
+            .unwrap_or(0)
+    };
+
+    // Commands whose additional keys aren't needed for routing, since they must share the first key's slot.
+    match cmd.as_slice() {
+        b"RENAME" | b"RENAMENX" | b"COPY" | b"SMOVE" | b"LMOVE" | b"BLMOVE" | b"RPOPLPUSH"
+        | b"BRPOPLPUSH" | b"GEOSEARCHSTORE" | b"ZRANGESTORE" => return keys_in_range(1, 3, 1),
+        b"SDIFF" | b"SDIFFSTORE" | b"SINTER" | b"SINTERSTORE" | b"SUNION" | b"SUNIONSTORE"
+        | b"PFCOUNT" | b"PFMERGE" => return keys_in_range(1, usize::MAX, 1),
+        b"BITOP" => return keys_in_range(2, usize::MAX, 1),
+        b"ZDIFFSTORE" | b"ZINTERSTORE" | b"ZUNIONSTORE" => {
+            let mut keys = keys_in_range(1, 2, 1);
+            keys.extend(keys_in_range(3, key_count(2).saturating_add(3), 1));
+            return keys;
+        }
+        _ => {}
+    }
+
+    match base_routing(&cmd) {
+        RouteBy::AllNodes
+        | RouteBy::AllPrimaries
+        | RouteBy::Random
+        | RouteBy::SecondArgSlot
+        | RouteBy::Undefined => Vec::new(),
+        RouteBy::MultiShard(MultiSlotArgPattern::KeysOnly) => keys_in_range(1, usize::MAX, 1),
+        RouteBy::MultiShard(MultiSlotArgPattern::KeyValuePairs) => keys_in_range(1, usize::MAX, 2),
+        RouteBy::MultiShard(MultiSlotArgPattern::KeysAndLastArg) => {
+            let mut keys = keys_in_range(1, usize::MAX, 1);
+            // The last argument is the path.
+            keys.pop();
+            keys
+        }
+        RouteBy::MultiShard(MultiSlotArgPattern::KeyWithTwoArgTriples) => {
+            keys_in_range(1, usize::MAX, 3)
+        }
+        RouteBy::FirstKey => keys_in_range(1, 2, 1),
+        RouteBy::SecondArg => keys_in_range(2, 3, 1),
+        RouteBy::SecondArgAfterKeyCount => keys_in_range(2, key_count(1).saturating_add(2), 1),
+        RouteBy::ThirdArgAfterKeyCount => keys_in_range(3, key_count(2).saturating_add(3), 1),
+        RouteBy::StreamsIndex => {
+            let Some(streams_position) = routable.position(b"STREAMS") else {
+                return Vec::new();
+            };
+            // The keys are followed by the same number of IDs.
+            let mut keys = keys_in_range(streams_position + 1, usize::MAX, 1);
+            keys.truncate(keys.len() / 2);
+            keys
+        }
+    }
+}
+
+/// Returns the slot of the keys that `routable` declares with a key count, as EVAL, EVALSHA and FCALL do, or `None` if
+/// it isn't such a command, declares no keys, or its key count isn't a number.
+///
+/// Only the declared keys are taken into account, so the script's or function's other arguments are never mistaken for
+/// keys. Returns an error naming the keys if they hash to different slots, which the server would reject, or if the
+/// key count exceeds the number of arguments.
+pub fn declared_keys_slot<R>(routable: &R) -> RedisResult<Option<u16>>
+where
+    R: Routable + ?Sized,
+{
+    let Some(cmd) = routable.command() else {
+        return Ok(None);
+    };
+    let key_count_index = match base_routing(&cmd) {
+        RouteBy::SecondArgAfterKeyCount => 1,
+        RouteBy::ThirdArgAfterKeyCount => 2,
+        _ => return Ok(None),
+    };
+    let Some(key_count) = routable
+        .arg_idx(key_count_index)
+        .and_then(|x| std::str::from_utf8(x).ok())
+        .and_then(|x| x.parse::<usize>().ok())
+    else {
+        return Ok(None);
+    };
+    let keys: Vec<&[u8]> = (key_count_index + 1..)
+        .take(key_count)
+        .map_while(|idx| routable.arg_idx(idx))
+        .collect();
+    if keys.len() < key_count {
+        return Err(RedisError::from((
+            ErrorKind::ClientError,
+            "Invalid number of keys",
+            format!(
+                "{} declares {key_count} keys, but only {} arguments follow the key count",
+                String::from_utf8_lossy(&cmd),
+                keys.len()
+            ),
+        )));
+    }
+    let Some(first_key) = keys.first() else {
+        return Ok(None);
+    };
+    let slot = get_slot(first_key);
+    if keys.iter().all(|key| get_slot(key) == slot) {
+        return Ok(Some(slot));
+    }
+    let keys_and_slots: Vec<String> = keys
+        .iter()
+        .map(|key| {
+            format!(
+                "`{}` (slot {})",
+                String::from_utf8_lossy(key),
+                get_slot(key)
+            )
+        })
+        .collect();
+    Err(RedisError::from((
+        ErrorKind::CrossSlot,
+        "Keys don't hash to the same slot",
+        format!(
+            "the keys of {} must share a slot in cluster mode, but got {}",
+            String::from_utf8_lossy(&cmd),
+            keys_and_slots.join(", ")
+        ),
+    )))
+}
+
 /// Returns `true` if the given `cmd` is a readonly command.
 pub fn is_readonly_cmd(cmd: &[u8]) -> bool {
     matches!(
@@ -1108,20 +1249,42 @@ pub fn is_readonly_cmd(cmd: &[u8]) -> bool {
     )
 }
 
+/// Returns true if `command`, in uppercase, is a container of subcommands, whose name is made of the command and the
+/// subcommand, e.g. `CONFIG GET`.
+pub fn is_container_command(command: &[u8]) -> bool {
+    matches!(
+        command,
+        b"XGROUP"
+            | b"OBJECT"
+            | b"SLOWLOG"
+            | b"FUNCTION"
+            | b"MODULE"
+            | b"COMMAND"
+            | b"PUBSUB"
+            | b"CONFIG"
+            | b"MEMORY"
+            | b"XINFO"
+            | b"CLIENT"
+            | b"ACL"
+            | b"SCRIPT"
+            | b"CLUSTER"
+            | b"LATENCY"
+            | b"TFUNCTION"
+            | b"FT.CONFIG"
+            | b"FT.CURSOR"
+    )
+}
+
 /// Objects that implement this trait define a request that can be routed by a cluster client to different nodes in the cluster.
 pub trait Routable {
-    /// Convenience function to return ascii uppercase version of the
-    /// the first argument (i.e., the command).
+    /// Returns the name of the command in ASCII uppercase, regardless of the case it was sent in. The name of a
+    /// subcommand of a container command, such as `config get`, is made of both words, e.g. `CONFIG GET`. This is the
+    /// name that routing and reply conversion match on.
     fn command(&self) -> Option<Vec<u8>> {
-        let primary_command = self.arg_idx(0).map(|x| x.to_ascii_uppercase())?;
-        let mut primary_command = match primary_command.as_slice() {
-            b"XGROUP" | b"OBJECT" | b"SLOWLOG" | b"FUNCTION" | b"MODULE" | b"COMMAND"
-            | b"PUBSUB" | b"CONFIG" | b"MEMORY" | b"XINFO" | b"CLIENT" | b"ACL" | b"SCRIPT"
-            | b"CLUSTER" | b"LATENCY" => primary_command,
-            _ => {
-                return Some(primary_command);
-            }
-        };
+        let mut primary_command = self.arg_idx(0).map(|x| x.to_ascii_uppercase())?;
+        if !is_container_command(&primary_command) {
+            return Some(primary_command);
+        }
 
         Some(match self.arg_idx(1) {
             Some(secondary_command) => {
@@ -1427,8 +1590,9 @@ fn random_slot() -> u16 {
 #[cfg(test)]
 mod tests_routing {
     use super::{
-        command_for_multi_slot_indices, AggregateOp, MultiSlotArgPattern, MultipleNodeRoutingInfo,
-        ResponsePolicy, Route, RoutingInfo, ShardAddrs, SingleNodeRoutingInfo, SlotAddr,
+        command_for_multi_slot_indices, command_keys, AggregateOp, MultiSlotArgPattern,
+        MultipleNodeRoutingInfo, ResponsePolicy, Route, RoutingInfo, ShardAddrs,
+        SingleNodeRoutingInfo, SlotAddr,
     };
     use crate::cluster_routing::ShardUpdateResult;
     use crate::{cluster_topology::slot, cmd, parser::parse_redis_value, Value};
@@ -1537,6 +1701,17 @@ mod tests_routing {
             )))
         );
 
+        // The users are read from a single node unless another route is given, so that the reply isn't a map of nodes.
+        for cmd in [
+            cmd("ACL LIST"),
+            cmd("ACL").arg("GETUSER").arg("user").clone(),
+        ] {
+            assert_eq!(
+                RoutingInfo::for_routable(&cmd),
+                Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random))
+            );
+        }
+
         assert_eq!(
             RoutingInfo::for_routable(&cmd("INFO")),
             Some(RoutingInfo::MultiNode((
@@ -1658,6 +1833,133 @@ mod tests_routing {
         }
     }
 
+    #[test]
+    fn test_command_name_is_case_insensitive() {
+        let command = |args: &[&str]| {
+            let mut cmd = cmd(args[0]);
+            for arg in &args[1..] {
+                cmd.arg(*arg);
+            }
+            cmd.command()
+        };
+        assert_eq!(command(&["hgetall", "key"]), Some(b"HGETALL".to_vec()));
+        assert_eq!(
+            command(&["Config", "get", "*"]),
+            Some(b"CONFIG GET".to_vec())
+        );
+        assert_eq!(
+            command(&["ft.config", "Get", "*"]),
+            Some(b"FT.CONFIG GET".to_vec())
+        );
+        assert_eq!(command(&["xinfo"]), Some(b"XINFO".to_vec()));
+        assert_eq!(command(&["get", "config"]), Some(b"GET".to_vec()));
+
+        let mut tfunction_list = cmd("tfunction");
+        tfunction_list.arg("list");
+        assert_eq!(
+            RoutingInfo::for_routable(&tfunction_list),
+            Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random))
+        );
+    }
+
+    #[test]
+    fn test_declared_keys_slot() {
+        let declared = |args: &[&str]| {
+            let mut cmd = cmd(args[0]);
+            for arg in &args[1..] {
+                cmd.arg(*arg);
+            }
+            declared_keys_slot(&cmd)
+        };
+        assert_eq!(
+            declared(&["EVAL", "script", "2", "{user}:a", "{user}:b", "arg"]).unwrap(),
+            Some(slot(b"user"))
+        );
+        // Arguments that follow the declared keys aren't keys, even if they hash to other slots.
+        assert_eq!(
+            declared(&["fcall", "func", "1", "key", "other", "args"]).unwrap(),
+            Some(slot(b"key"))
+        );
+        assert_eq!(declared(&["EVALSHA", "sha", "0", "arg"]).unwrap(), None);
+        assert_eq!(declared(&["GET", "key"]).unwrap(), None);
+
+        let err = declared(&["EVAL_RO", "script", "2", "foo", "bar"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::CrossSlot);
+        let detail = err.detail().unwrap();
+        assert!(
+            detail.contains("`foo`") && detail.contains("`bar`"),
+            "{detail}"
+        );
+
+        let err = declared(&["FCALL_RO", "func", "3", "key"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ClientError);
+    }
+
+    #[test]
+    fn test_command_keys() {
+        let keys = |args: &[&str]| -> Vec<Vec<u8>> {
+            let mut command = cmd(args[0]);
+            for arg in &args[1..] {
+                command.arg(*arg);
+            }
+            command_keys(&command)
+                .into_iter()
+                .map(|key| key.to_vec())
+                .collect()
+        };
+        let expected = |keys: &[&str]| -> Vec<Vec<u8>> {
+            keys.iter().map(|key| key.as_bytes().to_vec()).collect()
+        };
+
+        assert_eq!(keys(&["GET", "foo"]), expected(&["foo"]));
+        assert_eq!(keys(&["set", "foo", "bar"]), expected(&["foo"]));
+        assert_eq!(keys(&["MGET", "foo", "bar"]), expected(&["foo", "bar"]));
+        assert_eq!(
+            keys(&["MSET", "foo", "1", "bar", "2"]),
+            expected(&["foo", "bar"])
+        );
+        assert_eq!(
+            keys(&["JSON.MGET", "foo", "bar", "$.a"]),
+            expected(&["foo", "bar"])
+        );
+        assert_eq!(
+            keys(&["EVAL", "script", "2", "foo", "bar", "arg"]),
+            expected(&["foo", "bar"])
+        );
+        assert_eq!(
+            keys(&["ZUNION", "2", "foo", "bar", "WITHSCORES"]),
+            expected(&["foo", "bar"])
+        );
+        assert_eq!(
+            keys(&["XREAD", "COUNT", "2", "STREAMS", "foo", "bar", "0", "0"]),
+            expected(&["foo", "bar"])
+        );
+        assert_eq!(
+            keys(&["XINFO", "STREAM", "foo", "FULL"]),
+            expected(&["foo"])
+        );
+        assert_eq!(keys(&["RENAME", "foo", "bar"]), expected(&["foo", "bar"]));
+        assert_eq!(
+            keys(&[
+                "ZUNIONSTORE",
+                "dest",
+                "2",
+                "foo",
+                "bar",
+                "WEIGHTS",
+                "1",
+                "2"
+            ]),
+            expected(&["dest", "foo", "bar"])
+        );
+        assert_eq!(
+            keys(&["BITOP", "AND", "dest", "foo", "bar"]),
+            expected(&["dest", "foo", "bar"])
+        );
+        assert!(keys(&["PING"]).is_empty());
+        assert!(keys(&["CLIENT", "SETNAME", "name"]).is_empty());
+    }
+
     #[test]
     fn test_slot_for_packed_cmd() {
         assert!(matches!(RoutingInfo::for_routable(&parse_redis_value(&[
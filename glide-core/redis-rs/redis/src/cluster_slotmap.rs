@@ -1,11 +1,14 @@
 use std::sync::Arc;
 use std::{
-    collections::{BTreeMap, HashSet},
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap, HashSet},
     fmt::Display,
     sync::atomic::AtomicUsize,
 };
 
+use arc_swap::ArcSwap;
 use dashmap::DashMap;
+use rand::Rng;
 
 use crate::cluster_routing::{Route, ShardAddrs, Slot, SlotAddr};
 use crate::ErrorKind;
@@ -34,6 +37,80 @@ pub enum ReadFromReplicaStrategy {
     AZAffinity(String),
 }
 
+/// The weight of replicas that weren't assigned a weight in [`RoutingWeights`].
+pub const DEFAULT_ROUTING_WEIGHT: u32 = 100;
+
+/// Relative weights for choosing the replica that serves a read request, keyed by the node's address (`host:port`).
+///
+/// Replicas without an explicit weight get [`DEFAULT_ROUTING_WEIGHT`], so a canary replica with weight 1 that sits
+/// next to a replica with the default weight receives roughly 1% of the reads, and a replica with weight 0 receives none.
+/// The weights can be replaced at runtime, and are shared by all clones. Empty weights keep the regular strategy.
+#[derive(Debug, Default, Clone)]
+pub struct RoutingWeights(Arc<ArcSwap<HashMap<String, u32>>>);
+
+impl RoutingWeights {
+    /// Replaces the current weights.
+    pub fn set(&self, weights: HashMap<String, u32>) {
+        self.0.store(Arc::new(weights));
+    }
+
+    /// Returns true if no weights were assigned.
+    pub fn is_empty(&self) -> bool {
+        self.0.load().is_empty()
+    }
+
+    /// Returns the weight of the node at `address`.
+    pub fn weight(&self, address: &str) -> u32 {
+        self.0
+            .load()
+            .get(address)
+            .copied()
+            .unwrap_or(DEFAULT_ROUTING_WEIGHT)
+    }
+
+    /// Chooses one of the `candidates` randomly, in proportion to the weights of their addresses.
+    /// Returns the chosen candidate with its weight, or `None` if all of the candidates have weight 0.
+    /// The candidates are iterated twice rather than collected, since a candidate is chosen for every read.
+    pub fn choose<'a, T, I>(&self, candidates: I) -> Option<(T, u32)>
+    where
+        I: IntoIterator<Item = (&'a str, T)>,
+        I::IntoIter: Clone,
+    {
+        let weights = self.0.load();
+        let weighted = candidates.into_iter().filter_map(|(address, candidate)| {
+            let weight = weights
+                .get(address)
+                .copied()
+                .unwrap_or(DEFAULT_ROUTING_WEIGHT);
+            (weight > 0).then_some((candidate, weight))
+        });
+        let total: u64 = weighted.clone().map(|(_, weight)| weight as u64).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut point = rand::thread_rng().gen_range(0..total);
+        let mut last = None;
+        for (candidate, weight) in weighted {
+            if point < weight as u64 {
+                return Some((candidate, weight));
+            }
+            point -= weight as u64;
+            last = Some((candidate, weight));
+        }
+        // The candidates changed between the iterations, e.g. a node disconnected.
+        last
+    }
+
+    /// Returns the name of the class that `weight` belongs to, which is used to segment the routing metrics.
+    pub fn weight_class(weight: u32) -> &'static str {
+        match weight.cmp(&DEFAULT_ROUTING_WEIGHT) {
+            Ordering::Less => "canary",
+            Ordering::Equal => "default",
+            Ordering::Greater => "preferred",
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 pub(crate) struct SlotMap {
     pub(crate) slots: BTreeMap<u16, SlotMapValue>,
@@ -145,6 +222,19 @@ impl SlotMap {
         })
     }
 
+    /// Returns the address of the primary that serves `route`, without advancing the round robin of the replicas, or
+    /// `None` if the route may be served by one of the replicas.
+    pub(crate) fn primary_for_route(&self, route: &Route) -> Option<Arc<String>> {
+        let slot_value = self.slot_value_for_route(route)?;
+        let may_read_from_replica = route.slot_addr() != SlotAddr::Master
+            && !matches!(
+                self.read_from_replica,
+                ReadFromReplicaStrategy::AlwaysFromPrimary
+            )
+            && !slot_value.addrs.replicas().is_empty();
+        (!may_read_from_replica).then(|| slot_value.addrs.primary())
+    }
+
     /// Retrieves the shard addresses (`ShardAddrs`) for the specified `slot` by looking it up in the `slots` tree,
     /// returning a reference to the stored shard addresses if found.
     pub(crate) fn shard_addrs_for_slot(&self, slot: u16) -> Option<Arc<ShardAddrs>> {
@@ -1138,4 +1228,29 @@ mod tests_cluster_slotmap {
         ];
         assert_slot_map_and_shard_addrs(slot_map, min_slot, new_shard_addrs, after_slots);
     }
+
+    #[test]
+    fn test_routing_weights_choose() {
+        let weights = RoutingWeights::default();
+        assert!(weights.is_empty());
+        weights.set(HashMap::from([
+            ("canary:6379".to_string(), 0),
+            ("preferred:6379".to_string(), 200),
+        ]));
+        assert_eq!(weights.weight("canary:6379"), 0);
+        assert_eq!(weights.weight("other:6379"), DEFAULT_ROUTING_WEIGHT);
+
+        for _ in 0..100 {
+            let (chosen, weight) = weights
+                .choose([("canary:6379", 1), ("preferred:6379", 2)])
+                .unwrap();
+            assert_eq!(chosen, 2);
+            assert_eq!(RoutingWeights::weight_class(weight), "preferred");
+        }
+        assert!(weights.choose([("canary:6379", 1)]).is_none());
+
+        // weights are shared between clones
+        weights.clone().set(HashMap::new());
+        assert!(weights.is_empty());
+    }
 }
@@ -84,6 +84,65 @@ fn get_hashtag(key: &[u8]) -> Option<&[u8]> {
     (!rv.is_empty()).then_some(rv)
 }
 
+/// A range of slots whose owner is set by the user, instead of being read from the cluster.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PinnedSlots {
+    /// The first slot of the range.
+    pub start: u16,
+    /// The last slot of the range, inclusive.
+    pub end: u16,
+    /// The address of the node that the slots are routed to, as `host:port`.
+    pub address: String,
+}
+
+impl PinnedSlots {
+    pub(crate) fn contains(&self, slot: u16) -> bool {
+        self.start <= slot && slot <= self.end
+    }
+}
+
+/// Replaces the owners of the pinned ranges in `slots`. Discovered ranges that partially overlap a pinned range are
+/// cut around it, and the pinned ranges are routed to their node alone, without replicas.
+pub(crate) fn pin_slots(slots: Vec<Slot>, pinned_slots: &[PinnedSlots]) -> Vec<Slot> {
+    if pinned_slots.is_empty() {
+        return slots;
+    }
+    let mut result = Vec::with_capacity(slots.len() + pinned_slots.len());
+    for slot in slots {
+        let mut remaining = vec![(slot.start, slot.end)];
+        for pinned in pinned_slots {
+            remaining = remaining
+                .into_iter()
+                .flat_map(|(start, end)| {
+                    if pinned.end < start || pinned.start > end {
+                        return vec![(start, end)];
+                    }
+                    let mut pieces = Vec::with_capacity(2);
+                    if pinned.start > start {
+                        pieces.push((start, pinned.start - 1));
+                    }
+                    if pinned.end < end {
+                        pieces.push((pinned.end + 1, end));
+                    }
+                    pieces
+                })
+                .collect();
+        }
+        result.extend(
+            remaining.into_iter().map(|(start, end)| {
+                Slot::new(start, end, slot.master.clone(), slot.replicas.clone())
+            }),
+        );
+    }
+    result.extend(
+        pinned_slots
+            .iter()
+            .map(|pinned| Slot::new(pinned.start, pinned.end, pinned.address.clone(), vec![])),
+    );
+    result.sort_by_key(|slot| slot.start);
+    result
+}
+
 /// Returns the slot that matches `key`.
 pub fn get_slot(key: &[u8]) -> u16 {
     let key = match get_hashtag(key) {
@@ -190,6 +249,119 @@ pub(crate) fn parse_and_count_slots(
     Ok((count, slots))
 }
 
+/// The command that the cluster's topology was read with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TopologySource {
+    /// `CLUSTER SHARDS`, available since Redis 7.0, which reports the role and health of each node.
+    ClusterShards,
+    /// `CLUSTER SLOTS`, which servers that don't support `CLUSTER SHARDS` fall back to.
+    ClusterSlots,
+}
+
+impl TopologySource {
+    /// Returns the name of the command.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TopologySource::ClusterShards => "CLUSTER SHARDS",
+            TopologySource::ClusterSlots => "CLUSTER SLOTS",
+        }
+    }
+}
+
+/// Returns the fields of a `CLUSTER SHARDS` element, which is a map in RESP3 and a flat array of field names and
+/// values in RESP2.
+fn shard_fields(value: &Value) -> Option<HashMap<&[u8], &Value>> {
+    let as_name = |name: &Value| match name {
+        Value::BulkString(name) => Some(name.as_slice()),
+        Value::SimpleString(name) => Some(name.as_bytes()),
+        _ => None,
+    };
+    match value {
+        Value::Map(pairs) => pairs
+            .iter()
+            .map(|(name, value)| Some((as_name(name)?, value)))
+            .collect(),
+        Value::Array(items) if items.len() % 2 == 0 => items
+            .chunks(2)
+            .map(|pair| Some((as_name(&pair[0])?, &pair[1])))
+            .collect(),
+        _ => None,
+    }
+}
+
+/// Converts a `CLUSTER SHARDS` reply to the layout of a `CLUSTER SLOTS` reply, so that both are parsed by
+/// [`parse_and_count_slots`]. Like `CLUSTER SLOTS`, each shard's primary is listed first, and replicas are only listed
+/// if they're online. Returns `None` if the reply isn't a valid `CLUSTER SHARDS` reply.
+pub(crate) fn shards_to_slots_view(shards: &Value, tls: Option<TlsMode>) -> Option<Value> {
+    let Value::Array(shards) = shards else {
+        return None;
+    };
+    let text = |value: &Value| match value {
+        Value::BulkString(text) => Some(String::from_utf8_lossy(text).into_owned()),
+        Value::SimpleString(text) => Some(text.clone()),
+        _ => None,
+    };
+    let mut slots_view = Vec::new();
+    for shard in shards {
+        let shard = shard_fields(shard)?;
+        let Value::Array(ranges) = shard.get(b"slots".as_slice())? else {
+            return None;
+        };
+        let Value::Array(nodes) = shard.get(b"nodes".as_slice())? else {
+            return None;
+        };
+        let mut primary = None;
+        let mut replicas = Vec::new();
+        for node in nodes {
+            let node = shard_fields(node)?;
+            let port = tls
+                .and_then(|_| node.get(b"tls-port".as_slice()))
+                .or_else(|| node.get(b"port".as_slice()));
+            let Some(Value::Int(port)) = port else {
+                continue;
+            };
+            // An empty or missing endpoint is replaced by the answering node's host while parsing, like in CLUSTER SLOTS.
+            let endpoint = node
+                .get(b"endpoint".as_slice())
+                .and_then(|endpoint| text(endpoint))
+                .map_or(Value::Nil, |endpoint| {
+                    Value::BulkString(endpoint.into_bytes())
+                });
+            let address = Value::Array(vec![endpoint, Value::Int(*port)]);
+            let role = node.get(b"role".as_slice()).and_then(|role| text(role));
+            let health = node
+                .get(b"health".as_slice())
+                .and_then(|health| text(health));
+            let online = health.as_deref() == Some("online");
+            match role.as_deref() {
+                // A failed primary is still listed as a primary until the failover that replaces it completes, so it
+                // only takes the shard's slots if no other primary does, and an online primary is preferred.
+                Some("master") if health.as_deref() != Some("fail") => {
+                    if online || primary.is_none() {
+                        primary = Some(address);
+                    }
+                }
+                Some("master") => {}
+                _ if online => replicas.push(address),
+                _ => {}
+            }
+        }
+        // A shard without a primary that didn't fail, e.g. one that has no slots, is skipped like in CLUSTER SLOTS.
+        let Some(primary) = primary else {
+            continue;
+        };
+        for range in ranges.chunks(2) {
+            let [Value::Int(start), Value::Int(end)] = range else {
+                return None;
+            };
+            let mut slot = vec![Value::Int(*start), Value::Int(*end), primary.clone()];
+            slot.extend(replicas.iter().cloned());
+            slots_view.push(Value::Array(slot));
+        }
+    }
+    Some(Value::Array(slots_view))
+}
+
 fn calculate_hash<T: Hash>(t: &T) -> u64 {
     let mut s = DefaultHasher::new();
     t.hash(&mut s);
@@ -202,6 +374,7 @@ pub(crate) fn calculate_topology<'a>(
     tls_mode: Option<TlsMode>,
     num_of_queried_nodes: usize,
     read_from_replica: ReadFromReplicaStrategy,
+    pinned_slots: &[PinnedSlots],
 ) -> RedisResult<(SlotMap, TopologyHash)> {
     let mut hash_view_map = HashMap::new();
     for (host, view) in topology_views {
@@ -255,7 +428,7 @@ pub(crate) fn calculate_topology<'a>(
             "calculate_topology found topology map:\n{:?}",
             most_frequent_topology
         );
-        let slots_data = most_frequent_topology.slots_and_count.1;
+        let slots_data = pin_slots(most_frequent_topology.slots_and_count.1, pinned_slots);
         Ok((
             SlotMap::new(slots_data, read_from_replica),
             most_frequent_topology.hash_value,
@@ -318,6 +491,145 @@ mod tests {
         slot_value_with_replicas(start, end, vec![(node, port)])
     }
 
+    fn shard_node(fields: &[(&str, Value)]) -> Value {
+        Value::Array(
+            fields
+                .iter()
+                .flat_map(|(name, value)| {
+                    [Value::BulkString(name.as_bytes().to_vec()), value.clone()]
+                })
+                .collect(),
+        )
+    }
+
+    fn text(text: &str) -> Value {
+        Value::BulkString(text.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn shards_are_converted_to_the_slots_layout() {
+        let node = |endpoint: &str, port: i64, role: &str, health: &str| {
+            shard_node(&[
+                ("id", text("id")),
+                ("port", Value::Int(port)),
+                ("tls-port", Value::Int(port + 1000)),
+                ("endpoint", text(endpoint)),
+                ("role", text(role)),
+                ("replication-offset", Value::Int(0)),
+                ("health", text(health)),
+            ])
+        };
+        let shards = Value::Array(vec![
+            shard_node(&[
+                (
+                    "slots",
+                    Value::Array(vec![
+                        Value::Int(0),
+                        Value::Int(100),
+                        Value::Int(200),
+                        Value::Int(300),
+                    ]),
+                ),
+                (
+                    "nodes",
+                    Value::Array(vec![
+                        node("replica1", 6380, "replica", "online"),
+                        node("failed1", 6381, "replica", "fail"),
+                        node("primary1", 6379, "master", "online"),
+                    ]),
+                ),
+            ]),
+            // RESP3 replies hold maps.
+            Value::Map(vec![
+                (
+                    text("slots"),
+                    Value::Array(vec![Value::Int(101), Value::Int(199)]),
+                ),
+                (
+                    text("nodes"),
+                    Value::Array(vec![Value::Map(vec![
+                        (text("port"), Value::Int(6379)),
+                        (text("endpoint"), text("")),
+                        (text("role"), text("master")),
+                        (text("health"), text("online")),
+                    ])]),
+                ),
+            ]),
+        ]);
+
+        let address = |host: &str, port: i64| Value::Array(vec![text(host), Value::Int(port)]);
+        assert_eq!(
+            shards_to_slots_view(&shards, None),
+            Some(Value::Array(vec![
+                Value::Array(vec![
+                    Value::Int(0),
+                    Value::Int(100),
+                    address("primary1", 6379),
+                    address("replica1", 6380),
+                ]),
+                Value::Array(vec![
+                    Value::Int(200),
+                    Value::Int(300),
+                    address("primary1", 6379),
+                    address("replica1", 6380),
+                ]),
+                Value::Array(vec![Value::Int(101), Value::Int(199), address("", 6379)]),
+            ]))
+        );
+
+        let slots_view = shards_to_slots_view(&shards, Some(TlsMode::Secure)).unwrap();
+        let (count, slots) = parse_and_count_slots(&slots_view, None, "answering").unwrap();
+        assert_eq!(count, 100 + 100 + 98);
+        assert_eq!(slots[0].master(), "primary1:7379");
+        assert_eq!(slots[2].master(), "answering:6379");
+
+        assert_eq!(shards_to_slots_view(&Value::Okay, None), None);
+    }
+
+    #[test]
+    fn failed_primaries_dont_own_the_slots_of_their_shard() {
+        let node = |endpoint: &str, role: &str, health: &str| {
+            shard_node(&[
+                ("port", Value::Int(6379)),
+                ("endpoint", text(endpoint)),
+                ("role", text(role)),
+                ("health", text(health)),
+            ])
+        };
+        let shard = |nodes: Vec<Value>| {
+            shard_node(&[
+                ("slots", Value::Array(vec![Value::Int(0), Value::Int(100)])),
+                ("nodes", Value::Array(nodes)),
+            ])
+        };
+        let address = |host: &str| Value::Array(vec![text(host), Value::Int(6379)]);
+
+        let shards = Value::Array(vec![shard(vec![
+            node("failed", "master", "fail"),
+            node("loading", "master", "loading"),
+            node("promoted", "master", "online"),
+            node("replica", "replica", "online"),
+        ])]);
+        assert_eq!(
+            shards_to_slots_view(&shards, None),
+            Some(Value::Array(vec![Value::Array(vec![
+                Value::Int(0),
+                Value::Int(100),
+                address("promoted"),
+                address("replica"),
+            ])]))
+        );
+
+        let shards = Value::Array(vec![shard(vec![
+            node("failed", "master", "fail"),
+            node("replica", "replica", "online"),
+        ])]);
+        assert_eq!(
+            shards_to_slots_view(&shards, None),
+            Some(Value::Array(vec![]))
+        );
+    }
+
     #[test]
     fn parse_slots_with_different_replicas_order_returns_the_same_view() {
         let view1 = Value::Array(vec![
@@ -526,6 +838,7 @@ mod tests {
             None,
             queried_nodes,
             ReadFromReplicaStrategy::AlwaysFromPrimary,
+            &[],
         )
         .unwrap();
         let res = collect_shard_addrs(&topology_view);
@@ -549,6 +862,7 @@ mod tests {
             None,
             queried_nodes,
             ReadFromReplicaStrategy::AlwaysFromPrimary,
+            &[],
         );
         assert!(topology_view.is_err());
     }
@@ -568,6 +882,7 @@ mod tests {
             None,
             queried_nodes,
             ReadFromReplicaStrategy::AlwaysFromPrimary,
+            &[],
         )
         .unwrap();
         let res = collect_shard_addrs(&topology_view);
@@ -591,6 +906,7 @@ mod tests {
             None,
             queried_nodes,
             ReadFromReplicaStrategy::AlwaysFromPrimary,
+            &[],
         )
         .unwrap();
         let res = collect_shard_addrs(&topology_view);
@@ -615,6 +931,7 @@ mod tests {
             None,
             queried_nodes,
             ReadFromReplicaStrategy::AlwaysFromPrimary,
+            &[],
         )
         .unwrap();
         let res = collect_shard_addrs(&topology_view);
@@ -639,6 +956,7 @@ mod tests {
             None,
             queried_nodes,
             ReadFromReplicaStrategy::AlwaysFromPrimary,
+            &[],
         )
         .unwrap();
         let res = collect_shard_addrs(&topology_view);
@@ -646,4 +964,39 @@ mod tests {
         let expected = vec![node_1];
         assert_eq!(res, expected);
     }
+
+    #[test]
+    fn test_pinned_slots_override_the_discovered_owners() {
+        let slots = vec![
+            Slot::new(0, 8000, "node1:6379".into(), vec!["replica1:6379".into()]),
+            Slot::new(8001, 16383, "node2:6379".into(), vec![]),
+        ];
+        let pinned_slots = [
+            PinnedSlots {
+                start: 100,
+                end: 200,
+                address: "pinned:6379".into(),
+            },
+            PinnedSlots {
+                start: 7000,
+                end: 9000,
+                address: "pinned:6379".into(),
+            },
+        ];
+        let pinned = pin_slots(slots, &pinned_slots);
+        let ranges: Vec<(u16, u16, &str, usize)> = pinned
+            .iter()
+            .map(|slot| (slot.start, slot.end, slot.master(), slot.replicas.len()))
+            .collect();
+        assert_eq!(
+            ranges,
+            [
+                (0, 99, "node1:6379", 1),
+                (100, 200, "pinned:6379", 0),
+                (201, 6999, "node1:6379", 1),
+                (7000, 9000, "pinned:6379", 0),
+                (9001, 16383, "node2:6379", 0),
+            ]
+        );
+    }
 }
@@ -255,6 +255,13 @@ pub struct RedisConnectionInfo {
     pub client_name: Option<String>,
     /// Optionally a pubsub subscriptions that should be used for connection
     pub pubsub_subscriptions: Option<PubSubSubscriptionInfo>,
+    /// Whether to send `CLIENT NO-EVICT ON`, which protects the connection from eviction under memory pressure
+    pub no_evict: bool,
+    /// Whether to send `CLIENT NO-TOUCH ON`, so that the connection's commands don't affect the LRU/LFU of keys
+    pub no_touch: bool,
+    /// Optionally a scheme that authenticates async connections, instead of the username and password
+    #[cfg(feature = "aio")]
+    pub authenticator: Option<std::sync::Arc<dyn crate::aio::Authenticator>>,
 }
 
 impl FromStr for ConnectionInfo {
@@ -422,6 +429,10 @@ fn url_to_tcp_connection_info(url: url::Url) -> RedisResult<ConnectionInfo> {
             },
             client_name: None,
             pubsub_subscriptions: None,
+            no_evict: false,
+            no_touch: false,
+            #[cfg(feature = "aio")]
+            authenticator: None,
         },
     })
 }
@@ -455,6 +466,10 @@ fn url_to_unix_connection_info(url: url::Url) -> RedisResult<ConnectionInfo> {
             },
             client_name: None,
             pubsub_subscriptions: None,
+            no_evict: false,
+            no_touch: false,
+            #[cfg(feature = "aio")]
+            authenticator: None,
         },
     })
 }
@@ -706,7 +721,10 @@ impl ActualConnection {
                 let config = create_rustls_config(insecure, tls_params.clone())?;
                 let conn = rustls::ClientConnection::new(
                     Arc::new(config),
-                    rustls_pki_types::ServerName::try_from(host)?.to_owned(),
+                    rustls_pki_types::ServerName::try_from(TlsConnParams::server_name(
+                        tls_params, host,
+                    ))?
+                    .to_owned(),
                 )?;
                 let reader = match timeout {
                     None => {
@@ -1019,6 +1037,23 @@ pub(crate) fn client_set_info_pipeline() -> Pipeline {
     pipeline
 }
 
+/// The `CLIENT NO-EVICT ON` and `CLIENT NO-TOUCH ON` commands that `connection_info` asks for, with the flag that each
+/// of them sets.
+pub(crate) fn client_flag_cmds(connection_info: &RedisConnectionInfo) -> Vec<(&'static str, Cmd)> {
+    [
+        ("NO-EVICT", connection_info.no_evict),
+        ("NO-TOUCH", connection_info.no_touch),
+    ]
+    .into_iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(flag, _)| {
+        let mut client_flag_cmd = cmd("CLIENT");
+        client_flag_cmd.arg(flag).arg("ON");
+        (flag, client_flag_cmd)
+    })
+    .collect()
+}
+
 fn setup_connection(
     con: ActualConnection,
     connection_info: &RedisConnectionInfo,
@@ -1068,6 +1103,17 @@ fn setup_connection(
         }
     }
 
+    for (flag, client_flag_cmd) in client_flag_cmds(connection_info) {
+        match client_flag_cmd.query::<Value>(&mut rv) {
+            Ok(Value::Okay) => {}
+            _ => fail!((
+                ErrorKind::ResponseError,
+                "Redis server refused to set client flag",
+                flag.to_string()
+            )),
+        }
+    }
+
     // result is ignored, as per the command's instructions.
     // https://redis.io/commands/client-setinfo/
     #[cfg(not(feature = "disable-client-setinfo"))]
@@ -1928,6 +1974,10 @@ mod tests {
                         protocol: ProtocolVersion::RESP2,
                         client_name: None,
                         pubsub_subscriptions: None,
+                        no_evict: false,
+                        no_touch: false,
+                        #[cfg(feature = "aio")]
+                        authenticator: None,
                     },
                 },
             ),
@@ -0,0 +1,233 @@
+//! Process-wide damping of connection attempts.
+//!
+//! When a process hosts many clients of the same endpoints, a server restart makes all of them reconnect at once. If a
+//! rate limit is set, the attempts of all the clients to an endpoint share a token bucket, so the endpoint receives a
+//! bounded rate of attempts from the process. Attempts beyond the burst aren't failed, but delayed until their turn.
+//! The limit applies to the connections of cluster clients, and to the connections and reconnections of standalone
+//! clients.
+
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use telemetrylib::Telemetry;
+
+/// The rate of connection attempts that each endpoint may receive from the process.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConnectionRateLimit {
+    /// The number of attempts that may be made at once, after the endpoint received no attempts for a while.
+    pub burst: u32,
+    /// The number of attempts per second that may be made once the burst was used.
+    pub attempts_per_second: u32,
+}
+
+struct Bucket {
+    /// Negative when attempts are waiting for tokens, which are then owed in the order the attempts were made.
+    tokens: f64,
+    last_refill: Instant,
+}
+
+#[derive(Default)]
+struct Throttle {
+    limit: Option<ConnectionRateLimit>,
+    // The number of live requests of the limit, see [`request_connection_rate_limit`]. 0 if the limit was set through
+    // [`set_connection_rate_limit`].
+    requests: usize,
+    buckets: HashMap<String, Bucket>,
+}
+
+lazy_static! {
+    static ref THROTTLE: Mutex<Throttle> = Mutex::new(Throttle::default());
+}
+
+/// Sets the rate of connection attempts per endpoint. `None`, the default, removes the limit.
+pub fn set_connection_rate_limit(limit: Option<ConnectionRateLimit>) {
+    let mut throttle = THROTTLE.lock().unwrap();
+    throttle.limit = limit;
+    throttle.requests = 0;
+    throttle.buckets.clear();
+}
+
+/// Keeps a rate limit that was requested by a client, see [`request_connection_rate_limit`].
+#[derive(Debug)]
+#[must_use]
+pub struct ConnectionRateLimitRequest {
+    counted: bool,
+}
+
+impl Drop for ConnectionRateLimitRequest {
+    fn drop(&mut self) {
+        let mut throttle = THROTTLE.lock().unwrap();
+        if !self.counted || throttle.requests == 0 {
+            return;
+        }
+        throttle.requests -= 1;
+        if throttle.requests == 0 {
+            throttle.limit = None;
+            throttle.buckets.clear();
+        }
+    }
+}
+
+/// Sets the rate limit requested by a client's configuration, until the returned request is dropped along with all
+/// the other requests of the limit. Since the limit is shared by all the clients in the process, a limit that
+/// conflicts with the one that is already set is refused, and the current limit is returned. A limit that was set
+/// through [`set_connection_rate_limit`] is kept until it's changed there.
+pub fn request_connection_rate_limit(
+    limit: ConnectionRateLimit,
+) -> Result<ConnectionRateLimitRequest, ConnectionRateLimit> {
+    let mut throttle = THROTTLE.lock().unwrap();
+    match throttle.limit {
+        None => {
+            throttle.limit = Some(limit);
+            throttle.requests = 1;
+            throttle.buckets.clear();
+            Ok(ConnectionRateLimitRequest { counted: true })
+        }
+        Some(current) if current == limit => {
+            let counted = throttle.requests > 0;
+            if counted {
+                throttle.requests += 1;
+            }
+            Ok(ConnectionRateLimitRequest { counted })
+        }
+        Some(current) => Err(current),
+    }
+}
+
+/// Returns the rate of connection attempts per endpoint, if one was set.
+pub fn connection_rate_limit() -> Option<ConnectionRateLimit> {
+    THROTTLE.lock().unwrap().limit
+}
+
+/// Takes a token from the bucket of `endpoint`, and returns how long the attempt has to wait for it.
+fn reserve(endpoint: &str, now: Instant) -> Duration {
+    let mut throttle = THROTTLE.lock().unwrap();
+    let Some(limit) = throttle.limit else {
+        return Duration::ZERO;
+    };
+    let burst = limit.burst.max(1) as f64;
+    let rate = limit.attempts_per_second.max(1) as f64;
+    // A bucket that was refilled is the same as a new one, so it's dropped rather than kept for every endpoint that
+    // was ever connected to.
+    throttle.buckets.retain(|_, bucket| {
+        let elapsed = now.saturating_duration_since(bucket.last_refill);
+        bucket.tokens + elapsed.as_secs_f64() * rate < burst
+    });
+    let bucket = throttle
+        .buckets
+        .entry(endpoint.to_string())
+        .or_insert(Bucket {
+            tokens: burst,
+            last_refill: now,
+        });
+    let elapsed = now.saturating_duration_since(bucket.last_refill);
+    bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * rate).min(burst);
+    bucket.last_refill = now.max(bucket.last_refill);
+    bucket.tokens -= 1.0;
+    if bucket.tokens >= 0.0 {
+        Duration::ZERO
+    } else {
+        Duration::from_secs_f64(-bucket.tokens / rate)
+    }
+}
+
+/// Waits until an attempt to connect to `endpoint` is allowed by the rate limit. Returns immediately if no limit was
+/// set. Delayed attempts are counted in the telemetry.
+pub async fn wait_for_connection_attempt(endpoint: &str) {
+    let delay = reserve(endpoint, Instant::now());
+    if delay.is_zero() {
+        return;
+    }
+    Telemetry::incr_throttled_connection_attempts();
+    tokio::time::sleep(delay).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_attempts_beyond_the_burst_wait_their_turn() {
+        set_connection_rate_limit(Some(ConnectionRateLimit {
+            burst: 2,
+            attempts_per_second: 10,
+        }));
+        let start = Instant::now();
+        let delays: Vec<Duration> = (0..4).map(|_| reserve("host:6379", start)).collect();
+        assert_eq!(
+            delays,
+            [
+                Duration::ZERO,
+                Duration::ZERO,
+                Duration::from_millis(100),
+                Duration::from_millis(200)
+            ]
+        );
+        // Other endpoints have their own buckets.
+        assert_eq!(reserve("other:6379", start), Duration::ZERO);
+        // The owed tokens are refilled first.
+        assert_eq!(
+            reserve("host:6379", start + Duration::from_millis(200)),
+            Duration::from_millis(100)
+        );
+        assert_eq!(
+            reserve("host:6379", start + Duration::from_secs(10)),
+            Duration::ZERO
+        );
+
+        set_connection_rate_limit(None);
+        assert_eq!(reserve("host:6379", start), Duration::ZERO);
+    }
+
+    #[test]
+    #[serial]
+    fn test_refilled_buckets_are_dropped() {
+        set_connection_rate_limit(Some(ConnectionRateLimit {
+            burst: 1,
+            attempts_per_second: 10,
+        }));
+        let start = Instant::now();
+        reserve("host:6379", start);
+        reserve("other:6379", start);
+        assert_eq!(THROTTLE.lock().unwrap().buckets.len(), 2);
+
+        reserve("host:6379", start + Duration::from_secs(1));
+        assert_eq!(THROTTLE.lock().unwrap().buckets.len(), 1);
+
+        set_connection_rate_limit(None);
+    }
+
+    #[test]
+    #[serial]
+    fn test_requested_limit_is_removed_with_its_last_request() {
+        set_connection_rate_limit(None);
+        let limit = ConnectionRateLimit {
+            burst: 1,
+            attempts_per_second: 10,
+        };
+        let first = request_connection_rate_limit(limit).unwrap();
+        let second = request_connection_rate_limit(limit).unwrap();
+        let conflicting = ConnectionRateLimit {
+            burst: 2,
+            attempts_per_second: 10,
+        };
+        assert_eq!(
+            request_connection_rate_limit(conflicting).unwrap_err(),
+            limit
+        );
+
+        drop(first);
+        assert_eq!(connection_rate_limit(), Some(limit));
+        drop(second);
+        assert_eq!(connection_rate_limit(), None);
+
+        // A limit that was set explicitly outlives the requests.
+        set_connection_rate_limit(Some(limit));
+        drop(request_connection_rate_limit(limit).unwrap());
+        assert_eq!(connection_rate_limit(), Some(limit));
+        set_connection_rate_limit(None);
+    }
+}
@@ -376,7 +376,9 @@ pub use crate::connection::{
 };
 pub use crate::parser::{parse_redis_value, Parser};
 pub use crate::pipeline::Pipeline;
-pub use push_manager::{PushInfo, PushManager};
+pub use push_manager::{panic_message, PushInfo, PushManager};
+#[cfg(feature = "cluster-async")]
+pub use push_manager::{run_supervised, PANICKED_TASK_RESTART_DELAY};
 
 #[cfg(feature = "script")]
 #[cfg_attr(docsrs, doc(cfg(feature = "script")))]
@@ -424,6 +426,12 @@ pub use crate::{
 mod macros;
 mod pipeline;
 
+/// Approximate per-command and per-node byte counters.
+pub mod byte_counters;
+
+/// Process-wide rate limiting of connection attempts per endpoint.
+pub mod connection_throttle;
+
 #[cfg(feature = "acl")]
 #[cfg_attr(docsrs, doc(cfg(feature = "acl")))]
 pub mod acl;
@@ -498,7 +506,7 @@ pub mod sentinel;
 mod tls;
 
 #[cfg(feature = "tls-rustls")]
-pub use crate::tls::{ClientTlsConfig, TlsCertificates};
+pub use crate::tls::{ClientTlsConfig, TlsCertificates, TlsConnParams};
 
 mod client;
 mod cmd;
@@ -78,7 +78,7 @@ where
     opaque!(any_send_sync_partial_state(
         any()
             .then_partial(move |&mut b| {
-                if b == b'*' && count > MAX_RECURSE_DEPTH {
+                if matches!(b, b'*' | b'%' | b'|' | b'~' | b'>') && count > MAX_RECURSE_DEPTH {
                     combine::unexpected_any("Maximum recursion depth exceeded").left()
                 } else {
                     combine::value(b).right()
@@ -150,9 +150,14 @@ where
                 let error = || line().map(err_parser);
                 let map = || {
                     int().then_partial(move |&mut kv_length| {
-                        let length = kv_length as usize * 2;
-                        combine::count_min_max(length, length, value(Some(count + 1))).map(
-                            move |result: Vec<InternalValue>| {
+                        let Some(length) = usize::try_from(kv_length)
+                            .ok()
+                            .and_then(|kv_length| kv_length.checked_mul(2))
+                        else {
+                            return combine::unexpected_any("Invalid map length").left();
+                        };
+                        combine::count_min_max(length, length, value(Some(count + 1)))
+                            .map(move |result: Vec<InternalValue>| {
                                 let mut it = result.into_iter();
                                 let mut x = vec![];
                                 for _ in 0..kv_length {
@@ -161,16 +166,21 @@ where
                                     }
                                 }
                                 InternalValue::Map(x)
-                            },
-                        )
+                            })
+                            .right()
                     })
                 };
                 let attribute = || {
                     int().then_partial(move |&mut kv_length| {
                         // + 1 is for data!
-                        let length = kv_length as usize * 2 + 1;
-                        combine::count_min_max(length, length, value(Some(count + 1))).map(
-                            move |result: Vec<InternalValue>| {
+                        let Some(length) = usize::try_from(kv_length)
+                            .ok()
+                            .and_then(|kv_length| kv_length.checked_mul(2)?.checked_add(1))
+                        else {
+                            return combine::unexpected_any("Invalid attribute length").left();
+                        };
+                        combine::count_min_max(length, length, value(Some(count + 1)))
+                            .map(move |result: Vec<InternalValue>| {
                                 let mut it = result.into_iter();
                                 let mut attributes = vec![];
                                 for _ in 0..kv_length {
@@ -179,11 +189,11 @@ where
                                     }
                                 }
                                 InternalValue::Attribute {
-                                    data: Box::new(it.next().unwrap()),
+                                    data: Box::new(it.next().unwrap_or(InternalValue::Nil)),
                                     attributes,
                                 }
-                            },
-                        )
+                            })
+                            .right()
                     })
                 };
                 let set = || {
@@ -655,4 +665,35 @@ mod tests {
             Err(e) => assert!(matches!(e.kind(), ErrorKind::ParseError)),
         }
     }
+
+    #[test]
+    fn test_max_recursion_depth_of_resp3_aggregates() {
+        for marker in [
+            "%1\r\n+key\r\n",
+            "~1\r\n",
+            ">2\r\n+message\r\n",
+            "|1\r\n+key\r\n+value\r\n",
+        ] {
+            let bytes = marker.repeat(MAX_RECURSE_DEPTH + 10);
+            match parse_redis_value(bytes.as_bytes()) {
+                Ok(_) => panic!("Expected Err"),
+                Err(e) => assert!(matches!(e.kind(), ErrorKind::ParseError), "{marker}: {e}"),
+            }
+        }
+    }
+
+    #[test]
+    fn decode_invalid_aggregate_lengths() {
+        for bytes in [
+            &b"%-1\r\n"[..],
+            b"|-1\r\n+data\r\n",
+            b"%9223372036854775807\r\n",
+            b"|9223372036854775807\r\n",
+        ] {
+            match parse_redis_value(bytes) {
+                Ok(value) => panic!("Expected Err, got {value:?}"),
+                Err(e) => assert!(matches!(e.kind(), ErrorKind::ParseError), "{e}"),
+            }
+        }
+    }
 }
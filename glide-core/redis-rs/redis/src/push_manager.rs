@@ -1,6 +1,13 @@
 use crate::{PushKind, RedisResult, Value};
 use arc_swap::ArcSwap;
+use std::any::Any;
+#[cfg(feature = "cluster-async")]
+use std::future::Future;
+#[cfg(feature = "cluster-async")]
+use std::panic::AssertUnwindSafe;
 use std::sync::Arc;
+#[cfg(feature = "cluster-async")]
+use std::time::Duration;
 use tokio::sync::mpsc;
 
 /// Holds information about received Push data
@@ -10,12 +17,77 @@ pub struct PushInfo {
     pub kind: PushKind,
     /// Data from push message
     pub data: Vec<Value>,
+    /// The address of the node whose connection received the push, if it's known.
+    pub address: Option<String>,
+}
+
+impl PushInfo {
+    /// Creates the notification that is sent when the background task `task_name` panicked.
+    pub fn fatal_client_error(task_name: &str, panic_payload: &(dyn Any + Send)) -> Self {
+        PushInfo {
+            kind: PushKind::FatalClientError,
+            data: vec![
+                Value::SimpleString(task_name.to_string()),
+                Value::SimpleString(panic_message(panic_payload)),
+            ],
+            address: None,
+        }
+    }
+}
+
+/// Extracts the message from the payload of a caught panic.
+pub fn panic_message(panic_payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = panic_payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic_payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// The delay before restarting a background task that panicked, so that a task that keeps panicking won't spin.
+#[cfg(feature = "cluster-async")]
+pub const PANICKED_TASK_RESTART_DELAY: Duration = Duration::from_secs(1);
+
+/// Runs the background task created by `make_task`, and recreates it if it panics, until it returns.
+/// Each panic is logged and passed to `report_panic` as a `FatalClientError` notification, and the task is restarted
+/// after [`PANICKED_TASK_RESTART_DELAY`].
+///
+/// Only use this for tasks that can safely be restarted from scratch.
+#[cfg(feature = "cluster-async")]
+pub async fn run_supervised<F, Fut, R, RFut>(
+    task_name: &'static str,
+    mut make_task: F,
+    report_panic: R,
+) where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+    R: Fn(PushInfo) -> RFut,
+    RFut: Future<Output = ()>,
+{
+    use futures::FutureExt;
+
+    loop {
+        let Err(panic_payload) = AssertUnwindSafe(make_task()).catch_unwind().await else {
+            return;
+        };
+        tracing::warn!(
+            "Background task `{task_name}` panicked, restarting it: {}",
+            panic_message(panic_payload.as_ref())
+        );
+        let push_info = PushInfo::fatal_client_error(task_name, panic_payload.as_ref());
+        drop(panic_payload);
+        report_panic(push_info).await;
+        tokio::time::sleep(PANICKED_TASK_RESTART_DELAY).await;
+    }
 }
 
 /// Manages Push messages for single tokio channel
 #[derive(Clone, Default)]
 pub struct PushManager {
     sender: Arc<ArcSwap<Option<mpsc::UnboundedSender<PushInfo>>>>,
+    address: Option<String>,
 }
 impl PushManager {
     /// It checks if value's type is Push
@@ -35,6 +107,7 @@ impl PushManager {
                 let push_info = PushInfo {
                     kind: kind.clone(),
                     data: data.clone(),
+                    address: self.address.clone(),
                 };
                 if sender.send(push_info).is_err() {
                     self.sender.compare_and_swap(guard, Arc::new(None));
@@ -51,8 +124,15 @@ impl PushManager {
     pub fn new() -> Self {
         PushManager {
             sender: Arc::from(ArcSwap::from(Arc::new(None))),
+            address: None,
         }
     }
+
+    /// Marks the pushes that are sent by this `PushManager` as received from the node at `address`.
+    pub fn with_address(mut self, address: String) -> Self {
+        self.address = Some(address);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -80,6 +160,35 @@ mod tests {
         );
     }
     #[test]
+    fn test_fatal_client_error_from_panic_payload() {
+        let payload = std::panic::catch_unwind(|| panic!("boom {}", 1)).unwrap_err();
+        let push_info = PushInfo::fatal_client_error("heartbeat", payload.as_ref());
+        assert_eq!(push_info.kind, PushKind::FatalClientError);
+        assert_eq!(
+            push_info.data,
+            vec![
+                Value::SimpleString("heartbeat".to_string()),
+                Value::SimpleString("boom 1".to_string())
+            ]
+        );
+    }
+    #[test]
+    fn test_push_info_carries_the_address_of_the_node() {
+        let push_manager = PushManager::new().with_address("127.0.0.1:6379".to_string());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        push_manager.replace_sender(tx);
+
+        push_manager.try_send_raw(&Value::Push {
+            kind: PushKind::Disconnection,
+            data: vec![],
+        });
+
+        assert_eq!(
+            rx.try_recv().unwrap().address.as_deref(),
+            Some("127.0.0.1:6379")
+        );
+    }
+    #[test]
     fn test_push_manager_receiver_dropped() {
         let push_manager = PushManager::new();
         let (tx, rx) = mpsc::unbounded_channel();
@@ -110,6 +110,7 @@ pub(crate) fn retrieve_tls_certificates(
     Ok(TlsConnParams {
         client_tls_params,
         root_cert_store,
+        server_name: None,
     })
 }
 
@@ -135,8 +136,29 @@ impl Clone for ClientTlsParams {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct TlsConnParams {
     pub(crate) client_tls_params: Option<ClientTlsParams>,
     pub(crate) root_cert_store: Option<RootCertStore>,
+    /// The name that is sent as SNI and that the server's certificate is verified against, if it differs from the
+    /// dialed host, e.g. when connecting by IP address or through a tunnel.
+    pub(crate) server_name: Option<String>,
+}
+
+impl TlsConnParams {
+    /// Creates parameters that verify the server's certificate against `server_name` instead of the dialed host.
+    pub fn with_server_name(server_name: impl Into<String>) -> Self {
+        TlsConnParams {
+            server_name: Some(server_name.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the name that the server's certificate is verified against when dialing `host`.
+    pub(crate) fn server_name<'a>(tls_params: &'a Option<TlsConnParams>, host: &'a str) -> &'a str {
+        tls_params
+            .as_ref()
+            .and_then(|params| params.server_name.as_deref())
+            .unwrap_or(host)
+    }
 }
@@ -390,6 +390,13 @@ pub enum VerbatimFormat {
 pub enum PushKind {
     /// `Disconnection` is sent from the **library** when connection is closed.
     Disconnection,
+    /// `FatalClientError` is sent from the **library** when one of its background tasks panicked.
+    /// The data holds the task's name, followed by the panic message.
+    FatalClientError,
+    /// `GapDetected` is sent from the **library** when a subscription was restored after a disconnection, since
+    /// messages that were published in the meantime were lost. The data holds the channel or pattern, followed by
+    /// the number of messages that were delivered from it before the disconnection.
+    GapDetected,
     /// Other kind to catch future kinds.
     Other(String),
     /// `invalidate` is received when a key is changed/deleted.
@@ -454,6 +461,8 @@ impl fmt::Display for PushKind {
             PushKind::PSubscribe => write!(f, "psubscribe"),
             PushKind::SSubscribe => write!(f, "ssubscribe"),
             PushKind::Disconnection => write!(f, "disconnection"),
+            PushKind::FatalClientError => write!(f, "fatal_client_error"),
+            PushKind::GapDetected => write!(f, "gap_detected"),
         }
     }
 }
@@ -193,3 +193,19 @@ quickcheck! {
         );
     }
 }
+
+quickcheck! {
+    fn parse_arbitrary_bytes(input: Vec<u8>) -> () {
+        // Malformed replies must be reported as errors, not panic.
+        let _ = redis::parse_redis_value(&input);
+    }
+
+    fn parse_truncated_value(input: ArbitraryValue, cut: usize) -> () {
+        let mut encoded_input = Vec::new();
+        encode_value(&input.0, &mut encoded_input).unwrap();
+        let truncated = &encoded_input[..cut % encoded_input.len()];
+
+        let result = redis::parse_redis_value(truncated);
+        assert!(result.is_err(), "{:?} was parsed from {:?}", result, truncated);
+    }
+}
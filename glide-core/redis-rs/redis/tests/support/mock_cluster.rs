@@ -35,6 +35,8 @@ pub struct MockConnectionBehavior {
     pub connection_id_provider: AtomicUsize,
     pub returned_ip_type: ConnectionIPReturnType,
     pub return_connection_err: ShouldReturnConnectionError,
+    /// Whether pipelines are passed to the handler. Otherwise they get an empty response.
+    pub handle_pipelines: bool,
 }
 
 impl MockConnectionBehavior {
@@ -45,6 +47,7 @@ impl MockConnectionBehavior {
             connection_id_provider: AtomicUsize::new(0),
             returned_ip_type: ConnectionIPReturnType::default(),
             return_connection_err: ShouldReturnConnectionError::default(),
+            handle_pipelines: false,
         }
     }
 
@@ -85,6 +88,7 @@ pub fn get_mock_connection_with_port(name: &str, id: usize, port: u16) -> MockCo
         id,
         handler: get_mock_connection_handler(name),
         port,
+        handle_pipelines: false,
     }
 }
 
@@ -123,6 +127,7 @@ pub struct MockConnection {
     pub id: usize,
     pub handler: Handler,
     pub port: u16,
+    pub handle_pipelines: bool,
 }
 
 #[cfg(feature = "cluster-async")]
@@ -178,6 +183,7 @@ impl cluster_async::Connect for MockConnection {
                     .fetch_add(1, Ordering::SeqCst),
                 handler: conn_utils.get_handler(),
                 port,
+                handle_pipelines: conn_utils.handle_pipelines,
             },
             ip,
         )))
@@ -205,6 +211,7 @@ impl cluster::Connect for MockConnection {
                 .fetch_add(1, Ordering::SeqCst),
             handler: conn_utils.get_handler(),
             port,
+            handle_pipelines: conn_utils.handle_pipelines,
         })
     }
 
@@ -357,11 +364,22 @@ impl aio::ConnectionLike for MockConnection {
 
     fn req_packed_commands<'a>(
         &'a mut self,
-        _pipeline: &'a redis::Pipeline,
-        _offset: usize,
+        pipeline: &'a redis::Pipeline,
+        offset: usize,
         _count: usize,
     ) -> RedisFuture<'a, Vec<Value>> {
-        Box::pin(future::ok(vec![]))
+        if !self.handle_pipelines {
+            return Box::pin(future::ok(vec![]));
+        }
+        let res = (self.handler)(&pipeline.get_packed_pipeline(), self.port)
+            .expect_err("Handler did not specify a response");
+        Box::pin(future::ready(res.map(|value| match value {
+            Value::Array(mut values) => {
+                values.drain(..offset);
+                values
+            }
+            value => vec![value],
+        })))
     }
 
     fn get_db(&self) -> i64 {
@@ -455,6 +473,25 @@ impl MockEnv {
         client_builder: ClusterClientBuilder,
         id: &str,
         handler: impl Fn(&[u8], u16) -> Result<(), RedisResult<Value>> + Send + Sync + 'static,
+    ) -> Self {
+        Self::create(client_builder, id, handler, false)
+    }
+
+    /// Like [`MockEnv::with_client_builder`], but pipelines are passed to the handler too, which returns an array of
+    /// the replies of the pipeline's commands.
+    pub fn with_client_builder_handling_pipelines(
+        client_builder: ClusterClientBuilder,
+        id: &str,
+        handler: impl Fn(&[u8], u16) -> Result<(), RedisResult<Value>> + Send + Sync + 'static,
+    ) -> Self {
+        Self::create(client_builder, id, handler, true)
+    }
+
+    fn create(
+        client_builder: ClusterClientBuilder,
+        id: &str,
+        handler: impl Fn(&[u8], u16) -> Result<(), RedisResult<Value>> + Send + Sync + 'static,
+        handle_pipelines: bool,
     ) -> Self {
         #[cfg(feature = "cluster-async")]
         let runtime = tokio::runtime::Builder::new_current_thread()
@@ -468,6 +505,9 @@ impl MockEnv {
             &id,
             Arc::new(move |cmd, port| handler(cmd, port)),
         );
+        modify_mock_connection_behavior(&id, |behavior| {
+            behavior.handle_pipelines = handle_pipelines;
+        });
         let client = client_builder.build().unwrap();
         let connection = client.get_generic_connection(None).unwrap();
         #[cfg(feature = "cluster-async")]
@@ -1099,7 +1099,7 @@ mod basic_async {
             let pipe = build_simple_pipeline_for_invalidation();
             let _: RedisResult<()> = pipe.query_async(&mut manager).await;
             let _: i32 = manager.get("key_1").await.unwrap();
-            let PushInfo { kind, data } = rx.try_recv().unwrap();
+            let PushInfo { kind, data, .. } = rx.try_recv().unwrap();
             assert_eq!(
                 (
                     PushKind::Invalidate,
@@ -1114,7 +1114,7 @@ mod basic_async {
             drop(rx);
             let _: RedisResult<()> = pipe.query_async(&mut manager).await;
             let _: i32 = manager.get("key_1").await.unwrap();
-            let PushInfo { kind, data } = new_rx.try_recv().unwrap();
+            let PushInfo { kind, data, .. } = new_rx.try_recv().unwrap();
             assert_eq!(
                 (
                     PushKind::Invalidate,
@@ -231,6 +231,7 @@ mod test_connect_and_check {
             id: user_conn_id,
             handler: get_mock_connection_handler(name),
             port: 6379,
+            handle_pipelines: false,
         };
         let node = AsyncClusterNode::new(
             ConnectionDetails {
@@ -278,6 +279,7 @@ mod test_connect_and_check {
             id: user_conn_id,
             handler: get_mock_connection_handler(name),
             port: 6379,
+            handle_pipelines: false,
         };
         let prev_ip = Some(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)));
         let node = AsyncClusterNode::new(
@@ -330,11 +332,13 @@ mod test_connect_and_check {
             id: old_user_conn_id,
             handler: get_mock_connection_handler(name),
             port: 6379,
+            handle_pipelines: false,
         };
         let management_conn = MockConnection {
             id: management_conn_id,
             handler: get_mock_connection_handler(name),
             port: 6379,
+            handle_pipelines: false,
         };
 
         let node = AsyncClusterNode::new(
@@ -1594,7 +1594,7 @@ mod basic {
         for _ in 0..10 {
             let _: RedisResult<()> = pipe.query(&mut con);
             let _: i32 = con.get("key_1").unwrap();
-            let PushInfo { kind, data } = rx.try_recv().unwrap();
+            let PushInfo { kind, data, .. } = rx.try_recv().unwrap();
             assert_eq!(
                 (
                     PushKind::Invalidate,
@@ -1610,7 +1610,7 @@ mod basic {
         drop(rx);
         let _: RedisResult<()> = pipe.query(&mut con);
         let _: i32 = con.get("key_1").unwrap();
-        let PushInfo { kind, data } = new_rx.try_recv().unwrap();
+        let PushInfo { kind, data, .. } = new_rx.try_recv().unwrap();
         assert_eq!(
             (
                 PushKind::Invalidate,
@@ -158,7 +158,7 @@ mod cluster_async {
         for _ in 0..(subscribe_cnt + psubscribe_cnt + ssubscribe_cnt) {
             let result = notifications_rx.try_recv();
             assert!(result.is_ok());
-            let PushInfo { kind, data: _ } = result.unwrap();
+            let PushInfo { kind, .. } = result.unwrap();
             assert!(
                 kind == PushKind::Subscribe
                     || kind == PushKind::PSubscribe
@@ -3612,6 +3612,185 @@ mod cluster_async {
         assert_eq!(*found_ports.lock().unwrap(), vec![6380, 6381, 6383, 6384]);
     }
 
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_reissue_readonly_after_moved_from_replica() {
+        let name = "test_async_cluster_reissue_readonly_after_moved_from_replica";
+        let readonly_count = Arc::new(AtomicU16::new(0));
+        let readonly_count_clone = readonly_count.clone();
+        let replica_reads = Arc::new(AtomicU16::new(0));
+        let replica_reads_clone = replica_reads.clone();
+        let moved_reads_before = telemetrylib::Telemetry::replica_moved_reads();
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")]).read_from_replicas(),
+            name,
+            move |received_cmd: &[u8], port| {
+                if port == 6380 && contains_slice(received_cmd, b"READONLY") {
+                    readonly_count_clone.fetch_add(1, Ordering::Relaxed);
+                }
+                respond_startup_with_replica_using_config(
+                    name,
+                    received_cmd,
+                    Some(vec![MockSlotRange {
+                        primary_port: 6379,
+                        replica_ports: vec![6380],
+                        slot_range: (0..16383),
+                    }]),
+                )?;
+                // The replica rejects the first read, as if READONLY was lost.
+                if port == 6380 && replica_reads_clone.fetch_add(1, Ordering::Relaxed) == 0 {
+                    return Err(parse_redis_value(
+                        format!("-MOVED 12182 {name}:6379\r\n").as_bytes(),
+                    ));
+                }
+                Err(Ok(Value::Nil))
+            },
+        );
+
+        runtime.block_on(async {
+            for _ in 0..2 {
+                cmd("GET")
+                    .arg("foo")
+                    .query_async::<_, ()>(&mut connection)
+                    .await
+                    .unwrap();
+            }
+        });
+
+        // READONLY was sent when the replica connection was created, and again when it was recreated after the MOVED error.
+        assert_eq!(readonly_count.load(Ordering::Relaxed), 2);
+        assert_eq!(replica_reads.load(Ordering::Relaxed), 2);
+        assert_eq!(
+            telemetrylib::Telemetry::replica_moved_reads(),
+            moved_reads_before + 1
+        );
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_follow_moved_from_replica_to_another_shard_without_reconnecting() {
+        let name =
+            "test_async_cluster_follow_moved_from_replica_to_another_shard_without_reconnecting";
+        let readonly_count = Arc::new(AtomicU16::new(0));
+        let readonly_count_clone = readonly_count.clone();
+        let replica_reads = Arc::new(AtomicU16::new(0));
+        let replica_reads_clone = replica_reads.clone();
+        let moved_reads_before = telemetrylib::Telemetry::replica_moved_reads();
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")]).read_from_replicas(),
+            name,
+            move |received_cmd: &[u8], port| {
+                if port == 6382 && contains_slice(received_cmd, b"READONLY") {
+                    readonly_count_clone.fetch_add(1, Ordering::Relaxed);
+                }
+                respond_startup_with_replica_using_config(
+                    name,
+                    received_cmd,
+                    Some(vec![
+                        MockSlotRange {
+                            primary_port: 6379,
+                            replica_ports: vec![6380],
+                            slot_range: (0..8000),
+                        },
+                        MockSlotRange {
+                            primary_port: 6381,
+                            replica_ports: vec![6382],
+                            slot_range: (8001..16383),
+                        },
+                    ]),
+                )?;
+                // The slot was migrated to the other shard, so the replica redirects the first read there.
+                if port == 6382 && replica_reads_clone.fetch_add(1, Ordering::Relaxed) == 0 {
+                    return Err(parse_redis_value(
+                        format!("-MOVED 12182 {name}:6379\r\n").as_bytes(),
+                    ));
+                }
+                Err(Ok(Value::Nil))
+            },
+        );
+
+        runtime.block_on(async {
+            cmd("GET")
+                .arg("foo")
+                .query_async::<_, ()>(&mut connection)
+                .await
+                .unwrap();
+        });
+
+        // READONLY was only sent when the replica connection was created.
+        assert_eq!(readonly_count.load(Ordering::Relaxed), 1);
+        assert_eq!(
+            telemetrylib::Telemetry::replica_moved_reads(),
+            moved_reads_before
+        );
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_reissue_readonly_after_pipeline_moved_from_replica() {
+        let name = "test_async_cluster_reissue_readonly_after_pipeline_moved_from_replica";
+        let readonly_count = Arc::new(AtomicU16::new(0));
+        let readonly_count_clone = readonly_count.clone();
+        let replica_reads = Arc::new(AtomicU16::new(0));
+        let replica_reads_clone = replica_reads.clone();
+        let moved_reads_before = telemetrylib::Telemetry::replica_moved_reads();
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            ..
+        } = MockEnv::with_client_builder_handling_pipelines(
+            ClusterClient::builder(vec![&*format!("redis://{name}")]).read_from_replicas(),
+            name,
+            move |received_cmd: &[u8], port| {
+                if port == 6380 && contains_slice(received_cmd, b"READONLY") {
+                    readonly_count_clone.fetch_add(1, Ordering::Relaxed);
+                }
+                respond_startup_with_replica_using_config(
+                    name,
+                    received_cmd,
+                    Some(vec![MockSlotRange {
+                        primary_port: 6379,
+                        replica_ports: vec![6380],
+                        slot_range: (0..16383),
+                    }]),
+                )?;
+                // The replica rejects the first pipeline, as if READONLY was lost.
+                if port == 6380 && replica_reads_clone.fetch_add(1, Ordering::Relaxed) == 0 {
+                    return Err(parse_redis_value(
+                        format!("-MOVED 12182 {name}:6379\r\n").as_bytes(),
+                    ));
+                }
+                Err(Ok(Value::Array(vec![Value::Nil, Value::Nil])))
+            },
+        );
+
+        runtime.block_on(async {
+            let mut pipeline = redis::pipe();
+            pipeline.cmd("GET").arg("foo").cmd("GET").arg("foo");
+            for _ in 0..2 {
+                pipeline
+                    .query_async::<_, ()>(&mut connection)
+                    .await
+                    .unwrap();
+            }
+        });
+
+        assert_eq!(readonly_count.load(Ordering::Relaxed), 2);
+        assert_eq!(replica_reads.load(Ordering::Relaxed), 2);
+        assert_eq!(
+            telemetrylib::Telemetry::replica_moved_reads(),
+            moved_reads_before + 1
+        );
+    }
+
     fn get_queried_node_id_if_master(cluster_nodes_output: Value) -> Option<String> {
         // Returns the node ID of the connection that was queried for CLUSTER NODES (using the 'myself' flag), if it's a master.
         // Otherwise, returns None.
@@ -3835,7 +4014,7 @@ mod cluster_async {
             sleep(futures_time::time::Duration::from_secs(1)).await;
             let result = rx.try_recv();
             assert!(result.is_ok());
-            let PushInfo { kind, data } = result.unwrap();
+            let PushInfo { kind, data, .. } = result.unwrap();
             assert_eq!(
                 (kind, data),
                 (
@@ -3862,7 +4041,7 @@ mod cluster_async {
                 sleep(futures_time::time::Duration::from_secs(1)).await;
                 let result = rx.try_recv();
                 assert!(result.is_ok());
-                let PushInfo { kind, data } = result.unwrap();
+                let PushInfo { kind, data, .. } = result.unwrap();
                 assert_eq!(
                     (kind, data),
                     (
@@ -3902,7 +4081,7 @@ mod cluster_async {
             sleep(futures_time::time::Duration::from_secs(1)).await;
             let result = rx.try_recv();
             assert!(result.is_ok());
-            let PushInfo { kind, data } = result.unwrap();
+            let PushInfo { kind, data, .. } = result.unwrap();
             assert_eq!(
                 (kind, data),
                 (
@@ -3929,7 +4108,7 @@ mod cluster_async {
                 sleep(futures_time::time::Duration::from_secs(1)).await;
                 let result = rx.try_recv();
                 assert!(result.is_ok());
-                let PushInfo { kind, data } = result.unwrap();
+                let PushInfo { kind, data, .. } = result.unwrap();
                 assert_eq!(
                     (kind, data),
                     (
@@ -4015,7 +4194,7 @@ mod cluster_async {
             sleep(futures_time::time::Duration::from_secs(1)).await;
             let result = rx.try_recv();
             assert!(result.is_ok());
-            let PushInfo { kind, data } = result.unwrap();
+            let PushInfo { kind, data, .. } = result.unwrap();
             assert_eq!(
                 (kind, data),
                 (
@@ -4042,7 +4221,7 @@ mod cluster_async {
                 sleep(futures_time::time::Duration::from_secs(1)).await;
                 let result = rx.try_recv();
                 assert!(result.is_ok());
-                let PushInfo { kind, data } = result.unwrap();
+                let PushInfo { kind, data, .. } = result.unwrap();
                 assert_eq!(
                     (kind, data),
                     (
@@ -4135,7 +4314,7 @@ mod cluster_async {
             loop {
                 let result = rx.try_recv();
                 assert!(result.is_ok());
-                let PushInfo { kind, data } = result.unwrap();
+                let PushInfo { kind, data, .. } = result.unwrap();
                 // ignore disconnection and subscription notifications due to resubscriptions
                 if kind == PushKind::Message {
                     assert_eq!(
@@ -4166,7 +4345,7 @@ mod cluster_async {
 
                 let result = rx.try_recv();
                 assert!(result.is_ok());
-                let PushInfo { kind, data } = result.unwrap();
+                let PushInfo { kind, data, .. } = result.unwrap();
                 assert_eq!(
                     (kind, data),
                     (
@@ -4268,7 +4447,7 @@ mod cluster_async {
             for _ in 0..3 {
                 let result = rx.try_recv();
                 assert!(result.is_ok());
-                let PushInfo { kind, data: _ } = result.unwrap();
+                let PushInfo { kind, .. } = result.unwrap();
                 assert!(kind == PushKind::Message || kind == PushKind::PMessage);
                 if kind == PushKind::Message {
                     msg_cnt += 1;
@@ -4290,7 +4469,7 @@ mod cluster_async {
                 sleep(futures_time::time::Duration::from_secs(1)).await;
                 let result = rx.try_recv();
                 assert!(result.is_ok());
-                let PushInfo { kind, data } = result.unwrap();
+                let PushInfo { kind, data, .. } = result.unwrap();
                 assert_eq!(
                     (kind, data),
                     (
@@ -0,0 +1,205 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use super::AdaptiveTimeoutConfig;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// The number of latency samples that are kept per node. Older samples are discarded first.
+const LATENCY_WINDOW_SIZE: usize = 256;
+
+/// Below this number of samples the observed percentile isn't reliable, and the configured request timeout is used instead.
+const MIN_SAMPLES_FOR_PERCENTILE: usize = 20;
+
+/// Key used for requests whose target node is only resolved after the request was sent (e.g. reads that may be served by
+/// any replica).
+pub(crate) const CLIENT_WIDE_LATENCY_KEY: &str = "*";
+
+/// The percentile is recomputed after this many samples were recorded, rather than on every request.
+const PERCENTILE_REFRESH_INTERVAL: usize = 16;
+
+#[derive(Default)]
+struct LatencyWindow {
+    samples: VecDeque<Duration>,
+    recorded_since_refresh: usize,
+    /// The percentile of the samples as of the last refresh, if there were enough samples.
+    percentile: Option<Duration>,
+}
+
+impl LatencyWindow {
+    fn record(&mut self, latency: Duration, percentile: f64) {
+        if self.samples.len() == LATENCY_WINDOW_SIZE {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(latency);
+        self.recorded_since_refresh += 1;
+        if self.recorded_since_refresh >= PERCENTILE_REFRESH_INTERVAL
+            || self.samples.len() == MIN_SAMPLES_FOR_PERCENTILE
+        {
+            self.recorded_since_refresh = 0;
+            self.percentile = self.compute_percentile(percentile);
+        }
+    }
+
+    fn compute_percentile(&self, percentile: f64) -> Option<Duration> {
+        if self.samples.len() < MIN_SAMPLES_FOR_PERCENTILE {
+            return None;
+        }
+        let mut samples: Vec<Duration> = self.samples.iter().copied().collect();
+        // nearest-rank percentile
+        let rank = ((percentile / 100.0) * samples.len() as f64).ceil() as usize;
+        let index = rank.saturating_sub(1).min(samples.len() - 1);
+        Some(*samples.select_nth_unstable(index).1)
+    }
+}
+
+/// Derives request timeouts from the recent latencies of each node, bounded by the configured minimum and maximum.
+pub(crate) struct AdaptiveTimeout {
+    config: AdaptiveTimeoutConfig,
+    windows: Mutex<HashMap<String, LatencyWindow>>,
+}
+
+impl AdaptiveTimeout {
+    pub(crate) fn new(mut config: AdaptiveTimeoutConfig) -> Self {
+        // `Duration::clamp` panics if the bounds are inverted.
+        if config.max_timeout < config.min_timeout {
+            config.max_timeout = config.min_timeout;
+        }
+        if !(config.percentile > 0.0 && config.percentile <= 100.0) {
+            config.percentile = 99.0;
+        }
+        if !(config.multiplier.is_finite() && config.multiplier >= 1.0) {
+            config.multiplier = 1.0;
+        }
+        Self {
+            config,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn record(&self, node: &str, latency: Duration) {
+        let percentile = self.config.percentile;
+        let mut windows = self.windows.lock().unwrap();
+        match windows.get_mut(node) {
+            Some(window) => window.record(latency, percentile),
+            None => {
+                let mut window = LatencyWindow::default();
+                window.record(latency, percentile);
+                windows.insert(node.to_string(), window);
+            }
+        }
+    }
+
+    /// Returns the timeout for a request sent to `node`. If there are not enough samples for the node,
+    /// `configured_timeout` is used as the base value. The result is always within the configured bounds.
+    pub(crate) fn timeout_for(&self, node: &str, configured_timeout: Duration) -> Duration {
+        let observed = self
+            .windows
+            .lock()
+            .unwrap()
+            .get(node)
+            .and_then(|window| window.percentile)
+            .map(|latency| latency.mul_f64(self.config.multiplier));
+        observed
+            .unwrap_or(configured_timeout)
+            .clamp(self.config.min_timeout, self.config.max_timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adaptive_timeout() -> AdaptiveTimeout {
+        AdaptiveTimeout::new(AdaptiveTimeoutConfig {
+            min_timeout: Duration::from_millis(100),
+            max_timeout: Duration::from_millis(1000),
+            percentile: 90.0,
+            multiplier: 2.0,
+        })
+    }
+
+    #[test]
+    fn test_uses_configured_timeout_without_enough_samples() {
+        let adaptive_timeout = adaptive_timeout();
+        adaptive_timeout.record("node", Duration::from_millis(400));
+        assert_eq!(
+            adaptive_timeout.timeout_for("node", Duration::from_millis(250)),
+            Duration::from_millis(250)
+        );
+        assert_eq!(
+            adaptive_timeout.timeout_for("node", Duration::from_millis(5000)),
+            Duration::from_millis(1000)
+        );
+    }
+
+    #[test]
+    fn test_timeout_follows_node_percentile() {
+        let adaptive_timeout = adaptive_timeout();
+        for i in 1..=100 {
+            adaptive_timeout.record("node", Duration::from_millis(i * 2));
+        }
+        // p90 of 2..=200ms is 180ms, doubled by the multiplier.
+        assert_eq!(
+            adaptive_timeout.timeout_for("node", Duration::from_millis(250)),
+            Duration::from_millis(360)
+        );
+        // other nodes aren't affected.
+        assert_eq!(
+            adaptive_timeout.timeout_for("other", Duration::from_millis(250)),
+            Duration::from_millis(250)
+        );
+    }
+
+    #[test]
+    fn test_timeout_is_bounded() {
+        let adaptive_timeout = adaptive_timeout();
+        for _ in 0..MIN_SAMPLES_FOR_PERCENTILE {
+            adaptive_timeout.record("fast", Duration::from_millis(1));
+            adaptive_timeout.record("slow", Duration::from_secs(3));
+        }
+        assert_eq!(
+            adaptive_timeout.timeout_for("fast", Duration::from_millis(250)),
+            Duration::from_millis(100)
+        );
+        assert_eq!(
+            adaptive_timeout.timeout_for("slow", Duration::from_millis(250)),
+            Duration::from_millis(1000)
+        );
+    }
+
+    #[test]
+    fn test_window_drops_old_samples() {
+        let adaptive_timeout = adaptive_timeout();
+        for _ in 0..LATENCY_WINDOW_SIZE {
+            adaptive_timeout.record("node", Duration::from_millis(900));
+        }
+        for _ in 0..LATENCY_WINDOW_SIZE {
+            adaptive_timeout.record("node", Duration::from_millis(60));
+        }
+        assert_eq!(
+            adaptive_timeout.timeout_for("node", Duration::from_millis(250)),
+            Duration::from_millis(120)
+        );
+    }
+
+    #[test]
+    fn test_percentile_is_refreshed_after_an_interval() {
+        let adaptive_timeout = adaptive_timeout();
+        for _ in 0..MIN_SAMPLES_FOR_PERCENTILE {
+            adaptive_timeout.record("node", Duration::from_millis(60));
+        }
+        for _ in 0..PERCENTILE_REFRESH_INTERVAL - 1 {
+            adaptive_timeout.record("node", Duration::from_millis(400));
+        }
+        assert_eq!(
+            adaptive_timeout.timeout_for("node", Duration::from_millis(250)),
+            Duration::from_millis(120)
+        );
+        adaptive_timeout.record("node", Duration::from_millis(400));
+        assert_eq!(
+            adaptive_timeout.timeout_for("node", Duration::from_millis(250)),
+            Duration::from_millis(800)
+        );
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Canonical formatting of numeric command arguments, so that every wrapper sends the same bytes for the same number.
+
+use redis::{ErrorKind, RedisError, RedisResult};
+
+/// Formats `value` the way the server parses floats, such as ZADD scores.
+///
+/// The value is written in its shortest round-trip decimal form without an exponent, and infinities are written as
+/// `+inf` and `-inf`. NaN is rejected, since the server doesn't accept it.
+pub fn float_arg(value: f64) -> RedisResult<Vec<u8>> {
+    if value.is_nan() {
+        return Err(RedisError::from((
+            ErrorKind::TypeError,
+            "NaN can't be used as a command argument",
+        )));
+    }
+    if value.is_infinite() {
+        let infinity: &[u8] = if value.is_sign_positive() {
+            b"+inf"
+        } else {
+            b"-inf"
+        };
+        return Ok(infinity.to_vec());
+    }
+    // Unlike the `ToRedisArgs` implementation, `Display` never uses an exponent.
+    Ok(value.to_string().into_bytes())
+}
+
+/// Formats `value` as a decimal integer argument.
+pub fn int_arg(value: i64) -> Vec<u8> {
+    value.to_string().into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_float_arg() {
+        assert_eq!(float_arg(1.5).unwrap(), b"1.5");
+        assert_eq!(float_arg(-2.0).unwrap(), b"-2");
+        assert_eq!(float_arg(0.1 + 0.2).unwrap(), b"0.30000000000000004");
+        assert_eq!(float_arg(1e21).unwrap(), b"1000000000000000000000");
+        assert_eq!(float_arg(1e-7).unwrap(), b"0.0000001");
+        assert_eq!(float_arg(f64::INFINITY).unwrap(), b"+inf");
+        assert_eq!(float_arg(f64::NEG_INFINITY).unwrap(), b"-inf");
+        assert_eq!(
+            float_arg(f64::NAN).unwrap_err().kind(),
+            ErrorKind::TypeError
+        );
+    }
+
+    #[test]
+    fn test_float_arg_round_trips() {
+        for value in [
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            -123.456e-50,
+            9_007_199_254_740_992.0,
+        ] {
+            let formatted = String::from_utf8(float_arg(value).unwrap()).unwrap();
+            assert!(!formatted.contains('e'), "{formatted}");
+            assert_eq!(formatted.parse::<f64>().unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_int_arg() {
+        assert_eq!(int_arg(0), b"0");
+        assert_eq!(int_arg(i64::MIN), b"-9223372036854775808");
+        assert_eq!(int_arg(i64::MAX), b"9223372036854775807");
+    }
+}
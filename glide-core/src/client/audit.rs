@@ -0,0 +1,91 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use logger_core::log_info;
+use redis::cluster_routing::{command_keys, Routable};
+use redis::{Cmd, ErrorKind, Pipeline, RedisResult};
+
+/// The outcome of an audited command.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AuditOutcome {
+    Success,
+    /// Only the kind of the error is recorded, since error messages might contain values.
+    Error(ErrorKind),
+}
+
+impl AuditOutcome {
+    fn from_result<T>(result: &RedisResult<T>) -> Self {
+        match result {
+            Ok(_) => AuditOutcome::Success,
+            Err(err) => AuditOutcome::Error(err.kind()),
+        }
+    }
+}
+
+/// A command that was sent by a client. Values are never included - only the command's name and keys.
+#[derive(Debug)]
+pub struct AuditEvent<'a> {
+    /// The command's name, including the subcommand if there is one, e.g. `GET` or `CLIENT SETNAME`.
+    pub command: String,
+    pub keys: Vec<&'a [u8]>,
+    /// The context that the caller attached to the request, such as the identity of the end user.
+    pub context: Option<&'a str>,
+    pub outcome: AuditOutcome,
+}
+
+/// Receives an event for every command that a client sent, once the command completed.
+///
+/// Sinks are called on the request's task, so they should hand the event off quickly instead of blocking.
+pub trait AuditSink: Send + Sync {
+    fn record(&self, event: &AuditEvent<'_>);
+}
+
+/// Writes audit events to the client's log, at the info level. Used by clients that were created with `audit_log`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LogAuditSink;
+
+impl AuditSink for LogAuditSink {
+    fn record(&self, event: &AuditEvent<'_>) {
+        let keys = event
+            .keys
+            .iter()
+            .map(|key| String::from_utf8_lossy(key))
+            .collect::<Vec<_>>()
+            .join(" ");
+        log_info(
+            "audit",
+            format!(
+                "command: {}, keys: [{keys}], context: {}, outcome: {:?}",
+                event.command,
+                event.context.unwrap_or_default(),
+                event.outcome
+            ),
+        );
+    }
+}
+
+pub(super) fn record_command<T>(
+    sink: &dyn AuditSink,
+    cmd: &Cmd,
+    context: Option<&str>,
+    result: &RedisResult<T>,
+) {
+    let command = cmd.command().unwrap_or_default();
+    sink.record(&AuditEvent {
+        command: String::from_utf8_lossy(&command).into_owned(),
+        keys: command_keys(cmd),
+        context,
+        outcome: AuditOutcome::from_result(result),
+    });
+}
+
+/// Records every command of `pipeline` with the outcome of the whole pipeline.
+pub(super) fn record_pipeline<T>(
+    sink: &dyn AuditSink,
+    pipeline: &Pipeline,
+    context: Option<&str>,
+    result: &RedisResult<T>,
+) {
+    for cmd in pipeline.cmd_iter() {
+        record_command(sink, cmd, context, result);
+    }
+}
@@ -0,0 +1,208 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Client-side detection of hot keys. Accesses are counted in a count-min sketch, whose memory is fixed regardless of
+//! the number of distinct keys, and the keys with the highest estimates are kept as candidates. The counts are halved
+//! periodically, so keys that stopped being accessed cool down and make room for the keys that are hot now.
+
+use redis::cluster_routing::command_keys;
+use redis::Cmd;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+/// The number of rows of the sketch. Each row lowers the chance that a key's estimate is inflated by collisions.
+const SKETCH_DEPTH: usize = 4;
+/// The number of counters in each row of the sketch.
+const SKETCH_WIDTH: usize = 4096;
+/// The number of accesses after which all the counts are halved.
+const DECAY_INTERVAL: u64 = 10 * SKETCH_WIDTH as u64;
+
+struct HotKeyState {
+    sketch: Vec<u32>,
+    /// The estimated access counts of the hottest keys seen so far.
+    candidates: HashMap<Vec<u8>, u32>,
+    /// A lower bound of the lowest estimate in `candidates`. Estimates only grow between decays, so keys at or below it
+    /// can be skipped without searching the candidates.
+    min_candidate: u32,
+    /// The number of accesses since the counts were last halved.
+    accesses_since_decay: u64,
+}
+
+impl HotKeyState {
+    /// Counts an access of `key`, and returns its new estimate.
+    fn increment(&mut self, key: &[u8]) -> u32 {
+        let mut estimate = u32::MAX;
+        for row in 0..SKETCH_DEPTH {
+            let mut hasher = DefaultHasher::new();
+            row.hash(&mut hasher);
+            key.hash(&mut hasher);
+            let index = row * SKETCH_WIDTH + (hasher.finish() as usize % SKETCH_WIDTH);
+            let counter = &mut self.sketch[index];
+            *counter = counter.saturating_add(1);
+            estimate = estimate.min(*counter);
+        }
+        estimate
+    }
+
+    /// Halves all the counts, and drops the candidates that cooled down completely.
+    fn decay(&mut self) {
+        for counter in self.sketch.iter_mut() {
+            *counter /= 2;
+        }
+        self.candidates.retain(|_, estimate| {
+            *estimate /= 2;
+            *estimate > 0
+        });
+        self.min_candidate = self.candidates.values().copied().min().unwrap_or(0);
+        self.accesses_since_decay = 0;
+    }
+}
+
+/// Tracks the keys that a client accesses most often.
+pub(super) struct HotKeyTracker {
+    max_candidates: usize,
+    decay_interval: u64,
+    state: Mutex<HotKeyState>,
+}
+
+impl HotKeyTracker {
+    /// Creates a tracker that keeps up to `max_candidates` of the hottest keys.
+    pub(super) fn new(max_candidates: usize) -> Self {
+        Self::with_decay_interval(max_candidates, DECAY_INTERVAL)
+    }
+
+    fn with_decay_interval(max_candidates: usize, decay_interval: u64) -> Self {
+        HotKeyTracker {
+            max_candidates: max_candidates.max(1),
+            decay_interval: decay_interval.max(1),
+            state: Mutex::new(HotKeyState {
+                sketch: vec![0; SKETCH_DEPTH * SKETCH_WIDTH],
+                candidates: HashMap::new(),
+                min_candidate: 0,
+                accesses_since_decay: 0,
+            }),
+        }
+    }
+
+    /// Counts an access of each key of `cmd`.
+    pub(super) fn record_command(&self, cmd: &Cmd) {
+        let keys = command_keys(cmd);
+        if keys.is_empty() {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        for key in keys {
+            self.record_key(&mut state, key);
+        }
+    }
+
+    fn record_key(&self, state: &mut HotKeyState, key: &[u8]) {
+        state.accesses_since_decay += 1;
+        if state.accesses_since_decay >= self.decay_interval {
+            state.decay();
+        }
+        let estimate = state.increment(key);
+        if let Some(candidate) = state.candidates.get_mut(key) {
+            *candidate = estimate;
+            return;
+        }
+        if state.candidates.len() < self.max_candidates {
+            state.candidates.insert(key.to_vec(), estimate);
+            state.min_candidate = state.min_candidate.min(estimate);
+            return;
+        }
+        if estimate <= state.min_candidate {
+            return;
+        }
+        let Some((coldest, coldest_estimate)) = state
+            .candidates
+            .iter()
+            .min_by_key(|(_, estimate)| **estimate)
+            .map(|(key, estimate)| (key.clone(), *estimate))
+        else {
+            return;
+        };
+        if estimate > coldest_estimate {
+            state.candidates.remove(&coldest);
+            state.candidates.insert(key.to_vec(), estimate);
+            state.min_candidate = state.candidates.values().copied().min().unwrap_or(0);
+        } else {
+            state.min_candidate = coldest_estimate;
+        }
+    }
+
+    /// Returns up to `count` of the hottest keys with their estimated access counts, hottest first. The estimates may
+    /// exceed the actual counts since the last decays, but never fall below them.
+    pub(super) fn hottest_keys(&self, count: usize) -> Vec<(Vec<u8>, u64)> {
+        let state = self.state.lock().unwrap();
+        let mut keys: Vec<(Vec<u8>, u64)> = state
+            .candidates
+            .iter()
+            .map(|(key, estimate)| (key.clone(), *estimate as u64))
+            .collect();
+        keys.sort_by(|(first_key, first), (second_key, second)| {
+            second.cmp(first).then_with(|| first_key.cmp(second_key))
+        });
+        keys.truncate(count);
+        keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get(key: &str) -> Cmd {
+        let mut cmd = redis::cmd("GET");
+        cmd.arg(key);
+        cmd
+    }
+
+    #[test]
+    fn test_hottest_keys_are_kept_when_candidates_are_full() {
+        let tracker = HotKeyTracker::new(2);
+        for _ in 0..10 {
+            tracker.record_command(&get("hot"));
+        }
+        for index in 0..100 {
+            tracker.record_command(&get(&format!("cold{index}")));
+        }
+        for _ in 0..5 {
+            tracker.record_command(&get("warm"));
+        }
+        let mut mget = redis::cmd("MGET");
+        mget.arg("hot").arg("warm");
+        tracker.record_command(&mget);
+
+        let hottest = tracker.hottest_keys(5);
+        let keys: Vec<&[u8]> = hottest.iter().map(|(key, _)| key.as_slice()).collect();
+        assert_eq!(keys, [b"hot".as_slice(), b"warm"]);
+        assert!(hottest[0].1 >= 11);
+        assert!(hottest[1].1 >= 6);
+        assert_eq!(tracker.hottest_keys(1).len(), 1);
+    }
+
+    #[test]
+    fn test_cooled_down_key_drops_out_of_hottest_keys() {
+        let tracker = HotKeyTracker::with_decay_interval(1, 100);
+        for _ in 0..150 {
+            tracker.record_command(&get("old"));
+        }
+        // "new" is accessed less than "old" in total, but all of its accesses are recent.
+        for _ in 0..80 {
+            tracker.record_command(&get("new"));
+        }
+
+        let hottest = tracker.hottest_keys(1);
+        assert_eq!(hottest.len(), 1);
+        assert_eq!(hottest[0].0, b"new");
+    }
+
+    #[test]
+    fn test_keyless_commands_are_not_tracked() {
+        let tracker = HotKeyTracker::new(10);
+        tracker.record_command(&redis::cmd("PING"));
+        assert!(tracker.hottest_keys(10).is_empty());
+    }
+}
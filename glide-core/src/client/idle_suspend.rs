@@ -0,0 +1,177 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use super::task_supervisor::spawn_supervised;
+use super::ClientWrapper;
+use crate::clock;
+use logger_core::{log_debug, log_info, log_warn};
+use redis::PushInfo;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// The requests of a client, from which the time it has been idle is derived.
+struct Activity {
+    last_activity: Mutex<Instant>,
+    in_flight: AtomicUsize,
+}
+
+impl Activity {
+    fn new() -> Self {
+        Activity {
+            last_activity: Mutex::new(clock::now()),
+            in_flight: AtomicUsize::new(0),
+        }
+    }
+
+    fn start_request(&self) {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        *self.last_activity.lock().unwrap() = clock::now();
+    }
+
+    fn finish_request(&self) {
+        *self.last_activity.lock().unwrap() = clock::now();
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// Returns how long the client has been idle, or `None` if a request is in flight.
+    fn idle_time(&self) -> Option<Duration> {
+        if self.in_flight.load(Ordering::SeqCst) > 0 {
+            return None;
+        }
+        Some(clock::elapsed(*self.last_activity.lock().unwrap()))
+    }
+}
+
+/// Closes the connections of a client once it sent no requests for its idle timeout. The connections are reopened by
+/// the next request, which waits for them.
+pub(super) struct IdleSuspend {
+    idle_timeout: Duration,
+    activity: Activity,
+    suspended: AtomicBool,
+    // A clone of the client's connections, which is dropped along with the last clone of the client.
+    client: ClientWrapper,
+}
+
+/// Keeps the client from being suspended while a request is in flight.
+pub(super) struct ActiveRequest(Arc<IdleSuspend>);
+
+impl Drop for ActiveRequest {
+    fn drop(&mut self) {
+        self.0.activity.finish_request();
+    }
+}
+
+impl IdleSuspend {
+    pub(super) fn new(idle_timeout: Duration, client: ClientWrapper) -> Self {
+        IdleSuspend {
+            idle_timeout,
+            activity: Activity::new(),
+            suspended: AtomicBool::new(false),
+            client,
+        }
+    }
+
+    /// Marks the start of a request. The client isn't idle until the returned guard is dropped.
+    pub(super) fn start_request(self: &Arc<Self>) -> ActiveRequest {
+        self.activity.start_request();
+        self.suspended.store(false, Ordering::SeqCst);
+        ActiveRequest(self.clone())
+    }
+
+    async fn suspend(&self) {
+        if self.suspended.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        // A request may have started since the client was found idle. Since requests are counted before they clear
+        // `suspended`, either it's seen here, or it started after the client was marked as suspended, and the
+        // connections it finds closed are reopened.
+        if self.activity.in_flight.load(Ordering::SeqCst) > 0 {
+            self.suspended.store(false, Ordering::SeqCst);
+            return;
+        }
+        log_info(
+            "idle suspend",
+            format!(
+                "Closing the connections after {}ms without requests",
+                self.idle_timeout.as_millis()
+            ),
+        );
+        match &self.client {
+            ClientWrapper::Standalone(client) => client.suspend(),
+            ClientWrapper::Cluster { client } => {
+                if let Err(err) = client.clone().suspend().await {
+                    log_warn(
+                        "idle suspend",
+                        format!("Failed to close the connections: {err}"),
+                    );
+                    self.suspended.store(false, Ordering::SeqCst);
+                }
+            }
+        }
+    }
+
+    /// Checks whether the client has been idle for its idle timeout whenever it could have become so, and suspends it
+    /// if it has. The checks stop once the client is dropped.
+    pub(super) fn start_idle_checks(
+        idle_suspend: Weak<IdleSuspend>,
+        push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
+    ) {
+        spawn_supervised("idle check", push_sender, move || {
+            let idle_suspend = idle_suspend.clone();
+            async move {
+                loop {
+                    let Some(idle_suspend) = idle_suspend.upgrade() else {
+                        log_debug(
+                            "idle suspend",
+                            "idle checks stopped after client was dropped",
+                        );
+                        return;
+                    };
+                    let idle_timeout = idle_suspend.idle_timeout;
+                    let next_check = match idle_suspend.activity.idle_time() {
+                        Some(idle_time) if idle_time >= idle_timeout => {
+                            idle_suspend.suspend().await;
+                            idle_timeout
+                        }
+                        Some(idle_time) => idle_timeout - idle_time,
+                        None => idle_timeout,
+                    };
+                    // The client can't be dropped while the check holds it.
+                    drop(idle_suspend);
+                    tokio::time::sleep(next_check).await;
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{set_clock, ManualClock};
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_requests_in_flight_keep_the_client_active() {
+        let clock = Arc::new(ManualClock::new());
+        set_clock(Some(clock.clone()));
+        let activity = Activity::new();
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(activity.idle_time(), Some(Duration::from_secs(3)));
+
+        activity.start_request();
+        activity.start_request();
+        clock.advance(Duration::from_secs(20));
+        assert_eq!(activity.idle_time(), None);
+        activity.finish_request();
+        assert_eq!(activity.idle_time(), None);
+        activity.finish_request();
+        assert_eq!(activity.idle_time(), Some(Duration::ZERO));
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(activity.idle_time(), Some(Duration::from_secs(5)));
+
+        set_clock(None);
+    }
+}
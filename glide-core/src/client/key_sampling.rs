@@ -0,0 +1,152 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::Value;
+use std::collections::BTreeMap;
+
+/// A randomly sampled key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SampledKey {
+    pub key: Vec<u8>,
+    /// The number of bytes that the key and its value take in memory, as reported by `MEMORY USAGE`.
+    pub memory_usage: u64,
+    /// The logarithmic access frequency counter reported by `OBJECT FREQ`. `None` unless an LFU eviction policy is set.
+    pub frequency: Option<u64>,
+}
+
+/// The sizes and access frequencies of a random sample of keys, used to find big keys and hot keys.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeySample {
+    /// The sampled keys, biggest first.
+    pub keys: Vec<SampledKey>,
+    /// The number of sampled keys in each size range. Each range is identified by its inclusive upper bound in bytes,
+    /// which is a power of two.
+    pub size_distribution: BTreeMap<u64, u64>,
+    /// The number of sampled keys in each frequency range, identified like the ranges of `size_distribution`. Empty
+    /// if no frequencies were sampled.
+    pub frequency_distribution: BTreeMap<u64, u64>,
+}
+
+/// Returns the inclusive upper bound of the range that holds `value`.
+fn range_of(value: u64) -> u64 {
+    value.checked_next_power_of_two().unwrap_or(u64::MAX)
+}
+
+impl KeySample {
+    pub(super) fn new(mut keys: Vec<SampledKey>) -> Self {
+        keys.sort_by(|first, second| second.memory_usage.cmp(&first.memory_usage));
+        let mut size_distribution = BTreeMap::new();
+        let mut frequency_distribution = BTreeMap::new();
+        for key in keys.iter() {
+            *size_distribution
+                .entry(range_of(key.memory_usage))
+                .or_default() += 1;
+            if let Some(frequency) = key.frequency {
+                *frequency_distribution
+                    .entry(range_of(frequency))
+                    .or_default() += 1;
+            }
+        }
+        KeySample {
+            keys,
+            size_distribution,
+            frequency_distribution,
+        }
+    }
+
+    /// Returns the value that is passed to the wrappers - a map with `keys`, `size_distribution` and
+    /// `frequency_distribution` fields. Each key is a map with `key`, `memory_usage` and `frequency` fields, where the
+    /// frequency is nil if it wasn't sampled.
+    pub fn to_value(&self) -> Value {
+        let string = |value: &str| Value::SimpleString(value.to_string());
+        let distribution = |ranges: &BTreeMap<u64, u64>| {
+            Value::Map(
+                ranges
+                    .iter()
+                    .map(|(range, count)| (Value::Int(*range as i64), Value::Int(*count as i64)))
+                    .collect(),
+            )
+        };
+        let keys = self
+            .keys
+            .iter()
+            .map(|key| {
+                Value::Map(vec![
+                    (string("key"), Value::BulkString(key.key.clone())),
+                    (string("memory_usage"), Value::Int(key.memory_usage as i64)),
+                    (
+                        string("frequency"),
+                        key.frequency
+                            .map_or(Value::Nil, |frequency| Value::Int(frequency as i64)),
+                    ),
+                ])
+            })
+            .collect();
+        Value::Map(vec![
+            (string("keys"), Value::Array(keys)),
+            (
+                string("size_distribution"),
+                distribution(&self.size_distribution),
+            ),
+            (
+                string("frequency_distribution"),
+                distribution(&self.frequency_distribution),
+            ),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sampled_key(key: &str, memory_usage: u64, frequency: Option<u64>) -> SampledKey {
+        SampledKey {
+            key: key.as_bytes().to_vec(),
+            memory_usage,
+            frequency,
+        }
+    }
+
+    #[test]
+    fn test_sample_is_sorted_and_bucketed() {
+        let sample = KeySample::new(vec![
+            sampled_key("small", 56, Some(0)),
+            sampled_key("big", 2_000_000, Some(5)),
+            sampled_key("medium", 64, Some(4)),
+        ]);
+        let keys: Vec<&[u8]> = sample.keys.iter().map(|key| key.key.as_slice()).collect();
+        assert_eq!(keys, [b"big".as_slice(), b"medium", b"small"]);
+        assert_eq!(
+            sample.size_distribution,
+            BTreeMap::from([(64, 2), (2_097_152, 1)])
+        );
+        assert_eq!(
+            sample.frequency_distribution,
+            BTreeMap::from([(1, 1), (4, 1), (8, 1)])
+        );
+    }
+
+    #[test]
+    fn test_sample_to_value() {
+        let sample = KeySample::new(vec![sampled_key("key", 100, None)]);
+        let string = |value: &str| Value::SimpleString(value.to_string());
+        assert_eq!(
+            sample.to_value(),
+            Value::Map(vec![
+                (
+                    string("keys"),
+                    Value::Array(vec![Value::Map(vec![
+                        (string("key"), Value::BulkString(b"key".to_vec())),
+                        (string("memory_usage"), Value::Int(100)),
+                        (string("frequency"), Value::Nil),
+                    ])])
+                ),
+                (
+                    string("size_distribution"),
+                    Value::Map(vec![(Value::Int(128), Value::Int(1))])
+                ),
+                (string("frequency_distribution"), Value::Map(vec![])),
+            ])
+        );
+    }
+}
@@ -0,0 +1,200 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Default routes of keyless commands in cluster mode, for commands that were sent without a route.
+
+use arc_swap::ArcSwap;
+use redis::cluster_routing::{
+    MultipleNodeRoutingInfo, ResponsePolicy, Routable, RoutingInfo, SingleNodeRoutingInfo,
+};
+use redis::{Cmd, ErrorKind, RedisError, RedisResult};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Where a keyless command is sent, if the caller didn't route it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeylessRouting {
+    /// A random node. Commands that aren't read-only are sent to a random primary.
+    Random,
+    /// All primaries. The replies are combined by the policy if one is given, and are otherwise returned per node.
+    AllPrimaries(Option<ResponsePolicy>),
+    /// All nodes. The replies are combined by the policy if one is given, and are otherwise returned per node.
+    AllNodes(Option<ResponsePolicy>),
+    /// The node at this address.
+    ByAddress { host: String, port: u16 },
+}
+
+impl KeylessRouting {
+    fn to_routing_info(&self) -> RoutingInfo {
+        match self {
+            KeylessRouting::Random => RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random),
+            KeylessRouting::AllPrimaries(policy) => {
+                RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllMasters, *policy))
+            }
+            KeylessRouting::AllNodes(policy) => {
+                RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllNodes, *policy))
+            }
+            KeylessRouting::ByAddress { host, port } => {
+                RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+                    host: host.clone(),
+                    port: *port,
+                })
+            }
+        }
+    }
+}
+
+/// Routes of keyless commands that redis-rs would otherwise route by the slot of their first argument, as if it were
+/// a key. Other keyless commands, such as PING, DBSIZE or LOLWUT, are already routed by redis-rs.
+fn default_routing(command: &[u8]) -> Option<KeylessRouting> {
+    match command {
+        // Connection flags, which have to be set on the connections to all nodes.
+        b"CLIENT NO-EVICT" | b"CLIENT NO-TOUCH" => {
+            Some(KeylessRouting::AllNodes(Some(ResponsePolicy::AllSucceeded)))
+        }
+        b"CLIENT LIST" | b"COMMAND DOCS" | b"COMMAND INFO" => Some(KeylessRouting::Random),
+        _ => None,
+    }
+}
+
+/// The names of the commands that have a default route, which are checked before the command's full name is built.
+const DEFAULT_ROUTED_NAMES: [&[u8]; 2] = [b"CLIENT", b"COMMAND"];
+
+/// Returns true if the name of `cmd`, without its subcommand, is `name`, which is uppercase.
+fn is_named(cmd: &Cmd, name: &[u8]) -> bool {
+    cmd.arg_idx(0)
+        .is_some_and(|arg| arg.eq_ignore_ascii_case(name))
+}
+
+/// The routes of keyless commands, which are shared by all clones of a client. Routes that were set by the user take
+/// precedence over the defaults. They're read on every cluster command, so they're replaced as a whole when a route is
+/// set rather than locked.
+#[derive(Clone, Default)]
+pub(super) struct KeylessRoutingTable {
+    overrides: Arc<ArcSwap<HashMap<Vec<u8>, KeylessRouting>>>,
+}
+
+impl KeylessRoutingTable {
+    /// Sets the route of `command`, e.g. `DBSIZE` or `CLIENT LIST`. If `routing` is `None`, the default route is
+    /// restored. Commands that are routed by the slot of their keys are rejected, since routing them elsewhere would
+    /// only get them redirected.
+    pub(super) fn set(&self, command: &str, routing: Option<KeylessRouting>) -> RedisResult<()> {
+        let command = command.to_ascii_uppercase().into_bytes();
+        if RoutingInfo::is_key_routing_command(&command) && default_routing(&command).is_none() {
+            return Err(RedisError::from((
+                ErrorKind::ClientError,
+                "Only keyless commands can be routed",
+                format!(
+                    "{} is routed by the slot of its keys",
+                    String::from_utf8_lossy(&command)
+                ),
+            )));
+        }
+        self.overrides.rcu(|overrides| {
+            let mut overrides = HashMap::clone(overrides);
+            match &routing {
+                Some(routing) => {
+                    overrides.insert(command.clone(), routing.clone());
+                }
+                None => {
+                    overrides.remove(&command);
+                }
+            }
+            overrides
+        });
+        Ok(())
+    }
+
+    /// Returns the route of `cmd`, if it's a command whose route was set, or one that has a default route here.
+    pub(super) fn routing_for(&self, cmd: &Cmd) -> Option<RoutingInfo> {
+        let overrides = self.overrides.load();
+        // The full name of the command is only built for commands that might have a route here.
+        let might_be_routed = DEFAULT_ROUTED_NAMES.iter().any(|name| is_named(cmd, name))
+            || overrides.keys().any(|command| {
+                is_named(
+                    cmd,
+                    command
+                        .split(|byte| *byte == b' ')
+                        .next()
+                        .unwrap_or_default(),
+                )
+            });
+        if !might_be_routed {
+            return None;
+        }
+        let command = cmd.command()?;
+        overrides
+            .get(&command)
+            .cloned()
+            .or_else(|| default_routing(&command))
+            .map(|routing| routing.to_routing_info())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyless_commands_use_set_or_default_routes() {
+        let table = KeylessRoutingTable::default();
+        let mut client_list = redis::cmd("CLIENT");
+        client_list.arg("LIST");
+        assert_eq!(
+            table.routing_for(&client_list),
+            Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random))
+        );
+        let dbsize = redis::cmd("DBSIZE");
+        assert_eq!(table.routing_for(&dbsize), None);
+
+        table
+            .set("client list", Some(KeylessRouting::AllNodes(None)))
+            .unwrap();
+        table
+            .set(
+                "dbsize",
+                Some(KeylessRouting::ByAddress {
+                    host: "node".to_string(),
+                    port: 6379,
+                }),
+            )
+            .unwrap();
+        assert_eq!(
+            table.routing_for(&client_list),
+            Some(RoutingInfo::MultiNode((
+                MultipleNodeRoutingInfo::AllNodes,
+                None
+            )))
+        );
+        assert_eq!(
+            table.clone().routing_for(&dbsize),
+            Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+                host: "node".to_string(),
+                port: 6379
+            }))
+        );
+
+        table.set("CLIENT LIST", None).unwrap();
+        assert_eq!(
+            table.routing_for(&client_list),
+            Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random))
+        );
+    }
+
+    #[test]
+    fn test_keyed_commands_cant_be_routed() {
+        let table = KeylessRoutingTable::default();
+        for command in ["get", "MGET", "XREAD"] {
+            let err = table
+                .set(command, Some(KeylessRouting::AllNodes(None)))
+                .unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::ClientError);
+        }
+        let mut get = redis::cmd("GET");
+        get.arg("key");
+        assert_eq!(table.routing_for(&get), None);
+        // Keyless commands that redis-rs would route by their first argument have default routes.
+        table
+            .set("COMMAND INFO", Some(KeylessRouting::AllNodes(None)))
+            .unwrap();
+    }
+}
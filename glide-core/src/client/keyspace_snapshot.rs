@@ -0,0 +1,147 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::Value;
+use std::collections::BTreeMap;
+
+/// The number of keys in a single database.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DatabaseKeyCounts {
+    pub keys: u64,
+    /// The number of keys with an expiry.
+    pub expires: u64,
+}
+
+/// An approximate, deployment-wide summary of the keyspace.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeyspaceSnapshot {
+    /// The number of keys in the client's database, summed across all primaries.
+    pub total_keys: u64,
+    /// The key counts of every non-empty database, summed across all primaries, by database name (`db0`, `db1`, etc.).
+    pub databases: BTreeMap<String, DatabaseKeyCounts>,
+    /// The number of sampled keys of each type (`string`, `hash`, etc.). Empty if no keys were sampled.
+    pub sampled_types: BTreeMap<String, u64>,
+}
+
+impl KeyspaceSnapshot {
+    /// Returns the value that is passed to the wrappers - a map with `total_keys`, `databases` and `sampled_types` fields.
+    pub fn to_value(&self) -> Value {
+        let string = |value: &str| Value::SimpleString(value.to_string());
+        let databases = self
+            .databases
+            .iter()
+            .map(|(name, counts)| {
+                (
+                    string(name),
+                    Value::Map(vec![
+                        (string("keys"), Value::Int(counts.keys as i64)),
+                        (string("expires"), Value::Int(counts.expires as i64)),
+                    ]),
+                )
+            })
+            .collect();
+        let sampled_types = self
+            .sampled_types
+            .iter()
+            .map(|(key_type, count)| (string(key_type), Value::Int(*count as i64)))
+            .collect();
+        Value::Map(vec![
+            (string("total_keys"), Value::Int(self.total_keys as i64)),
+            (string("databases"), Value::Map(databases)),
+            (string("sampled_types"), Value::Map(sampled_types)),
+        ])
+    }
+
+    /// Adds the database key counts from the `keyspace` section of a single node's INFO reply.
+    pub(super) fn add_keyspace_info(&mut self, info: &str) {
+        for line in info.lines() {
+            let Some((database, fields)) = line.trim().split_once(':') else {
+                continue;
+            };
+            if !database.starts_with("db") {
+                continue;
+            }
+            let counts = self.databases.entry(database.to_string()).or_default();
+            for field in fields.split(',') {
+                match field.split_once('=') {
+                    Some(("keys", keys)) => counts.keys += keys.parse::<u64>().unwrap_or(0),
+                    Some(("expires", expires)) => {
+                        counts.expires += expires.parse::<u64>().unwrap_or(0)
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyspace_info_is_summed_across_nodes() {
+        let mut snapshot = KeyspaceSnapshot::default();
+        snapshot.add_keyspace_info(
+            "# Keyspace\r\ndb0:keys=10,expires=2,avg_ttl=100\r\ndb3:keys=1,expires=0,avg_ttl=0\r\n",
+        );
+        snapshot.add_keyspace_info("# Keyspace\r\ndb0:keys=5,expires=1,avg_ttl=0\r\n");
+        assert_eq!(
+            snapshot.databases,
+            BTreeMap::from([
+                (
+                    "db0".to_string(),
+                    DatabaseKeyCounts {
+                        keys: 15,
+                        expires: 3
+                    }
+                ),
+                (
+                    "db3".to_string(),
+                    DatabaseKeyCounts {
+                        keys: 1,
+                        expires: 0
+                    }
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_snapshot_to_value() {
+        let snapshot = KeyspaceSnapshot {
+            total_keys: 4,
+            databases: BTreeMap::from([(
+                "db0".to_string(),
+                DatabaseKeyCounts {
+                    keys: 4,
+                    expires: 1,
+                },
+            )]),
+            sampled_types: BTreeMap::from([("hash".to_string(), 1), ("string".to_string(), 3)]),
+        };
+        let string = |value: &str| Value::SimpleString(value.to_string());
+        assert_eq!(
+            snapshot.to_value(),
+            Value::Map(vec![
+                (string("total_keys"), Value::Int(4)),
+                (
+                    string("databases"),
+                    Value::Map(vec![(
+                        string("db0"),
+                        Value::Map(vec![
+                            (string("keys"), Value::Int(4)),
+                            (string("expires"), Value::Int(1)),
+                        ])
+                    )])
+                ),
+                (
+                    string("sampled_types"),
+                    Value::Map(vec![
+                        (string("hash"), Value::Int(1)),
+                        (string("string"), Value::Int(3)),
+                    ])
+                ),
+            ])
+        );
+    }
+}
@@ -2,30 +2,71 @@
 
 mod types;
 
-use crate::cluster_scan_container::insert_cluster_scan_cursor;
+use self::adaptive_timeout::{AdaptiveTimeout, CLIENT_WIDE_LATENCY_KEY};
+use crate::clock;
+use crate::cluster_scan_container::{
+    get_cluster_scan_cursor, insert_cluster_scan_cursor, remove_scan_state_cursor,
+};
+use crate::connection_budget::{self, ConnectionShare};
+use crate::memory_budget;
 use crate::scripts_container::get_script;
-use futures::FutureExt;
+pub use arg_formatting::{float_arg, int_arg};
+pub use audit::{AuditEvent, AuditOutcome, AuditSink, LogAuditSink};
+use futures::{FutureExt, StreamExt};
+use hot_keys::HotKeyTracker;
+use idle_suspend::IdleSuspend;
+pub use key_sampling::{KeySample, SampledKey};
+pub use keyless_routing::KeylessRouting;
+use keyless_routing::KeylessRoutingTable;
+pub use keyspace_snapshot::{DatabaseKeyCounts, KeyspaceSnapshot};
 use logger_core::{log_info, log_warn};
+pub use prepared_transaction::PreparedTransaction;
+pub use readiness::{ReadinessCheck, ReadinessReport};
 use redis::aio::ConnectionLike;
 use redis::cluster_async::ClusterConnection;
 use redis::cluster_routing::{
-    MultipleNodeRoutingInfo, ResponsePolicy, Routable, RoutingInfo, SingleNodeRoutingInfo,
+    MultiSlotArgPattern, MultipleNodeRoutingInfo, ResponsePolicy, Routable, Route, RoutingInfo,
+    SingleNodeRoutingInfo, SlotAddr,
 };
-use redis::cluster_slotmap::ReadFromReplicaStrategy;
+use redis::cluster_slotmap::{ReadFromReplicaStrategy, RoutingWeights};
+pub use redis::cluster_topology::TopologySource;
 use redis::{
     ClusterScanArgs, Cmd, ErrorKind, FromRedisValue, PushInfo, RedisError, RedisResult,
     ScanStateRC, Value,
 };
+pub use serializer::{ArgSerializer, JsonSerializer};
+pub use session_recording::{parse_recording, RecordedExchange, SessionRecorder, REDACTED};
 pub use standalone_client::StandaloneClient;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::io;
 use std::sync::atomic::{AtomicIsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
+pub use sync_client::SyncClient;
+use telemetrylib::Telemetry;
 pub use types::*;
 
-use self::value_conversion::{convert_to_expected_type, expected_type_for_cmd, get_value_type};
+use self::prepared_transaction::convert_transaction_replies;
+use self::value_conversion::{
+    convert_to_expected_type, expected_type_for_client_cmd, get_value_type, ExpectedReturnType,
+};
+mod adaptive_timeout;
+mod arg_formatting;
+mod audit;
+mod hot_keys;
+mod idle_suspend;
+mod key_sampling;
+mod keyless_routing;
+mod keyspace_snapshot;
+mod prepared_transaction;
+mod readiness;
 mod reconnecting_connection;
+mod serializer;
+mod session_recording;
 mod standalone_client;
+mod sync_client;
+mod task_supervisor;
 mod value_conversion;
 use redis::InfoDict;
 use tokio::sync::mpsc;
@@ -39,6 +80,14 @@ pub const DEFAULT_PERIODIC_TOPOLOGY_CHECKS_INTERVAL: Duration = Duration::from_s
 /// Note: If you change the default value, make sure to change the documentation in *all* wrappers.
 pub const DEFAULT_CONNECTION_TIMEOUT: Duration = Duration::from_millis(250);
 pub const FINISHED_SCAN_CURSOR: &str = "finished";
+/// The base delay, in milliseconds, between attempts of an optimistic transaction. Kept low, since the conflicting
+/// writes are usually short.
+const OPTIMISTIC_TRANSACTION_BACKOFF_FACTOR: u32 = 10;
+/// The maximum number of keys in each MGET sent by [`Client::parallel_mget`], so that no single request blocks the
+/// server for long.
+const PARALLEL_MGET_BATCH_SIZE: usize = 500;
+/// The number of commands that [`Client::export_batch`] sends for each key: MULTI, TYPE, PTTL, DUMP and EXEC.
+const EXPORT_TRANSACTION_LEN: usize = 5;
 
 /// The value of 1000 for the maximum number of inflight requests is determined based on Little's Law in queuing theory:
 ///
@@ -73,6 +122,9 @@ pub(super) fn get_redis_connection_info(
     let db = connection_request.database_id;
     let client_name = connection_request.client_name.clone();
     let pubsub_subscriptions = connection_request.pubsub_subscriptions.clone();
+    let no_evict = connection_request.no_evict;
+    let no_touch = connection_request.no_touch;
+    let authenticator = connection_request.authenticator.clone();
     match &connection_request.authentication_info {
         Some(info) => redis::RedisConnectionInfo {
             db,
@@ -81,28 +133,54 @@ pub(super) fn get_redis_connection_info(
             protocol,
             client_name,
             pubsub_subscriptions,
+            no_evict,
+            no_touch,
+            authenticator,
         },
         None => redis::RedisConnectionInfo {
             db,
             protocol,
             client_name,
             pubsub_subscriptions,
+            no_evict,
+            no_touch,
+            authenticator,
             ..Default::default()
         },
     }
 }
 
+/// How the connections to the nodes are secured.
+#[derive(Clone, Default)]
+pub(super) struct NodeTls {
+    pub(super) mode: TlsMode,
+    /// The name that the nodes' certificates are verified against, if it differs from the hosts they're dialed at.
+    pub(super) server_name: Option<String>,
+}
+
+impl NodeTls {
+    pub(super) fn from_request(request: &ConnectionRequest) -> Self {
+        NodeTls {
+            mode: request.tls_mode.unwrap_or_default(),
+            server_name: request.tls_server_name.clone(),
+        }
+    }
+}
+
 pub(super) fn get_connection_info(
     address: &NodeAddress,
-    tls_mode: TlsMode,
+    tls: &NodeTls,
     redis_connection_info: redis::RedisConnectionInfo,
 ) -> redis::ConnectionInfo {
-    let addr = if tls_mode != TlsMode::NoTls {
+    let addr = if tls.mode != TlsMode::NoTls {
         redis::ConnectionAddr::TcpTls {
             host: address.host.to_string(),
             port: get_port(address),
-            insecure: tls_mode == TlsMode::InsecureTls,
-            tls_params: None,
+            insecure: tls.mode == TlsMode::InsecureTls,
+            tls_params: tls
+                .server_name
+                .clone()
+                .map(redis::TlsConnParams::with_server_name),
         }
     } else {
         redis::ConnectionAddr::Tcp(address.host.to_string(), get_port(address))
@@ -125,6 +203,62 @@ pub struct Client {
     request_timeout: Duration,
     // Setting this counter to limit the inflight requests, in case of any queue is blocked, so we return error to the customer.
     inflight_requests_allowed: Arc<AtomicIsize>,
+    // Derives the request timeout from the observed latencies, if configured.
+    adaptive_timeout: Option<Arc<AdaptiveTimeout>>,
+    // Shared with the internal client, so that updates apply to the following reads.
+    routing_weights: RoutingWeights,
+    // The routes of keyless commands that were sent without a route, in cluster mode.
+    keyless_routing: KeylessRoutingTable,
+    // Replication that writes wait for, if configured.
+    write_durability: Option<WriteDurability>,
+    // Whether TTL/PTTL replies are converted to a `KeyTtl`.
+    structured_ttl: bool,
+    // Whether TIME, LASTSAVE and OBJECT IDLETIME replies are converted to a `ServerTime`.
+    typed_time: bool,
+    // Encodes the typed arguments of commands.
+    serializer: Arc<dyn ArgSerializer>,
+    // Receives the audit events of the commands sent by this client, if configured.
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    // Attached to the audit events of the requests sent by this client.
+    audit_context: Option<Arc<str>>,
+    // Records the commands sent by this client and their replies, if configured.
+    session_recorder: Option<Arc<SessionRecorder>>,
+    // Counts the accesses of each key, if configured.
+    hot_keys: Option<Arc<HotKeyTracker>>,
+    // Replies larger than this many bytes are reported, if configured.
+    big_reply_threshold: Option<usize>,
+    // The connections reserved for this client from the process' connection budget.
+    connection_share: Arc<ConnectionShare>,
+    // Keeps the connection rate limit that this client requested, if configured.
+    _connection_rate_limit: Option<Arc<redis::connection_throttle::ConnectionRateLimitRequest>>,
+    // Closes the connections while the client is idle, if configured.
+    idle_suspend: Option<Arc<IdleSuspend>>,
+}
+
+/// The position of a scan that is driven by the client, such as in [`Client::delete_by_pattern`].
+#[derive(Clone, Default)]
+struct KeyScan {
+    cluster_cursor: ScanStateRC,
+    // Also used by scans of a single key, such as HSCAN, in cluster mode.
+    standalone_cursor: u64,
+}
+
+/// The position of a scan that is paginated by [`Client::scan_page`]. The default cursor starts a new scan.
+///
+/// A cursor should only be passed back with the target that it was created for.
+#[derive(Clone, Default)]
+pub struct ScanCursor {
+    scan: KeyScan,
+    // Items that were read from the server, but didn't fit in the previous page.
+    pending: VecDeque<Value>,
+    finished: bool,
+}
+
+impl ScanCursor {
+    /// Whether all the items were returned.
+    pub fn is_finished(&self) -> bool {
+        self.finished && self.pending.is_empty()
+    }
 }
 
 async fn run_with_timeout<T>(
@@ -222,9 +356,9 @@ fn get_timeout_from_cmd_arg(
     }
 }
 
-fn get_request_timeout(cmd: &Cmd, default_timeout: Duration) -> RedisResult<Option<Duration>> {
+fn get_request_timeout_option(cmd: &Cmd) -> RedisResult<RequestTimeoutOption> {
     let command = cmd.command().unwrap_or_default();
-    let timeout = match command.as_slice() {
+    match command.as_slice() {
         b"BLPOP" | b"BRPOP" | b"BLMOVE" | b"BZPOPMAX" | b"BZPOPMIN" | b"BRPOPLPUSH" => {
             get_timeout_from_cmd_arg(cmd, cmd.args_iter().len() - 1, TimeUnit::Seconds)
         }
@@ -235,9 +369,11 @@ fn get_request_timeout(cmd: &Cmd, default_timeout: Duration) -> RedisResult<Opti
             .unwrap_or(Ok(RequestTimeoutOption::ClientConfig)),
         b"WAIT" => get_timeout_from_cmd_arg(cmd, 2, TimeUnit::Milliseconds),
         _ => Ok(RequestTimeoutOption::ClientConfig),
-    }?;
+    }
+}
 
-    match timeout {
+fn get_request_timeout(cmd: &Cmd, default_timeout: Duration) -> RedisResult<Option<Duration>> {
+    match get_request_timeout_option(cmd)? {
         RequestTimeoutOption::NoTimeout => Ok(None),
         RequestTimeoutOption::ClientConfig => Ok(Some(default_timeout)),
         RequestTimeoutOption::BlockingCommand(blocking_cmd_duration) => {
@@ -246,56 +382,637 @@ fn get_request_timeout(cmd: &Cmd, default_timeout: Duration) -> RedisResult<Opti
     }
 }
 
+/// Rejects limits that the server would reject or misinterpret: a `COUNT` below 1 in SCAN, HSCAN, SSCAN and ZSCAN,
+/// and a negative `LIMIT` in SINTERCARD. Arguments that aren't integers are left for the server to reject.
+fn check_limit_args(cmd: &Cmd) -> RedisResult<()> {
+    let command = cmd.command().unwrap_or_default();
+    let (options_start, option, min) = match command.as_slice() {
+        b"SCAN" => (2, b"COUNT".as_slice(), 1),
+        b"HSCAN" | b"SSCAN" | b"ZSCAN" => (3, b"COUNT".as_slice(), 1),
+        b"SINTERCARD" => {
+            let numkeys = cmd
+                .arg_idx(1)
+                .and_then(|numkeys| std::str::from_utf8(numkeys).ok()?.parse::<usize>().ok());
+            match numkeys {
+                Some(numkeys) => (numkeys.saturating_add(2), b"LIMIT".as_slice(), 0),
+                None => return Ok(()),
+            }
+        }
+        _ => return Ok(()),
+    };
+    let mut index = options_start;
+    while let Some(arg) = cmd.arg_idx(index) {
+        if !arg.eq_ignore_ascii_case(option) {
+            index += 1;
+            continue;
+        }
+        let value = cmd
+            .arg_idx(index + 1)
+            .and_then(|value| std::str::from_utf8(value).ok()?.parse::<i64>().ok());
+        if let Some(value) = value.filter(|value| *value < min) {
+            return Err(RedisError::from((
+                ErrorKind::ClientError,
+                "Invalid limit",
+                format!(
+                    "{} must be at least {min}, got {value}",
+                    String::from_utf8_lossy(option)
+                ),
+            )));
+        }
+        // Skip the value, so that it is not mistaken for an option.
+        index += 2;
+    }
+    Ok(())
+}
+
+/// Returns the slot of the keys that `pattern` may form, as the server computes it for SORT, or `None` if they may be
+/// in any slot. That's the case unless the pattern has a non-empty hash tag before any wildcard or escaped character.
+fn sort_pattern_slot(pattern: &[u8]) -> Option<u16> {
+    let mut tag_start = None;
+    for (index, char) in pattern.iter().enumerate() {
+        match (char, tag_start) {
+            (b'*' | b'?' | b'[' | b'\\', _) => return None,
+            (b'{', None) => tag_start = Some(index),
+            (b'}', Some(start)) if index == start + 1 => return None,
+            (b'}', Some(start)) => {
+                return Some(redis::cluster_topology::get_slot(
+                    &pattern[start + 1..index],
+                ))
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Rejects SORT and SORT_RO commands whose BY or GET patterns may form keys in other slots than the sorted key, which
+/// the server rejects in cluster mode. A BY pattern without `*` skips the sorting and a `GET #` returns the elements
+/// themselves, so neither touches other keys.
+fn check_cluster_sort_patterns(cmd: &Cmd) -> RedisResult<()> {
+    let command = cmd.command().unwrap_or_default();
+    if !matches!(command.as_slice(), b"SORT" | b"SORT_RO") {
+        return Ok(());
+    }
+    let Some(key) = cmd.arg_idx(1) else {
+        return Ok(());
+    };
+    let key_slot = redis::cluster_topology::get_slot(key);
+    let mut index = 2;
+    while let Some(arg) = cmd.arg_idx(index) {
+        let (option, touches_keys): (&str, fn(&[u8]) -> bool) = if arg.eq_ignore_ascii_case(b"BY") {
+            ("BY", |pattern| pattern.contains(&b'*'))
+        } else if arg.eq_ignore_ascii_case(b"GET") {
+            ("GET", |pattern| pattern != b"#")
+        } else {
+            index += if arg.eq_ignore_ascii_case(b"LIMIT") {
+                3
+            } else if arg.eq_ignore_ascii_case(b"STORE") {
+                2
+            } else {
+                1
+            };
+            continue;
+        };
+        let Some(pattern) = cmd.arg_idx(index + 1) else {
+            return Ok(());
+        };
+        if touches_keys(pattern) && sort_pattern_slot(pattern) != Some(key_slot) {
+            return Err(RedisError::from((
+                ErrorKind::ClientError,
+                "Unsupported SORT pattern in cluster mode",
+                format!(
+                    "the keys formed by the {option} pattern `{}` may be in other slots than the key `{}`. Add a \
+                     hash tag that matches the key's slot, e.g. `{{tag}}`, before any wildcard in the pattern",
+                    String::from_utf8_lossy(pattern),
+                    String::from_utf8_lossy(key)
+                ),
+            )));
+        }
+        index += 2;
+    }
+    Ok(())
+}
+
+fn is_readonly(cmd: &Cmd) -> bool {
+    cmd.command()
+        .is_some_and(|command| redis::cluster_routing::is_readonly_cmd(&command))
+}
+
+/// Returns the route of the primary that `cmd` writes to, so that `WAIT` is sent on the same connection.
+/// Returns `None` for reads, and for commands whose primary isn't known before they're sent.
+fn write_durability_routing(cmd: &Cmd, routing: Option<&RoutingInfo>) -> Option<RoutingInfo> {
+    if is_readonly(cmd) {
+        return None;
+    }
+    let routing = match routing {
+        Some(routing) => routing.clone(),
+        None => RoutingInfo::for_routable(cmd)?,
+    };
+    match routing {
+        RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))
+            if route.slot_addr() == SlotAddr::Master =>
+        {
+            Some(RoutingInfo::SingleNode(
+                SingleNodeRoutingInfo::SpecificNode(route),
+            ))
+        }
+        RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress { host, port }) => {
+            Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+                host,
+                port,
+            }))
+        }
+        _ => None,
+    }
+}
+
+/// Logs a warning and records a big reply in the telemetry if `value`, the reply to `cmd`, is larger than `threshold`
+/// bytes. Returns the size of the reply if it was reported.
+fn report_big_reply(cmd: &Cmd, value: &Value, threshold: usize) -> Option<usize> {
+    let size = redis::byte_counters::response_size(value);
+    if size <= threshold {
+        return None;
+    }
+    let command = String::from_utf8_lossy(&cmd.command().unwrap_or_default()).into_owned();
+    let key = redis::cluster_routing::command_keys(cmd)
+        .first()
+        .map(|key| String::from_utf8_lossy(key).into_owned());
+    let key_description = match &key {
+        Some(key) => format!(" on key `{key}`"),
+        None => String::new(),
+    };
+    log_warn(
+        "big reply",
+        format!(
+            "Received a reply of {size} bytes to {command}{key_description}, above the threshold of {threshold} bytes"
+        ),
+    );
+    Telemetry::record_big_reply(&command, key.as_deref(), size);
+    Some(size)
+}
+
+/// Reports the big replies among `replies`, which are the replies to the commands of `pipeline` in order, like
+/// [`report_big_reply`].
+fn report_big_pipeline_replies(pipeline: &redis::Pipeline, replies: &[Value], threshold: usize) {
+    for (cmd, reply) in pipeline.cmd_iter().zip(replies) {
+        report_big_reply(cmd, reply, threshold);
+    }
+}
+
+/// Returns the slots that the commands of `pipeline` write to. Writes whose slot isn't known before they're sent, such
+/// as keyless ones, are skipped.
+fn batch_write_slots(pipeline: &redis::Pipeline) -> BTreeSet<u16> {
+    let mut slots = BTreeSet::new();
+    for cmd in pipeline.cmd_iter().filter(|cmd| !is_readonly(cmd)) {
+        match RoutingInfo::for_routable(cmd) {
+            Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))) => {
+                slots.insert(route.slot());
+            }
+            Some(RoutingInfo::MultiNode((MultipleNodeRoutingInfo::MultiSlot((routes, _)), _))) => {
+                slots.extend(routes.iter().map(|(route, _)| route.slot()));
+            }
+            _ => {}
+        }
+    }
+    slots
+}
+
 impl Client {
     pub fn send_command<'a>(
         &'a mut self,
         cmd: &'a Cmd,
         routing: Option<RoutingInfo>,
     ) -> redis::RedisFuture<'a, Value> {
-        let expected_type = expected_type_for_cmd(cmd);
-        let request_timeout = match get_request_timeout(cmd, self.request_timeout) {
+        let expected_type = expected_type_for_client_cmd(cmd, self.structured_ttl, self.typed_time);
+        let routing = match self.internal_client {
+            ClientWrapper::Cluster { .. } => {
+                routing.or_else(|| self.keyless_routing.routing_for(cmd))
+            }
+            ClientWrapper::Standalone(_) => routing,
+        };
+        // Blocking commands wait on the server side, so their latency doesn't reflect the node's responsiveness.
+        let latency_tracking = self
+            .adaptive_timeout
+            .clone()
+            .filter(|_| {
+                matches!(
+                    get_request_timeout_option(cmd),
+                    Ok(RequestTimeoutOption::ClientConfig)
+                )
+            })
+            .map(|adaptive_timeout| (adaptive_timeout, self.latency_key(cmd, routing.as_ref())));
+        let default_timeout = match &latency_tracking {
+            Some((adaptive_timeout, node)) => {
+                adaptive_timeout.timeout_for(node, self.request_timeout)
+            }
+            None => self.request_timeout,
+        };
+        let request_timeout = match get_request_timeout(cmd, default_timeout) {
             Ok(request_timeout) => request_timeout,
             Err(err) => {
                 return async { Err(err) }.boxed();
             }
         };
-        run_with_timeout(request_timeout, async move {
-            match self.internal_client {
+        if let Err(err) = check_limit_args(cmd) {
+            return async { Err(err) }.boxed();
+        }
+        if let ClientWrapper::Cluster { .. } = self.internal_client {
+            if let Err(err) = check_cluster_sort_patterns(cmd)
+                .and_then(|_| redis::cluster_routing::declared_keys_slot(cmd))
+            {
+                return async { Err(err) }.boxed();
+            }
+        }
+        if let Some(hot_keys) = &self.hot_keys {
+            hot_keys.record_command(cmd);
+        }
+        let Some(memory_reservation) = memory_budget::try_reserve(memory_budget::cmd_size(cmd))
+        else {
+            return async { Err(memory_budget::cap_reached_error()) }.boxed();
+        };
+        let audit = self
+            .audit_sink
+            .clone()
+            .map(|sink| (sink, self.audit_context.clone()));
+        let session_recorder = self.session_recorder.clone();
+        let big_reply_threshold = self.big_reply_threshold;
+        let active_request = self.start_request();
+        let start = clock::now();
+        let result = run_with_timeout(request_timeout, async move {
+            let result = match self.internal_client {
                 ClientWrapper::Standalone(ref mut client) => client.send_command(cmd).await,
                 ClientWrapper::Cluster { ref mut client } => {
-                    let routing =
-                        if let Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)) =
-                            routing
-                        {
-                            let cmd_name = cmd.command().unwrap_or_default();
-                            let cmd_name = String::from_utf8_lossy(&cmd_name);
-                            if redis::cluster_routing::is_readonly_cmd(cmd_name.as_bytes()) {
-                                // A read-only command, go ahead and send it to a random node
-                                RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)
-                            } else {
-                                // A "Random" node was selected, but the command is a "@write" command
-                                // change the routing to "RandomPrimary"
-                                log_warn(
+                    let routing = if let Some(RoutingInfo::SingleNode(
+                        SingleNodeRoutingInfo::Random,
+                    )) = routing
+                    {
+                        let cmd_name = cmd.command().unwrap_or_default();
+                        let cmd_name = String::from_utf8_lossy(&cmd_name);
+                        if redis::cluster_routing::is_readonly_cmd(cmd_name.as_bytes()) {
+                            // A read-only command, go ahead and send it to a random node
+                            RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)
+                        } else {
+                            // A "Random" node was selected, but the command is a "@write" command
+                            // change the routing to "RandomPrimary"
+                            log_warn(
                                     "send_command",
                                     format!(
                                         "User provided 'Random' routing which is not suitable for the writeable command '{cmd_name}'. Changing it to 'RandomPrimary'"
                                     ),
                                 );
-                                RoutingInfo::SingleNode(SingleNodeRoutingInfo::RandomPrimary)
-                            }
-                        } else {
-                            routing
-                                .or_else(|| RoutingInfo::for_routable(cmd))
-                                .unwrap_or(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random))
-                        };
+                            RoutingInfo::SingleNode(SingleNodeRoutingInfo::RandomPrimary)
+                        }
+                    } else {
+                        routing
+                            .or_else(|| RoutingInfo::for_routable(cmd))
+                            .unwrap_or(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random))
+                    };
                     client.route_command(cmd, routing).await
                 }
+            };
+            if let (Some(threshold), Ok(value)) = (big_reply_threshold, &result) {
+                report_big_reply(cmd, value, threshold);
             }
-            .and_then(|value| convert_to_expected_type(value, expected_type))
-        })
+            result
+        });
+        async move {
+            let result = result.await;
+            // Recorded after the timeout, so that requests that timed out are recorded too.
+            if let Some(session_recorder) = session_recorder {
+                session_recorder.record(cmd, &result);
+            }
+            let result = result.and_then(|value| convert_to_expected_type(value, expected_type));
+            drop(active_request);
+            drop(memory_reservation);
+            if let Some((adaptive_timeout, node)) = latency_tracking {
+                adaptive_timeout.record(&node, clock::elapsed(start));
+            }
+            if let Some((sink, context)) = audit {
+                audit::record_command(sink.as_ref(), cmd, context.as_deref(), &result);
+            }
+            result
+        }
         .boxed()
     }
 
+    /// Sends `cmd` without waiting for its reply, for writes that may be lost, such as high-volume telemetry. Errors
+    /// that the server replies with are lost too, so only failures to send the command are returned.
+    ///
+    /// The command is preceded by `CLIENT REPLY SKIP`, so the server doesn't reply at all, and the call returns once the
+    /// command was written. If the server doesn't allow `CLIENT REPLY`, the reply is awaited and discarded instead, and
+    /// its error is returned. In cluster mode the command is sent to a single node - the primary of its keys' slot, or
+    /// a random primary for keyless commands.
+    pub async fn send_command_without_reply(
+        &mut self,
+        cmd: &Cmd,
+        routing: Option<RoutingInfo>,
+    ) -> RedisResult<()> {
+        check_limit_args(cmd)?;
+        if let Some(hot_keys) = &self.hot_keys {
+            hot_keys.record_command(cmd);
+        }
+        let Some(_memory_reservation) = memory_budget::try_reserve(memory_budget::cmd_size(cmd))
+        else {
+            return Err(memory_budget::cap_reached_error());
+        };
+        let _active_request = self.start_request();
+        // There's no reply to record, so a command that was sent is recorded with a nil reply.
+        let result = self
+            .send_command_without_reply_to_server(cmd, routing)
+            .await
+            .map(|()| Value::Nil);
+        if let Some(session_recorder) = &self.session_recorder {
+            session_recorder.record(cmd, &result);
+        }
+        if let Some(sink) = &self.audit_sink {
+            audit::record_command(sink.as_ref(), cmd, self.audit_context.as_deref(), &result);
+        }
+        result.map(|_| ())
+    }
+
+    async fn send_command_without_reply_to_server(
+        &mut self,
+        cmd: &Cmd,
+        routing: Option<RoutingInfo>,
+    ) -> RedisResult<()> {
+        match self.internal_client {
+            ClientWrapper::Standalone(ref mut client) => {
+                run_with_timeout(
+                    Some(self.request_timeout),
+                    client.send_command_without_reply(cmd),
+                )
+                .await
+            }
+            ClientWrapper::Cluster { ref mut client } => {
+                let route = match routing.or_else(|| RoutingInfo::for_routable(cmd)) {
+                    Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)) | None => {
+                        SingleNodeRoutingInfo::RandomPrimary
+                    }
+                    Some(RoutingInfo::SingleNode(route)) => route,
+                    Some(RoutingInfo::MultiNode(_)) => {
+                        return Err((
+                            ErrorKind::ClientError,
+                            "Commands without a reply must be routed to a single node",
+                        )
+                            .into());
+                    }
+                };
+                run_with_timeout(
+                    Some(self.request_timeout),
+                    client.route_command_without_reply(cmd, route),
+                )
+                .await
+            }
+        }
+    }
+
+    /// Returns the timeout of a `WAIT`, which is the request timeout if `timeout` is zero, since `WAIT` would otherwise
+    /// block until the replicas acknowledged the writes.
+    fn wait_timeout(&self, timeout: Duration) -> Duration {
+        if timeout.is_zero() {
+            self.request_timeout
+        } else {
+            timeout
+        }
+    }
+
+    /// Sends `cmd`, and if write durability is configured and `cmd` is a write to a single primary, issues `WAIT`
+    /// on that primary afterwards. Returns the response along with the number of replicas that acknowledged the write.
+    ///
+    /// A failure of the `WAIT` itself doesn't fail the request, since the write was already applied.
+    pub async fn send_command_with_write_durability(
+        &mut self,
+        cmd: &Cmd,
+        routing: Option<RoutingInfo>,
+    ) -> RedisResult<(Value, Option<u32>)> {
+        let wait_routing = match self.write_durability {
+            Some(_) => write_durability_routing(cmd, routing.as_ref()),
+            None => None,
+        };
+        let value = self.send_command(cmd, routing).await?;
+        let (Some(write_durability), Some(wait_routing)) = (self.write_durability, wait_routing)
+        else {
+            return Ok((value, None));
+        };
+        let mut wait_cmd = redis::cmd("WAIT");
+        wait_cmd
+            .arg(write_durability.num_replicas)
+            .arg(self.wait_timeout(write_durability.timeout).as_millis() as u64);
+        let replication_count = match self.send_command(&wait_cmd, Some(wait_routing)).await {
+            Ok(Value::Int(count)) => u32::try_from(count).ok(),
+            Ok(value) => {
+                log_warn(
+                    "write durability",
+                    format!("Received unexpected response for WAIT: {value:?}"),
+                );
+                None
+            }
+            Err(err) => {
+                log_warn("write durability", format!("WAIT failed: {err}"));
+                None
+            }
+        };
+        Ok((value, replication_count))
+    }
+
+    /// Issues `WAIT` on each primary that `pipeline` wrote to, once the pipeline was sent, and returns the number of
+    /// replicas that acknowledged the writes of each shard, by the address of its primary. Since `WAIT` covers all the
+    /// preceding writes on its connection, the count of a shard holds for every write of the batch to that shard.
+    ///
+    /// The primaries are those that own the written slots when this is called, so a batch that was interrupted by a
+    /// slot migration might not be fully covered. Writes whose slot isn't known before they're sent are skipped.
+    pub async fn wait_for_batch(
+        &mut self,
+        pipeline: &redis::Pipeline,
+        num_replicas: u32,
+        timeout: Duration,
+    ) -> RedisResult<HashMap<String, u32>> {
+        let mut wait_cmd = redis::cmd("WAIT");
+        wait_cmd
+            .arg(num_replicas)
+            .arg(self.wait_timeout(timeout).as_millis() as u64);
+        let parse_count = |value: Value| -> RedisResult<u32> {
+            match value {
+                Value::Int(count) => u32::try_from(count).map_err(|_| {
+                    RedisError::from((
+                        ErrorKind::ResponseError,
+                        "Received unexpected response for WAIT",
+                        count.to_string(),
+                    ))
+                }),
+                value => Err(RedisError::from((
+                    ErrorKind::ResponseError,
+                    "Received unexpected response for WAIT",
+                    format!("{value:?}"),
+                ))),
+            }
+        };
+        let primaries = match self.internal_client {
+            ClientWrapper::Standalone(ref client) => {
+                if pipeline.cmd_iter().all(is_readonly) {
+                    return Ok(HashMap::new());
+                }
+                let address = client.primary_address();
+                let count = parse_count(self.send_command(&wait_cmd, None).await?)?;
+                return Ok(HashMap::from([(address, count)]));
+            }
+            ClientWrapper::Cluster { ref client } => {
+                let slots = batch_write_slots(pipeline);
+                // Several slots may share a primary, and the WAITs sent to the same primary would be queued one after
+                // the other, so the slots are grouped by their primary in the slot map.
+                let mut primaries = BTreeSet::new();
+                let mut unknown_slots = Vec::new();
+                for slot in slots {
+                    match client.primary_for_route(&Route::new(slot, SlotAddr::Master)) {
+                        Some(address) => {
+                            primaries.insert(address);
+                        }
+                        None => unknown_slots.push(slot),
+                    }
+                }
+                if !unknown_slots.is_empty() {
+                    // The primaries of the slots that aren't in the slot map are found by the addresses that reply to a
+                    // PING in each slot.
+                    let routes = unknown_slots
+                        .into_iter()
+                        .map(|slot| (Route::new(slot, SlotAddr::Master), Vec::new()))
+                        .collect();
+                    let routing = RoutingInfo::MultiNode((
+                        MultipleNodeRoutingInfo::MultiSlot((routes, MultiSlotArgPattern::KeysOnly)),
+                        None,
+                    ));
+                    let response = self
+                        .send_command(&redis::cmd("PING"), Some(routing))
+                        .await?;
+                    let Value::Map(replies) = response else {
+                        return Err(RedisError::from((
+                            ErrorKind::ResponseError,
+                            "Received unexpected response for PING",
+                            format!("{response:?}"),
+                        )));
+                    };
+                    for (address, _) in replies {
+                        primaries.insert(String::from_owned_redis_value(address)?);
+                    }
+                }
+                primaries
+            }
+        };
+        let waits = primaries.into_iter().map(|address| {
+            let mut client = self.clone();
+            let wait_cmd = &wait_cmd;
+            async move {
+                let Some((host, port)) = address
+                    .rsplit_once(':')
+                    .and_then(|(host, port)| Some((host.to_string(), port.parse().ok()?)))
+                else {
+                    return Err(RedisError::from((
+                        ErrorKind::ClientError,
+                        "Invalid node address",
+                        address,
+                    )));
+                };
+                let routing =
+                    RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress { host, port });
+                let count = parse_count(client.send_command(wait_cmd, Some(routing)).await?)?;
+                Ok((address, count))
+            }
+        });
+        futures::future::try_join_all(waits)
+            .await
+            .map(|counts| counts.into_iter().collect())
+    }
+
+    /// Returns up to `count` of the keys that this client accessed most often, with their estimated access counts,
+    /// hottest first. The estimates may exceed the actual counts, but never fall below them. Each key of a
+    /// multi-key command counts as an access.
+    ///
+    /// Fails if the client wasn't created with `hot_keys_tracked`.
+    pub fn hottest_keys(&self, count: usize) -> RedisResult<Vec<(Vec<u8>, u64)>> {
+        match &self.hot_keys {
+            Some(hot_keys) => Ok(hot_keys.hottest_keys(count)),
+            None => Err(RedisError::from((
+                ErrorKind::ClientError,
+                "Hot key tracking is disabled",
+                "the client wasn't created with `hot_keys_tracked`".to_string(),
+            ))),
+        }
+    }
+
+    /// Replaces the serializer used by [`Client::append_typed_arg`]. Only affects this client, and the clients that
+    /// are cloned from it afterwards.
+    pub fn set_serializer(&mut self, serializer: Arc<dyn ArgSerializer>) {
+        self.serializer = serializer;
+    }
+
+    /// Serializes `value` with the client's serializer, and appends it to `cmd` as a single argument.
+    pub fn append_typed_arg<T: serde::Serialize + ?Sized>(
+        &self,
+        cmd: &mut Cmd,
+        value: &T,
+    ) -> RedisResult<()> {
+        serializer::append_typed_arg(self.serializer.as_ref(), cmd, value)
+    }
+
+    /// Replaces the sink that receives the audit events of this client's commands, or stops auditing if `None`.
+    /// Only affects this client, and the clients that are cloned from it afterwards.
+    pub fn set_audit_sink(&mut self, audit_sink: Option<Arc<dyn AuditSink>>) {
+        self.audit_sink = audit_sink;
+    }
+
+    /// Sets the context that is attached to the audit events of the commands sent by this client, such as the
+    /// identity of the end user on whose behalf they are sent.
+    pub fn set_audit_context(&mut self, audit_context: Option<&str>) {
+        self.audit_context = audit_context.map(Arc::from);
+    }
+
+    /// Replaces the recorder of this client's commands and their replies, or stops recording if `None`.
+    /// Only affects this client, and the clients that are cloned from it afterwards.
+    pub fn set_session_recorder(&mut self, session_recorder: Option<Arc<SessionRecorder>>) {
+        self.session_recorder = session_recorder;
+    }
+
+    /// Returns the key under which the latency of `cmd` is tracked - the node's address when it is known before
+    /// the command is sent, which in cluster mode is the primary of the command's slot, or a client-wide key otherwise.
+    fn latency_key(&self, cmd: &Cmd, routing: Option<&RoutingInfo>) -> String {
+        if let Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress { host, port })) =
+            routing
+        {
+            return format!("{host}:{port}");
+        }
+        match self.internal_client {
+            ClientWrapper::Standalone(ref client) => {
+                let is_readonly = cmd
+                    .command()
+                    .is_some_and(|command| redis::cluster_routing::is_readonly_cmd(&command));
+                // Writes are always sent to the primary, while reads may be spread across the replicas.
+                if is_readonly && client.has_replicas() {
+                    CLIENT_WIDE_LATENCY_KEY.to_string()
+                } else {
+                    client.primary_address()
+                }
+            }
+            ClientWrapper::Cluster { ref client } => {
+                let computed_routing;
+                let routing = match routing {
+                    Some(routing) => Some(routing),
+                    None => {
+                        computed_routing = RoutingInfo::for_routable(cmd);
+                        computed_routing.as_ref()
+                    }
+                };
+                // Reads that may be served by a replica are tracked together, like in standalone mode.
+                match routing {
+                    Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))) => {
+                        client.primary_for_route(route)
+                    }
+                    _ => None,
+                }
+                .unwrap_or_else(|| CLIENT_WIDE_LATENCY_KEY.to_string())
+            }
+        }
+    }
+
     // Cluster scan is not passed to redis-rs as a regular command, so we need to handle it separately.
     // We send the command to a specific function in the redis-rs cluster client, which internally handles the
     // the complication of a command scan, and generate the command base on the logic in the redis-rs library.
@@ -314,6 +1031,7 @@ impl Client {
         scan_state_cursor: &'a ScanStateRC,
         cluster_scan_args: ClusterScanArgs,
     ) -> RedisResult<Value> {
+        let _active_request = self.start_request();
         match self.internal_client {
             ClientWrapper::Standalone(_) => {
                 unreachable!("Cluster scan is not supported in standalone mode")
@@ -332,10 +1050,649 @@ impl Client {
         }
     }
 
-    fn get_transaction_values(
+    /// Publishes `message` to `channel`, and returns the number of subscribers that received it.
+    /// If `sharded` is set, the message is published with SPUBLISH, which in cluster mode is sent to the primary that
+    /// owns the channel's slot. Otherwise PUBLISH is used, which the cluster propagates to all nodes.
+    pub async fn publish(
+        &mut self,
+        channel: &[u8],
+        message: &[u8],
+        sharded: bool,
+    ) -> RedisResult<i64> {
+        let (cmd, routing) = publish_cmd(channel, message, sharded);
+        redis::from_owned_redis_value(self.send_command(&cmd, routing).await?)
+    }
+
+    /// Publishes `message` to each of `channels`, and returns the number of subscribers that received it on each
+    /// channel, in the order of `channels`. The messages are sent in a single pipeline, so they're published one after
+    /// the other in the order of `channels`, with a single round trip. If `sharded` is set in cluster mode, the
+    /// channels are grouped by the primary of their slot instead, and each group is sent in its own pipeline to that
+    /// primary, so the order is only kept among the channels of a primary.
+    ///
+    /// In cluster mode, the count of a message that isn't sharded only includes the subscribers that are connected to
+    /// the node that the pipeline was sent to, since PUBLISH replies before the message is propagated to the other
+    /// nodes. It isn't the number of subscribers in the whole cluster.
+    pub async fn publish_to_channels(
+        &mut self,
+        channels: &[&[u8]],
+        message: &[u8],
+        sharded: bool,
+    ) -> RedisResult<Vec<i64>> {
+        let batches = match self.internal_client {
+            ClientWrapper::Standalone(_) => publish_batches(channels, sharded, None),
+            ClientWrapper::Cluster { ref client } => {
+                let node_for_slot =
+                    |slot: u16| client.primary_for_route(&Route::new(slot, SlotAddr::Master));
+                publish_batches(channels, sharded, Some(&node_for_slot))
+            }
+        };
+        let requests = batches.into_iter().map(|(route, indices)| {
+            let mut client = self.clone();
+            async move {
+                let mut pipeline = redis::pipe();
+                for index in indices.iter() {
+                    pipeline.add_command(publish_cmd(channels[*index], message, sharded).0);
+                }
+                let values = client.send_pipeline_to_node(&pipeline, route).await?;
+                Ok::<_, RedisError>((indices, values))
+            }
+        });
+        let mut counts = vec![0; channels.len()];
+        for (indices, values) in futures::future::try_join_all(requests).await? {
+            for (index, value) in indices.into_iter().zip(values) {
+                counts[index] = redis::from_owned_redis_value(value)?;
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Sends the commands of `pipeline` without a transaction, and returns their replies. Standalone clients send it to
+    /// the primary, regardless of `route`.
+    async fn send_pipeline_to_node(
+        &mut self,
         pipeline: &redis::Pipeline,
+        route: SingleNodeRoutingInfo,
+    ) -> RedisResult<Vec<Value>> {
+        let count = pipeline.cmd_iter().count();
+        let big_reply_threshold = self.big_reply_threshold;
+        let _active_request = self.start_request();
+        let replies = run_with_timeout(Some(self.request_timeout), async move {
+            match self.internal_client {
+                ClientWrapper::Standalone(ref mut client) => {
+                    client.send_pipeline(pipeline, 0, count).await
+                }
+                ClientWrapper::Cluster { ref mut client } => {
+                    client.route_pipeline(pipeline, 0, count, route).await
+                }
+            }
+        })
+        .await;
+        if let (Some(threshold), Ok(replies)) = (big_reply_threshold, &replies) {
+            report_big_pipeline_replies(pipeline, replies, threshold);
+        }
+        replies
+    }
+
+    /// Reads `keys` with MGETs that are sent in parallel, at most `max_concurrency` requests at a time. In cluster mode
+    /// the keys are grouped by slot, so that no MGET fails on keys from different slots, and the MGETs of the slots that
+    /// share a primary are pipelined together, so that each node gets as few requests as possible.
+    ///
+    /// Returns the value of each key, in the order of `keys`. If a request fails, each of its keys gets the error,
+    /// while the keys of the other requests are unaffected.
+    pub async fn parallel_mget(
+        &mut self,
+        keys: &[&[u8]],
+        max_concurrency: usize,
+    ) -> Vec<RedisResult<Value>> {
+        let batches = match self.internal_client {
+            ClientWrapper::Standalone(_) => mget_batches(keys, None),
+            ClientWrapper::Cluster { ref client } => {
+                let node_for_slot =
+                    |slot: u16| client.primary_for_route(&Route::new(slot, SlotAddr::Master));
+                mget_batches(keys, Some(&node_for_slot))
+            }
+        };
+        let mut results: Vec<RedisResult<Value>> = Vec::with_capacity(keys.len());
+        results.resize_with(keys.len(), || Ok(Value::Nil));
+        let mut batch_results = futures::stream::iter(batches.into_iter().map(|mgets| {
+            let mut client = self.clone();
+            async move {
+                let mut pipeline = redis::pipe();
+                for indices in mgets.iter() {
+                    let mut cmd = redis::cmd("MGET");
+                    for index in indices.iter() {
+                        cmd.arg(keys[*index]);
+                    }
+                    pipeline.add_command(cmd);
+                }
+                let replies = match pipeline.cmd_iter().next() {
+                    Some(cmd) if mgets.len() == 1 => client
+                        .send_command(cmd, None)
+                        .await
+                        .map(|value| vec![value]),
+                    _ => {
+                        // All the MGETs of the request are served by the shard of the first one.
+                        let route = SingleNodeRoutingInfo::SpecificNode(Route::new(
+                            redis::cluster_topology::get_slot(keys[mgets[0][0]]),
+                            SlotAddr::ReplicaOptional,
+                        ));
+                        client.send_pipeline_to_node(&pipeline, route).await
+                    }
+                };
+                let result = replies.and_then(|replies| {
+                    mgets
+                        .iter()
+                        .zip(replies)
+                        .map(|(indices, reply)| match reply {
+                            Value::Array(values) if values.len() == indices.len() => Ok(values),
+                            value => Err(RedisError::from((
+                                ErrorKind::ResponseError,
+                                "Received unexpected response for MGET",
+                                format!("{value:?}"),
+                            ))),
+                        })
+                        .collect::<RedisResult<Vec<_>>>()
+                });
+                (mgets, result)
+            }
+        }))
+        .buffer_unordered(max_concurrency.max(1));
+        while let Some((mgets, result)) = batch_results.next().await {
+            match result {
+                Ok(replies) => {
+                    for (indices, values) in mgets.into_iter().zip(replies) {
+                        for (index, value) in indices.into_iter().zip(values) {
+                            results[index] = Ok(value);
+                        }
+                    }
+                }
+                Err(err) => {
+                    for index in mgets.into_iter().flatten() {
+                        results[index] = Err(RedisError::from((
+                            err.kind(),
+                            "MGET failed",
+                            err.to_string(),
+                        )));
+                    }
+                }
+            }
+        }
+        results
+    }
+
+    /// Deletes all keys that match `pattern`, on all primaries, by scanning them in batches and unlinking each batch.
+    /// Unlike `KEYS` + `DEL`, no single request blocks the server for long.
+    ///
+    /// Returns the number of deleted keys, along with the number of keys deleted so far after each batch. Keys that
+    /// are written while the operation runs might not be deleted.
+    pub async fn delete_by_pattern(
+        &mut self,
+        pattern: &[u8],
+        options: DeleteByPatternOptions,
+    ) -> RedisResult<DeleteByPatternResult> {
+        let start = clock::now();
+        let mut deleted: u64 = 0;
+        let mut progress = Vec::new();
+        let mut scan = KeyScan::default();
+        loop {
+            let (keys, finished) = self
+                .scan_keys(&mut scan, pattern, options.batch_size)
+                .await?;
+
+            if !keys.is_empty() {
+                let mut unlink = redis::cmd("UNLINK");
+                unlink.arg(&keys);
+                let unlinked: u64 =
+                    redis::from_owned_redis_value(self.send_command(&unlink, None).await?)?;
+                deleted += unlinked;
+                progress.push(deleted);
+                if let Some(max_keys_per_second) = options.max_keys_per_second {
+                    // Wait until the average rate since the start drops back to the limit.
+                    let expected_duration =
+                        Duration::from_secs_f64(deleted as f64 / max_keys_per_second.max(1) as f64);
+                    if let Some(delay) = expected_duration.checked_sub(clock::elapsed(start)) {
+                        tokio::time::sleep(delay).await;
+                    }
+                }
+            }
+
+            if finished {
+                return Ok(DeleteByPatternResult { deleted, progress });
+            }
+        }
+    }
+
+    /// Returns the next batch of keys that match `pattern`, and whether the scan finished.
+    async fn scan_keys(
+        &mut self,
+        scan: &mut KeyScan,
+        pattern: &[u8],
+        batch_size: u32,
+    ) -> RedisResult<(Vec<Vec<u8>>, bool)> {
+        let request_timeout = self.request_timeout;
+        match self.internal_client {
+            ClientWrapper::Cluster { ref mut client } => {
+                let args = ClusterScanArgs::builder()
+                    .with_match_pattern(pattern)
+                    .with_count(batch_size)
+                    .build();
+                let (cursor, keys) = client
+                    .cluster_scan(scan.cluster_cursor.clone(), args)
+                    .await?;
+                let finished = cursor.is_finished();
+                scan.cluster_cursor = cursor;
+                Ok((redis::from_owned_redis_value(Value::Array(keys))?, finished))
+            }
+            ClientWrapper::Standalone(ref mut client) => {
+                let mut cmd = redis::cmd("SCAN");
+                cmd.arg(scan.standalone_cursor)
+                    .arg("MATCH")
+                    .arg(pattern)
+                    .arg("COUNT")
+                    .arg(batch_size);
+                // The cursor is only meaningful to the node that returned it, so don't spread the scan across replicas.
+                let response =
+                    run_with_timeout(Some(request_timeout), client.send_command_to_primary(&cmd))
+                        .await?;
+                let (cursor, keys): (u64, Vec<Vec<u8>>) = redis::from_owned_redis_value(response)?;
+                scan.standalone_cursor = cursor;
+                Ok((keys, cursor == 0))
+            }
+        }
+    }
+
+    /// Returns the next page of items of `target`, and advances `cursor` past them. The page ends once it holds
+    /// `options.max_items` items, once `options.time_budget` is exhausted, or once the scan finished, which is reported
+    /// by [`ScanCursor::is_finished`]. A page may be empty, e.g. if none of the scanned items matched the pattern.
+    ///
+    /// If an error is returned, `cursor` is left where the page started, so the page can be requested again.
+    pub async fn scan_page(
+        &mut self,
+        target: &ScanTarget,
+        cursor: &mut ScanCursor,
+        options: &ScanPageOptions,
+    ) -> RedisResult<Vec<Value>> {
+        if options.count == 0 || options.max_items == 0 {
+            return Err(RedisError::from((
+                ErrorKind::ClientError,
+                "Invalid scan page options",
+                "count and max_items must be positive".to_string(),
+            )));
+        }
+        let start = clock::now();
+        let mut items = Vec::new();
+        let mut sent_command = false;
+        loop {
+            let room = options.max_items - items.len();
+            items.extend(cursor.pending.drain(..room.min(cursor.pending.len())));
+            let out_of_time = sent_command && clock::elapsed(start) >= options.time_budget;
+            if items.len() == options.max_items || out_of_time || cursor.is_finished() {
+                return Ok(items);
+            }
+            let batch = self
+                .scan_batch(
+                    target,
+                    &mut cursor.scan,
+                    options.pattern.as_deref(),
+                    options.count,
+                )
+                .await;
+            let (batch, finished) = match batch {
+                Ok(batch) => batch,
+                Err(err) => {
+                    for item in items.into_iter().rev() {
+                        cursor.pending.push_front(item);
+                    }
+                    return Err(err);
+                }
+            };
+            sent_command = true;
+            cursor.pending.extend(batch);
+            cursor.finished = finished;
+        }
+    }
+
+    /// Returns the next batch of items of `target`, and whether the scan finished.
+    async fn scan_batch(
+        &mut self,
+        target: &ScanTarget,
+        scan: &mut KeyScan,
+        pattern: Option<&[u8]>,
+        count: u32,
+    ) -> RedisResult<(Vec<Value>, bool)> {
+        let (command, key, in_pairs) = match target {
+            ScanTarget::Keys => {
+                let (keys, finished) = self
+                    .scan_keys(scan, pattern.unwrap_or(b"*".as_slice()), count)
+                    .await?;
+                return Ok((keys.into_iter().map(Value::BulkString).collect(), finished));
+            }
+            ScanTarget::Hash(key) => ("HSCAN", key, true),
+            ScanTarget::Set(key) => ("SSCAN", key, false),
+            ScanTarget::SortedSet(key) => ("ZSCAN", key, true),
+        };
+        let mut cmd = redis::cmd(command);
+        cmd.arg(key).arg(scan.standalone_cursor);
+        if let Some(pattern) = pattern {
+            cmd.arg("MATCH").arg(pattern);
+        }
+        cmd.arg("COUNT").arg(count);
+        // The cursor is only meaningful to the node that returned it, so don't spread the scan across replicas.
+        let request_timeout = self.request_timeout;
+        let response = match self.internal_client {
+            ClientWrapper::Standalone(ref mut client) => {
+                run_with_timeout(Some(request_timeout), client.send_command_to_primary(&cmd))
+                    .await?
+            }
+            ClientWrapper::Cluster { ref mut client } => {
+                let routing = RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(
+                    Route::new(redis::cluster_topology::get_slot(key), SlotAddr::Master),
+                ));
+                run_with_timeout(Some(request_timeout), client.route_command(&cmd, routing)).await?
+            }
+        };
+        let (cursor, items): (u64, Vec<Value>) = redis::from_owned_redis_value(response)?;
+        scan.standalone_cursor = cursor;
+        let items = if in_pairs {
+            let mut items = items.into_iter();
+            std::iter::from_fn(|| Some(Value::Array(vec![items.next()?, items.next()?]))).collect()
+        } else {
+            items
+        };
+        Ok((items, cursor == 0))
+    }
+
+    /// Exports the keys of the next SCAN batch of `batch_size` keys that match `pattern`, on all primaries, and advances
+    /// `cursor` past them. The export finished once [`ScanCursor::is_finished`] returns true. Each key holds its type,
+    /// its remaining TTL and its DUMP payload, which can be restored with [`Client::import_keys`]. A batch may be
+    /// empty, e.g. if none of the scanned keys matched the pattern.
+    ///
+    /// Keys that are deleted while the operation runs are skipped. If an error is returned, `cursor` is left where the
+    /// batch started, so the batch can be requested again.
+    pub async fn export_keys(
+        &mut self,
+        cursor: &mut ScanCursor,
+        pattern: &[u8],
+        batch_size: u32,
+    ) -> RedisResult<Vec<ExportedKey>> {
+        if cursor.is_finished() {
+            return Ok(Vec::new());
+        }
+        let mut scan = cursor.scan.clone();
+        let (keys, finished) = self.scan_keys(&mut scan, pattern, batch_size).await?;
+        let batches = match self.internal_client {
+            ClientWrapper::Standalone(_) => vec![keys],
+            ClientWrapper::Cluster { ref client } => {
+                let node_for_slot =
+                    |slot: u16| client.primary_for_route(&Route::new(slot, SlotAddr::Master));
+                export_batches(keys, &node_for_slot)
+            }
+        };
+        let mut exported_keys = Vec::new();
+        for keys in batches {
+            exported_keys.extend(self.export_batch(keys).await?);
+        }
+        cursor.scan = scan;
+        cursor.finished = finished;
+        Ok(exported_keys)
+    }
+
+    /// Like [`Client::export_keys`], with a cursor that is passed as a string, like the cursors of
+    /// [`Client::cluster_scan`]. An empty cursor starts the export. Returns an array of the next cursor, which is
+    /// `finished` once the export completed, and of the exported keys, see [`ExportedKey::to_value`].
+    pub async fn export_keys_page(
+        &mut self,
+        cursor_id: &str,
+        pattern: &[u8],
+        batch_size: u32,
+    ) -> RedisResult<Value> {
+        let mut cursor = ScanCursor::default();
+        if !cursor_id.is_empty() {
+            match self.internal_client {
+                ClientWrapper::Cluster { .. } => {
+                    cursor.scan.cluster_cursor = get_cluster_scan_cursor(cursor_id.to_string())?;
+                }
+                ClientWrapper::Standalone(_) => {
+                    cursor.scan.standalone_cursor = cursor_id.parse().map_err(|_| {
+                        RedisError::from((
+                            ErrorKind::ClientError,
+                            "Invalid export cursor",
+                            cursor_id.to_string(),
+                        ))
+                    })?;
+                }
+            }
+        }
+        let exported_keys = self.export_keys(&mut cursor, pattern, batch_size).await?;
+        // A page's cursor is only passed back once, so it's replaced by the cursor of the next page.
+        if !cursor_id.is_empty() {
+            if let ClientWrapper::Cluster { .. } = self.internal_client {
+                remove_scan_state_cursor(cursor_id.to_string());
+            }
+        }
+        let next_cursor = if cursor.is_finished() {
+            FINISHED_SCAN_CURSOR.to_string()
+        } else {
+            match self.internal_client {
+                ClientWrapper::Cluster { .. } => {
+                    insert_cluster_scan_cursor(cursor.scan.cluster_cursor)
+                }
+                ClientWrapper::Standalone(_) => cursor.scan.standalone_cursor.to_string(),
+            }
+        };
+        Ok(Value::Array(vec![
+            Value::BulkString(next_cursor.into_bytes()),
+            Value::Array(
+                exported_keys
+                    .into_iter()
+                    .map(ExportedKey::to_value)
+                    .collect(),
+            ),
+        ]))
+    }
+
+    /// Reads the type, TTL and payload of each of `keys`, which are all served by the same primary, in a single
+    /// pipeline. Each key is read in a transaction of its own, so that its type, TTL and payload are consistent. Keys
+    /// that don't exist are skipped.
+    async fn export_batch(&mut self, keys: Vec<Vec<u8>>) -> RedisResult<Vec<ExportedKey>> {
+        let Some(first_key) = keys.first() else {
+            return Ok(Vec::new());
+        };
+        let route = SingleNodeRoutingInfo::SpecificNode(Route::new(
+            redis::cluster_topology::get_slot(first_key),
+            SlotAddr::Master,
+        ));
+        let mut pipeline = redis::pipe();
+        for key in keys.iter() {
+            pipeline.cmd("MULTI");
+            pipeline.cmd("TYPE").arg(key);
+            pipeline.cmd("PTTL").arg(key);
+            pipeline.cmd("DUMP").arg(key);
+            pipeline.cmd("EXEC");
+        }
+        let replies = self.send_pipeline_to_node(&pipeline, route).await?;
+        // Only the replies of the EXECs hold values, the other commands were only queued.
+        let exec_replies = replies
+            .into_iter()
+            .skip(EXPORT_TRANSACTION_LEN - 1)
+            .step_by(EXPORT_TRANSACTION_LEN);
+        let mut exported_keys = Vec::with_capacity(keys.len());
+        for (key, response) in keys.into_iter().zip(exec_replies) {
+            if let Some(exported_key) = exported_key(key, response)? {
+                exported_keys.push(exported_key);
+            }
+        }
+        Ok(exported_keys)
+    }
+
+    /// Restores keys that were exported by [`Client::export_keys`], using RESTORE. If `replace` is set, existing keys
+    /// are overwritten; otherwise, importing a key that already exists fails.
+    ///
+    /// Returns the number of imported keys. Stops on the first failure.
+    pub async fn import_keys(&mut self, keys: &[ExportedKey], replace: bool) -> RedisResult<u64> {
+        let mut imported: u64 = 0;
+        for exported_key in keys {
+            let mut restore = redis::cmd("RESTORE");
+            // A TTL of 0 means that the key doesn't expire, so a key that was about to expire is kept for 1ms instead.
+            let ttl = exported_key
+                .ttl
+                .map_or(0, |ttl| ttl.as_millis().clamp(1, u64::MAX as u128) as u64);
+            restore
+                .arg(&exported_key.key)
+                .arg(ttl)
+                .arg(&exported_key.payload);
+            if replace {
+                restore.arg("REPLACE");
+            }
+            self.send_command(&restore, None).await?;
+            imported += 1;
+        }
+        Ok(imported)
+    }
+
+    /// Sends a command of a keyspace snapshot to the primary, since replicas might lag behind it. In cluster mode,
+    /// `routing` must only select primaries.
+    async fn send_snapshot_command(
+        &mut self,
+        cmd: &Cmd,
+        routing: Option<RoutingInfo>,
+    ) -> RedisResult<Value> {
+        let request_timeout = self.request_timeout;
+        match self.internal_client {
+            ClientWrapper::Standalone(ref mut client) => {
+                run_with_timeout(Some(request_timeout), client.send_command_to_primary(cmd)).await
+            }
+            ClientWrapper::Cluster { .. } => self.send_command(cmd, routing).await,
+        }
+    }
+
+    /// Collects the key counts of all primaries, using DBSIZE and `INFO keyspace`. If `sample_size` isn't zero, also
+    /// samples that many keys with RANDOMKEY, and counts their types. All the commands are sent to primaries.
+    pub async fn keyspace_snapshot(&mut self, sample_size: u32) -> RedisResult<KeyspaceSnapshot> {
+        let mut snapshot = KeyspaceSnapshot {
+            total_keys: redis::from_owned_redis_value(
+                self.send_snapshot_command(&redis::cmd("DBSIZE"), None)
+                    .await?,
+            )?,
+            ..Default::default()
+        };
+
+        let mut info = redis::cmd("INFO");
+        info.arg("keyspace");
+        let all_primaries = RoutingInfo::MultiNode((
+            MultipleNodeRoutingInfo::AllMasters,
+            Some(ResponsePolicy::Special),
+        ));
+        match self
+            .send_snapshot_command(&info, Some(all_primaries))
+            .await?
+        {
+            Value::Map(node_infos) => {
+                for (_, node_info) in node_infos {
+                    snapshot.add_keyspace_info(&String::from_owned_redis_value(node_info)?);
+                }
+            }
+            node_info => snapshot.add_keyspace_info(&String::from_owned_redis_value(node_info)?),
+        }
+
+        let random_primary = RoutingInfo::SingleNode(SingleNodeRoutingInfo::RandomPrimary);
+        for _ in 0..sample_size {
+            let key = match self
+                .send_snapshot_command(&redis::cmd("RANDOMKEY"), Some(random_primary.clone()))
+                .await?
+            {
+                // The node that was selected might be empty, while others aren't.
+                Value::Nil => continue,
+                key => redis::from_owned_redis_value::<Vec<u8>>(key)?,
+            };
+            let key_primary = RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(
+                Route::new(redis::cluster_topology::get_slot(&key), SlotAddr::Master),
+            ));
+            let mut key_type = redis::cmd("TYPE");
+            key_type.arg(key);
+            let key_type: String = redis::from_owned_redis_value(
+                self.send_snapshot_command(&key_type, Some(key_primary))
+                    .await?,
+            )?;
+            *snapshot.sampled_types.entry(key_type).or_default() += 1;
+        }
+        Ok(snapshot)
+    }
+
+    /// Samples up to `sample_size` distinct random keys, drawn from all primaries in turns, and reads the memory usage
+    /// of each with `MEMORY USAGE`. If an LFU eviction policy is set, the access frequency of each key is read as well,
+    /// with `OBJECT FREQ`.
+    ///
+    /// Fewer keys are sampled if the keyspace is small, since the number of draws is bounded. Keys that are deleted
+    /// while the sample is taken are skipped.
+    pub async fn sample_keys(&mut self, sample_size: u32) -> RedisResult<KeySample> {
+        let sample_size = sample_size as usize;
+        let all_primaries = RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllMasters, None));
+        let mut keys: Vec<Vec<u8>> = Vec::with_capacity(sample_size);
+        let mut sampled = HashSet::new();
+        // Each round draws a key from every primary, so that the sample is spread across the shards.
+        for _ in 0..sample_size {
+            if keys.len() == sample_size {
+                break;
+            }
+            let drawn = match self
+                .send_command(&redis::cmd("RANDOMKEY"), Some(all_primaries.clone()))
+                .await?
+            {
+                Value::Map(node_keys) => node_keys.into_iter().map(|(_, key)| key).collect(),
+                key => vec![key],
+            };
+            for key in drawn {
+                if keys.len() == sample_size {
+                    break;
+                }
+                // The primary might be empty.
+                if key == Value::Nil {
+                    continue;
+                }
+                let key: Vec<u8> = redis::from_owned_redis_value(key)?;
+                if sampled.insert(key.clone()) {
+                    keys.push(key);
+                }
+            }
+        }
+
+        let mut sampled_keys = Vec::with_capacity(keys.len());
+        let mut lfu_enabled = true;
+        for key in keys {
+            let mut memory_usage = redis::cmd("MEMORY");
+            memory_usage.arg("USAGE").arg(&key);
+            let memory_usage = match self.send_command(&memory_usage, None).await? {
+                Value::Nil => continue,
+                memory_usage => redis::from_owned_redis_value(memory_usage)?,
+            };
+            let mut frequency = None;
+            if lfu_enabled {
+                let mut object_freq = redis::cmd("OBJECT");
+                object_freq.arg("FREQ").arg(&key);
+                match self.send_command(&object_freq, None).await {
+                    Ok(Value::Nil) => {}
+                    Ok(value) => frequency = Some(redis::from_owned_redis_value(value)?),
+                    // The server rejects OBJECT FREQ unless an LFU policy is set, and the wording of the error varies
+                    // between servers and versions, so any error reply means that the frequencies aren't available.
+                    Err(err) if err.code().is_some() => {
+                        lfu_enabled = false;
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+            sampled_keys.push(SampledKey {
+                key,
+                memory_usage,
+                frequency,
+            });
+        }
+        Ok(KeySample::new(sampled_keys))
+    }
+
+    fn get_transaction_values(
         mut values: Vec<Value>,
-        command_count: usize,
+        expected_types: &[Option<ExpectedReturnType>],
         offset: usize,
     ) -> RedisResult<Value> {
         assert_eq!(values.len(), 1);
@@ -365,52 +1722,239 @@ impl Client {
                     .into());
             }
         };
-        Self::convert_transaction_values_to_expected_types(pipeline, values, command_count)
+        convert_transaction_replies(values, expected_types)
     }
 
-    fn convert_transaction_values_to_expected_types(
-        pipeline: &redis::Pipeline,
-        values: Vec<Value>,
-        command_count: usize,
-    ) -> RedisResult<Value> {
-        let values = values
-            .into_iter()
-            .zip(pipeline.cmd_iter().map(expected_type_for_cmd))
-            .map(|(value, expected_type)| convert_to_expected_type(value, expected_type))
-            .try_fold(
-                Vec::with_capacity(command_count),
-                |mut acc, result| -> RedisResult<_> {
-                    acc.push(result?);
-                    Ok(acc)
-                },
-            )?;
-        Ok(Value::Array(values))
+    pub fn send_transaction<'a>(
+        &'a mut self,
+        pipeline: &'a redis::Pipeline,
+        routing: Option<RoutingInfo>,
+    ) -> redis::RedisFuture<'a, Value> {
+        let expected_types: Vec<_> = pipeline
+            .cmd_iter()
+            .map(|cmd| expected_type_for_client_cmd(cmd, self.structured_ttl, self.typed_time))
+            .collect();
+        self.send_transaction_with_expected_types(pipeline, Cow::Owned(expected_types), routing)
     }
 
-    pub fn send_transaction<'a>(
+    /// Decides once how the reply of each of the transaction's commands is converted, so that the transaction can be
+    /// sent repeatedly with [`Client::send_prepared_transaction`] without inspecting its commands again.
+    pub fn prepare_transaction(&self, pipeline: redis::Pipeline) -> PreparedTransaction {
+        PreparedTransaction::new(pipeline, self.structured_ttl, self.typed_time)
+    }
+
+    /// Like [`Client::send_transaction`], but the replies are converted as decided when `transaction` was prepared.
+    pub fn send_prepared_transaction<'a>(
+        &'a mut self,
+        transaction: &'a PreparedTransaction,
+        routing: Option<RoutingInfo>,
+    ) -> redis::RedisFuture<'a, Value> {
+        self.send_transaction_with_expected_types(
+            transaction.pipeline(),
+            Cow::Borrowed(transaction.expected_types()),
+            routing,
+        )
+    }
+
+    fn send_transaction_with_expected_types<'a>(
         &'a mut self,
         pipeline: &'a redis::Pipeline,
+        expected_types: Cow<'a, [Option<ExpectedReturnType<'static>>]>,
         routing: Option<RoutingInfo>,
     ) -> redis::RedisFuture<'a, Value> {
-        let command_count = pipeline.cmd_iter().count();
-        let offset = command_count + 1;
-        run_with_timeout(Some(self.request_timeout), async move {
-            let values = match self.internal_client {
+        let offset = expected_types.len() + 1;
+        if let Some(hot_keys) = &self.hot_keys {
+            for cmd in pipeline.cmd_iter() {
+                hot_keys.record_command(cmd);
+            }
+        }
+        let Some(memory_reservation) =
+            memory_budget::try_reserve(memory_budget::pipeline_size(pipeline))
+        else {
+            return async { Err(memory_budget::cap_reached_error()) }.boxed();
+        };
+        let audit = self
+            .audit_sink
+            .clone()
+            .map(|sink| (sink, self.audit_context.clone()));
+        let session_recorder = self.session_recorder.clone();
+        let big_reply_threshold = self.big_reply_threshold;
+        let active_request = self.start_request();
+        let values = run_with_timeout(Some(self.request_timeout), async move {
+            let _active_request = active_request;
+            let _memory_reservation = memory_reservation;
+            match self.internal_client {
                 ClientWrapper::Standalone(ref mut client) => {
                     client.send_pipeline(pipeline, offset, 1).await
                 }
 
-                ClientWrapper::Cluster { ref mut client } => match routing {
-                    Some(RoutingInfo::SingleNode(route)) => {
-                        client.route_pipeline(pipeline, offset, 1, route).await
+                ClientWrapper::Cluster { ref mut client } => match routing {
+                    Some(RoutingInfo::SingleNode(route)) => {
+                        client.route_pipeline(pipeline, offset, 1, route).await
+                    }
+                    _ => client.req_packed_commands(pipeline, offset, 1).await,
+                },
+            }
+        });
+        async move {
+            let values = values.await;
+            if let Some(session_recorder) = session_recorder {
+                session_recorder.record_transaction(pipeline, &values);
+            }
+            // The only reply is that of EXEC, which holds the replies of the transaction's commands.
+            if let (Some(threshold), Ok([Value::Array(replies)])) =
+                (big_reply_threshold, values.as_deref())
+            {
+                report_big_pipeline_replies(pipeline, replies, threshold);
+            }
+            let result = values
+                .and_then(|values| Self::get_transaction_values(values, &expected_types, offset));
+            if let Some((sink, context)) = audit {
+                audit::record_pipeline(sink.as_ref(), pipeline, context.as_deref(), &result);
+            }
+            result
+        }
+        .boxed()
+    }
+
+    /// Runs the transaction built by `build_transaction` while `watched_keys` are watched, and re-runs it from scratch
+    /// whenever `EXEC` is aborted because a watched key was modified, up to `max_retries` times with exponential backoff.
+    /// `build_transaction` is called after the keys are watched on every attempt, so it may read them in order to build
+    /// the transaction.
+    ///
+    /// `WATCH` and the transaction are sent on a connection to the keys' primary that isn't shared with other requests,
+    /// since their transactions would clear the watched keys. The keys are unwatched if an attempt fails.
+    ///
+    /// Returns `Value::Nil` if the transaction was still aborted after the last retry.
+    pub async fn run_optimistic_transaction<F, Fut>(
+        &mut self,
+        watched_keys: &[&[u8]],
+        routing: Option<RoutingInfo>,
+        max_retries: u32,
+        mut build_transaction: F,
+    ) -> RedisResult<Value>
+    where
+        F: FnMut(Client) -> Fut,
+        Fut: futures::Future<Output = RedisResult<redis::Pipeline>>,
+    {
+        let mut watch = redis::cmd("WATCH");
+        for key in watched_keys {
+            watch.arg(*key);
+        }
+        let (mut connection, _reservation) = self.dedicated_connection(&watch, routing).await?;
+        let mut backoff = crate::retry_strategies::get_exponential_backoff(
+            crate::retry_strategies::EXPONENT_BASE,
+            OPTIMISTIC_TRANSACTION_BACKOFF_FACTOR,
+            max_retries,
+        )
+        .get_iterator();
+        loop {
+            let _active_request = self.start_request();
+            let result = self
+                .run_watched_transaction(&mut connection, &watch, &mut build_transaction)
+                .await;
+            let result = match result {
+                Ok(result) => result,
+                Err(err) => {
+                    let _ = run_with_timeout(
+                        Some(self.request_timeout),
+                        connection.req_packed_command(&redis::cmd("UNWATCH")),
+                    )
+                    .await;
+                    return Err(err);
+                }
+            };
+            if result != Value::Nil {
+                return Ok(result);
+            }
+            let Some(delay) = backoff.next() else {
+                return Ok(Value::Nil);
+            };
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Opens a connection to the primary that `watch` would be routed to, which isn't shared with other requests. The
+    /// connection is reserved from the client's connection budget until the returned reservation is dropped.
+    async fn dedicated_connection(
+        &mut self,
+        watch: &Cmd,
+        routing: Option<RoutingInfo>,
+    ) -> RedisResult<(
+        redis::aio::MultiplexedConnection,
+        connection_budget::DedicatedConnectionReservation,
+    )> {
+        let reservation = self
+            .connection_share
+            .reserve_dedicated()
+            .map_err(|message| {
+                RedisError::from((
+                    ErrorKind::ClientError,
+                    connection_budget::BUDGET_EXHAUSTED,
+                    message,
+                ))
+            })?;
+        let connection = match self.internal_client {
+            ClientWrapper::Standalone(ref client) => client.dedicated_primary_connection().await,
+            ClientWrapper::Cluster { ref mut client } => {
+                // WATCH has to reach the same primary as the transaction, or it would have no effect.
+                match routing.or_else(|| RoutingInfo::for_routable(watch)) {
+                    Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))) => {
+                        client.dedicated_connection(route).await
                     }
-                    _ => client.req_packed_commands(pipeline, offset, 1).await,
-                },
-            }?;
+                    _ => Err((
+                        ErrorKind::ClientError,
+                        "Optimistic transactions must be routed to the primary of a single slot",
+                    )
+                        .into()),
+                }
+            }
+        }?;
+        Ok((connection, reservation))
+    }
 
-            Self::get_transaction_values(pipeline, values, command_count, offset)
-        })
-        .boxed()
+    /// Watches the keys, and runs the transaction that `build_transaction` returns on `connection`. Returns `Value::Nil`
+    /// if `EXEC` was aborted.
+    async fn run_watched_transaction<F, Fut>(
+        &self,
+        connection: &mut redis::aio::MultiplexedConnection,
+        watch: &Cmd,
+        build_transaction: &mut F,
+    ) -> RedisResult<Value>
+    where
+        F: FnMut(Client) -> Fut,
+        Fut: futures::Future<Output = RedisResult<redis::Pipeline>>,
+    {
+        run_with_timeout(
+            Some(self.request_timeout),
+            connection.req_packed_command(watch),
+        )
+        .await?;
+        let pipeline = build_transaction(self.clone()).await?;
+        let expected_types: Vec<_> = pipeline
+            .cmd_iter()
+            .map(|cmd| expected_type_for_client_cmd(cmd, self.structured_ttl, self.typed_time))
+            .collect();
+        let offset = expected_types.len() + 1;
+        let values = run_with_timeout(
+            Some(self.request_timeout),
+            connection.req_packed_commands(&pipeline, offset, 1),
+        )
+        .await;
+        if let Some(session_recorder) = &self.session_recorder {
+            session_recorder.record_transaction(&pipeline, &values);
+        }
+        let result =
+            values.and_then(|values| Self::get_transaction_values(values, &expected_types, offset));
+        if let Some(sink) = &self.audit_sink {
+            audit::record_pipeline(
+                sink.as_ref(),
+                &pipeline,
+                self.audit_context.as_deref(),
+                &result,
+            );
+        }
+        result
     }
 
     pub async fn invoke_script<'a>(
@@ -437,6 +1981,13 @@ impl Client {
         }
     }
 
+    /// Keeps the client from being suspended for being idle until the returned guard is dropped.
+    fn start_request(&self) -> Option<idle_suspend::ActiveRequest> {
+        self.idle_suspend
+            .as_ref()
+            .map(|idle_suspend| idle_suspend.start_request())
+    }
+
     pub fn reserve_inflight_request(&self) -> bool {
         // We use this approach of checking the `inflight_requests_allowed` value
         // twice, before and after decrementing, to prevent it from reaching negative
@@ -466,6 +2017,31 @@ impl Client {
             .fetch_add(1, Ordering::SeqCst)
     }
 
+    /// Replace the weights used to choose the replica that serves a read request, keyed by node address (`host:port`).
+    /// Nodes without a weight get the default weight, and empty weights restore the configured read strategy.
+    pub fn update_routing_weights(&self, weights: HashMap<String, u32>) {
+        log_info(
+            "update_routing_weights",
+            format!("Updated routing weights: {weights:?}"),
+        );
+        self.routing_weights.set(weights);
+    }
+
+    /// Sets where `command`, e.g. `DBSIZE` or `CLIENT LIST`, is sent in cluster mode when it's sent without a route.
+    /// This is meant for keyless commands - keyed commands are still redirected to the node that owns their slot.
+    /// If `routing` is `None`, the default route is restored. The route applies to all clones of this client.
+    /// Returns an error for commands that are routed by the slot of their keys.
+    pub fn set_keyless_routing(
+        &self,
+        command: &str,
+        routing: Option<KeylessRouting>,
+    ) -> RedisResult<()> {
+        let description = format!("Updated the route of {command}: {routing:?}");
+        self.keyless_routing.set(command, routing)?;
+        log_info("set_keyless_routing", description);
+        Ok(())
+    }
+
     /// Update the password used to authenticate with the servers.
     /// If None is passed, the password will be removed.
     /// If `immediate_auth` is true, the password will be used to authenticate with the servers immediately using the `AUTH` command.
@@ -507,6 +2083,15 @@ impl Client {
         }
     }
 
+    /// Returns the command that the cluster's topology was last read with - CLUSTER SHARDS, or CLUSTER SLOTS on servers
+    /// that don't support it. Used for debugging topology issues. `None` in standalone mode.
+    pub async fn topology_source(&mut self) -> RedisResult<Option<TopologySource>> {
+        match self.internal_client {
+            ClientWrapper::Standalone(_) => Ok(None),
+            ClientWrapper::Cluster { ref mut client } => client.topology_source().await,
+        }
+    }
+
     async fn send_immediate_auth(&mut self, password: Option<String>) -> RedisResult<Value> {
         match &password {
             Some(pw) if pw.is_empty() => Err(RedisError::from((
@@ -530,6 +2115,187 @@ impl Client {
     }
 }
 
+/// Returns the routing that pins requests carrying `affinity_token` to the same node, as long as the topology is stable.
+/// The node is the primary that owns the token's hash slot, so tokens follow their slot when it migrates.
+///
+/// Only requests that would otherwise be sent to a random node are pinned, since sending keyed requests elsewhere would
+/// only cause a redirection. Pass `None` as `cmd` for requests without keys that aren't represented by a single command,
+/// such as script invocations.
+pub fn affinity_routing(affinity_token: &[u8], cmd: Option<&Cmd>) -> Option<RoutingInfo> {
+    if affinity_token.is_empty() {
+        return None;
+    }
+    let routed_randomly = match cmd {
+        Some(cmd) => matches!(
+            RoutingInfo::for_routable(cmd),
+            None | Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random))
+        ),
+        None => true,
+    };
+    routed_randomly.then(|| {
+        RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+            redis::cluster_topology::get_slot(affinity_token),
+            SlotAddr::Master,
+        )))
+    })
+}
+
+/// Converts the reply of the transaction that read the type, TTL and payload of `key` in [`Client::export_batch`].
+/// Returns `None` if the key doesn't exist.
+fn exported_key(key: Vec<u8>, response: Value) -> RedisResult<Option<ExportedKey>> {
+    let (key_type, ttl, payload): (String, i64, Option<Vec<u8>>) = match response {
+        Value::Nil => return Ok(None),
+        response => redis::from_owned_redis_value(response)?,
+    };
+    let ttl = KeyTtl::from_reply(ttl, false);
+    let (Some(payload), KeyTtl::NoExpiry | KeyTtl::Ttl(_)) = (payload, ttl) else {
+        return Ok(None);
+    };
+    Ok(Some(ExportedKey {
+        key,
+        key_type,
+        ttl: match ttl {
+            KeyTtl::Ttl(ttl) => Some(ttl),
+            _ => None,
+        },
+        payload,
+    }))
+}
+
+/// Splits the keys of a cluster scan batch into the batches of [`Client::export_batch`], by the primary that serves
+/// them, as returned by `node_for_slot`. Keys whose primary isn't known are exported by the node of their own slot.
+fn export_batches(
+    keys: Vec<Vec<u8>>,
+    node_for_slot: &dyn Fn(u16) -> Option<String>,
+) -> Vec<Vec<Vec<u8>>> {
+    let mut nodes: BTreeMap<String, Vec<Vec<u8>>> = BTreeMap::new();
+    let mut slots: BTreeMap<u16, Vec<Vec<u8>>> = BTreeMap::new();
+    for key in keys {
+        let slot = redis::cluster_topology::get_slot(&key);
+        match node_for_slot(slot) {
+            Some(node) => nodes.entry(node).or_default().push(key),
+            None => slots.entry(slot).or_default().push(key),
+        }
+    }
+    nodes.into_values().chain(slots.into_values()).collect()
+}
+
+/// Splits the indices of `keys` into the requests of [`Client::parallel_mget`], each a list of MGETs of at most
+/// [`PARALLEL_MGET_BATCH_SIZE`] keys in total. In cluster mode, where `node_for_slot` returns the primary of a slot,
+/// each MGET only holds keys of a single slot, and the MGETs of a request are all served by the same primary. Slots
+/// whose primary isn't known get requests of their own.
+fn mget_batches(
+    keys: &[&[u8]],
+    node_for_slot: Option<&dyn Fn(u16) -> Option<String>>,
+) -> Vec<Vec<Vec<usize>>> {
+    let Some(node_for_slot) = node_for_slot else {
+        let indices: Vec<usize> = (0..keys.len()).collect();
+        return indices
+            .chunks(PARALLEL_MGET_BATCH_SIZE)
+            .map(|chunk| vec![chunk.to_vec()])
+            .collect();
+    };
+    let mut slots: BTreeMap<u16, Vec<usize>> = BTreeMap::new();
+    for (index, key) in keys.iter().enumerate() {
+        slots
+            .entry(redis::cluster_topology::get_slot(key))
+            .or_default()
+            .push(index);
+    }
+    let mut nodes: BTreeMap<Option<String>, Vec<Vec<Vec<usize>>>> = BTreeMap::new();
+    let mut requests = Vec::new();
+    for (slot, indices) in slots {
+        let node = node_for_slot(slot);
+        let mgets = indices
+            .chunks(PARALLEL_MGET_BATCH_SIZE)
+            .map(<[usize]>::to_vec);
+        if node.is_none() {
+            requests.extend(mgets.map(|mget| vec![mget]));
+            continue;
+        }
+        let node_requests = nodes.entry(node).or_default();
+        for mget in mgets {
+            let fits = node_requests
+                .last()
+                .is_some_and(|request: &Vec<Vec<usize>>| {
+                    request.iter().map(Vec::len).sum::<usize>() + mget.len()
+                        <= PARALLEL_MGET_BATCH_SIZE
+                });
+            if fits {
+                node_requests.last_mut().unwrap().push(mget);
+            } else {
+                node_requests.push(vec![mget]);
+            }
+        }
+    }
+    requests.extend(nodes.into_values().flatten());
+    requests
+}
+
+/// Groups the indices of `channels` into the pipelines that publish to them, along with the node that each pipeline is
+/// sent to. Sharded messages in cluster mode, where `node_for_slot` returns the primary of a slot, are grouped by the
+/// primary of their slot and sent to it. Slots whose primary isn't known get pipelines of their own. Other messages
+/// are published in a single pipeline, which may be sent to any node, since the cluster propagates PUBLISH to all
+/// nodes.
+fn publish_batches(
+    channels: &[&[u8]],
+    sharded: bool,
+    node_for_slot: Option<&dyn Fn(u16) -> Option<String>>,
+) -> Vec<(SingleNodeRoutingInfo, Vec<usize>)> {
+    if channels.is_empty() {
+        return vec![];
+    }
+    let Some(node_for_slot) = node_for_slot.filter(|_| sharded) else {
+        return vec![(SingleNodeRoutingInfo::Random, (0..channels.len()).collect())];
+    };
+    let mut slots: BTreeMap<u16, Vec<usize>> = BTreeMap::new();
+    for (index, channel) in channels.iter().enumerate() {
+        slots
+            .entry(redis::cluster_topology::get_slot(channel))
+            .or_default()
+            .push(index);
+    }
+    let mut nodes: BTreeMap<String, (u16, Vec<usize>)> = BTreeMap::new();
+    let mut batches = Vec::new();
+    for (slot, indices) in slots {
+        match node_for_slot(slot) {
+            Some(node) => nodes
+                .entry(node)
+                .or_insert_with(|| (slot, Vec::new()))
+                .1
+                .extend(indices),
+            None => batches.push((slot, indices)),
+        }
+    }
+    batches.extend(nodes.into_values());
+    batches
+        .into_iter()
+        .map(|(slot, mut indices)| {
+            // The order is kept among the channels of each node.
+            indices.sort_unstable();
+            (
+                SingleNodeRoutingInfo::SpecificNode(Route::new(slot, SlotAddr::Master)),
+                indices,
+            )
+        })
+        .collect()
+}
+
+/// Returns the publish command for `channel`, and its routing.
+fn publish_cmd(channel: &[u8], message: &[u8], sharded: bool) -> (Cmd, Option<RoutingInfo>) {
+    let mut cmd = redis::cmd(if sharded { "SPUBLISH" } else { "PUBLISH" });
+    cmd.arg(channel).arg(message);
+    // Sharded messages only reach the subscribers of the slot's shard. Replicas accept SPUBLISH without redirecting
+    // it, so it's routed explicitly to the primary. Standalone clients ignore the routing.
+    let routing = sharded.then(|| {
+        RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+            redis::cluster_topology::get_slot(channel),
+            SlotAddr::Master,
+        )))
+    });
+    (cmd, routing)
+}
+
 fn load_cmd(code: &[u8]) -> Cmd {
     let mut cmd = redis::cmd("SCRIPT");
     cmd.arg("LOAD").arg(code);
@@ -557,14 +2323,17 @@ fn to_duration(time_in_millis: Option<u32>, default: Duration) -> Duration {
 async fn create_cluster_client(
     request: ConnectionRequest,
     push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
+    routing_weights: RoutingWeights,
+    connections_check: redis::cluster::ConnectionsCheck,
 ) -> RedisResult<redis::cluster_async::ClusterConnection> {
     // TODO - implement timeout for each connection attempt
-    let tls_mode = request.tls_mode.unwrap_or_default();
+    let node_tls = NodeTls::from_request(&request);
+    let tls_mode = node_tls.mode;
     let redis_connection_info = get_redis_connection_info(&request);
     let initial_nodes: Vec<_> = request
         .addresses
         .into_iter()
-        .map(|address| get_connection_info(&address, tls_mode, redis_connection_info.clone()))
+        .map(|address| get_connection_info(&address, &node_tls, redis_connection_info.clone()))
         .collect();
     let periodic_topology_checks = match request.periodic_checks {
         Some(PeriodicCheck::Disabled) => None,
@@ -575,7 +2344,9 @@ async fn create_cluster_client(
     let connection_timeout = to_duration(request.connection_timeout, DEFAULT_CONNECTION_TIMEOUT);
     let mut builder = redis::cluster::ClusterClientBuilder::new(initial_nodes)
         .connection_timeout(connection_timeout)
-        .retries(DEFAULT_RETRIES);
+        .retries(DEFAULT_RETRIES)
+        .routing_weights(routing_weights)
+        .connections_check(connections_check);
     let read_from_strategy = request.read_from.unwrap_or_default();
     builder = builder.read_from(match read_from_strategy {
         ReadFrom::AZAffinity(az) => ReadFromReplicaStrategy::AZAffinity(az),
@@ -589,6 +2360,9 @@ async fn create_cluster_client(
     if let Some(client_name) = redis_connection_info.client_name {
         builder = builder.client_name(client_name);
     }
+    builder = builder
+        .no_evict(redis_connection_info.no_evict)
+        .no_touch(redis_connection_info.no_touch);
     if tls_mode != TlsMode::NoTls {
         let tls = if tls_mode == TlsMode::SecureTls {
             redis::cluster::TlsMode::Secure
@@ -597,6 +2371,12 @@ async fn create_cluster_client(
         };
         builder = builder.tls(tls);
     }
+    if let Some(server_name) = request.tls_server_name {
+        builder = builder.tls_server_name(server_name);
+    }
+    if request.override_slot_discovery {
+        builder = builder.pinned_slots(request.pinned_slots);
+    }
     if let Some(pubsub_subscriptions) = redis_connection_info.pubsub_subscriptions.clone() {
         builder = builder.pubsub_subscriptions(pubsub_subscriptions);
     }
@@ -661,6 +2441,12 @@ pub enum ConnectionError {
     Standalone(standalone_client::StandaloneClientConnectionError),
     Cluster(redis::RedisError),
     Timeout,
+    /// The connection request couldn't be applied, e.g. because a file that it refers to couldn't be opened.
+    InvalidConfiguration(String),
+    /// The runtime that drives a [`SyncClient`] couldn't be created.
+    Runtime(io::Error),
+    /// The connections of the client would exceed the limit set by [`crate::connection_budget::set_connection_limit`].
+    ConnectionBudgetExhausted(String),
 }
 
 impl std::fmt::Debug for ConnectionError {
@@ -669,6 +2455,14 @@ impl std::fmt::Debug for ConnectionError {
             Self::Standalone(arg0) => f.debug_tuple("Standalone").field(arg0).finish(),
             Self::Cluster(arg0) => f.debug_tuple("Cluster").field(arg0).finish(),
             Self::Timeout => write!(f, "Timeout"),
+            Self::InvalidConfiguration(arg0) => {
+                f.debug_tuple("InvalidConfiguration").field(arg0).finish()
+            }
+            Self::Runtime(arg0) => f.debug_tuple("Runtime").field(arg0).finish(),
+            Self::ConnectionBudgetExhausted(arg0) => f
+                .debug_tuple("ConnectionBudgetExhausted")
+                .field(arg0)
+                .finish(),
         }
     }
 }
@@ -679,6 +2473,14 @@ impl std::fmt::Display for ConnectionError {
             ConnectionError::Standalone(err) => write!(f, "{err:?}"),
             ConnectionError::Cluster(err) => write!(f, "{err}"),
             ConnectionError::Timeout => f.write_str("connection attempt timed out"),
+            ConnectionError::InvalidConfiguration(err) => write!(f, "invalid configuration: {err}"),
+            ConnectionError::Runtime(err) => write!(f, "failed to create a runtime: {err}"),
+            ConnectionError::ConnectionBudgetExhausted(err) => {
+                write!(
+                    f,
+                    "the connection budget of the process is exhausted: {err}"
+                )
+            }
         }
     }
 }
@@ -777,8 +2579,90 @@ fn sanitized_request_string(request: &ConnectionRequest) -> String {
         request.inflight_requests_limit,
     );
 
+    let adaptive_timeout = request
+        .adaptive_timeout
+        .as_ref()
+        .map(|config| {
+            format!(
+                "\nAdaptive timeout: p{} x{} bounded by [{:?}, {:?}]",
+                config.percentile, config.multiplier, config.min_timeout, config.max_timeout
+            )
+        })
+        .unwrap_or_default();
+
+    let write_durability = request
+        .write_durability
+        .as_ref()
+        .map(|config| {
+            format!(
+                "\nWrite durability: {} replicas within {:?}",
+                config.num_replicas, config.timeout
+            )
+        })
+        .unwrap_or_default();
+
+    let structured_ttl = if request.structured_ttl {
+        "\nStructured TTL"
+    } else {
+        ""
+    };
+    let typed_time = if request.typed_time {
+        "\nTyped time"
+    } else {
+        ""
+    };
+
+    let client_flags = match (request.no_evict, request.no_touch) {
+        (true, true) => "\nClient flags: NO-EVICT, NO-TOUCH",
+        (true, false) => "\nClient flags: NO-EVICT",
+        (false, true) => "\nClient flags: NO-TOUCH",
+        (false, false) => "",
+    };
+
+    let audit_log = if request.audit_log { "\nAudit log" } else { "" };
+    let session_recording =
+        format_optional_value("Session recording", request.session_recording_path.as_ref());
+    let hot_keys_tracked = format_optional_value("Hot keys tracked", request.hot_keys_tracked);
+    let big_reply_threshold =
+        format_optional_value("Big reply threshold", request.big_reply_threshold);
+    let idle_timeout = format_optional_value("Idle timeout", request.idle_timeout);
+    let memory_cap = format_optional_value("Memory cap", request.memory_cap);
+    let connection_limit = format_optional_value("Connection limit", request.connection_limit);
+    let connection_rate_limit = match request.connection_rate_limit {
+        Some(limit) => format!(
+            "\nConnection rate limit: burst {}, {} attempts per second",
+            limit.burst, limit.attempts_per_second
+        ),
+        None => String::new(),
+    };
+    let readiness_checks = match &request.readiness_checks {
+        Some(checks) => format!(
+            "\nReadiness checks: modules [{}], commands [{}]{}",
+            checks.required_modules.join(", "),
+            checks.required_commands.join(", "),
+            if checks.disconnect {
+                ", then disconnect"
+            } else {
+                ""
+            }
+        ),
+        None => String::new(),
+    };
+    let tls_server_name =
+        format_optional_value("TLS server name", request.tls_server_name.as_ref());
+    let pinned_slots = if request.override_slot_discovery {
+        let ranges: Vec<String> = request
+            .pinned_slots
+            .iter()
+            .map(|pinned| format!("{}-{} -> {}", pinned.start, pinned.end, pinned.address))
+            .collect();
+        format!("\nPinned slots: {}", ranges.join(", "))
+    } else {
+        String::new()
+    };
+
     format!(
-        "\nAddresses: {addresses}{tls_mode}{cluster_mode}{request_timeout}{connection_timeout}{rfr_strategy}{connection_retry_strategy}{database_id}{protocol}{client_name}{periodic_checks}{pubsub_subscriptions}{inflight_requests_limit}",
+        "\nAddresses: {addresses}{tls_mode}{tls_server_name}{cluster_mode}{request_timeout}{connection_timeout}{rfr_strategy}{connection_retry_strategy}{database_id}{protocol}{client_name}{periodic_checks}{pubsub_subscriptions}{inflight_requests_limit}{adaptive_timeout}{write_durability}{structured_ttl}{typed_time}{client_flags}{audit_log}{session_recording}{hot_keys_tracked}{big_reply_threshold}{idle_timeout}{memory_cap}{connection_rate_limit}{connection_limit}{readiness_checks}{pinned_slots}",
     )
 }
 
@@ -793,6 +2677,9 @@ impl Client {
             "Connection configuration",
             sanitized_request_string(&request),
         );
+        request
+            .validate()
+            .map_err(ConnectionError::InvalidConfiguration)?;
         let request_timeout = to_duration(request.request_timeout, DEFAULT_RESPONSE_TIMEOUT);
         let inflight_requests_limit = request
             .inflight_requests_limit
@@ -800,29 +2687,378 @@ impl Client {
         let inflight_requests_allowed = Arc::new(AtomicIsize::new(
             inflight_requests_limit.try_into().unwrap(),
         ));
+        let adaptive_timeout = request
+            .adaptive_timeout
+            .clone()
+            .map(|config| Arc::new(AdaptiveTimeout::new(config)));
+        let write_durability = request.write_durability;
+        let structured_ttl = request.structured_ttl;
+        let typed_time = request.typed_time;
+        let audit_sink = request
+            .audit_log
+            .then(|| Arc::new(LogAuditSink) as Arc<dyn AuditSink>);
+        let session_recorder = match &request.session_recording_path {
+            Some(path) => Some(Arc::new(SessionRecorder::with_file(path).map_err(
+                |err| {
+                    ConnectionError::InvalidConfiguration(format!(
+                        "failed to open the session recording `{path}`: {err}"
+                    ))
+                },
+            )?)),
+            None => None,
+        };
+        let hot_keys = request
+            .hot_keys_tracked
+            .map(|tracked| Arc::new(HotKeyTracker::new(tracked as usize)));
+        let big_reply_threshold = request
+            .big_reply_threshold
+            .map(|threshold| threshold as usize);
+        let connection_rate_limit = match request.connection_rate_limit {
+            Some(limit) => Some(Arc::new(
+                redis::connection_throttle::request_connection_rate_limit(limit).map_err(
+                    |current_limit| {
+                        ConnectionError::InvalidConfiguration(format!(
+                            "the connection rate limit {limit:?} conflicts with the process-wide limit {current_limit:?}, which was set by another client or by `set_connection_rate_limit`"
+                        ))
+                    },
+                )?,
+            )),
+            None => None,
+        };
+        if let Some(memory_cap) = request.memory_cap {
+            memory_budget::request_memory_cap(usize::try_from(memory_cap).unwrap_or(usize::MAX))
+                .map_err(|current_cap| {
+                    ConnectionError::InvalidConfiguration(format!(
+                        "the memory cap of {memory_cap} bytes conflicts with the process-wide cap of {current_cap} bytes, which was set by another client or by `set_memory_cap`"
+                    ))
+                })?;
+        }
+        if let Some(limit) = request.connection_limit {
+            connection_budget::request_connection_limit(limit as usize).map_err(|current_limit| {
+                ConnectionError::InvalidConfiguration(format!(
+                    "the connection limit of {limit} conflicts with the process-wide limit of {current_limit}, which was set by another client or by `set_connection_limit`"
+                ))
+            })?;
+        }
+        let idle_checks_sender = push_sender.clone();
+        let idle_timeout = request
+            .idle_timeout
+            .map(|timeout| Duration::from_millis(timeout as u64));
+        // A standalone client connects to each of the addresses and never discovers other nodes, so its reservation is
+        // kept as is, and a cluster client connects to each of the seed nodes, with a user and a management connection. A cluster client reserves the connections of the topology it
+        // discovers before connecting to its nodes, and they are counted again once it's connected.
+        let connection_share = Arc::new(
+            ConnectionShare::reserve(if request.cluster_mode_enabled {
+                request.addresses.len() * 2
+            } else {
+                request.addresses.len()
+            })
+            .map_err(ConnectionError::ConnectionBudgetExhausted)?,
+        );
         tokio::time::timeout(DEFAULT_CLIENT_CREATION_TIMEOUT, async move {
-            let internal_client = if request.cluster_mode_enabled {
-                let client = create_cluster_client(request, push_sender)
+            let (internal_client, routing_weights) = if request.cluster_mode_enabled {
+                let routing_weights = RoutingWeights::default();
+                let mut client = create_cluster_client(
+                    request,
+                    push_sender,
+                    routing_weights.clone(),
+                    connection_share.cluster_connections_check(),
+                )
+                .await
+                .map_err(|err| {
+                    if err.kind() == redis::ErrorKind::InvalidClientConfig
+                        && err
+                            .to_string()
+                            .starts_with(connection_budget::BUDGET_EXHAUSTED)
+                    {
+                        ConnectionError::ConnectionBudgetExhausted(
+                            err.detail().unwrap_or_default().to_string(),
+                        )
+                    } else {
+                        ConnectionError::Cluster(err)
+                    }
+                })?;
+                let connections = client
+                    .connections_count()
                     .await
                     .map_err(ConnectionError::Cluster)?;
-                ClientWrapper::Cluster { client }
+                connection_share
+                    .resize(connections)
+                    .map_err(ConnectionError::ConnectionBudgetExhausted)?;
+                (ClientWrapper::Cluster { client }, routing_weights)
             } else {
-                ClientWrapper::Standalone(
-                    StandaloneClient::create_client(request, push_sender)
-                        .await
-                        .map_err(ConnectionError::Standalone)?,
-                )
+                let client = StandaloneClient::create_client(request, push_sender)
+                    .await
+                    .map_err(ConnectionError::Standalone)?;
+                let routing_weights = client.routing_weights();
+                (ClientWrapper::Standalone(client), routing_weights)
             };
+            let idle_suspend = idle_timeout.map(|idle_timeout| {
+                let idle_suspend =
+                    Arc::new(IdleSuspend::new(idle_timeout, internal_client.clone()));
+                IdleSuspend::start_idle_checks(Arc::downgrade(&idle_suspend), idle_checks_sender);
+                idle_suspend
+            });
 
             Ok(Self {
                 internal_client,
                 request_timeout,
                 inflight_requests_allowed,
+                adaptive_timeout,
+                routing_weights,
+                keyless_routing: KeylessRoutingTable::default(),
+                write_durability,
+                structured_ttl,
+                typed_time,
+                serializer: Arc::new(JsonSerializer),
+                audit_sink,
+                audit_context: None,
+                session_recorder,
+                hot_keys,
+                big_reply_threshold,
+                connection_share,
+                _connection_rate_limit: connection_rate_limit,
+                idle_suspend,
             })
         })
         .await
         .map_err(|_| ConnectionError::Timeout)?
     }
+
+    /// Checks that a client created with `request` can serve traffic, e.g. before a deployment takes traffic, without
+    /// sending any of it: that the configuration is valid, that the servers accept the client's connections, which
+    /// authenticate and select the database, that there are replicas to read from if the client reads from replicas,
+    /// and the checks of `request.readiness_checks`.
+    ///
+    /// Returns the report of the checks, along with the client, unless it couldn't connect or the checks ask to
+    /// disconnect.
+    pub async fn validate(
+        request: ConnectionRequest,
+        push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
+    ) -> (ReadinessReport, Option<Client>) {
+        let mut report = ReadinessReport::default();
+        if let Err(problems) = request.validate() {
+            report.add("configuration", false, problems);
+            return (report, None);
+        }
+        report.add("configuration", true, "no conflicting options");
+
+        let checks = request.readiness_checks.clone().unwrap_or_default();
+        let read_from = request.read_from.clone().unwrap_or_default();
+        let database_id = request.database_id;
+        let mut client = match Client::new(request, push_sender).await {
+            Ok(client) => client,
+            Err(err) => {
+                report.add("connection", false, err.to_string());
+                return (report, None);
+            }
+        };
+        report.add(
+            "connection",
+            true,
+            format!("connected and authenticated, using database {database_id}"),
+        );
+
+        if read_from != ReadFrom::Primary {
+            client.check_replicas(&mut report).await;
+        }
+        for module in checks.required_modules.iter() {
+            client.check_module(module, &mut report).await;
+        }
+        if !checks.required_commands.is_empty() {
+            client
+                .check_commands(&checks.required_commands, &mut report)
+                .await;
+        }
+        (report, (!checks.disconnect).then_some(client))
+    }
+
+    /// Sends `cmd` to every node, and returns the reply of each node along with its address. Standalone clients only
+    /// send it to the primary.
+    async fn send_to_all_nodes(&mut self, cmd: &Cmd) -> RedisResult<Vec<(String, Value)>> {
+        match self.internal_client {
+            ClientWrapper::Standalone(ref client) => {
+                let address = client.primary_address();
+                Ok(vec![(address, self.send_command(cmd, None).await?)])
+            }
+            ClientWrapper::Cluster { .. } => {
+                let all_nodes = RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllNodes, None));
+                match self.send_command(cmd, Some(all_nodes)).await? {
+                    Value::Map(replies) => replies
+                        .into_iter()
+                        .map(|(address, reply)| {
+                            Ok((String::from_owned_redis_value(address)?, reply))
+                        })
+                        .collect(),
+                    reply => Err((
+                        ErrorKind::ResponseError,
+                        "Expected a reply from each node",
+                        format!("{reply:?}"),
+                    )
+                        .into()),
+                }
+            }
+        }
+    }
+
+    async fn check_replicas(&mut self, report: &mut ReadinessReport) {
+        const CHECK: &str = "read from replicas";
+        let primaries = match self.internal_client {
+            ClientWrapper::Standalone(ref mut client) => {
+                let connected_replicas = client.connected_replicas();
+                if connected_replicas == 0 {
+                    report.add(
+                        CHECK,
+                        false,
+                        "none of the addresses is a connected replica, so reads are served by the primary",
+                    );
+                    return;
+                }
+                // The replicas' connections might outlive their replication, e.g. after REPLICAOF NO ONE.
+                let mut info = redis::cmd("INFO");
+                info.arg("replication");
+                let replicating = match client.send_command_to_primary(&info).await {
+                    Ok(info) => readiness::connected_replicas(
+                        &String::from_redis_value(&info).unwrap_or_default(),
+                    ),
+                    Err(err) => {
+                        report.add(CHECK, false, format!("INFO failed: {err}"));
+                        return;
+                    }
+                };
+                if replicating == 0 {
+                    report.add(
+                        CHECK,
+                        false,
+                        "the primary has no connected replicas, so reads are served by the primary",
+                    );
+                } else {
+                    report.add(
+                        CHECK,
+                        true,
+                        format!(
+                            "{connected_replicas} of the addresses are connected replicas, and the primary has {replicating} connected replicas"
+                        ),
+                    );
+                }
+                return;
+            }
+            ClientWrapper::Cluster { .. } => {
+                let mut info = redis::cmd("INFO");
+                info.arg("replication");
+                let all_primaries = RoutingInfo::MultiNode((
+                    MultipleNodeRoutingInfo::AllMasters,
+                    Some(ResponsePolicy::Special),
+                ));
+                match self.send_command(&info, Some(all_primaries)).await {
+                    Ok(Value::Map(primaries)) => primaries,
+                    Ok(reply) => {
+                        report.add(CHECK, false, format!("unexpected INFO reply {reply:?}"));
+                        return;
+                    }
+                    Err(err) => {
+                        report.add(CHECK, false, format!("INFO failed: {err}"));
+                        return;
+                    }
+                }
+            }
+        };
+        let mut without_replicas = Vec::new();
+        for (address, info) in primaries.iter() {
+            let info = String::from_redis_value(info).unwrap_or_default();
+            if readiness::connected_replicas(&info) == 0 {
+                without_replicas.push(String::from_redis_value(address).unwrap_or_default());
+            }
+        }
+        if without_replicas.is_empty() {
+            report.add(
+                CHECK,
+                true,
+                format!("all {} primaries have replicas", primaries.len()),
+            );
+        } else {
+            without_replicas.sort();
+            report.add(
+                CHECK,
+                false,
+                format!(
+                    "the primaries {} have no replicas, so their reads are served by the primaries",
+                    without_replicas.join(", ")
+                ),
+            );
+        }
+    }
+
+    async fn check_module(&mut self, module: &str, report: &mut ReadinessReport) {
+        let check = format!("module {module}");
+        let mut module_list = redis::cmd("MODULE");
+        module_list.arg("LIST");
+        let replies = match self.send_to_all_nodes(&module_list).await {
+            Ok(replies) => replies,
+            Err(err) => {
+                report.add(check, false, format!("MODULE LIST failed: {err}"));
+                return;
+            }
+        };
+        let mut missing_on: Vec<String> = replies
+            .iter()
+            .filter(|(_, modules)| {
+                !readiness::module_names(modules).contains(&module.to_lowercase())
+            })
+            .map(|(address, _)| address.clone())
+            .collect();
+        if missing_on.is_empty() {
+            report.add(check, true, format!("loaded on {} nodes", replies.len()));
+        } else {
+            missing_on.sort();
+            report.add(
+                check,
+                false,
+                format!("not loaded on {}", missing_on.join(", ")),
+            );
+        }
+    }
+
+    async fn check_commands(&mut self, commands: &[String], report: &mut ReadinessReport) {
+        let mut command_info = redis::cmd("COMMAND");
+        command_info.arg("INFO").arg(commands);
+        let replies = match self.send_to_all_nodes(&command_info).await {
+            Ok(replies) => replies,
+            Err(err) => {
+                for command in commands {
+                    report.add(
+                        format!("command {command}"),
+                        false,
+                        format!("COMMAND INFO failed: {err}"),
+                    );
+                }
+                return;
+            }
+        };
+        let mut missing_on: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (address, reply) in replies.iter() {
+            for command in readiness::missing_commands(commands, reply) {
+                missing_on
+                    .entry(command)
+                    .or_default()
+                    .push(address.as_str());
+            }
+        }
+        for command in commands {
+            let check = format!("command {command}");
+            match missing_on.get_mut(command.as_str()) {
+                Some(addresses) => {
+                    addresses.sort();
+                    report.add(
+                        check,
+                        false,
+                        format!("not supported by {}", addresses.join(", ")),
+                    );
+                }
+                None => report.add(check, true, format!("supported by {} nodes", replies.len())),
+            }
+        }
+    }
 }
 
 pub trait GlideClientForTests {
@@ -853,6 +3089,16 @@ impl GlideClientForTests for StandaloneClient {
     }
 }
 
+/// Builds a command from its name and arguments, e.g. `test_command(&["SET", "key", "value"])`.
+#[cfg(test)]
+pub(crate) fn test_command(args: &[&str]) -> Cmd {
+    let mut cmd = redis::cmd(args[0]);
+    for arg in &args[1..] {
+        cmd.arg(*arg);
+    }
+    cmd
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
@@ -860,8 +3106,12 @@ mod tests {
     use redis::Cmd;
 
     use crate::client::{
-        get_request_timeout, RequestTimeoutOption, TimeUnit, BLOCKING_CMD_TIMEOUT_EXTENSION,
+        affinity_routing, batch_write_slots, check_cluster_sort_patterns, check_limit_args,
+        export_batches, get_request_timeout, mget_batches, publish_batches, publish_cmd,
+        report_big_pipeline_replies, report_big_reply, test_command, write_durability_routing,
+        RequestTimeoutOption, TimeUnit, BLOCKING_CMD_TIMEOUT_EXTENSION, PARALLEL_MGET_BATCH_SIZE,
     };
+    use redis::cluster_routing::{Routable, Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr};
 
     use super::get_timeout_from_cmd_arg;
 
@@ -1012,4 +3262,275 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), Some(Duration::from_millis(100)));
     }
+
+    #[test]
+    fn test_affinity_routing_pins_keyless_commands_to_the_token_slot() {
+        let expected = Some(RoutingInfo::SingleNode(
+            SingleNodeRoutingInfo::SpecificNode(Route::new(
+                redis::cluster_topology::get_slot(b"session"),
+                SlotAddr::Master,
+            )),
+        ));
+        assert_eq!(
+            affinity_routing(b"session", Some(&redis::cmd("TIME"))),
+            expected
+        );
+        let mut eval = redis::cmd("EVALSHA");
+        eval.arg("hash").arg(0);
+        assert_eq!(affinity_routing(b"session", Some(&eval)), expected);
+        assert_eq!(affinity_routing(b"session", None), expected);
+    }
+
+    #[test]
+    fn test_affinity_routing_ignores_keyed_commands_and_empty_tokens() {
+        let mut get = redis::cmd("GET");
+        get.arg("key");
+        assert_eq!(affinity_routing(b"session", Some(&get)), None);
+        assert_eq!(
+            affinity_routing(b"session", Some(&redis::cmd("DBSIZE"))),
+            None
+        );
+        assert_eq!(affinity_routing(b"", Some(&redis::cmd("TIME"))), None);
+    }
+
+    #[test]
+    fn test_write_durability_routing_targets_the_written_primary() {
+        let mut set = redis::cmd("SET");
+        set.arg("key").arg("value");
+        assert_eq!(
+            write_durability_routing(&set, None),
+            Some(RoutingInfo::SingleNode(
+                SingleNodeRoutingInfo::SpecificNode(Route::new(
+                    redis::cluster_topology::get_slot(b"key"),
+                    SlotAddr::Master
+                ))
+            ))
+        );
+        let by_address = RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+            host: "host".to_string(),
+            port: 6379,
+        });
+        assert_eq!(
+            write_durability_routing(&set, Some(&by_address)),
+            Some(by_address)
+        );
+    }
+
+    #[test]
+    fn test_write_durability_routing_skips_reads_and_unknown_primaries() {
+        let mut get = redis::cmd("GET");
+        get.arg("key");
+        assert_eq!(write_durability_routing(&get, None), None);
+        let mut mset = redis::cmd("MSET");
+        mset.arg("key1").arg("value").arg("key2").arg("value");
+        assert_eq!(write_durability_routing(&mset, None), None);
+        assert_eq!(
+            write_durability_routing(
+                &redis::cmd("FLUSHALL"),
+                Some(&RoutingInfo::SingleNode(
+                    SingleNodeRoutingInfo::RandomPrimary
+                ))
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_batch_write_slots_skips_reads_and_keyless_writes() {
+        let mut pipeline = redis::pipe();
+        pipeline
+            .set("key1", "value")
+            .get("key2")
+            .cmd("MSET")
+            .arg("key3")
+            .arg("value")
+            .arg("key4")
+            .arg("value")
+            .cmd("FLUSHALL")
+            .set("key1", "value");
+        let slots: Vec<u16> = ["key1", "key3", "key4"]
+            .iter()
+            .map(|key| redis::cluster_topology::get_slot(key.as_bytes()))
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        assert_eq!(
+            batch_write_slots(&pipeline).into_iter().collect::<Vec<_>>(),
+            slots
+        );
+    }
+
+    #[test]
+    fn test_only_replies_above_the_threshold_are_reported_as_big() {
+        let mut get = redis::cmd("GET");
+        get.arg("big-reply-key");
+        let value = redis::Value::BulkString(vec![b'a'; 100]);
+        // `$100\r\n` + the payload + `\r\n`.
+        assert_eq!(report_big_reply(&get, &value, 106), None);
+        assert_eq!(report_big_reply(&get, &value, 105), Some(106));
+        let counters = telemetrylib::Telemetry::big_replies()
+            .remove("GET")
+            .unwrap();
+        assert!(counters.count >= 1);
+        assert!(counters.largest >= 106);
+    }
+
+    #[test]
+    fn test_big_replies_of_pipelines_are_reported_by_command() {
+        let mut pipeline = redis::pipe();
+        pipeline.cmd("HGETALL").arg("big-hash");
+        pipeline.cmd("LRANGE").arg("small-list").arg(0).arg(-1);
+        let replies = [
+            redis::Value::Array(vec![redis::Value::BulkString(vec![b'a'; 100])]),
+            redis::Value::Array(vec![]),
+        ];
+        report_big_pipeline_replies(&pipeline, &replies, 50);
+        let big_replies = telemetrylib::Telemetry::big_replies();
+        assert!(big_replies["HGETALL"].count >= 1);
+        assert!(!big_replies.contains_key("LRANGE"));
+    }
+
+    #[test]
+    fn test_sharded_publish_is_routed_to_the_channel_primary() {
+        let (cmd, routing) = publish_cmd(b"channel", b"message", true);
+        assert_eq!(cmd.command(), Some(b"SPUBLISH".to_vec()));
+        assert_eq!(
+            routing,
+            Some(RoutingInfo::SingleNode(
+                SingleNodeRoutingInfo::SpecificNode(Route::new(
+                    redis::cluster_topology::get_slot(b"channel"),
+                    SlotAddr::Master
+                ))
+            ))
+        );
+
+        let (cmd, routing) = publish_cmd(b"channel", b"message", false);
+        assert_eq!(cmd.command(), Some(b"PUBLISH".to_vec()));
+        assert_eq!(routing, None);
+    }
+
+    #[test]
+    fn test_publish_batches_group_sharded_channels_by_node() {
+        let channels: Vec<&[u8]> = vec![b"{a}1", b"{b}1", b"{a}2", b"{c}1"];
+        let slot = |channel: &[u8]| redis::cluster_topology::get_slot(channel);
+        let (slot_a, slot_b) = (slot(b"{a}"), slot(b"{b}"));
+        // {a} and {b} are served by the same node, and the node of {c} is unknown.
+        let node_for_slot =
+            |slot: u16| (slot == slot_a || slot == slot_b).then(|| "node:6379".to_string());
+        assert_eq!(
+            publish_batches(&channels, false, Some(&node_for_slot)),
+            vec![(SingleNodeRoutingInfo::Random, vec![0, 1, 2, 3])]
+        );
+        assert_eq!(
+            publish_batches(&channels, true, None),
+            vec![(SingleNodeRoutingInfo::Random, vec![0, 1, 2, 3])]
+        );
+
+        let mut batches = publish_batches(&channels, true, Some(&node_for_slot));
+        batches.sort_by_key(|(_, indices)| indices.clone());
+        let route =
+            |slot: u16| SingleNodeRoutingInfo::SpecificNode(Route::new(slot, SlotAddr::Master));
+        assert_eq!(
+            batches,
+            vec![
+                (route(slot_a.min(slot_b)), vec![0, 1, 2]),
+                (route(slot(b"{c}")), vec![3])
+            ]
+        );
+        assert!(publish_batches(&[], true, Some(&node_for_slot)).is_empty());
+    }
+
+    #[test]
+    fn test_export_batches_group_keys_by_node() {
+        let keys: Vec<Vec<u8>> = [b"{a}1", b"{b}1", b"{c}1", b"{a}2", b"{c}2"]
+            .iter()
+            .map(|key| key.to_vec())
+            .collect();
+        let slot = |key: &[u8]| redis::cluster_topology::get_slot(key);
+        let (slot_a, slot_b) = (slot(b"{a}"), slot(b"{b}"));
+        // {a} and {b} are served by the same node, and the node of {c} is unknown.
+        let node_for_slot =
+            |slot: u16| (slot == slot_a || slot == slot_b).then(|| "node:6379".to_string());
+        assert_eq!(
+            export_batches(keys.clone(), &node_for_slot),
+            vec![
+                vec![keys[0].clone(), keys[1].clone(), keys[3].clone()],
+                vec![keys[2].clone(), keys[4].clone()],
+            ]
+        );
+        assert!(export_batches(Vec::new(), &node_for_slot).is_empty());
+    }
+
+    #[test]
+    fn test_mget_batches_group_keys_by_slot_and_node() {
+        let keys: Vec<&[u8]> = vec![b"{a}1", b"{b}1", b"{a}2", b"{b}2", b"{a}3", b"{c}1"];
+        let slot = |key: &[u8]| redis::cluster_topology::get_slot(key);
+        let (slot_a, slot_b) = (slot(b"{a}"), slot(b"{b}"));
+        // {a} and {b} are served by the same node, and the node of {c} is unknown.
+        let node_for_slot =
+            |slot: u16| (slot == slot_a || slot == slot_b).then(|| "node:6379".to_string());
+        let mut batches = mget_batches(&keys, Some(&node_for_slot));
+        for mgets in batches.iter_mut() {
+            mgets.sort();
+        }
+        batches.sort();
+        assert_eq!(
+            batches,
+            vec![vec![vec![0, 2, 4], vec![1, 3]], vec![vec![5]]]
+        );
+
+        assert_eq!(
+            mget_batches(&keys, None),
+            vec![vec![vec![0, 1, 2, 3, 4, 5]]]
+        );
+
+        let many_keys = vec![b"key".as_slice(); PARALLEL_MGET_BATCH_SIZE + 1];
+        let batch_sizes: Vec<usize> = mget_batches(&many_keys, None)
+            .iter()
+            .map(|mgets| mgets[0].len())
+            .collect();
+        assert_eq!(batch_sizes, vec![PARALLEL_MGET_BATCH_SIZE, 1]);
+        // The MGETs of a node are split into requests once they exceed the batch size.
+        let single_node = |_: u16| Some("node:6379".to_string());
+        let batches = mget_batches(&many_keys, Some(&single_node));
+        assert_eq!(
+            batches,
+            vec![
+                vec![(0..PARALLEL_MGET_BATCH_SIZE).collect()],
+                vec![vec![PARALLEL_MGET_BATCH_SIZE]]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_limit_args() {
+        let check = |args: &[&str]| check_limit_args(&test_command(args));
+        assert!(check(&["SCAN", "0", "COUNT", "10"]).is_ok());
+        assert!(check(&["SCAN", "0", "count", "0"]).is_err());
+        assert!(check(&["HSCAN", "key", "0", "MATCH", "*", "COUNT", "-1"]).is_err());
+        // The key and the pattern aren't mistaken for the option.
+        assert!(check(&["SSCAN", "count", "0"]).is_ok());
+        assert!(check(&["ZSCAN", "key", "0", "MATCH", "count", "COUNT", "5"]).is_ok());
+
+        assert!(check(&["SINTERCARD", "2", "a", "b", "LIMIT", "0"]).is_ok());
+        assert!(check(&["SINTERCARD", "2", "a", "b", "LIMIT", "-1"]).is_err());
+        assert!(check(&["SINTERCARD", "2", "limit", "-1"]).is_ok());
+    }
+
+    #[test]
+    fn test_check_cluster_sort_patterns() {
+        let check = |args: &[&str]| check_cluster_sort_patterns(&test_command(args));
+        assert!(check(&["SORT", "{user}:ids", "BY", "{user}:weight_*", "GET", "#"]).is_ok());
+        assert!(check(&["SORT_RO", "{user}:ids", "get", "{user}:name_*->first"]).is_ok());
+        // Patterns without a wildcard in BY skip the sorting, and don't touch other keys.
+        assert!(check(&["SORT", "ids", "BY", "nosort", "LIMIT", "0", "10", "ALPHA"]).is_ok());
+        assert!(check(&["SORT", "ids", "STORE", "by", "DESC"]).is_ok());
+
+        assert!(check(&["SORT", "ids", "BY", "weight_*"]).is_err());
+        assert!(check(&["SORT", "{user}:ids", "BY", "{other}:weight_*"]).is_err());
+        // The tag has to come before any wildcard, and can't be empty.
+        assert!(check(&["SORT", "{user}:ids", "GET", "*_{user}"]).is_err());
+        assert!(check(&["SORT", "ids", "GET", "{}ids*"]).is_err());
+        assert!(check(&["SORT", "{user}:ids", "GET", "#", "GET", "name_*"]).is_err());
+    }
 }
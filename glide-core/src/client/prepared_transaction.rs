@@ -0,0 +1,87 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use super::value_conversion::{
+    convert_to_expected_type, expected_type_for_client_cmd, ExpectedReturnType,
+};
+use redis::{Pipeline, RedisResult, Value};
+
+/// A transaction whose reply conversions were decided when it was created, so that sending it repeatedly doesn't
+/// inspect each of its commands again. Sent with [`super::Client::send_prepared_transaction`].
+pub struct PreparedTransaction {
+    pipeline: Pipeline,
+    expected_types: Vec<Option<ExpectedReturnType<'static>>>,
+}
+
+impl PreparedTransaction {
+    /// `structured_ttl` and `typed_time` have the same meaning as in [`super::ConnectionRequest`].
+    pub fn new(pipeline: Pipeline, structured_ttl: bool, typed_time: bool) -> Self {
+        let expected_types = pipeline
+            .cmd_iter()
+            .map(|cmd| expected_type_for_client_cmd(cmd, structured_ttl, typed_time))
+            .collect();
+        PreparedTransaction {
+            pipeline,
+            expected_types,
+        }
+    }
+
+    pub fn pipeline(&self) -> &Pipeline {
+        &self.pipeline
+    }
+
+    /// Converts the replies of the transaction's commands, in order, the same way the client converts them.
+    pub fn convert_replies(&self, replies: Vec<Value>) -> RedisResult<Value> {
+        convert_transaction_replies(replies, &self.expected_types)
+    }
+
+    pub(super) fn expected_types(&self) -> &[Option<ExpectedReturnType<'static>>] {
+        &self.expected_types
+    }
+}
+
+pub(super) fn convert_transaction_replies(
+    replies: Vec<Value>,
+    expected_types: &[Option<ExpectedReturnType>],
+) -> RedisResult<Value> {
+    replies
+        .into_iter()
+        .zip(expected_types.iter().copied())
+        .map(|(reply, expected_type)| convert_to_expected_type(reply, expected_type))
+        .collect::<RedisResult<_>>()
+        .map(Value::Array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prepared_transaction_converts_replies_like_the_client() {
+        let mut pipeline = redis::pipe();
+        pipeline.atomic();
+        pipeline.cmd("SET").arg("key").arg("value");
+        pipeline.cmd("HGETALL").arg("hash");
+        pipeline.cmd("PTTL").arg("key");
+        let prepared = PreparedTransaction::new(pipeline, false, false);
+
+        let replies = vec![
+            Value::Okay,
+            Value::Array(vec![
+                Value::BulkString(b"field".to_vec()),
+                Value::BulkString(b"value".to_vec()),
+            ]),
+            Value::Int(-1),
+        ];
+        let expected = Value::Array(vec![
+            Value::Okay,
+            Value::Map(vec![(
+                Value::BulkString(b"field".to_vec()),
+                Value::BulkString(b"value".to_vec()),
+            )]),
+            Value::Int(-1),
+        ]);
+        // The decisions are reused, so converting again gives the same result.
+        assert_eq!(prepared.convert_replies(replies.clone()).unwrap(), expected);
+        assert_eq!(prepared.convert_replies(replies).unwrap(), expected);
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::Value;
+use std::collections::HashSet;
+
+/// The outcome of a single check of [`Client::validate`](crate::client::Client::validate).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReadinessCheck {
+    /// What was checked, e.g. `connection` or `module search`.
+    pub name: String,
+    pub passed: bool,
+    /// Why the check failed, or what was found if it passed.
+    pub detail: String,
+}
+
+/// The checks that were run by [`Client::validate`](crate::client::Client::validate), in the order they were run.
+/// Checks that depend on a failed check aren't run.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReadinessReport {
+    pub checks: Vec<ReadinessCheck>,
+}
+
+impl ReadinessReport {
+    /// Whether all the checks passed.
+    pub fn is_ready(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+
+    /// Returns the value that is passed to the wrappers - a map with `ready` and `checks` fields, where each check is
+    /// a map with `name`, `passed` and `detail` fields.
+    pub fn to_value(&self) -> Value {
+        let string = |value: &str| Value::SimpleString(value.to_string());
+        let checks = self
+            .checks
+            .iter()
+            .map(|check| {
+                Value::Map(vec![
+                    (string("name"), string(&check.name)),
+                    (string("passed"), Value::Boolean(check.passed)),
+                    (string("detail"), string(&check.detail)),
+                ])
+            })
+            .collect();
+        Value::Map(vec![
+            (string("ready"), Value::Boolean(self.is_ready())),
+            (string("checks"), Value::Array(checks)),
+        ])
+    }
+
+    pub(super) fn add(&mut self, name: impl Into<String>, passed: bool, detail: impl Into<String>) {
+        self.checks.push(ReadinessCheck {
+            name: name.into(),
+            passed,
+            detail: detail.into(),
+        });
+    }
+}
+
+/// Returns the lowercase names of the modules in a single node's `MODULE LIST` reply. Each module is a map in RESP3,
+/// and an array of alternating fields and values in RESP2.
+pub(super) fn module_names(modules: &Value) -> HashSet<String> {
+    let Value::Array(modules) = modules else {
+        return HashSet::new();
+    };
+    let text = |value: &Value| match value {
+        Value::BulkString(bytes) => Some(String::from_utf8_lossy(bytes).to_string()),
+        Value::SimpleString(string) => Some(string.clone()),
+        _ => None,
+    };
+    modules
+        .iter()
+        .filter_map(|module| match module {
+            Value::Map(fields) => fields
+                .iter()
+                .find(|(field, _)| text(field).as_deref() == Some("name"))
+                .and_then(|(_, name)| text(name)),
+            Value::Array(fields) => fields
+                .chunks(2)
+                .find(|pair| text(&pair[0]).as_deref() == Some("name"))
+                .and_then(|pair| pair.get(1).and_then(text)),
+            _ => None,
+        })
+        .map(|name| name.to_lowercase())
+        .collect()
+}
+
+/// Returns the commands of `commands` that a single node doesn't support, given its `COMMAND INFO` reply for them, in
+/// which unknown commands are nil.
+pub(super) fn missing_commands<'a>(commands: &'a [String], reply: &Value) -> Vec<&'a str> {
+    let Value::Array(infos) = reply else {
+        return commands.iter().map(String::as_str).collect();
+    };
+    commands
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| matches!(infos.get(*index), None | Some(Value::Nil)))
+        .map(|(_, command)| command.as_str())
+        .collect()
+}
+
+/// Returns the number of replicas in the `replication` section of a primary's INFO reply.
+pub(super) fn connected_replicas(info: &str) -> u64 {
+    info.lines()
+        .find_map(|line| line.trim().strip_prefix("connected_slaves:"))
+        .and_then(|count| count.parse().ok())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bulk(value: &str) -> Value {
+        Value::BulkString(value.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_module_names_are_read_from_resp2_and_resp3_replies() {
+        let resp3 = Value::Array(vec![Value::Map(vec![
+            (bulk("name"), bulk("ReJSON")),
+            (bulk("ver"), Value::Int(20609)),
+        ])]);
+        let resp2 = Value::Array(vec![Value::Array(vec![
+            bulk("name"),
+            bulk("search"),
+            bulk("ver"),
+            Value::Int(21005),
+        ])]);
+        assert_eq!(module_names(&resp3), HashSet::from(["rejson".to_string()]));
+        assert_eq!(module_names(&resp2), HashSet::from(["search".to_string()]));
+        assert!(module_names(&Value::Array(vec![])).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_commands_are_missing() {
+        let commands = vec!["GET".to_string(), "FT.SEARCH".to_string()];
+        let reply = Value::Array(vec![Value::Array(vec![bulk("get")]), Value::Nil]);
+        assert_eq!(missing_commands(&commands, &reply), vec!["FT.SEARCH"]);
+        assert_eq!(
+            missing_commands(&commands, &Value::Nil),
+            vec!["GET", "FT.SEARCH"]
+        );
+    }
+
+    #[test]
+    fn test_replicas_are_counted_from_info() {
+        let info = "# Replication\r\nrole:master\r\nconnected_slaves:2\r\nslave0:ip=10.0.0.2,port=6379\r\n";
+        assert_eq!(connected_replicas(info), 2);
+        assert_eq!(connected_replicas("# Replication\r\nrole:master\r\n"), 0);
+    }
+
+    #[test]
+    fn test_report_is_ready_only_if_all_checks_passed() {
+        let mut report = ReadinessReport::default();
+        report.add("connection", true, "connected");
+        assert!(report.is_ready());
+        report.add("read from replicas", false, "no replicas");
+        assert!(!report.is_ready());
+        let Value::Map(fields) = report.to_value() else {
+            panic!("Expected a map");
+        };
+        assert_eq!(fields[0].1, Value::Boolean(false));
+    }
+}
@@ -1,6 +1,6 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
-use super::{NodeAddress, TlsMode};
+use super::{NodeAddress, NodeTls};
 use crate::retry_strategies::RetryStrategy;
 use async_trait::async_trait;
 use futures_intrusive::sync::ManualResetEvent;
@@ -12,12 +12,12 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::time::Duration;
-use telemetrylib::Telemetry;
+use telemetrylib::{NodeEvent, Telemetry};
 use tokio::sync::{mpsc, Notify};
-use tokio::task;
 use tokio::time::timeout;
 use tokio_retry2::{Retry, RetryError};
 
+use super::task_supervisor::spawn_supervised;
 use super::{run_with_timeout, DEFAULT_CONNECTION_TIMEOUT};
 
 /// The reason behind the call to `reconnect()`
@@ -27,6 +27,8 @@ pub enum ReconnectReason {
     ConnectionDropped,
     /// Connection creation error
     CreateError,
+    /// A command was sent after the connection was suspended
+    Resume,
 }
 
 /// The object that is used in order to recreate a connection after a disconnect.
@@ -35,6 +37,8 @@ struct ConnectionBackend {
     connection_available_signal: ManualResetEvent,
     /// Information needed in order to create a new connection.
     connection_info: redis::Client,
+    /// The address of the node, which is kept so that reads can be routed without formatting it.
+    node_address: String,
     /// Once this flag is set, the internal connection needs no longer try to reconnect to the server, because all the outer clients were dropped.
     client_dropped_flagged: AtomicBool,
 }
@@ -47,6 +51,8 @@ enum ConnectionState {
     Reconnecting,
     /// Initial state of connection when no connection was created during initialization.
     InitializedDisconnected,
+    /// The connection was closed because the client was idle, and is reopened by the next request.
+    Suspended,
 }
 
 struct InnerReconnectingConnection {
@@ -70,6 +76,11 @@ async fn get_multiplexed_connection(
     client: &redis::Client,
     connection_options: &GlideConnectionOptions,
 ) -> RedisResult<MultiplexedConnection> {
+    // The wait for the rate limit isn't part of the connection timeout, which only covers the attempt itself.
+    redis::connection_throttle::wait_for_connection_attempt(
+        &client.get_connection_info().addr.to_string(),
+    )
+    .await;
     run_with_timeout(
         Some(
             connection_options
@@ -181,12 +192,12 @@ async fn create_connection(
 
 fn get_client(
     address: &NodeAddress,
-    tls_mode: TlsMode,
+    tls: &NodeTls,
     redis_connection_info: redis::RedisConnectionInfo,
 ) -> redis::Client {
     redis::Client::open(super::get_connection_info(
         address,
-        tls_mode,
+        tls,
         redis_connection_info,
     ))
     .unwrap() // can unwrap, because [open] fails only on trying to convert input to ConnectionInfo, and we pass ConnectionInfo.
@@ -209,7 +220,7 @@ impl ReconnectingConnection {
         address: &NodeAddress,
         connection_retry_strategy: RetryStrategy,
         redis_connection_info: RedisConnectionInfo,
-        tls_mode: TlsMode,
+        tls: &NodeTls,
         push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
         discover_az: bool,
         connection_timeout: Duration,
@@ -219,8 +230,9 @@ impl ReconnectingConnection {
             format!("Attempting connection to {address}"),
         );
 
-        let connection_info = get_client(address, tls_mode, redis_connection_info);
+        let connection_info = get_client(address, tls, redis_connection_info);
         let backend = ConnectionBackend {
+            node_address: connection_info.get_connection_info().addr.to_string(),
             connection_info,
             connection_available_signal: ManualResetEvent::new(true),
             client_dropped_flagged: AtomicBool::new(false),
@@ -236,12 +248,29 @@ impl ReconnectingConnection {
     }
 
     pub(crate) fn node_address(&self) -> String {
-        self.inner
+        self.address().to_string()
+    }
+
+    /// Returns the address of the node without allocating.
+    pub(crate) fn address(&self) -> &str {
+        &self.inner.backend.node_address
+    }
+
+    /// Opens a new connection to the node with the same connection options, which isn't shared with other requests,
+    /// doesn't serve subscriptions and isn't reconnected. It's closed once it's dropped.
+    pub(super) async fn open_dedicated_connection(&self) -> RedisResult<MultiplexedConnection> {
+        let mut info = self
+            .inner
             .backend
             .connection_info
             .get_connection_info()
-            .addr
-            .to_string()
+            .clone();
+        // The subscriptions are served by the shared connection.
+        info.redis.pubsub_subscriptions = None;
+        let mut connection_options = self.connection_options.clone();
+        // The connection isn't reconnected, so its disconnection doesn't need to be reported.
+        connection_options.disconnect_notifier = None;
+        get_multiplexed_connection(&redis::Client::open(info)?, &connection_options).await
     }
 
     pub(super) fn is_dropped(&self) -> bool {
@@ -253,8 +282,10 @@ impl ReconnectingConnection {
 
     pub(super) fn mark_as_dropped(&self) {
         // Update the telemetry for each connection that is dropped. A dropped connection
-        // will not be re-connected, so update the telemetry here
-        Telemetry::decr_total_connections(1);
+        // will not be re-connected, so update the telemetry here. Suspended connections were already counted as closed.
+        if !self.is_suspended() {
+            Telemetry::decr_total_connections(1);
+        }
         self.inner
             .backend
             .client_dropped_flagged
@@ -276,9 +307,39 @@ impl ReconnectingConnection {
             if let Some(connection) = self.try_get_connection().await {
                 return Ok(connection);
             }
+            if self.is_suspended() {
+                self.reconnect(ReconnectReason::Resume);
+            }
         }
     }
 
+    fn is_suspended(&self) -> bool {
+        matches!(
+            *self.inner.state.lock().unwrap(),
+            ConnectionState::Suspended
+        )
+    }
+
+    /// Closes the connection until the next call to `get_connection`, which reopens it. Connections that aren't
+    /// connected are left as they are.
+    pub(super) fn suspend(&self) {
+        let connection = {
+            let mut guard = self.inner.state.lock().unwrap();
+            if !matches!(*guard, ConnectionState::Connected(_)) {
+                return;
+            }
+            std::mem::replace(&mut *guard, ConnectionState::Suspended)
+        };
+        // The connection is closed once its last clone, which might be held by a request that is still in flight, is
+        // dropped.
+        drop(connection);
+        log_debug(
+            "suspend",
+            format!("Connection to {} suspended", self.node_address()),
+        );
+        Telemetry::decr_total_connections(1);
+    }
+
     /// Attempt to re-connect the connection.
     ///
     /// This function spawns a task to perform the reconnection in the background
@@ -296,6 +357,7 @@ impl ReconnectingConnection {
         log_debug("reconnect", "starting");
 
         let connection_clone = self.clone();
+        let resumed = reason == ReconnectReason::Resume;
 
         if reason.eq(&ReconnectReason::ConnectionDropped) {
             // Attempting to reconnect a connection that was dropped (for any reason) - update the telemetry by reducing
@@ -305,47 +367,61 @@ impl ReconnectingConnection {
 
         // The reconnect task is spawned instead of awaited here, so that the reconnect attempt will continue in the
         // background, regardless of whether the calling task is dropped or not.
-        task::spawn(async move {
-            let client = &connection_clone.inner.backend.connection_info;
-            for sleep_duration in internal_retry_iterator() {
-                if connection_clone.is_dropped() {
-                    log_debug(
-                        "ReconnectingConnection",
-                        "reconnect stopped after client was dropped",
-                    );
-                    // Client was dropped, reconnection attempts can stop
-                    return;
-                }
-                match get_multiplexed_connection(client, &connection_clone.connection_options).await
-                {
-                    Ok(mut connection) => {
-                        if connection
-                            .send_packed_command(&redis::cmd("PING"))
-                            .await
-                            .is_err()
-                        {
-                            tokio::time::sleep(sleep_duration).await;
-                            continue;
-                        }
-                        {
-                            let mut guard = connection_clone.inner.state.lock().unwrap();
-                            log_debug("reconnect", "completed successfully");
-                            connection_clone
-                                .inner
-                                .backend
-                                .connection_available_signal
-                                .set();
-                            *guard = ConnectionState::Connected(connection);
-                        }
-                        Telemetry::incr_total_connections(1);
+        spawn_supervised("reconnect", self.push_sender(), move || {
+            let connection_clone = connection_clone.clone();
+            async move {
+                let client = &connection_clone.inner.backend.connection_info;
+                for sleep_duration in internal_retry_iterator() {
+                    if connection_clone.is_dropped() {
+                        log_debug(
+                            "ReconnectingConnection",
+                            "reconnect stopped after client was dropped",
+                        );
+                        // Client was dropped, reconnection attempts can stop
                         return;
                     }
-                    Err(_) => tokio::time::sleep(sleep_duration).await,
+                    match get_multiplexed_connection(client, &connection_clone.connection_options)
+                        .await
+                    {
+                        Ok(mut connection) => {
+                            if connection
+                                .send_packed_command(&redis::cmd("PING"))
+                                .await
+                                .is_err()
+                            {
+                                tokio::time::sleep(sleep_duration).await;
+                                continue;
+                            }
+                            {
+                                let mut guard = connection_clone.inner.state.lock().unwrap();
+                                log_debug("reconnect", "completed successfully");
+                                connection_clone
+                                    .inner
+                                    .backend
+                                    .connection_available_signal
+                                    .set();
+                                *guard = ConnectionState::Connected(connection);
+                            }
+                            Telemetry::incr_total_connections(1);
+                            if !resumed {
+                                Telemetry::record_node_event(
+                                    &connection_clone.node_address(),
+                                    NodeEvent::Reconnect,
+                                );
+                            }
+                            return;
+                        }
+                        Err(_) => tokio::time::sleep(sleep_duration).await,
+                    }
                 }
             }
         });
     }
 
+    pub(super) fn push_sender(&self) -> Option<mpsc::UnboundedSender<PushInfo>> {
+        self.connection_options.push_sender.clone()
+    }
+
     pub fn is_connected(&self) -> bool {
         !matches!(
             *self.inner.state.lock().unwrap(),
@@ -0,0 +1,88 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::{Cmd, ErrorKind, RedisError, RedisResult};
+use serde::Serialize;
+
+/// Encodes typed values into command arguments, so that embedders can pass their own types to commands.
+///
+/// Values are handed to the serializer as a [`serde_json::Value`], which keeps the trait object safe, while still
+/// allowing any self-describing format - JSON, MessagePack, CBOR, etc.
+pub trait ArgSerializer: Send + Sync {
+    fn serialize(&self, value: serde_json::Value) -> RedisResult<Vec<u8>>;
+}
+
+/// Encodes values as JSON. Used by clients that weren't given another serializer.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JsonSerializer;
+
+impl ArgSerializer for JsonSerializer {
+    fn serialize(&self, value: serde_json::Value) -> RedisResult<Vec<u8>> {
+        serde_json::to_vec(&value).map_err(serialization_error)
+    }
+}
+
+fn serialization_error(err: serde_json::Error) -> RedisError {
+    RedisError::from((
+        ErrorKind::TypeError,
+        "Failed to serialize argument",
+        err.to_string(),
+    ))
+}
+
+/// Serializes `value` with `serializer`, and appends the result to `cmd`.
+pub(super) fn append_typed_arg<T: Serialize + ?Sized>(
+    serializer: &dyn ArgSerializer,
+    cmd: &mut Cmd,
+    value: &T,
+) -> RedisResult<()> {
+    let value = serde_json::to_value(value).map_err(serialization_error)?;
+    cmd.arg(serializer.serialize(value)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct User {
+        name: &'static str,
+        age: u32,
+    }
+
+    struct UppercaseSerializer;
+
+    impl ArgSerializer for UppercaseSerializer {
+        fn serialize(&self, value: serde_json::Value) -> RedisResult<Vec<u8>> {
+            Ok(value.to_string().to_uppercase().into_bytes())
+        }
+    }
+
+    #[test]
+    fn test_typed_args_are_serialized_before_being_appended() {
+        let user = User {
+            name: "alice",
+            age: 30,
+        };
+        let mut cmd = redis::cmd("SET");
+        cmd.arg("key");
+        append_typed_arg(&JsonSerializer, &mut cmd, &user).unwrap();
+        append_typed_arg(&UppercaseSerializer, &mut cmd, &user).unwrap();
+        let args: Vec<_> = cmd
+            .args_iter()
+            .map(|arg| match arg {
+                redis::Arg::Simple(arg) => arg.to_vec(),
+                redis::Arg::Cursor => Vec::new(),
+            })
+            .collect();
+        assert_eq!(
+            args,
+            vec![
+                b"SET".to_vec(),
+                b"key".to_vec(),
+                br#"{"age":30,"name":"alice"}"#.to_vec(),
+                br#"{"AGE":30,"NAME":"ALICE"}"#.to_vec(),
+            ]
+        );
+    }
+}
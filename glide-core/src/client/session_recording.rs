@@ -0,0 +1,520 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Recording of the commands that a client sent and the replies that it received, so that a user's session can be
+//! replayed against a mocked server. Secrets, such as passwords, are redacted before they are recorded.
+
+use logger_core::log_warn;
+use redis::{Arg, Cmd, ErrorKind, Pipeline, RedisError, RedisResult, Value};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::sync::Mutex;
+use tokio::sync::mpsc;
+
+/// Replaces arguments that hold secrets.
+pub const REDACTED: &str = "<redacted>";
+
+/// The number of exchanges that a recorder without a file keeps. Older exchanges are dropped.
+pub const MAX_RECORDED_EXCHANGES: usize = 10_000;
+
+/// A command and the reply it received.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedExchange {
+    /// The command's arguments, with secrets redacted.
+    #[serde(with = "recorded_args")]
+    pub request: Vec<Vec<u8>>,
+    /// The RESP encoding of the reply, before the client converted it. `None` if the request failed without a reply
+    /// from the server, e.g. because the connection was lost or the request timed out.
+    #[serde(with = "recorded_response")]
+    pub response: Option<Vec<u8>>,
+}
+
+impl RecordedExchange {
+    fn new(cmd: &Cmd, result: &RedisResult<Value>) -> Self {
+        let response = match result {
+            Ok(value) => {
+                let mut encoded = Vec::new();
+                encode_reply(value, &mut encoded);
+                Some(encoded)
+            }
+            Err(err) => error_reply(err),
+        };
+        RecordedExchange {
+            request: redacted_args(cmd),
+            response,
+        }
+    }
+
+    /// Builds the command that was recorded.
+    pub fn to_cmd(&self) -> Cmd {
+        let mut cmd = Cmd::new();
+        for arg in &self.request {
+            cmd.arg(arg.as_slice());
+        }
+        cmd
+    }
+}
+
+/// Records the exchanges of a client, in the order in which they completed. A recorder with a file appends each
+/// exchange to it as a JSON line, so that the recording survives a crash, and doesn't keep the exchanges in memory.
+/// The lines are written by a dedicated thread, so that recording doesn't block the client.
+#[derive(Debug, Default)]
+pub struct SessionRecorder {
+    exchanges: Mutex<VecDeque<RecordedExchange>>,
+    writer: Option<mpsc::UnboundedSender<String>>,
+}
+
+impl SessionRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a recorder that appends to the file at `path`. The file is closed once the recorder is dropped and the
+    /// recorded exchanges were written.
+    pub fn with_file(path: &str) -> io::Result<Self> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        let (writer, mut lines) = mpsc::unbounded_channel::<String>();
+        std::thread::Builder::new()
+            .name("session-recording".to_string())
+            .spawn(move || {
+                while let Some(line) = lines.blocking_recv() {
+                    if let Err(err) = file.write_all(line.as_bytes()) {
+                        log_warn(
+                            "session recording",
+                            format!("Failed to write to the recording: {err}"),
+                        );
+                    }
+                }
+            })?;
+        Ok(SessionRecorder {
+            exchanges: Mutex::default(),
+            writer: Some(writer),
+        })
+    }
+
+    fn add(&self, exchange: RecordedExchange) {
+        if let Some(writer) = &self.writer {
+            let mut line = serde_json::to_string(&exchange).unwrap_or_default();
+            line.push('\n');
+            let _ = writer.send(line);
+            return;
+        }
+        let mut exchanges = self.exchanges.lock().unwrap();
+        if exchanges.len() == MAX_RECORDED_EXCHANGES {
+            exchanges.pop_front();
+        }
+        exchanges.push_back(exchange);
+    }
+
+    pub(super) fn record(&self, cmd: &Cmd, result: &RedisResult<Value>) {
+        self.add(RecordedExchange::new(cmd, result));
+    }
+
+    /// Records a transaction as it was sent to the server - `MULTI`, the queued commands, and `EXEC` with the replies
+    /// of the commands, or the error that the transaction failed with. `exec_result` holds the reply to `EXEC`.
+    pub(super) fn record_transaction(
+        &self,
+        transaction: &Pipeline,
+        exec_result: &RedisResult<Vec<Value>>,
+    ) {
+        self.record(&redis::cmd("MULTI"), &Ok(Value::Okay));
+        for cmd in transaction.cmd_iter() {
+            self.record(cmd, &Ok(Value::SimpleString("QUEUED".to_string())));
+        }
+        let exec = redis::cmd("EXEC");
+        match exec_result {
+            Ok(values) => self.record(&exec, &Ok(values.first().cloned().unwrap_or(Value::Nil))),
+            Err(err) => self.add(RecordedExchange {
+                request: redacted_args(&exec),
+                response: error_reply(err),
+            }),
+        }
+    }
+
+    /// Returns the exchanges that were recorded so far and are kept in memory - none if the recorder has a file.
+    pub fn exchanges(&self) -> Vec<RecordedExchange> {
+        self.exchanges.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Returns the exchanges that are kept in memory, as JSON lines that can be read by [`parse_recording`].
+    pub fn to_json_lines(&self) -> String {
+        self.exchanges
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|exchange| serde_json::to_string(exchange).unwrap_or_default() + "\n")
+            .collect()
+    }
+}
+
+/// Returns the RESP encoding of the error reply that `err` was created from, or `None` if it wasn't a reply.
+fn error_reply(err: &RedisError) -> Option<Vec<u8>> {
+    err.code().map(|code| match err.detail() {
+        Some(detail) => format!("-{code} {detail}\r\n").into_bytes(),
+        None => format!("-{code}\r\n").into_bytes(),
+    })
+}
+
+/// Bytes are recorded as a string if they're valid UTF-8, so that recordings are readable, and as an array of bytes
+/// otherwise, so that they're recorded losslessly.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum RecordedBytes {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl RecordedBytes {
+    fn new(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => RecordedBytes::Text(text.to_string()),
+            Err(_) => RecordedBytes::Binary(bytes.to_vec()),
+        }
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        match self {
+            RecordedBytes::Text(text) => text.into_bytes(),
+            RecordedBytes::Binary(bytes) => bytes,
+        }
+    }
+}
+
+mod recorded_args {
+    use super::*;
+
+    pub(super) fn serialize<S: Serializer>(
+        args: &[Vec<u8>],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(args.iter().map(|arg| RecordedBytes::new(arg)))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vec<u8>>, D::Error> {
+        let args = Vec::<RecordedBytes>::deserialize(deserializer)?;
+        Ok(args.into_iter().map(RecordedBytes::into_bytes).collect())
+    }
+}
+
+mod recorded_response {
+    use super::*;
+
+    pub(super) fn serialize<S: Serializer>(
+        response: &Option<Vec<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        response
+            .as_deref()
+            .map(RecordedBytes::new)
+            .serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<u8>>, D::Error> {
+        let response = Option::<RecordedBytes>::deserialize(deserializer)?;
+        Ok(response.map(RecordedBytes::into_bytes))
+    }
+}
+
+/// Parses a recording that was written as JSON lines. Empty lines are skipped.
+pub fn parse_recording(recording: &str) -> RedisResult<Vec<RecordedExchange>> {
+    recording
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).map_err(|err| {
+                RedisError::from((
+                    ErrorKind::ClientError,
+                    "Invalid session recording",
+                    err.to_string(),
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Returns the arguments of `cmd`, with passwords replaced by [`REDACTED`].
+fn redacted_args(cmd: &Cmd) -> Vec<Vec<u8>> {
+    let mut args: Vec<Vec<u8>> = cmd
+        .args_iter()
+        .map(|arg| match arg {
+            Arg::Simple(arg) => arg.to_vec(),
+            Arg::Cursor => b"0".to_vec(),
+        })
+        .collect();
+    let upper =
+        |index: usize| -> Option<Vec<u8>> { args.get(index).map(|arg| arg.to_ascii_uppercase()) };
+    let mut secrets = Vec::new();
+    match upper(0).as_deref() {
+        Some(b"AUTH") => secrets.extend(1..args.len()),
+        Some(b"HELLO") => {
+            if let Some(auth) =
+                (1..args.len()).find(|index| upper(*index).as_deref() == Some(b"AUTH"))
+            {
+                secrets.push(auth + 2);
+            }
+        }
+        Some(b"MIGRATE") => {
+            for index in 1..args.len() {
+                match upper(index).as_deref() {
+                    Some(b"AUTH") => secrets.push(index + 1),
+                    Some(b"AUTH2") => secrets.push(index + 2),
+                    _ => {}
+                }
+            }
+        }
+        Some(b"CONFIG") if upper(1).as_deref() == Some(b"SET") => {
+            for index in (2..args.len()).step_by(2) {
+                if upper(index).is_some_and(|parameter| {
+                    parameter.ends_with(b"PASS") || parameter.ends_with(b"AUTH")
+                }) {
+                    secrets.push(index + 1);
+                }
+            }
+        }
+        Some(b"ACL") if upper(1).as_deref() == Some(b"SETUSER") => {
+            // Rules that add or remove passwords, by value or by hash.
+            secrets.extend(
+                (3..args.len()).filter(|index| {
+                    matches!(args[*index].first(), Some(b'>' | b'<' | b'#' | b'!'))
+                }),
+            );
+        }
+        _ => {}
+    }
+    for index in secrets {
+        if let Some(arg) = args.get_mut(index) {
+            *arg = REDACTED.as_bytes().to_vec();
+        }
+    }
+    args
+}
+
+fn encode_reply(value: &Value, out: &mut Vec<u8>) {
+    let encode_all = |values: &[Value], prefix: char, out: &mut Vec<u8>| {
+        out.extend(format!("{prefix}{}\r\n", values.len()).into_bytes());
+        for value in values {
+            encode_reply(value, out);
+        }
+    };
+    let encode_pairs = |pairs: &[(Value, Value)], prefix: char, out: &mut Vec<u8>| {
+        out.extend(format!("{prefix}{}\r\n", pairs.len()).into_bytes());
+        for (key, value) in pairs {
+            encode_reply(key, out);
+            encode_reply(value, out);
+        }
+    };
+    match value {
+        Value::Nil => out.extend_from_slice(b"$-1\r\n"),
+        Value::Int(value) => out.extend(format!(":{value}\r\n").into_bytes()),
+        Value::BulkString(bytes) => {
+            out.extend(format!("${}\r\n", bytes.len()).into_bytes());
+            out.extend_from_slice(bytes);
+            out.extend_from_slice(b"\r\n");
+        }
+        Value::Array(values) => encode_all(values, '*', out),
+        Value::Okay => out.extend_from_slice(b"+OK\r\n"),
+        Value::SimpleString(string) => out.extend(format!("+{string}\r\n").into_bytes()),
+        Value::Map(pairs) => encode_pairs(pairs, '%', out),
+        Value::Attribute { data, attributes } => {
+            encode_pairs(attributes, '|', out);
+            encode_reply(data, out);
+        }
+        Value::Set(values) => encode_all(values, '~', out),
+        Value::Double(value) => out.extend(format!(",{value}\r\n").into_bytes()),
+        Value::Boolean(value) => {
+            out.extend(format!("#{}\r\n", if *value { 't' } else { 'f' }).into_bytes())
+        }
+        Value::VerbatimString { format, text } => {
+            out.extend(format!("={}\r\n{format}:{text}\r\n", text.len() + 4).into_bytes())
+        }
+        Value::BigNumber(value) => out.extend(format!("({value}\r\n").into_bytes()),
+        Value::Push { kind, data } => {
+            out.extend(format!(">{}\r\n+{kind}\r\n", data.len() + 1).into_bytes());
+            for value in data {
+                encode_reply(value, out);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::test_command;
+
+    fn recorded_request(args: &[&str]) -> Vec<String> {
+        RecordedExchange::new(&test_command(args), &Ok(Value::Okay))
+            .request
+            .into_iter()
+            .map(|arg| String::from_utf8(arg).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_secrets_are_redacted() {
+        assert_eq!(
+            recorded_request(&["AUTH", "user", "pass"]),
+            ["AUTH", REDACTED, REDACTED]
+        );
+        assert_eq!(
+            recorded_request(&["HELLO", "3", "AUTH", "user", "pass", "SETNAME", "name"]),
+            ["HELLO", "3", "AUTH", "user", REDACTED, "SETNAME", "name"]
+        );
+        assert_eq!(
+            recorded_request(&["CONFIG", "SET", "maxmemory", "1mb", "requirepass", "pass"]),
+            ["CONFIG", "SET", "maxmemory", "1mb", "requirepass", REDACTED]
+        );
+        assert_eq!(
+            recorded_request(&["ACL", "SETUSER", "user", "on", ">pass", "~*"]),
+            ["ACL", "SETUSER", "user", "on", REDACTED, "~*"]
+        );
+        assert_eq!(
+            recorded_request(&[
+                "MIGRATE", "host", "6379", "key", "0", "5000", "AUTH2", "user", "pass"
+            ]),
+            ["MIGRATE", "host", "6379", "key", "0", "5000", "AUTH2", "user", REDACTED]
+        );
+        assert_eq!(
+            recorded_request(&["SET", "auth", "pass"]),
+            ["SET", "auth", "pass"]
+        );
+    }
+
+    #[test]
+    fn test_recording_round_trips_through_json_lines() {
+        let recorder = SessionRecorder::new();
+        let mut get = redis::cmd("GET");
+        get.arg("key");
+        recorder.record(&get, &Ok(Value::BulkString(b"value".to_vec())));
+        let mut hgetall = redis::cmd("HGETALL");
+        hgetall.arg("hash");
+        recorder.record(
+            &hgetall,
+            &Ok(Value::Array(vec![
+                Value::BulkString(b"field".to_vec()),
+                Value::Int(1),
+            ])),
+        );
+        recorder.record(
+            &get,
+            &Err(RedisError::from((
+                ErrorKind::ResponseError,
+                "An error was signalled by the server",
+                "unknown command".to_string(),
+            ))),
+        );
+        recorder.record(
+            &get,
+            &Err(RedisError::from(io::Error::from(io::ErrorKind::BrokenPipe))),
+        );
+
+        let exchanges = parse_recording(&recorder.to_json_lines()).unwrap();
+        assert_eq!(exchanges, recorder.exchanges());
+        let responses: Vec<Option<&[u8]>> = exchanges
+            .iter()
+            .map(|exchange| exchange.response.as_deref())
+            .collect();
+        assert_eq!(
+            responses,
+            [
+                Some(&b"$5\r\nvalue\r\n"[..]),
+                Some(&b"*2\r\n$5\r\nfield\r\n:1\r\n"[..]),
+                Some(&b"-ERR unknown command\r\n"[..]),
+                None,
+            ]
+        );
+        for (index, exchange) in exchanges.iter().enumerate() {
+            let expected = if index == 1 { &hgetall } else { &get };
+            assert_eq!(
+                exchange.to_cmd().get_packed_command(),
+                expected.get_packed_command()
+            );
+        }
+    }
+
+    #[test]
+    fn test_binary_arguments_and_replies_are_recorded_losslessly() {
+        let recorder = SessionRecorder::new();
+        let payload = vec![0xff, 0x00, 0xc3, 0x28];
+        let mut set = redis::cmd("SET");
+        set.arg("key").arg(payload.as_slice());
+        recorder.record(&set, &Ok(Value::BulkString(payload.clone())));
+
+        let exchanges = parse_recording(&recorder.to_json_lines()).unwrap();
+        assert_eq!(exchanges[0].request[2], payload);
+        let mut expected_response = b"$4\r\n".to_vec();
+        expected_response.extend_from_slice(&payload);
+        expected_response.extend_from_slice(b"\r\n");
+        assert_eq!(exchanges[0].response, Some(expected_response));
+        assert_eq!(
+            exchanges[0].to_cmd().get_packed_command(),
+            set.get_packed_command()
+        );
+    }
+
+    #[test]
+    fn test_only_the_last_exchanges_are_kept_in_memory() {
+        let recorder = SessionRecorder::new();
+        for index in 0..MAX_RECORDED_EXCHANGES + 1 {
+            let mut get = redis::cmd("GET");
+            get.arg(index);
+            recorder.record(&get, &Ok(Value::Nil));
+        }
+        let exchanges = recorder.exchanges();
+        assert_eq!(exchanges.len(), MAX_RECORDED_EXCHANGES);
+        assert_eq!(exchanges[0].request[1], b"1");
+    }
+
+    #[test]
+    fn test_transactions_are_recorded_as_sent() {
+        let recorder = SessionRecorder::new();
+        let mut transaction = redis::pipe();
+        transaction.atomic().cmd("SET").arg("key").arg("value");
+        recorder.record_transaction(&transaction, &Ok(vec![Value::Array(vec![Value::Okay])]));
+
+        let exchanges = recorder.exchanges();
+        let commands: Vec<&[u8]> = exchanges
+            .iter()
+            .map(|exchange| exchange.request[0].as_slice())
+            .collect();
+        assert_eq!(commands, [&b"MULTI"[..], b"SET", b"EXEC"]);
+        assert_eq!(exchanges[1].response.as_deref(), Some(&b"+QUEUED\r\n"[..]));
+        assert_eq!(
+            exchanges[2].response.as_deref(),
+            Some(&b"*1\r\n+OK\r\n"[..])
+        );
+    }
+
+    #[test]
+    fn test_recording_to_a_file_is_written_by_the_writer_thread() {
+        let path =
+            std::env::temp_dir().join(format!("session-recording-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let recorder = SessionRecorder::with_file(path.to_str().unwrap()).unwrap();
+        let mut get = redis::cmd("GET");
+        get.arg("key");
+        recorder.record(&get, &Ok(Value::Nil));
+        assert!(recorder.exchanges().is_empty());
+
+        let mut recording = String::new();
+        for _ in 0..100 {
+            recording = std::fs::read_to_string(&path).unwrap();
+            if !recording.is_empty() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        let _ = std::fs::remove_file(&path);
+        let exchanges = parse_recording(&recording).unwrap();
+        assert_eq!(exchanges.len(), 1);
+        assert_eq!(
+            exchanges[0].to_cmd().get_packed_command(),
+            get.get_packed_command()
+        );
+    }
+}
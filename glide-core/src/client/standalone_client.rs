@@ -2,24 +2,25 @@
 
 use super::get_redis_connection_info;
 use super::reconnecting_connection::{ReconnectReason, ReconnectingConnection};
+use super::task_supervisor::spawn_supervised;
 use super::{to_duration, DEFAULT_CONNECTION_TIMEOUT};
-use super::{ConnectionRequest, NodeAddress, TlsMode};
+use super::{ConnectionRequest, NodeAddress, NodeTls, PeriodicCheck};
 use crate::client::types::ReadFrom as ClientReadFrom;
 use crate::retry_strategies::RetryStrategy;
 use futures::{future, stream, StreamExt};
 use logger_core::log_debug;
 use logger_core::log_warn;
 use rand::Rng;
-use redis::aio::ConnectionLike;
+use redis::aio::{ConnectionLike, MultiplexedConnection};
 use redis::cluster_routing::{self, is_readonly_cmd, ResponsePolicy, Routable, RoutingInfo};
+use redis::cluster_slotmap::RoutingWeights;
 use redis::{PushInfo, RedisError, RedisResult, Value};
-use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::{Arc, Weak};
 use std::time::Duration;
-use telemetrylib::Telemetry;
+use telemetrylib::{NodeEvent, Telemetry};
 use tokio::sync::mpsc;
-use tokio::task;
 
 #[derive(Debug)]
 enum ReadFrom {
@@ -35,10 +36,14 @@ enum ReadFrom {
 
 #[derive(Debug)]
 struct DropWrapper {
-    /// Connection to the primary node in the client.
-    primary_index: usize,
+    /// Connection to the primary node in the client. Updated by the role checks when another node becomes the primary.
+    primary_index: AtomicUsize,
+    /// Set when the role checks found that the primary became a replica, and no other node took its place. Writes
+    /// fail while it's set, instead of being sent to a replica.
+    primary_lost: AtomicBool,
     nodes: Vec<ReconnectingConnection>,
     read_from: ReadFrom,
+    routing_weights: RoutingWeights,
 }
 
 impl Drop for DropWrapper {
@@ -122,7 +127,7 @@ impl StandaloneClient {
         redis_connection_info.pubsub_subscriptions = None;
         let retry_strategy = RetryStrategy::new(connection_request.connection_retry_strategy);
 
-        let tls_mode = connection_request.tls_mode;
+        let tls = NodeTls::from_request(&connection_request);
         let node_count = connection_request.addresses.len();
         // randomize pubsub nodes, maybe a batter option is to always use the primary
         let pubsub_node_index = rand::thread_rng().gen_range(0..node_count);
@@ -147,7 +152,7 @@ impl StandaloneClient {
                     } else {
                         &pubsub_connection_info
                     },
-                    tls_mode.unwrap_or(TlsMode::NoTls),
+                    &tls,
                     &push_sender,
                     discover_az,
                     connection_timeout,
@@ -210,6 +215,13 @@ impl StandaloneClient {
             );
         }
         let read_from = get_read_from(connection_request.read_from);
+        let role_checks_interval = match connection_request.periodic_checks {
+            Some(PeriodicCheck::Disabled) => None,
+            Some(PeriodicCheck::ManualInterval(interval)) => Some(interval),
+            Some(PeriodicCheck::Enabled) | None => {
+                Some(super::DEFAULT_PERIODIC_TOPOLOGY_CHECKS_INTERVAL)
+            }
+        };
 
         #[cfg(feature = "standalone_heartbeat")]
         for node in nodes.iter() {
@@ -223,23 +235,101 @@ impl StandaloneClient {
         // Successfully created new client. Update the telemetry
         Telemetry::incr_total_clients(1);
 
-        Ok(Self {
+        let client = Self {
             inner: Arc::new(DropWrapper {
-                primary_index,
+                primary_index: AtomicUsize::new(primary_index),
+                primary_lost: AtomicBool::new(false),
                 nodes,
                 read_from,
+                routing_weights: RoutingWeights::default(),
             }),
-        })
+        };
+        if let Some(interval) = role_checks_interval {
+            Self::start_role_checks(Arc::downgrade(&client.inner), interval, push_sender.clone());
+        }
+        Ok(client)
+    }
+
+    pub(super) fn primary_address(&self) -> String {
+        self.get_primary_connection().node_address()
+    }
+
+    /// Returns the number of replicas that are currently connected. Every node other than the primary reported a
+    /// replica role when the client was created, unless it couldn't be reached then.
+    pub(super) fn connected_replicas(&self) -> usize {
+        let primary_index = self.primary_index();
+        self.inner
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(index, node)| *index != primary_index && node.is_connected())
+            .count()
+    }
+
+    /// Whether reads may be served by a replica, which requires at least one connected replica.
+    pub(super) fn has_replicas(&self) -> bool {
+        self.connected_replicas() > 0
+    }
+
+    /// Opens a connection to the primary that isn't shared with other requests, e.g. for `WATCH` and the transaction
+    /// that follows it.
+    pub(super) async fn dedicated_primary_connection(&self) -> RedisResult<MultiplexedConnection> {
+        self.get_primary_connection()
+            .open_dedicated_connection()
+            .await
     }
 
     fn get_primary_connection(&self) -> &ReconnectingConnection {
-        self.inner.nodes.get(self.inner.primary_index).unwrap()
+        self.inner.nodes.get(self.primary_index()).unwrap()
+    }
+
+    fn primary_index(&self) -> usize {
+        self.inner.primary_index.load(Ordering::Relaxed)
+    }
+
+    /// Fails if the primary became a replica and no new primary was found, so that writes aren't sent to a replica.
+    fn check_primary_for_writes(&self) -> RedisResult<()> {
+        if self.inner.primary_lost.load(Ordering::Relaxed) {
+            return Err(RedisError::from((
+                redis::ErrorKind::ReadOnly,
+                "The primary became a replica, and no new primary was found",
+            )));
+        }
+        Ok(())
+    }
+
+    pub(super) fn routing_weights(&self) -> RoutingWeights {
+        self.inner.routing_weights.clone()
+    }
+
+    /// Chooses a connected replica in proportion to the routing weights, falling back to the primary if all of the
+    /// connected replicas have weight 0.
+    fn weighted_read_from_replica(&self) -> &ReconnectingConnection {
+        let primary_index = self.primary_index();
+        let chosen = self.inner.routing_weights.choose(
+            self.inner
+                .nodes
+                .iter()
+                .enumerate()
+                .filter(|(index, node)| *index != primary_index && node.is_connected())
+                .map(|(_, node)| (node.address(), node)),
+        );
+        match chosen {
+            Some((node, weight)) => {
+                Telemetry::incr_weighted_reads(RoutingWeights::weight_class(weight));
+                node
+            }
+            None => self.get_primary_connection(),
+        }
     }
 
     fn round_robin_read_from_replica(
         &self,
         latest_read_replica_index: &Arc<AtomicUsize>,
     ) -> &ReconnectingConnection {
+        if !self.inner.routing_weights.is_empty() {
+            return self.weighted_read_from_replica();
+        }
         let initial_index = latest_read_replica_index.load(Ordering::Relaxed);
         let mut check_count = 0;
         loop {
@@ -250,7 +340,7 @@ impl StandaloneClient {
                 return self.get_primary_connection();
             }
             let index = (initial_index + check_count) % self.inner.nodes.len();
-            if index == self.inner.primary_index {
+            if index == self.primary_index() {
                 continue;
             }
             let Some(connection) = self.inner.nodes.get(index) else {
@@ -335,6 +425,9 @@ impl StandaloneClient {
     ) -> RedisResult<Value> {
         let mut connection = reconnecting_connection.get_connection().await?;
         let result = connection.send_packed_command(cmd).await;
+        let node_address = reconnecting_connection.node_address();
+        redis::byte_counters::record_command(cmd, &node_address, result.as_ref().ok());
+        Telemetry::record_node_event(&node_address, NodeEvent::for_request(result.is_ok()));
         match result {
             Err(err) if err.is_unrecoverable_error() => {
                 log_warn("send request", format!("received disconnect error `{err}`"));
@@ -421,6 +514,9 @@ impl StandaloneClient {
         cmd: &redis::Cmd,
         readonly: bool,
     ) -> RedisResult<Value> {
+        if !readonly {
+            self.check_primary_for_writes()?;
+        }
         let reconnecting_connection = self.get_connection(readonly).await;
         Self::send_request(cmd, reconnecting_connection).await
     }
@@ -438,17 +534,47 @@ impl StandaloneClient {
             .await
     }
 
+    /// Sends `cmd` to the primary, even if it's read-only. Used for commands that have to reach the same node
+    /// across calls, such as SCAN.
+    pub(super) async fn send_command_to_primary(&mut self, cmd: &redis::Cmd) -> RedisResult<Value> {
+        Self::send_request(cmd, self.get_primary_connection()).await
+    }
+
+    /// Sends `cmd` to the primary without a reply, see
+    /// [`redis::aio::MultiplexedConnection::send_packed_command_without_reply`].
+    pub(super) async fn send_command_without_reply(&mut self, cmd: &redis::Cmd) -> RedisResult<()> {
+        self.check_primary_for_writes()?;
+        let reconnecting_connection = self.get_primary_connection();
+        let mut connection = reconnecting_connection.get_connection().await?;
+        let result = connection.send_packed_command_without_reply(cmd).await;
+        match result {
+            Err(err) if err.is_unrecoverable_error() => {
+                log_warn(
+                    "send request without reply",
+                    format!("received disconnect error `{err}`"),
+                );
+                reconnecting_connection.reconnect(ReconnectReason::ConnectionDropped);
+                Err(err)
+            }
+            _ => result,
+        }
+    }
+
     pub async fn send_pipeline(
         &mut self,
         pipeline: &redis::Pipeline,
         offset: usize,
         count: usize,
     ) -> RedisResult<Vec<Value>> {
+        self.check_primary_for_writes()?;
         let reconnecting_connection = self.get_primary_connection();
         let mut connection = reconnecting_connection.get_connection().await?;
         let result = connection
             .send_packed_commands(pipeline, offset, count)
             .await;
+        let node_address = reconnecting_connection.node_address();
+        redis::byte_counters::record_pipeline(pipeline, &node_address, result.as_deref().ok());
+        Telemetry::record_node_event(&node_address, NodeEvent::for_request(result.is_ok()));
         match result {
             Err(err) if err.is_unrecoverable_error() => {
                 log_warn(
@@ -464,35 +590,67 @@ impl StandaloneClient {
 
     #[cfg(feature = "standalone_heartbeat")]
     fn start_heartbeat(reconnecting_connection: ReconnectingConnection) {
-        task::spawn(async move {
-            loop {
-                tokio::time::sleep(super::HEARTBEAT_SLEEP_DURATION).await;
-                if reconnecting_connection.is_dropped() {
-                    log_debug(
-                        "StandaloneClient",
-                        "heartbeat stopped after connection was dropped",
-                    );
-                    // Client was dropped, heartbeat can stop.
-                    return;
+        let push_sender = reconnecting_connection.push_sender();
+        spawn_supervised("heartbeat", push_sender, move || {
+            let reconnecting_connection = reconnecting_connection.clone();
+            async move {
+                loop {
+                    tokio::time::sleep(super::HEARTBEAT_SLEEP_DURATION).await;
+                    if reconnecting_connection.is_dropped() {
+                        log_debug(
+                            "StandaloneClient",
+                            "heartbeat stopped after connection was dropped",
+                        );
+                        // Client was dropped, heartbeat can stop.
+                        return;
+                    }
+
+                    let Some(mut connection) = reconnecting_connection.try_get_connection().await
+                    else {
+                        log_debug(
+                            "StandaloneClient",
+                            "heartbeat stopped while connection is reconnecting",
+                        );
+                        // Client is reconnecting..
+                        continue;
+                    };
+                    log_debug("StandaloneClient", "performing heartbeat");
+                    if connection
+                        .send_packed_command(&redis::cmd("PING"))
+                        .await
+                        .is_err_and(|err| {
+                            err.is_connection_dropped() || err.is_connection_refusal()
+                        })
+                    {
+                        log_debug("StandaloneClient", "heartbeat triggered reconnect");
+                        reconnecting_connection.reconnect(ReconnectReason::ConnectionDropped);
+                    }
                 }
+            }
+        });
+    }
 
-                let Some(mut connection) = reconnecting_connection.try_get_connection().await
-                else {
-                    log_debug(
-                        "StandaloneClient",
-                        "heartbeat stopped while connection is reconnecting",
-                    );
-                    // Client is reconnecting..
-                    continue;
-                };
-                log_debug("StandaloneClient", "performing heartbeat");
-                if connection
-                    .send_packed_command(&redis::cmd("PING"))
-                    .await
-                    .is_err_and(|err| err.is_connection_dropped() || err.is_connection_refusal())
-                {
-                    log_debug("StandaloneClient", "heartbeat triggered reconnect");
-                    reconnecting_connection.reconnect(ReconnectReason::ConnectionDropped);
+    /// Periodically checks that the primary is still a primary, since it might have become a replica without
+    /// disconnecting, e.g. after a manual REPLICAOF or the healing of a split brain. If it did, the nodes are asked for
+    /// their roles, and the node that became the primary, if there's exactly one, receives the writes from then on.
+    fn start_role_checks(
+        inner: Weak<DropWrapper>,
+        interval: Duration,
+        push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
+    ) {
+        spawn_supervised("role check", push_sender, move || {
+            let inner = inner.clone();
+            async move {
+                loop {
+                    tokio::time::sleep(interval).await;
+                    let Some(inner) = inner.upgrade() else {
+                        log_debug(
+                            "StandaloneClient",
+                            "role checks stopped after client was dropped",
+                        );
+                        return;
+                    };
+                    inner.check_roles().await;
                 }
             }
         });
@@ -502,42 +660,54 @@ impl StandaloneClient {
     // This function is cheaper alternative to start_heartbeat(),
     // as it avoids sending PING commands to the server, checking only the connection state.
     fn start_periodic_connection_check(reconnecting_connection: ReconnectingConnection) {
-        task::spawn(async move {
-            loop {
-                reconnecting_connection
-                    .wait_for_disconnect_with_timeout(&super::CONNECTION_CHECKS_INTERVAL)
-                    .await;
-                // check connection is valid
-                if reconnecting_connection.is_dropped() {
-                    log_debug(
-                        "StandaloneClient",
-                        "connection checker stopped after connection was dropped",
-                    );
-
-                    // Client was dropped, checker can stop.
-                    return;
-                }
+        let push_sender = reconnecting_connection.push_sender();
+        spawn_supervised("connection check", push_sender, move || {
+            let reconnecting_connection = reconnecting_connection.clone();
+            async move {
+                loop {
+                    reconnecting_connection
+                        .wait_for_disconnect_with_timeout(&super::CONNECTION_CHECKS_INTERVAL)
+                        .await;
+                    // check connection is valid
+                    if reconnecting_connection.is_dropped() {
+                        log_debug(
+                            "StandaloneClient",
+                            "connection checker stopped after connection was dropped",
+                        );
 
-                let Some(connection) = reconnecting_connection.try_get_connection().await else {
-                    log_debug(
-                        "StandaloneClient",
-                        "connection checker is skipping a connections since its reconnecting",
-                    );
-                    // Client is reconnecting..
-                    continue;
-                };
+                        // Client was dropped, checker can stop.
+                        return;
+                    }
 
-                if connection.is_closed() {
-                    log_debug(
-                        "StandaloneClient",
-                        "connection checker has triggered reconnect",
-                    );
-                    reconnecting_connection.reconnect(ReconnectReason::ConnectionDropped);
+                    let Some(connection) = reconnecting_connection.try_get_connection().await
+                    else {
+                        log_debug(
+                            "StandaloneClient",
+                            "connection checker is skipping a connections since its reconnecting",
+                        );
+                        // Client is reconnecting..
+                        continue;
+                    };
+
+                    if connection.is_closed() {
+                        log_debug(
+                            "StandaloneClient",
+                            "connection checker has triggered reconnect",
+                        );
+                        reconnecting_connection.reconnect(ReconnectReason::ConnectionDropped);
+                    }
                 }
             }
         });
     }
 
+    /// Closes the connections to all the nodes. Each of them is reopened by the next request that is sent to its node.
+    pub(crate) fn suspend(&self) {
+        for node in self.inner.nodes.iter() {
+            node.suspend();
+        }
+    }
+
     /// Update the password used to authenticate with the servers.
     /// If the password is `None`, the password will be removed.
     pub async fn update_connection_password(
@@ -557,7 +727,7 @@ async fn get_connection_and_replication_info(
     address: &NodeAddress,
     retry_strategy: &RetryStrategy,
     connection_info: &redis::RedisConnectionInfo,
-    tls_mode: TlsMode,
+    tls: &NodeTls,
     push_sender: &Option<mpsc::UnboundedSender<PushInfo>>,
     discover_az: bool,
     connection_timeout: Duration,
@@ -566,7 +736,7 @@ async fn get_connection_and_replication_info(
         address,
         retry_strategy.clone(),
         connection_info.clone(),
-        tls_mode,
+        tls,
         push_sender.clone(),
         discover_az,
         connection_timeout,
@@ -598,6 +768,87 @@ async fn get_connection_and_replication_info(
     }
 }
 
+impl DropWrapper {
+    async fn check_roles(&self) {
+        let old_primary = &self.nodes[self.primary_index.load(Ordering::Relaxed)];
+        match node_role(old_primary).await {
+            // The node is unreachable, which is handled by reconnecting.
+            None => return,
+            Some(role) if role == b"master" => {
+                if self.primary_lost.swap(false, Ordering::Relaxed) {
+                    log_warn(
+                        "role check",
+                        format!(
+                            "The node at {} is a primary again",
+                            old_primary.node_address()
+                        ),
+                    );
+                }
+                return;
+            }
+            Some(_) => {}
+        }
+
+        let was_lost = self.primary_lost.load(Ordering::Relaxed);
+        if !was_lost {
+            log_warn(
+                "role check",
+                format!(
+                    "The primary at {} became a replica, looking for the new primary",
+                    old_primary.node_address()
+                ),
+            );
+            Telemetry::record_node_event(&old_primary.node_address(), NodeEvent::RoleChange);
+        }
+        let roles = future::join_all(self.nodes.iter().map(node_role)).await;
+        let primaries: Vec<usize> = roles
+            .iter()
+            .enumerate()
+            .filter(|(_, role)| role.as_deref() == Some(b"master".as_slice()))
+            .map(|(index, _)| index)
+            .collect();
+        if let [new_primary] = primaries.as_slice() {
+            let new_primary_address = self.nodes[*new_primary].node_address();
+            log_warn(
+                "role check",
+                format!("The node at {new_primary_address} is the new primary"),
+            );
+            Telemetry::record_node_event(&new_primary_address, NodeEvent::RoleChange);
+            self.primary_index.store(*new_primary, Ordering::Relaxed);
+            self.primary_lost.store(false, Ordering::Relaxed);
+            return;
+        }
+        if !was_lost {
+            log_warn(
+                "role check",
+                format!(
+                    "Found {} primaries instead of one, writes will fail until a single primary is found",
+                    primaries.len()
+                ),
+            );
+        }
+        self.primary_lost.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Returns the role that the node reports in the first element of the ROLE reply, e.g. `master` or `slave`. `None` if
+/// the node is reconnecting or didn't reply.
+async fn node_role(node: &ReconnectingConnection) -> Option<Vec<u8>> {
+    let mut connection = node.try_get_connection().await?;
+    let reply = connection
+        .send_packed_command(&redis::cmd("ROLE"))
+        .await
+        .ok()?;
+    let Value::Array(values) = reply else {
+        return None;
+    };
+    match values.into_iter().next()? {
+        Value::BulkString(role) => Some(role),
+        Value::SimpleString(role) => Some(role.into_bytes()),
+        _ => None,
+    }
+}
+
 fn get_read_from(read_from: Option<super::ReadFrom>) -> ReadFrom {
     match read_from {
         Some(super::ReadFrom::Primary) => ReadFrom::Primary,
@@ -0,0 +1,91 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! A blocking facade over [`Client`], for applications that don't run an async runtime.
+
+use super::{Client, ConnectionError, ConnectionRequest};
+use redis::cluster_routing::RoutingInfo;
+use redis::{Cmd, Pipeline, PushInfo, RedisResult, Value};
+use std::future::Future;
+use std::sync::Arc;
+use tokio::runtime::{Builder, Runtime};
+use tokio::sync::mpsc;
+
+/// A client whose calls block the calling thread until the reply arrives. Replies are converted and commands are
+/// routed exactly as by [`Client`].
+///
+/// The client owns the runtime that drives its connections, so its background tasks, such as reconnections and
+/// periodic checks, keep running between calls. It is cheap to clone, and clones share the connections and the
+/// runtime, so a client can be used from many threads at once.
+///
+/// The calls must not be made from within an async context, since blocking there would stall the executor. For the
+/// same reason, the last clone of a client must not be dropped from within an async context.
+#[derive(Clone)]
+pub struct SyncClient {
+    client: Client,
+    runtime: Arc<Runtime>,
+}
+
+impl SyncClient {
+    /// Creates a client and connects it, blocking until the connection is established or has failed.
+    pub fn new(
+        request: ConnectionRequest,
+        push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
+    ) -> Result<Self, ConnectionError> {
+        let runtime = Builder::new_multi_thread()
+            .enable_all()
+            .worker_threads(1)
+            .thread_name("Valkey-GLIDE sync client thread")
+            .build()
+            .map_err(ConnectionError::Runtime)?;
+        let client = runtime.block_on(Client::new(request, push_sender))?;
+        Ok(SyncClient {
+            client,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    pub fn send_command(&self, cmd: &Cmd, routing: Option<RoutingInfo>) -> RedisResult<Value> {
+        self.run(|mut client| async move { client.send_command(cmd, routing).await })
+    }
+
+    pub fn send_transaction(
+        &self,
+        pipeline: &Pipeline,
+        routing: Option<RoutingInfo>,
+    ) -> RedisResult<Value> {
+        self.run(|mut client| async move { client.send_transaction(pipeline, routing).await })
+    }
+
+    pub fn invoke_script(
+        &self,
+        hash: &str,
+        keys: &Vec<&[u8]>,
+        args: &Vec<&[u8]>,
+        routing: Option<RoutingInfo>,
+    ) -> RedisResult<Value> {
+        self.run(|mut client| async move { client.invoke_script(hash, keys, args, routing).await })
+    }
+
+    /// See [`Client::update_connection_password`].
+    pub fn update_connection_password(
+        &self,
+        password: Option<String>,
+        immediate_auth: bool,
+    ) -> RedisResult<Value> {
+        self.run(|mut client| async move {
+            client
+                .update_connection_password(password, immediate_auth)
+                .await
+        })
+    }
+
+    /// Runs `operation` on a clone of the underlying client, and blocks until it completes. This gives access to the
+    /// parts of [`Client`]'s API that have no blocking counterpart here.
+    pub fn run<F, Fut>(&self, operation: F) -> Fut::Output
+    where
+        F: FnOnce(Client) -> Fut,
+        Fut: Future,
+    {
+        self.runtime.block_on(operation(self.client.clone()))
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::PushInfo;
+use std::future::Future;
+use tokio::sync::mpsc;
+use tokio::task;
+
+/// Spawns the background task created by `make_task`, supervised by [`redis::run_supervised`].
+/// Each panic is reported to the wrapper as a `FatalClientError` push notification, if `push_sender` is set.
+///
+/// Only use this for tasks that can safely be restarted from scratch.
+pub(super) fn spawn_supervised<F, Fut>(
+    task_name: &'static str,
+    push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
+    make_task: F,
+) where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    task::spawn(redis::run_supervised(
+        task_name,
+        make_task,
+        move |push_info| {
+            if let Some(push_sender) = &push_sender {
+                let _ = push_sender.send(push_info);
+            }
+            async {}
+        },
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use redis::{PushKind, Value};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_panicked_task_is_reported_and_restarted() {
+        let (push_sender, mut push_receiver) = mpsc::unbounded_channel();
+        let (done_sender, mut done_receiver) = mpsc::unbounded_channel();
+        let runs = Arc::new(AtomicUsize::new(0));
+        let runs_clone = runs.clone();
+        spawn_supervised("test task", Some(push_sender), move || {
+            let run = runs_clone.fetch_add(1, Ordering::Relaxed);
+            let done_sender = done_sender.clone();
+            async move {
+                if run == 0 {
+                    panic!("first run failed");
+                }
+                let _ = done_sender.send(());
+            }
+        });
+
+        let push_info = push_receiver.recv().await.unwrap();
+        assert_eq!(push_info.kind, PushKind::FatalClientError);
+        assert_eq!(
+            push_info.data,
+            vec![
+                Value::SimpleString("test task".to_string()),
+                Value::SimpleString("first run failed".to_string())
+            ]
+        );
+        done_receiver.recv().await.unwrap();
+        assert_eq!(runs.load(Ordering::Relaxed), 2);
+    }
+}
@@ -4,6 +4,7 @@
 use logger_core::log_warn;
 #[allow(unused_imports)]
 use std::collections::HashSet;
+use std::sync::Arc;
 use std::time::Duration;
 
 #[cfg(feature = "socket-layer")]
@@ -25,6 +26,203 @@ pub struct ConnectionRequest {
     pub periodic_checks: Option<PeriodicCheck>,
     pub pubsub_subscriptions: Option<redis::PubSubSubscriptionInfo>,
     pub inflight_requests_limit: Option<u32>,
+    pub adaptive_timeout: Option<AdaptiveTimeoutConfig>,
+    pub write_durability: Option<WriteDurability>,
+    pub structured_ttl: bool,
+    pub typed_time: bool,
+    pub no_evict: bool,
+    pub no_touch: bool,
+    pub audit_log: bool,
+    pub session_recording_path: Option<String>,
+    /// The number of hottest keys kept by the client's access tracking. Tracking is disabled if unset.
+    pub hot_keys_tracked: Option<u32>,
+    /// The size in bytes above which a reply is reported as big. Replies aren't checked if unset.
+    pub big_reply_threshold: Option<u32>,
+    /// The name that the servers' certificates are verified against, and that is sent as SNI, instead of the hosts of
+    /// `addresses`.
+    pub tls_server_name: Option<String>,
+    /// Slot ranges that are routed to the given nodes, instead of their owners in the cluster. Only applied if
+    /// `override_slot_discovery` is set.
+    pub pinned_slots: Vec<redis::cluster_topology::PinnedSlots>,
+    pub override_slot_discovery: bool,
+    /// The time in milliseconds without commands after which the connections are closed, until the next command
+    /// reopens them. Connections are kept open if unset.
+    pub idle_timeout: Option<u32>,
+    /// The checks that are run by [`Client::validate`](crate::client::Client::validate).
+    pub readiness_checks: Option<ReadinessChecks>,
+    /// The process-wide memory cap in bytes, see [`crate::memory_budget::set_memory_cap`], which is set when the client
+    /// is created. The current cap is kept if unset, and the client isn't created if it conflicts with the current cap.
+    pub memory_cap: Option<u64>,
+    /// The process-wide rate of connection attempts per endpoint, see
+    /// [`redis::connection_throttle::request_connection_rate_limit`], which is set when the client is created and removed
+    /// once the clients that requested it were dropped. The current rate is kept if unset, and the client isn't created
+    /// if it conflicts with the current rate.
+    pub connection_rate_limit: Option<redis::connection_throttle::ConnectionRateLimit>,
+    /// The process-wide limit of connections, see [`crate::connection_budget::set_connection_limit`], which is set when
+    /// the client is created. The current limit is kept if unset, and the client isn't created if it conflicts with the
+    /// current limit.
+    pub connection_limit: Option<u32>,
+    /// A scheme that authenticates the connections instead of `authentication_info`, e.g. with tokens that are fetched
+    /// from an identity provider. It can only be set through the Rust API.
+    pub authenticator: Option<Arc<dyn redis::aio::Authenticator>>,
+    /// Values of the protobuf request that this version doesn't recognize, such as enum values that were added in
+    /// newer wrappers. They're reported by [`ConnectionRequest::validate`].
+    pub unrecognized_values: Vec<String>,
+    /// Values of the protobuf request that don't fit their options, such as ports above 65535. They're reported by
+    /// [`ConnectionRequest::validate`].
+    pub invalid_values: Vec<String>,
+}
+
+impl ConnectionRequest {
+    /// Checks for options that conflict with each other, or that would only fail later on, e.g. while connecting or
+    /// by panicking in a background task. Returns a message that lists every problem that was found.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut problems = Vec::new();
+        if self.addresses.is_empty() {
+            problems.push("no addresses were provided".to_string());
+        }
+        for address in self.addresses.iter() {
+            if address.host.is_empty() {
+                problems.push(format!(
+                    "the address with port {} has no host",
+                    address.port
+                ));
+            }
+        }
+        if self.cluster_mode_enabled && self.database_id != 0 {
+            problems.push(format!(
+                "database_id {} can't be selected in cluster mode, which only supports database 0",
+                self.database_id
+            ));
+        }
+        let has_sharded_subscriptions =
+            self.pubsub_subscriptions
+                .as_ref()
+                .is_some_and(|subscriptions| {
+                    subscriptions
+                        .get(&redis::PubSubSubscriptionKind::Sharded)
+                        .is_some_and(|channels| !channels.is_empty())
+                });
+        if has_sharded_subscriptions && !self.cluster_mode_enabled {
+            problems.push("sharded pubsub subscriptions require cluster mode".to_string());
+        }
+        let has_subscriptions = self
+            .pubsub_subscriptions
+            .as_ref()
+            .is_some_and(|subscriptions| {
+                subscriptions.values().any(|channels| !channels.is_empty())
+            });
+        if self.idle_timeout.is_some() && has_subscriptions {
+            problems.push(
+                "idle_timeout can't be combined with pubsub subscriptions, since messages would be lost while the connections are closed"
+                    .to_string(),
+            );
+        }
+        if let Some(PeriodicCheck::ManualInterval(interval)) = &self.periodic_checks {
+            if interval.is_zero() {
+                problems.push(
+                    "the manual interval of periodic_checks must be at least 1 second".to_string(),
+                );
+            }
+        }
+        if let Some(client_name) = &self.client_name {
+            if client_name.contains(char::is_whitespace) {
+                problems.push(format!(
+                    "client_name `{client_name}` can't contain spaces or newlines"
+                ));
+            }
+        }
+        if let Some(server_name) = &self.tls_server_name {
+            if server_name.is_empty() {
+                problems.push("tls_server_name is empty".to_string());
+            } else if self.tls_mode.unwrap_or_default() == TlsMode::NoTls {
+                problems.push(format!(
+                    "tls_server_name `{server_name}` was set, but TLS is disabled"
+                ));
+            }
+        }
+        if self.override_slot_discovery {
+            if !self.cluster_mode_enabled {
+                problems.push("override_slot_discovery requires cluster mode".to_string());
+            }
+            if self.pinned_slots.is_empty() {
+                problems
+                    .push("override_slot_discovery was set, but no slots were pinned".to_string());
+            }
+        }
+        let mut pinned_slots: Vec<_> = self.pinned_slots.iter().collect();
+        pinned_slots.sort_by_key(|pinned| pinned.start);
+        for pinned in pinned_slots.iter() {
+            if pinned.start > pinned.end || pinned.end > 16383 {
+                problems.push(format!(
+                    "the pinned slot range {}-{} isn't a valid range of slots 0-16383",
+                    pinned.start, pinned.end
+                ));
+            }
+            if pinned.address.starts_with(':') {
+                problems.push(format!(
+                    "the pinned slot range {}-{} has no host",
+                    pinned.start, pinned.end
+                ));
+            }
+        }
+        for pair in pinned_slots.windows(2) {
+            if pair[1].start <= pair[0].end {
+                problems.push(format!(
+                    "the pinned slot ranges {}-{} and {}-{} overlap",
+                    pair[0].start, pair[0].end, pair[1].start, pair[1].end
+                ));
+            }
+        }
+        if self.authenticator.is_some() && self.authentication_info.is_some() {
+            problems.push(
+                "an authenticator and authentication_info can't both be set, since the authenticator replaces it"
+                    .to_string(),
+            );
+        }
+        if self
+            .session_recording_path
+            .as_ref()
+            .is_some_and(|path| path.is_empty())
+        {
+            problems.push("session_recording_path is empty".to_string());
+        }
+        if let Some(adaptive_timeout) = &self.adaptive_timeout {
+            // A minimum of 0, which is the default of the protobuf field, would let every request time out at once.
+            if adaptive_timeout.min_timeout.is_zero() {
+                problems.push("the min_timeout of adaptive_timeout must be positive".to_string());
+            }
+            if adaptive_timeout.max_timeout < adaptive_timeout.min_timeout {
+                problems.push(format!(
+                    "the max_timeout of adaptive_timeout ({}ms) is lower than its min_timeout ({}ms)",
+                    adaptive_timeout.max_timeout.as_millis(),
+                    adaptive_timeout.min_timeout.as_millis()
+                ));
+            }
+        }
+        if self
+            .connection_rate_limit
+            .is_some_and(|limit| limit.burst == 0 || limit.attempts_per_second == 0)
+        {
+            problems.push(
+                "the burst and attempts_per_second of connection_rate_limit must be positive"
+                    .to_string(),
+            );
+        }
+        for value in self.unrecognized_values.iter() {
+            problems.push(format!(
+                "{value} isn't recognized by this version of the client core, and might have been sent by a newer wrapper"
+            ));
+        }
+        for value in self.invalid_values.iter() {
+            problems.push(format!("{value} is out of range"));
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems.join("; "))
+        }
+    }
 }
 
 pub struct AuthenticationInfo {
@@ -68,6 +266,302 @@ pub enum TlsMode {
     SecureTls,
 }
 
+/// Bounds for deriving the request timeout from the observed latencies, instead of using a fixed `request_timeout`.
+#[derive(Clone, Debug)]
+pub struct AdaptiveTimeoutConfig {
+    /// The lowest timeout that may be used, regardless of the observed latencies.
+    pub min_timeout: Duration,
+    /// The highest timeout that may be used, regardless of the observed latencies.
+    pub max_timeout: Duration,
+    /// The latency percentile (0-100] that the timeout is derived from.
+    pub percentile: f64,
+    /// Factor applied to the observed percentile, in order to leave a margin above it.
+    pub multiplier: f64,
+}
+
+/// Replication that write commands wait for, by issuing `WAIT` after each of them.
+#[derive(Clone, Copy, Debug)]
+pub struct WriteDurability {
+    /// The number of replicas that should acknowledge each write.
+    pub num_replicas: u32,
+    /// How long to wait for the acknowledgements. A zero timeout waits for the request timeout.
+    pub timeout: Duration,
+}
+
+/// What [`Client::validate`](crate::client::Client::validate) checks, besides connecting to the servers and checking
+/// that reads can be served from replicas if the client reads from replicas.
+#[derive(Clone, Debug, Default)]
+pub struct ReadinessChecks {
+    /// Modules that should be loaded on every node, as named by `MODULE LIST`.
+    pub required_modules: Vec<String>,
+    /// Commands that every node should support, such as `FT.SEARCH` or `CLIENT|NO-EVICT`.
+    pub required_commands: Vec<String>,
+    /// Whether the client is closed once the checks were run.
+    pub disconnect: bool,
+}
+
+/// Options for [`Client::delete_by_pattern`](crate::client::Client::delete_by_pattern).
+#[derive(Clone, Copy, Debug)]
+pub struct DeleteByPatternOptions {
+    /// The `COUNT` hint passed to `SCAN`, which bounds the number of keys that are unlinked in each batch.
+    pub batch_size: u32,
+    /// The highest number of keys to delete per second. No limit is applied if unset.
+    pub max_keys_per_second: Option<u32>,
+}
+
+impl Default for DeleteByPatternOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 100,
+            max_keys_per_second: None,
+        }
+    }
+}
+
+/// The result of [`Client::delete_by_pattern`](crate::client::Client::delete_by_pattern).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeleteByPatternResult {
+    /// The number of deleted keys.
+    pub deleted: u64,
+    /// The number of keys deleted so far after each batch that had matching keys.
+    pub progress: Vec<u64>,
+}
+
+impl DeleteByPatternResult {
+    /// Returns the value that is passed to the wrappers - a map with `deleted` and `progress` fields.
+    pub fn to_value(&self) -> redis::Value {
+        let string = |value: &str| redis::Value::SimpleString(value.to_string());
+        redis::Value::Map(vec![
+            (string("deleted"), redis::Value::Int(self.deleted as i64)),
+            (
+                string("progress"),
+                redis::Value::Array(
+                    self.progress
+                        .iter()
+                        .map(|deleted| redis::Value::Int(*deleted as i64))
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+}
+
+/// The collection that [`Client::scan_page`](crate::client::Client::scan_page) iterates over.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScanTarget {
+    /// The keys of the database, with SCAN. In cluster mode the keys of all primaries are scanned.
+    Keys,
+    /// The fields of a hash, with HSCAN. Each item is a `[field, value]` array.
+    Hash(Vec<u8>),
+    /// The members of a set, with SSCAN.
+    Set(Vec<u8>),
+    /// The members of a sorted set, with ZSCAN. Each item is a `[member, score]` array.
+    SortedSet(Vec<u8>),
+}
+
+/// Options for [`Client::scan_page`](crate::client::Client::scan_page).
+#[derive(Clone, Debug)]
+pub struct ScanPageOptions {
+    /// Only items that match this glob-style pattern are returned.
+    pub pattern: Option<Vec<u8>>,
+    /// The `COUNT` hint passed to each scan command. Must be positive.
+    pub count: u32,
+    /// The highest number of items in a page. Must be positive.
+    pub max_items: usize,
+    /// No further scan command is sent once this much time has passed since the page was requested. The first command
+    /// is always sent, so that every page makes progress.
+    pub time_budget: Duration,
+}
+
+impl Default for ScanPageOptions {
+    fn default() -> Self {
+        Self {
+            pattern: None,
+            count: 100,
+            max_items: 1000,
+            time_budget: Duration::from_secs(1),
+        }
+    }
+}
+
+/// A key that was exported by [`Client::export_keys`](crate::client::Client::export_keys), which can be restored by
+/// [`Client::import_keys`](crate::client::Client::import_keys).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExportedKey {
+    pub key: Vec<u8>,
+    pub key_type: String,
+    /// The remaining time to live, if the key expires.
+    pub ttl: Option<Duration>,
+    /// The serialized value, as returned by DUMP.
+    pub payload: Vec<u8>,
+}
+
+impl ExportedKey {
+    /// Returns the value that is passed to the wrappers - `[key, type, TTL in milliseconds, payload]`. The TTL is -1 if
+    /// the key doesn't expire.
+    pub fn to_value(self) -> redis::Value {
+        let ttl = self
+            .ttl
+            .map_or(-1, |ttl| i64::try_from(ttl.as_millis()).unwrap_or(i64::MAX));
+        redis::Value::Array(vec![
+            redis::Value::BulkString(self.key),
+            redis::Value::SimpleString(self.key_type),
+            redis::Value::Int(ttl),
+            redis::Value::BulkString(self.payload),
+        ])
+    }
+}
+
+/// The remaining time to live of a key, as reported by TTL/PTTL.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyTtl {
+    /// The key exists, but has no associated expiry.
+    NoExpiry,
+    /// The key doesn't exist.
+    KeyMissing,
+    Ttl(Duration),
+}
+
+impl KeyTtl {
+    const TYPE_FIELD: &'static str = "type";
+    const MILLISECONDS_FIELD: &'static str = "milliseconds";
+
+    /// Interprets the reply of TTL (if `reply_in_seconds` is set) or PTTL, including the negative sentinels.
+    pub(crate) fn from_reply(reply: i64, reply_in_seconds: bool) -> Self {
+        match reply {
+            -1 => KeyTtl::NoExpiry,
+            reply if reply < 0 => KeyTtl::KeyMissing,
+            reply if reply_in_seconds => KeyTtl::Ttl(Duration::from_secs(reply as u64)),
+            reply => KeyTtl::Ttl(Duration::from_millis(reply as u64)),
+        }
+    }
+
+    /// Returns the value that is passed to the wrappers - a map with a `type` field, which is one of `no_expiry`,
+    /// `key_missing` or `ttl`, and for `ttl` also a `milliseconds` field.
+    pub fn to_value(self) -> redis::Value {
+        let type_field = |name: &str| {
+            (
+                redis::Value::SimpleString(Self::TYPE_FIELD.to_string()),
+                redis::Value::SimpleString(name.to_string()),
+            )
+        };
+        match self {
+            KeyTtl::NoExpiry => redis::Value::Map(vec![type_field("no_expiry")]),
+            KeyTtl::KeyMissing => redis::Value::Map(vec![type_field("key_missing")]),
+            KeyTtl::Ttl(ttl) => redis::Value::Map(vec![
+                type_field("ttl"),
+                (
+                    redis::Value::SimpleString(Self::MILLISECONDS_FIELD.to_string()),
+                    redis::Value::Int(ttl.as_millis() as i64),
+                ),
+            ]),
+        }
+    }
+
+    /// Parses a value that was created by [`KeyTtl::to_value`].
+    pub fn from_value(value: &redis::Value) -> Option<Self> {
+        let redis::Value::Map(fields) = value else {
+            return None;
+        };
+        let field = |name: &str| {
+            fields.iter().find_map(|(key, value)| match key {
+                redis::Value::SimpleString(key) if key == name => Some(value),
+                _ => None,
+            })
+        };
+        match field(Self::TYPE_FIELD)? {
+            redis::Value::SimpleString(type_name) => match type_name.as_str() {
+                "no_expiry" => Some(KeyTtl::NoExpiry),
+                "key_missing" => Some(KeyTtl::KeyMissing),
+                "ttl" => match field(Self::MILLISECONDS_FIELD)? {
+                    redis::Value::Int(milliseconds) => {
+                        Some(KeyTtl::Ttl(Duration::from_millis(*milliseconds as u64)))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// A time reported by TIME, LASTSAVE or OBJECT IDLETIME.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ServerTime {
+    /// A point in time, as the time that elapsed since the Unix epoch. Reported by TIME and LASTSAVE.
+    UnixTime(Duration),
+    /// The time that elapsed since an event, e.g. since a key was last accessed. Reported by OBJECT IDLETIME.
+    Elapsed(Duration),
+}
+
+impl ServerTime {
+    const TYPE_FIELD: &'static str = "type";
+    const SECONDS_FIELD: &'static str = "seconds";
+    const MICROSECONDS_FIELD: &'static str = "microseconds";
+
+    pub fn duration(self) -> Duration {
+        match self {
+            ServerTime::UnixTime(duration) | ServerTime::Elapsed(duration) => duration,
+        }
+    }
+
+    /// Returns the value that is passed to the wrappers - a map with a `type` field, which is `unix_time` or
+    /// `elapsed`, a `seconds` field, and a `microseconds` field with the microseconds that elapsed in the last second.
+    pub fn to_value(self) -> redis::Value {
+        let type_name = match self {
+            ServerTime::UnixTime(_) => "unix_time",
+            ServerTime::Elapsed(_) => "elapsed",
+        };
+        let field =
+            |name: &str, value: redis::Value| (redis::Value::SimpleString(name.to_string()), value);
+        let duration = self.duration();
+        redis::Value::Map(vec![
+            field(
+                Self::TYPE_FIELD,
+                redis::Value::SimpleString(type_name.to_string()),
+            ),
+            field(
+                Self::SECONDS_FIELD,
+                redis::Value::Int(duration.as_secs() as i64),
+            ),
+            field(
+                Self::MICROSECONDS_FIELD,
+                redis::Value::Int(duration.subsec_micros().into()),
+            ),
+        ])
+    }
+
+    /// Parses a value that was created by [`ServerTime::to_value`].
+    pub fn from_value(value: &redis::Value) -> Option<Self> {
+        let redis::Value::Map(fields) = value else {
+            return None;
+        };
+        let field = |name: &str| {
+            fields.iter().find_map(|(key, value)| match key {
+                redis::Value::SimpleString(key) if key == name => Some(value),
+                _ => None,
+            })
+        };
+        let (redis::Value::Int(seconds), redis::Value::Int(microseconds)) = (
+            field(Self::SECONDS_FIELD)?,
+            field(Self::MICROSECONDS_FIELD)?,
+        ) else {
+            return None;
+        };
+        let duration = Duration::from_secs(u64::try_from(*seconds).ok()?)
+            + Duration::from_micros(u64::try_from(*microseconds).ok()?);
+        match field(Self::TYPE_FIELD)? {
+            redis::Value::SimpleString(type_name) => match type_name.as_str() {
+                "unix_time" => Some(ServerTime::UnixTime(duration)),
+                "elapsed" => Some(ServerTime::Elapsed(duration)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
 pub struct ConnectionRetryStrategy {
     pub exponent_base: u32,
     pub factor: u32,
@@ -92,13 +586,42 @@ fn none_if_zero(value: u32) -> Option<u32> {
     }
 }
 
+/// Returns the value of a protobuf enum, or records it in `unrecognized_values` if this version doesn't know it.
+#[cfg(feature = "socket-layer")]
+fn recognized_enum_value<E>(
+    name: &str,
+    value: Result<E, i32>,
+    unrecognized_values: &mut Vec<String>,
+) -> Option<E> {
+    match value {
+        Ok(value) => Some(value),
+        Err(value) => {
+            unrecognized_values.push(format!("{name} value {value}"));
+            None
+        }
+    }
+}
+
 #[cfg(feature = "socket-layer")]
 impl From<protobuf::ConnectionRequest> for ConnectionRequest {
     fn from(value: protobuf::ConnectionRequest) -> Self {
-        let read_from = value.read_from.enum_value().ok().map(|val| match val {
+        let mut unrecognized_values = Vec::new();
+        let mut invalid_values = Vec::new();
+        let read_from = recognized_enum_value(
+            "read_from",
+            value.read_from.enum_value(),
+            &mut unrecognized_values,
+        )
+        .map(|val| match val {
             protobuf::ReadFrom::Primary => ReadFrom::Primary,
             protobuf::ReadFrom::PreferReplica => ReadFrom::PreferReplica,
-            protobuf::ReadFrom::LowestLatency => todo!(),
+            protobuf::ReadFrom::LowestLatency => {
+                log_warn(
+                    "types",
+                    "`ReadFrom::LowestLatency` isn't supported. Falling back to `ReadFrom::PreferReplica`",
+                );
+                ReadFrom::PreferReplica
+            }
             protobuf::ReadFrom::AZAffinity => {
                 if let Some(client_az) = chars_to_string_option(&value.client_az) {
                     ReadFrom::AZAffinity(client_az)
@@ -127,12 +650,22 @@ impl From<protobuf::ConnectionRequest> for ConnectionRequest {
         });
 
         let database_id = value.database_id as i64;
-        let protocol = value.protocol.enum_value().ok().map(|val| match val {
+        let protocol = recognized_enum_value(
+            "protocol",
+            value.protocol.enum_value(),
+            &mut unrecognized_values,
+        )
+        .map(|val| match val {
             protobuf::ProtocolVersion::RESP3 => redis::ProtocolVersion::RESP3,
             protobuf::ProtocolVersion::RESP2 => redis::ProtocolVersion::RESP2,
         });
 
-        let tls_mode = value.tls_mode.enum_value().ok().map(|val| match val {
+        let tls_mode = recognized_enum_value(
+            "tls_mode",
+            value.tls_mode.enum_value(),
+            &mut unrecognized_values,
+        )
+        .map(|val| match val {
             protobuf::TlsMode::NoTls => TlsMode::NoTls,
             protobuf::TlsMode::SecureTls => TlsMode::SecureTls,
             protobuf::TlsMode::InsecureTls => TlsMode::InsecureTls,
@@ -181,13 +714,7 @@ impl From<protobuf::ConnectionRequest> for ConnectionRequest {
                     1 => redis::PubSubSubscriptionKind::Pattern,
                     2 => redis::PubSubSubscriptionKind::Sharded,
                     3_u32..=u32::MAX => {
-                        log_warn(
-                            "client creation",
-                            format!(
-                                "Omitting pubsub subscription on an unknown type: {:?}",
-                                *pubsub_type
-                            ),
-                        );
+                        unrecognized_values.push(format!("pubsub subscription type {pubsub_type}"));
                         continue;
                     }
                 };
@@ -206,6 +733,83 @@ impl From<protobuf::ConnectionRequest> for ConnectionRequest {
 
         let inflight_requests_limit = none_if_zero(value.inflight_requests_limit);
 
+        let adaptive_timeout =
+            value
+                .adaptive_timeout
+                .0
+                .map(|adaptive_timeout| AdaptiveTimeoutConfig {
+                    min_timeout: Duration::from_millis(adaptive_timeout.min_timeout.into()),
+                    max_timeout: Duration::from_millis(adaptive_timeout.max_timeout.into()),
+                    percentile: adaptive_timeout.percentile,
+                    multiplier: adaptive_timeout.multiplier,
+                });
+
+        let write_durability = value
+            .write_durability
+            .0
+            .map(|write_durability| WriteDurability {
+                num_replicas: write_durability.num_replicas,
+                timeout: Duration::from_millis(write_durability.timeout.into()),
+            });
+
+        let structured_ttl = value.structured_ttl;
+        let typed_time = value.typed_time;
+        let no_evict = value.no_evict;
+        let no_touch = value.no_touch;
+        let audit_log = value.audit_log;
+        let session_recording_path = value
+            .session_recording_path
+            .as_ref()
+            .map(|path| path.to_string());
+        let hot_keys_tracked = none_if_zero(value.hot_keys_tracked);
+        let big_reply_threshold = none_if_zero(value.big_reply_threshold);
+        let tls_server_name = value
+            .tls_server_name
+            .as_ref()
+            .map(|server_name| server_name.to_string());
+        let pinned_slots = value
+            .pinned_slots
+            .iter()
+            .map(|pinned| {
+                let address = pinned.address.as_ref().cloned().unwrap_or_default();
+                let port = u16::try_from(address.port).unwrap_or_else(|_| {
+                    invalid_values.push(format!(
+                        "the port {} of the pinned slot range {}-{}",
+                        address.port, pinned.start, pinned.end
+                    ));
+                    0
+                });
+                redis::cluster_topology::PinnedSlots {
+                    start: pinned.start.try_into().unwrap_or(u16::MAX),
+                    end: pinned.end.try_into().unwrap_or(u16::MAX),
+                    address: format!("{}:{}", address.host, port),
+                }
+            })
+            .collect();
+        let override_slot_discovery = value.override_slot_discovery;
+        let idle_timeout = none_if_zero(value.idle_timeout);
+        let readiness_checks = value.readiness_checks.0.map(|checks| ReadinessChecks {
+            required_modules: checks
+                .required_modules
+                .iter()
+                .map(|module| module.to_string())
+                .collect(),
+            required_commands: checks
+                .required_commands
+                .iter()
+                .map(|command| command.to_string())
+                .collect(),
+            disconnect: checks.disconnect,
+        });
+        let memory_cap = (value.memory_cap != 0).then_some(value.memory_cap);
+        let connection_limit = (value.connection_limit != 0).then_some(value.connection_limit);
+        let connection_rate_limit = value.connection_rate_limit.0.map(|limit| {
+            redis::connection_throttle::ConnectionRateLimit {
+                burst: limit.burst,
+                attempts_per_second: limit.attempts_per_second,
+            }
+        });
+
         ConnectionRequest {
             read_from,
             client_name,
@@ -221,6 +825,27 @@ impl From<protobuf::ConnectionRequest> for ConnectionRequest {
             periodic_checks,
             pubsub_subscriptions,
             inflight_requests_limit,
+            adaptive_timeout,
+            write_durability,
+            structured_ttl,
+            typed_time,
+            no_evict,
+            no_touch,
+            audit_log,
+            session_recording_path,
+            hot_keys_tracked,
+            big_reply_threshold,
+            tls_server_name,
+            pinned_slots,
+            override_slot_discovery,
+            idle_timeout,
+            readiness_checks,
+            memory_cap,
+            connection_rate_limit,
+            connection_limit,
+            authenticator: None,
+            unrecognized_values,
+            invalid_values,
         }
     }
 }
@@ -1,8 +1,10 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
+use super::{KeyTtl, ServerTime};
 use redis::{
     cluster_routing::Routable, from_owned_redis_value, Cmd, ErrorKind, RedisResult, Value,
 };
+use std::time::Duration;
 
 #[derive(Clone, Copy)]
 pub(crate) enum ExpectedReturnType<'a> {
@@ -18,7 +20,9 @@ pub(crate) enum ExpectedReturnType<'a> {
     MapOfStringToDouble,
     Double,
     Boolean,
+    BooleanOrNull,
     BulkString,
+    BulkStringOrNull,
     Set,
     DoubleOrNull,
     ZRankReturnType,
@@ -40,10 +44,18 @@ pub(crate) enum ExpectedReturnType<'a> {
     ZMPopReturnType,
     KeyWithMemberAndScore,
     FunctionStatsReturnType,
+    AclUserReturnType,
+    AclListReturnType,
     GeoSearchReturnType,
     SimpleString,
     XAutoClaimReturnType,
     XInfoStreamFullReturnType,
+    KeyTtl {
+        reply_in_seconds: bool,
+    },
+    ServerTime,
+    UnixTime,
+    ElapsedTime,
 }
 
 pub(crate) fn convert_to_expected_type(
@@ -75,8 +87,8 @@ pub(crate) fn convert_to_expected_type(
                 let result = map
                     .into_iter()
                     .map(|(key, inner_value)| {
-                        let key_str = convert_to_expected_type(key, Some(ExpectedReturnType::BulkString)).unwrap();
-                        let value_converted = convert_to_expected_type(inner_value, Some(ExpectedReturnType::Double)).unwrap();
+                        let key_str = convert_to_expected_type(key, Some(ExpectedReturnType::BulkString))?;
+                        let value_converted = convert_to_expected_type(inner_value, Some(ExpectedReturnType::Double))?;
                         Ok((key_str, value_converted))
                     })
                     .collect::<RedisResult<_>>();
@@ -106,10 +118,23 @@ pub(crate) fn convert_to_expected_type(
             )
                 .into()),
         },
-        ExpectedReturnType::Double => Ok(Value::Double(from_owned_redis_value::<f64>(value)?)),
-        ExpectedReturnType::Boolean => Ok(Value::Boolean(from_owned_redis_value::<bool>(value)?)),
+        // RESP3 doubles and booleans are already of the expected type, and are passed through as is. Only RESP2 replies
+        // are parsed.
+        ExpectedReturnType::Double => match value {
+            Value::Double(_) => Ok(value),
+            _ => Ok(Value::Double(from_owned_redis_value::<f64>(value)?)),
+        },
+        ExpectedReturnType::Boolean => match value {
+            Value::Boolean(_) => Ok(value),
+            _ => Ok(Value::Boolean(from_owned_redis_value::<bool>(value)?)),
+        },
+        // Used for the elements of arrays, where a nil has to keep its position rather than be taken as false.
+        ExpectedReturnType::BooleanOrNull => match value {
+            Value::Nil | Value::Boolean(_) => Ok(value),
+            _ => Ok(Value::Boolean(from_owned_redis_value::<bool>(value)?)),
+        },
         ExpectedReturnType::DoubleOrNull => match value {
-            Value::Nil => Ok(value),
+            Value::Nil | Value::Double(_) => Ok(value),
             _ => Ok(Value::Double(from_owned_redis_value::<f64>(value)?)),
         },
         ExpectedReturnType::ZRankReturnType => match value {
@@ -139,6 +164,19 @@ pub(crate) fn convert_to_expected_type(
             Value::BulkString(_) => Ok(value),
             _ => Ok(Value::BulkString(from_owned_redis_value::<String>(value)?.into())),
         },
+        // A missing value is nil, and an empty value is an empty bulk string, in both protocols.
+        ExpectedReturnType::BulkStringOrNull => match value {
+            Value::Nil | Value::BulkString(_) => Ok(value),
+            Value::SimpleString(_) | Value::VerbatimString { .. } => Ok(Value::BulkString(
+                from_owned_redis_value::<String>(value)?.into(),
+            )),
+            _ => Err((
+                ErrorKind::TypeError,
+                "Response couldn't be converted to bulk string or nil",
+                format!("(response was {:?})", get_value_type(&value)),
+            )
+                .into()),
+        },
         ExpectedReturnType::SimpleString => Ok(Value::SimpleString(
             from_owned_redis_value::<String>(value)?,
         )),
@@ -147,7 +185,7 @@ pub(crate) fn convert_to_expected_type(
                 let converted_array: RedisResult<Vec<_>> = array
                     .into_iter()
                     .map(|item| match item {
-                        Value::Nil => Ok(Value::Nil),
+                        Value::Nil | Value::Boolean(_) => Ok(item),
                         _ => match from_owned_redis_value::<bool>(item.clone()) {
                             Ok(boolean_value) => Ok(Value::Boolean(boolean_value)),
                             _ => Err((
@@ -180,7 +218,7 @@ pub(crate) fn convert_to_expected_type(
                 .into()),
         },
         ExpectedReturnType::ArrayOfBools => match value {
-            Value::Array(array) => convert_array_elements(array, ExpectedReturnType::Boolean),
+            Value::Array(array) => convert_array_elements(array, ExpectedReturnType::BooleanOrNull),
             _ => Err((
                 ErrorKind::TypeError,
                 "Response couldn't be converted to an array of boolean",
@@ -285,8 +323,8 @@ pub(crate) fn convert_to_expected_type(
             match value {
                 // RESP 2 response
                 Value::BulkString(bytes) => {
-                    let text = std::str::from_utf8(&bytes).unwrap();
-                    let res = convert_lolwut_string(text);
+                    let text = String::from_utf8_lossy(&bytes);
+                    let res = convert_lolwut_string(&text);
                     Ok(Value::BulkString(Vec::from(res)))
                 }
                 // RESP 3 response
@@ -586,6 +624,60 @@ pub(crate) fn convert_to_expected_type(
             }
             _ => Err((ErrorKind::TypeError, "Response couldn't be converted").into()),
         },
+        // `ACL GETUSER` returns the rules of a user, or nil if there's no such user. RESP2 returns a flat array of
+        // field names and values, and the selectors as flat arrays as well. Both are converted to the RESP3 format:
+        /*
+        1# "flags" => 1) "on"
+        2# "passwords" => 1) "<password hash>"
+        3# "commands" => "+@all"
+        4# "keys" => "~*"
+        5# "channels" => "&*"
+        6# "selectors" =>
+           1) 1# "commands" => "-@all +get"
+              2# "keys" => "%R~cache:*"
+              3# "channels" => ""
+        */
+        // In cluster mode the replies of all nodes are returned, as a map of the nodes' addresses to their replies.
+        ExpectedReturnType::AclUserReturnType => match value {
+            Value::Nil => Ok(value),
+            Value::Array(array) => convert_acl_user(convert_array_to_map_by_type(
+                array,
+                Some(ExpectedReturnType::BulkString),
+                None,
+            )?),
+            Value::Map(map) if is_acl_user(&map) => convert_acl_user(convert_map_entries(
+                map,
+                Some(ExpectedReturnType::BulkString),
+                None,
+            )?),
+            Value::Map(map) => convert_map_entries(
+                map,
+                None,
+                Some(ExpectedReturnType::AclUserReturnType),
+            ),
+            _ => Err((
+                ErrorKind::TypeError,
+                "Response couldn't be converted to an ACL user",
+                format!("(response was {:?})", get_value_type(&value)),
+            )
+                .into()),
+        },
+        // `ACL LIST` returns the rules of each user as a single string, like
+        // "user alice on #<password hash> ~cache:* &* -@all +get (~other:* +set)".
+        // Each string is parsed into a map with the name of the user and the fields of `ACL GETUSER`.
+        ExpectedReturnType::AclListReturnType => match value {
+            Value::Array(array) => array
+                .into_iter()
+                .map(|rules| parse_acl_rules(&from_owned_redis_value::<String>(rules)?))
+                .collect::<RedisResult<_>>()
+                .map(Value::Array),
+            _ => Err((
+                ErrorKind::TypeError,
+                "Response couldn't be converted to ACL rules",
+                format!("(response was {:?})", get_value_type(&value)),
+            )
+                .into()),
+        },
         // Used by XAUTOCLAIM. The command returns a list of length 2 if the server version is less than 7.0.0 or a list
         // of length 3 otherwise. It has the following response format:
         /* server version < 7.0.0 example:
@@ -754,6 +846,49 @@ pub(crate) fn convert_to_expected_type(
         - convert any group in the group array to a map, if there are any groups
         - convert the root of the response into a map
         */
+        ExpectedReturnType::KeyTtl { reply_in_seconds } => match value {
+            Value::Int(reply) => Ok(KeyTtl::from_reply(reply, reply_in_seconds).to_value()),
+            _ => Err((
+                ErrorKind::TypeError,
+                "Response couldn't be converted to TTL",
+                format!("(response was {:?})", get_value_type(&value)),
+            )
+                .into()),
+        },
+        // TIME replies with the seconds and the microseconds that elapsed in the current second, as strings.
+        ExpectedReturnType::ServerTime => match value {
+            Value::Array(array) if array.len() == 2 => {
+                let (seconds, microseconds): (u64, u64) =
+                    from_owned_redis_value(Value::Array(array))?;
+                Ok(ServerTime::UnixTime(
+                    Duration::from_secs(seconds) + Duration::from_micros(microseconds),
+                )
+                .to_value())
+            }
+            _ => Err((
+                ErrorKind::TypeError,
+                "Response couldn't be converted to server time",
+                format!("(response was {:?})", get_value_type(&value)),
+            )
+                .into()),
+        },
+        ExpectedReturnType::UnixTime | ExpectedReturnType::ElapsedTime => match value {
+            Value::Nil => Ok(value),
+            Value::Int(seconds) if seconds >= 0 => {
+                let duration = Duration::from_secs(seconds as u64);
+                Ok(match expected {
+                    ExpectedReturnType::UnixTime => ServerTime::UnixTime(duration),
+                    _ => ServerTime::Elapsed(duration),
+                }
+                .to_value())
+            }
+            _ => Err((
+                ErrorKind::TypeError,
+                "Response couldn't be converted to server time",
+                format!("(response was {:?})", get_value_type(&value)),
+            )
+                .into()),
+        },
         ExpectedReturnType::XInfoStreamFullReturnType => match value {
             Value::Map(_) => Ok(value),  // Response is already in RESP3 format - no conversion needed
             Value::Array(mut array) => {
@@ -921,7 +1056,7 @@ pub(crate) fn convert_to_expected_type(
             Very first element in the response is meaningless and should be ignored.
             */
             Value::Array(array) => {
-                let mut res = Vec::with_capacity(array.len() - 1);
+                let mut res = Vec::with_capacity(array.len().saturating_sub(1));
                 for aggregation in array.into_iter().skip(1) {
                     let Value::Array(fields) = aggregation else {
                         return Err((
@@ -972,7 +1107,7 @@ pub(crate) fn convert_to_expected_type(
 
             Response may contain only 1 element, no conversion in that case.
             */
-            Value::Array(ref array) if array.len() == 1 => Ok(value),
+            Value::Array(ref array) if array.len() <= 1 => Ok(value),
             Value::Array(mut array) => {
                 Ok(Value::Array(vec![
                     array.remove(0),
@@ -1072,7 +1207,9 @@ pub(crate) fn convert_to_expected_type(
                     let Value::Map(mut field_params) = convert_to_expected_type(field, Some(ExpectedReturnType::Map {
                         key_type: &None,
                         value_type: &None,
-                    }))? else { unreachable!() };
+                    }))? else {
+                        return Err((ErrorKind::TypeError, "Response couldn't be converted for FT.INFO, a field was nil").into());
+                    };
                     let Some(vector_params_pair) = field_params.iter_mut().find(|(key, _)| {
                         *key == Value::SimpleString("vector_params".into())
                     }) else { return Ok(Value::Map(field_params)) };
@@ -1147,6 +1284,174 @@ pub(crate) fn convert_to_expected_type(
     }
 }
 
+/// Converts the flags, passwords and selectors of an `ACL GETUSER` reply whose fields were already converted to a map.
+fn convert_acl_user(user: Value) -> RedisResult<Value> {
+    let Value::Map(fields) = user else {
+        return Ok(user);
+    };
+    fields
+        .into_iter()
+        .map(|(field, value)| {
+            let value = match (&field, value) {
+                (Value::BulkString(name), Value::Array(strings))
+                    if name == b"flags" || name == b"passwords" =>
+                {
+                    convert_array_elements(strings, ExpectedReturnType::BulkString)?
+                }
+                (Value::BulkString(name), Value::Array(selectors)) if name == b"selectors" => {
+                    Value::Array(
+                        selectors
+                            .into_iter()
+                            .map(|selector| match selector {
+                                Value::Array(array) => convert_array_to_map_by_type(
+                                    array,
+                                    Some(ExpectedReturnType::BulkString),
+                                    None,
+                                ),
+                                Value::Map(map) => convert_map_entries(
+                                    map,
+                                    Some(ExpectedReturnType::BulkString),
+                                    None,
+                                ),
+                                _ => Err((
+                                    ErrorKind::TypeError,
+                                    "Response couldn't be converted to an ACL selector",
+                                    format!("(response was {:?})", get_value_type(&selector)),
+                                )
+                                    .into()),
+                            })
+                            .collect::<RedisResult<_>>()?,
+                    )
+                }
+                (_, value) => value,
+            };
+            Ok((field, value))
+        })
+        .collect::<RedisResult<_>>()
+        .map(Value::Map)
+}
+
+/// Splits ACL rules by spaces, keeping each selector, which is wrapped in parentheses, as a single rule.
+fn split_acl_rules(rules: &str) -> Vec<&str> {
+    let mut split = Vec::new();
+    let mut start = None;
+    let mut depth = 0;
+    for (index, character) in rules.char_indices() {
+        match character {
+            ' ' if depth == 0 => {
+                if let Some(start) = start.take() {
+                    split.push(&rules[start..index]);
+                }
+                continue;
+            }
+            '(' if start.is_none() || depth > 0 => depth += 1,
+            ')' if depth > 0 => depth -= 1,
+            _ => {}
+        }
+        start.get_or_insert(index);
+    }
+    if let Some(start) = start {
+        split.push(&rules[start..]);
+    }
+    split
+}
+
+/// The fields of `ACL GETUSER` that rules of the given kinds are joined into.
+#[derive(Default)]
+struct AclRules<'a> {
+    flags: Vec<&'a str>,
+    passwords: Vec<&'a str>,
+    commands: Vec<&'a str>,
+    keys: Vec<&'a str>,
+    channels: Vec<&'a str>,
+    selectors: Vec<AclRules<'a>>,
+}
+
+impl<'a> AclRules<'a> {
+    fn add(&mut self, rule: &'a str) {
+        match rule {
+            "allkeys" | "resetkeys" => self.keys.push(rule),
+            "allchannels" | "resetchannels" => self.channels.push(rule),
+            "allcommands" | "nocommands" => self.commands.push(rule),
+            _ if rule.starts_with('~') || rule.starts_with('%') => self.keys.push(rule),
+            _ if rule.starts_with('&') => self.channels.push(rule),
+            _ if rule.starts_with('+') || rule.starts_with('-') => self.commands.push(rule),
+            _ if rule.starts_with('#') => self.passwords.push(&rule[1..]),
+            _ if rule.starts_with('(') && rule.ends_with(')') => {
+                let mut selector = AclRules::default();
+                for rule in split_acl_rules(&rule[1..rule.len() - 1]) {
+                    selector.add(rule);
+                }
+                self.selectors.push(selector);
+            }
+            _ => self.flags.push(rule),
+        }
+    }
+
+    fn joined_fields(&self) -> Vec<(Value, Value)> {
+        let field = |name: &str| Value::BulkString(name.as_bytes().to_vec());
+        let joined = |rules: &[&str]| Value::BulkString(rules.join(" ").into_bytes());
+        vec![
+            (field("commands"), joined(&self.commands)),
+            (field("keys"), joined(&self.keys)),
+            (field("channels"), joined(&self.channels)),
+        ]
+    }
+}
+
+/// Parses a line of `ACL LIST` into a map of the user's name and the fields of `ACL GETUSER`.
+fn parse_acl_rules(line: &str) -> RedisResult<Value> {
+    let mut split = split_acl_rules(line).into_iter();
+    let (Some("user"), Some(name)) = (split.next(), split.next()) else {
+        return Err((
+            ErrorKind::TypeError,
+            "Response couldn't be converted to ACL rules",
+            format!("(response was {line:?})"),
+        )
+            .into());
+    };
+    let mut rules = AclRules::default();
+    for rule in split {
+        rules.add(rule);
+    }
+    let field = |name: &str| Value::BulkString(name.as_bytes().to_vec());
+    let strings = |values: &[&str]| {
+        Value::Array(
+            values
+                .iter()
+                .map(|value| Value::BulkString(value.as_bytes().to_vec()))
+                .collect(),
+        )
+    };
+    let mut fields = vec![
+        (field("name"), Value::BulkString(name.as_bytes().to_vec())),
+        (field("flags"), strings(&rules.flags)),
+        (field("passwords"), strings(&rules.passwords)),
+    ];
+    fields.extend(rules.joined_fields());
+    fields.push((
+        field("selectors"),
+        Value::Array(
+            rules
+                .selectors
+                .iter()
+                .map(|selector| Value::Map(selector.joined_fields()))
+                .collect(),
+        ),
+    ));
+    Ok(Value::Map(fields))
+}
+
+/// Whether `map` is the reply of a node to `ACL GETUSER`, whose first field is the flags, rather than a map of nodes'
+/// addresses to their replies.
+fn is_acl_user(map: &[(Value, Value)]) -> bool {
+    map.first().is_some_and(|(field, _)| match field {
+        Value::BulkString(field) => field == b"flags",
+        Value::SimpleString(field) => field == "flags",
+        _ => false,
+    })
+}
+
 /// Similar to [`convert_array_to_map_by_type`], but converts keys and values to the given types inside the map.
 /// The input data is [`Value::Map`] payload, the output is the new [`Value::Map`].
 fn convert_map_entries(
@@ -1189,9 +1494,9 @@ fn convert_array_elements(
     element_type: ExpectedReturnType,
 ) -> RedisResult<Value> {
     let converted_array = array
-        .iter()
-        .map(|v| convert_to_expected_type(v.clone(), Some(element_type)).unwrap())
-        .collect();
+        .into_iter()
+        .map(|v| convert_to_expected_type(v, Some(element_type)))
+        .collect::<RedisResult<_>>()?;
     Ok(Value::Array(converted_array))
 }
 
@@ -1391,7 +1696,7 @@ fn is_array(val: Value) -> bool {
     matches!(val, Value::Array(_))
 }
 
-pub(crate) fn expected_type_for_cmd(cmd: &Cmd) -> Option<ExpectedReturnType> {
+pub(crate) fn expected_type_for_cmd(cmd: &Cmd) -> Option<ExpectedReturnType<'static>> {
     let command = cmd.command()?;
 
     // TODO use enum to avoid mistakes
@@ -1444,6 +1749,13 @@ pub(crate) fn expected_type_for_cmd(cmd: &Cmd) -> Option<ExpectedReturnType> {
             value_type: &None,
         }),
         b"INCRBYFLOAT" | b"HINCRBYFLOAT" | b"ZINCRBY" => Some(ExpectedReturnType::Double),
+        b"GETDEL" | b"GETEX" => Some(ExpectedReturnType::BulkStringOrNull),
+        // SET returns the old value only with GET, which can only come after the key and the value.
+        b"SET" => cmd
+            .args_iter()
+            .skip(3)
+            .any(|arg| matches!(arg, redis::Arg::Simple(arg) if arg.eq_ignore_ascii_case(b"GET")))
+            .then_some(ExpectedReturnType::BulkStringOrNull),
         b"HEXISTS"
         | b"HSETNX"
         | b"EXPIRE"
@@ -1499,6 +1811,11 @@ pub(crate) fn expected_type_for_cmd(cmd: &Cmd) -> Option<ExpectedReturnType> {
         b"FUNCTION LIST" => Some(ExpectedReturnType::ArrayOfMaps(&Some(
             ExpectedReturnType::ArrayOfMaps(&Some(ExpectedReturnType::StringOrSet)),
         ))),
+        b"ACL GETUSER" => Some(ExpectedReturnType::AclUserReturnType),
+        b"ACL LIST" => Some(ExpectedReturnType::SingleOrMultiNode(
+            &Some(ExpectedReturnType::AclListReturnType),
+            None,
+        )),
         b"FUNCTION STATS" => Some(ExpectedReturnType::SingleOrMultiNode(
             &Some(ExpectedReturnType::FunctionStatsReturnType),
             Some(&is_array),
@@ -1531,7 +1848,10 @@ pub(crate) fn expected_type_for_cmd(cmd: &Cmd) -> Option<ExpectedReturnType> {
         b"FT.SEARCH" => Some(ExpectedReturnType::FTSearchReturnType),
         // TODO replace with tuple
         b"FT.PROFILE" => Some(ExpectedReturnType::FTProfileReturnType(
-            if cmd.arg_idx(2).is_some_and(|a| a == b"SEARCH") {
+            if cmd
+                .arg_idx(2)
+                .is_some_and(|a| a.eq_ignore_ascii_case(b"SEARCH"))
+            {
                 &Some(ExpectedReturnType::FTSearchReturnType)
             } else {
                 &Some(ExpectedReturnType::FTAggregateReturnType)
@@ -1542,6 +1862,50 @@ pub(crate) fn expected_type_for_cmd(cmd: &Cmd) -> Option<ExpectedReturnType> {
     }
 }
 
+/// Like [`expected_type_for_cmd`], but also converts TTL/PTTL replies into a [`KeyTtl`] if `structured_ttl` is set,
+/// and TIME, LASTSAVE and OBJECT IDLETIME replies into a [`ServerTime`] if `typed_time` is set.
+pub(crate) fn expected_type_for_client_cmd(
+    cmd: &Cmd,
+    structured_ttl: bool,
+    typed_time: bool,
+) -> Option<ExpectedReturnType<'static>> {
+    if typed_time {
+        match cmd.command()?.as_slice() {
+            // Both can be routed to all nodes in cluster mode.
+            b"TIME" => {
+                return Some(ExpectedReturnType::SingleOrMultiNode(
+                    &Some(ExpectedReturnType::ServerTime),
+                    None,
+                ))
+            }
+            b"LASTSAVE" => {
+                return Some(ExpectedReturnType::SingleOrMultiNode(
+                    &Some(ExpectedReturnType::UnixTime),
+                    None,
+                ))
+            }
+            b"OBJECT IDLETIME" => return Some(ExpectedReturnType::ElapsedTime),
+            _ => {}
+        }
+    }
+    if structured_ttl {
+        match cmd.command()?.as_slice() {
+            b"TTL" => {
+                return Some(ExpectedReturnType::KeyTtl {
+                    reply_in_seconds: true,
+                })
+            }
+            b"PTTL" => {
+                return Some(ExpectedReturnType::KeyTtl {
+                    reply_in_seconds: false,
+                })
+            }
+            _ => {}
+        }
+    }
+    expected_type_for_cmd(cmd)
+}
+
 /// Gets the enum variant as a string for the `value` given.
 pub(crate) fn get_value_type<'a>(value: &Value) -> &'a str {
     match value {
@@ -1566,6 +1930,447 @@ pub(crate) fn get_value_type<'a>(value: &Value) -> &'a str {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::client::test_command;
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+    use redis::VerbatimFormat;
+    use std::time::Duration;
+
+    #[test]
+    fn structured_ttl_expected_return_type() {
+        let mut ttl = redis::cmd("TTL");
+        ttl.arg("key");
+        assert!(expected_type_for_client_cmd(&ttl, false, false).is_none());
+        assert!(matches!(
+            expected_type_for_client_cmd(&ttl, true, false),
+            Some(ExpectedReturnType::KeyTtl {
+                reply_in_seconds: true
+            })
+        ));
+        let mut pttl = redis::cmd("PTTL");
+        pttl.arg("key");
+        assert!(matches!(
+            expected_type_for_client_cmd(&pttl, true, false),
+            Some(ExpectedReturnType::KeyTtl {
+                reply_in_seconds: false
+            })
+        ));
+    }
+
+    #[test]
+    fn convert_structured_ttl() {
+        let convert = |reply, reply_in_seconds| {
+            let value = convert_to_expected_type(
+                Value::Int(reply),
+                Some(ExpectedReturnType::KeyTtl { reply_in_seconds }),
+            )
+            .unwrap();
+            KeyTtl::from_value(&value)
+        };
+        assert_eq!(convert(-2, true), Some(KeyTtl::KeyMissing));
+        assert_eq!(convert(-1, false), Some(KeyTtl::NoExpiry));
+        assert_eq!(convert(5, true), Some(KeyTtl::Ttl(Duration::from_secs(5))));
+        assert_eq!(
+            convert(1500, false),
+            Some(KeyTtl::Ttl(Duration::from_millis(1500)))
+        );
+        assert!(convert_to_expected_type(
+            Value::Nil,
+            Some(ExpectedReturnType::KeyTtl {
+                reply_in_seconds: true
+            })
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn resp2_and_resp3_doubles_and_booleans_convert_alike() {
+        let convert = |value, expected| convert_to_expected_type(value, Some(expected)).unwrap();
+        for (resp2, resp3) in [
+            (Value::BulkString(b"1.5".to_vec()), Value::Double(1.5)),
+            (
+                Value::BulkString(b"-inf".to_vec()),
+                Value::Double(f64::NEG_INFINITY),
+            ),
+            (
+                Value::BulkString(b"0.30000000000000004".to_vec()),
+                Value::Double(0.1 + 0.2),
+            ),
+        ] {
+            assert_eq!(
+                convert(resp2.clone(), ExpectedReturnType::Double),
+                convert(resp3.clone(), ExpectedReturnType::Double)
+            );
+            assert_eq!(
+                convert(resp2, ExpectedReturnType::DoubleOrNull),
+                convert(resp3.clone(), ExpectedReturnType::DoubleOrNull)
+            );
+            // RESP3 doubles are passed through, with their exact bits.
+            let Value::Double(converted) = convert(resp3.clone(), ExpectedReturnType::Double)
+            else {
+                panic!("Expected a double");
+            };
+            let Value::Double(original) = resp3 else {
+                unreachable!()
+            };
+            assert_eq!(converted.to_bits(), original.to_bits());
+        }
+        let nan = convert(Value::Double(f64::NAN), ExpectedReturnType::Double);
+        assert!(matches!(nan, Value::Double(value) if value.is_nan()));
+
+        for (resp2, resp3) in [
+            (Value::Int(1), Value::Boolean(true)),
+            (Value::Int(0), Value::Boolean(false)),
+        ] {
+            assert_eq!(
+                convert(resp2.clone(), ExpectedReturnType::Boolean),
+                convert(resp3.clone(), ExpectedReturnType::Boolean)
+            );
+            assert_eq!(convert(resp3.clone(), ExpectedReturnType::Boolean), resp3);
+            assert_eq!(
+                convert(Value::Array(vec![resp2]), ExpectedReturnType::ArrayOfBools),
+                convert(Value::Array(vec![resp3]), ExpectedReturnType::ArrayOfBools)
+            );
+        }
+        assert_eq!(
+            convert(
+                Value::Array(vec![Value::Boolean(true), Value::Nil]),
+                ExpectedReturnType::JsonToggleReturnType
+            ),
+            Value::Array(vec![Value::Boolean(true), Value::Nil])
+        );
+    }
+
+    #[test]
+    fn old_values_are_converted_to_bulk_string_or_nil() {
+        for args in [
+            &["GETDEL", "key"][..],
+            &["GETEX", "key", "PERSIST"],
+            &["SET", "key", "value", "GET"],
+            &["SET", "key", "value", "NX", "get", "EX", "10"],
+        ] {
+            assert!(
+                matches!(
+                    expected_type_for_cmd(&test_command(args)),
+                    Some(ExpectedReturnType::BulkStringOrNull)
+                ),
+                "{args:?}"
+            );
+        }
+        // A key or a value named GET doesn't make SET return the old value.
+        assert!(expected_type_for_cmd(&test_command(&["SET", "GET", "GET"])).is_none());
+        assert!(expected_type_for_cmd(&test_command(&["SET", "key", "value"])).is_none());
+
+        let convert =
+            |value| convert_to_expected_type(value, Some(ExpectedReturnType::BulkStringOrNull));
+        assert_eq!(convert(Value::Nil).unwrap(), Value::Nil);
+        assert_eq!(
+            convert(Value::BulkString(vec![])).unwrap(),
+            Value::BulkString(vec![])
+        );
+        assert_eq!(
+            convert(Value::SimpleString("value".to_string())).unwrap(),
+            Value::BulkString(b"value".to_vec())
+        );
+        assert_eq!(
+            convert(Value::VerbatimString {
+                format: VerbatimFormat::Text,
+                text: "value".to_string()
+            })
+            .unwrap(),
+            Value::BulkString(b"value".to_vec())
+        );
+        assert!(convert(Value::Int(1)).is_err());
+        assert!(convert(Value::Array(vec![])).is_err());
+    }
+
+    #[test]
+    fn acl_users_convert_alike_in_resp2_and_resp3() {
+        let bulk = |value: &str| Value::BulkString(value.as_bytes().to_vec());
+        let simple = |value: &str| Value::SimpleString(value.to_string());
+        let resp2 = Value::Array(vec![
+            bulk("flags"),
+            Value::Array(vec![bulk("on")]),
+            bulk("passwords"),
+            Value::Array(vec![bulk("hash")]),
+            bulk("commands"),
+            bulk("+@all"),
+            bulk("keys"),
+            bulk("~*"),
+            bulk("channels"),
+            bulk(""),
+            bulk("selectors"),
+            Value::Array(vec![Value::Array(vec![
+                bulk("commands"),
+                bulk("-@all +get"),
+                bulk("keys"),
+                bulk("%R~cache:*"),
+                bulk("channels"),
+                bulk(""),
+            ])]),
+        ]);
+        let resp3 = Value::Map(vec![
+            (bulk("flags"), Value::Array(vec![simple("on")])),
+            (bulk("passwords"), Value::Array(vec![bulk("hash")])),
+            (bulk("commands"), bulk("+@all")),
+            (bulk("keys"), bulk("~*")),
+            (bulk("channels"), bulk("")),
+            (
+                bulk("selectors"),
+                Value::Array(vec![Value::Map(vec![
+                    (bulk("commands"), bulk("-@all +get")),
+                    (bulk("keys"), bulk("%R~cache:*")),
+                    (bulk("channels"), bulk("")),
+                ])]),
+            ),
+        ]);
+        let expected = Value::Map(vec![
+            (bulk("flags"), Value::Array(vec![bulk("on")])),
+            (bulk("passwords"), Value::Array(vec![bulk("hash")])),
+            (bulk("commands"), bulk("+@all")),
+            (bulk("keys"), bulk("~*")),
+            (bulk("channels"), bulk("")),
+            (
+                bulk("selectors"),
+                Value::Array(vec![Value::Map(vec![
+                    (bulk("commands"), bulk("-@all +get")),
+                    (bulk("keys"), bulk("%R~cache:*")),
+                    (bulk("channels"), bulk("")),
+                ])]),
+            ),
+        ]);
+        let cmd = {
+            let mut cmd = redis::cmd("ACL");
+            cmd.arg("GETUSER").arg("user");
+            cmd
+        };
+        let convert = |value| convert_to_expected_type(value, expected_type_for_cmd(&cmd));
+        assert_eq!(convert(resp2.clone()).unwrap(), expected);
+        assert_eq!(convert(resp3.clone()).unwrap(), expected);
+        assert_eq!(convert(Value::Nil).unwrap(), Value::Nil);
+
+        // The replies of all nodes in cluster mode.
+        assert_eq!(
+            convert(Value::Map(vec![
+                (bulk("node1:6379"), resp2),
+                (bulk("node2:6379"), resp3),
+                (bulk("node3:6379"), Value::Nil),
+            ]))
+            .unwrap(),
+            Value::Map(vec![
+                (bulk("node1:6379"), expected.clone()),
+                (bulk("node2:6379"), expected),
+                (bulk("node3:6379"), Value::Nil),
+            ])
+        );
+        assert!(convert(Value::Int(1)).is_err());
+    }
+
+    #[test]
+    fn acl_list_is_parsed_into_users() {
+        let bulk = |value: &str| Value::BulkString(value.as_bytes().to_vec());
+        let strings =
+            |values: &[&str]| Value::Array(values.iter().map(|value| bulk(value)).collect());
+        let cmd = {
+            let mut cmd = redis::cmd("ACL");
+            cmd.arg("LIST");
+            cmd
+        };
+        let reply = Value::Array(vec![
+            bulk("user alice on #hash1 #hash2 ~cache:* %R~other:* resetchannels &news:* -@all +get (~temp:* +set) (&* +publish)"),
+            bulk("user default on nopass sanitize-payload ~* &* +@all"),
+        ]);
+        let alice = Value::Map(vec![
+            (bulk("name"), bulk("alice")),
+            (bulk("flags"), strings(&["on"])),
+            (bulk("passwords"), strings(&["hash1", "hash2"])),
+            (bulk("commands"), bulk("-@all +get")),
+            (bulk("keys"), bulk("~cache:* %R~other:*")),
+            (bulk("channels"), bulk("resetchannels &news:*")),
+            (
+                bulk("selectors"),
+                Value::Array(vec![
+                    Value::Map(vec![
+                        (bulk("commands"), bulk("+set")),
+                        (bulk("keys"), bulk("~temp:*")),
+                        (bulk("channels"), bulk("")),
+                    ]),
+                    Value::Map(vec![
+                        (bulk("commands"), bulk("+publish")),
+                        (bulk("keys"), bulk("")),
+                        (bulk("channels"), bulk("&*")),
+                    ]),
+                ]),
+            ),
+        ]);
+        let default = Value::Map(vec![
+            (bulk("name"), bulk("default")),
+            (
+                bulk("flags"),
+                strings(&["on", "nopass", "sanitize-payload"]),
+            ),
+            (bulk("passwords"), strings(&[])),
+            (bulk("commands"), bulk("+@all")),
+            (bulk("keys"), bulk("~*")),
+            (bulk("channels"), bulk("&*")),
+            (bulk("selectors"), Value::Array(vec![])),
+        ]);
+        let convert = |value| convert_to_expected_type(value, expected_type_for_cmd(&cmd));
+        assert_eq!(
+            convert(reply.clone()).unwrap(),
+            Value::Array(vec![alice.clone(), default.clone()])
+        );
+        assert_eq!(
+            convert(Value::Map(vec![(bulk("node:6379"), reply)])).unwrap(),
+            Value::Map(vec![(
+                bulk("node:6379"),
+                Value::Array(vec![alice, default])
+            )])
+        );
+        assert!(convert(Value::Array(vec![bulk("alice on")])).is_err());
+    }
+
+    #[test]
+    fn nils_keep_their_positions_in_converted_arrays() {
+        let convert = |args: &[&str], value| {
+            convert_to_expected_type(value, expected_type_for_cmd(&test_command(args))).unwrap()
+        };
+
+        let zmscore = ["ZMSCORE", "key", "missing", "member", "missing"];
+        let expected = Value::Array(vec![Value::Nil, Value::Double(1.5), Value::Nil]);
+        assert_eq!(
+            convert(
+                &zmscore,
+                Value::Array(vec![
+                    Value::Nil,
+                    Value::BulkString(b"1.5".to_vec()),
+                    Value::Nil
+                ])
+            ),
+            expected
+        );
+        assert_eq!(convert(&zmscore, expected.clone()), expected);
+
+        let smismember = ["SMISMEMBER", "key", "member", "missing", "member"];
+        let expected = Value::Array(vec![
+            Value::Boolean(true),
+            Value::Nil,
+            Value::Boolean(false),
+        ]);
+        assert_eq!(
+            convert(
+                &smismember,
+                Value::Array(vec![Value::Int(1), Value::Nil, Value::Int(0)])
+            ),
+            expected
+        );
+        assert_eq!(convert(&smismember, expected.clone()), expected);
+
+        // LPOS replies aren't converted, so their elements are passed as they are.
+        let lpos = ["LPOS", "key", "element", "COUNT", "0"];
+        assert!(expected_type_for_cmd(&test_command(&lpos)).is_none());
+        let positions = Value::Array(vec![Value::Int(0), Value::Nil, Value::Int(3)]);
+        assert_eq!(convert(&lpos, positions.clone()), positions);
+    }
+
+    #[test]
+    fn expected_type_ignores_the_case_of_command_names() {
+        assert!(matches!(
+            expected_type_for_cmd(&test_command(&["hgetall", "key"])),
+            Some(ExpectedReturnType::Map { .. })
+        ));
+        assert!(matches!(
+            expected_type_for_cmd(&test_command(&["ft.config", "get", "*"])),
+            Some(ExpectedReturnType::Map { .. })
+        ));
+        assert!(matches!(
+            expected_type_for_cmd(&test_command(&["Xinfo", "stream", "key", "full"])),
+            Some(ExpectedReturnType::XInfoStreamFullReturnType)
+        ));
+        assert!(matches!(
+            expected_type_for_cmd(&test_command(&["script", "exists", "sha"])),
+            Some(ExpectedReturnType::ArrayOfBools)
+        ));
+        assert!(matches!(
+            expected_type_for_cmd(&test_command(&[
+                "ft.profile",
+                "index",
+                "search",
+                "query",
+                "*"
+            ])),
+            Some(ExpectedReturnType::FTProfileReturnType(&Some(
+                ExpectedReturnType::FTSearchReturnType
+            )))
+        ));
+    }
+
+    #[test]
+    fn typed_time_expected_return_type() {
+        let time = redis::cmd("TIME");
+        assert!(expected_type_for_client_cmd(&time, false, false).is_none());
+        assert!(matches!(
+            expected_type_for_client_cmd(&time, false, true),
+            Some(ExpectedReturnType::SingleOrMultiNode(
+                Some(ExpectedReturnType::ServerTime),
+                None
+            ))
+        ));
+        assert!(matches!(
+            expected_type_for_client_cmd(&redis::cmd("LASTSAVE"), false, true),
+            Some(ExpectedReturnType::SingleOrMultiNode(
+                Some(ExpectedReturnType::UnixTime),
+                None
+            ))
+        ));
+        let mut idletime = redis::cmd("OBJECT");
+        idletime.arg("IDLETIME").arg("key");
+        assert!(matches!(
+            expected_type_for_client_cmd(&idletime, false, true),
+            Some(ExpectedReturnType::ElapsedTime)
+        ));
+    }
+
+    #[test]
+    fn convert_typed_time() {
+        let time = convert_to_expected_type(
+            Value::Array(vec![
+                Value::BulkString(b"1700000000".to_vec()),
+                Value::BulkString(b"123456".to_vec()),
+            ]),
+            Some(ExpectedReturnType::ServerTime),
+        )
+        .unwrap();
+        assert_eq!(
+            ServerTime::from_value(&time),
+            Some(ServerTime::UnixTime(
+                Duration::from_secs(1700000000) + Duration::from_micros(123456)
+            ))
+        );
+        assert!(convert_to_expected_type(
+            Value::Array(vec![Value::BulkString(b"now".to_vec()), Value::Int(0)]),
+            Some(ExpectedReturnType::ServerTime),
+        )
+        .is_err());
+
+        let lastsave =
+            convert_to_expected_type(Value::Int(1700000000), Some(ExpectedReturnType::UnixTime))
+                .unwrap();
+        assert_eq!(
+            ServerTime::from_value(&lastsave),
+            Some(ServerTime::UnixTime(Duration::from_secs(1700000000)))
+        );
+        let idletime =
+            convert_to_expected_type(Value::Int(5), Some(ExpectedReturnType::ElapsedTime)).unwrap();
+        assert_eq!(
+            ServerTime::from_value(&idletime),
+            Some(ServerTime::Elapsed(Duration::from_secs(5)))
+        );
+        // The key doesn't exist.
+        assert_eq!(
+            convert_to_expected_type(Value::Nil, Some(ExpectedReturnType::ElapsedTime)),
+            Ok(Value::Nil)
+        );
+    }
 
     #[test]
     fn xinfo_stream_expected_return_type() {
@@ -3407,4 +4212,155 @@ mod tests {
             })
         ));
     }
+
+    /// A reply of any shape. Strings are often picked from the field names that the conversions look for, so that the
+    /// property tests reach past the outermost checks.
+    #[derive(Clone, Debug)]
+    struct ArbitraryReply(Value);
+
+    impl Arbitrary for ArbitraryReply {
+        fn arbitrary(g: &mut Gen) -> Self {
+            ArbitraryReply(arbitrary_reply(g, 4))
+        }
+    }
+
+    fn arbitrary_string(g: &mut Gen) -> Vec<u8> {
+        const FIELDS: &[&str] = &[
+            "",
+            "0",
+            "-1",
+            "1.5",
+            "inf",
+            "-inf",
+            "nan",
+            "OK",
+            "type",
+            "ttl",
+            "milliseconds",
+            "name",
+            "flags",
+            "functions",
+            "library_name",
+            "running_script",
+            "engines",
+            "entries",
+            "groups",
+            "consumers",
+            "pending",
+            "Results",
+            "attributes",
+            "fields",
+            "values",
+        ];
+        if bool::arbitrary(g) {
+            g.choose(FIELDS).unwrap().as_bytes().to_vec()
+        } else {
+            Vec::arbitrary(g)
+        }
+    }
+
+    fn arbitrary_reply(g: &mut Gen, depth: u32) -> Value {
+        let elements = |g: &mut Gen| -> Vec<Value> {
+            (0..usize::arbitrary(g) % 4)
+                .map(|_| arbitrary_reply(g, depth - 1))
+                .collect()
+        };
+        let variant_count = if depth == 0 { 8 } else { 12 };
+        match u8::arbitrary(g) % variant_count {
+            0 => Value::Nil,
+            1 => Value::Okay,
+            2 => Value::Int(i64::arbitrary(g)),
+            3 => Value::Double(f64::arbitrary(g)),
+            4 => Value::Boolean(bool::arbitrary(g)),
+            5 => Value::BulkString(arbitrary_string(g)),
+            6 => Value::SimpleString(String::from_utf8_lossy(&arbitrary_string(g)).into_owned()),
+            7 => Value::VerbatimString {
+                format: VerbatimFormat::Text,
+                text: String::from_utf8_lossy(&arbitrary_string(g)).into_owned(),
+            },
+            8 => Value::Array(elements(g)),
+            9 => Value::Set(elements(g)),
+            10 => Value::Map(elements(g).into_iter().zip(elements(g)).collect()),
+            11 => Value::Attribute {
+                data: Box::new(arbitrary_reply(g, depth - 1)),
+                attributes: elements(g).into_iter().zip(elements(g)).collect(),
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// Commands that cover every branch of [`expected_type_for_client_cmd`].
+    fn commands_with_expected_types() -> Vec<Cmd> {
+        [
+            "HGETALL k",
+            "CONFIG GET x",
+            "XCLAIM k g c 0 id JUSTID",
+            "XCLAIM k g c 0 id",
+            "XAUTOCLAIM k g c 0 0",
+            "XINFO GROUPS k",
+            "XRANGE k - +",
+            "XREAD STREAMS k 0",
+            "LCS a b IDX",
+            "INCRBYFLOAT k 1",
+            "GETDEL k",
+            "SET k v GET",
+            "HEXISTS k f",
+            "SMISMEMBER k m",
+            "SMEMBERS k",
+            "ZSCORE k m",
+            "ZMSCORE k m",
+            "ZPOPMIN k",
+            "ZMPOP 1 k MIN",
+            "JSON.TOGGLE k",
+            "GEOPOS k m",
+            "LMPOP 1 k LEFT",
+            "HRANDFIELD k 1 WITHVALUES",
+            "ZRANDMEMBER k 1 WITHSCORES",
+            "ZADD k INCR 1 m",
+            "ZRANGE k 0 1 WITHSCORES",
+            "ZRANK k m WITHSCORE",
+            "BZPOPMIN k 0",
+            "SPOP k 2",
+            "LOLWUT",
+            "FUNCTION LIST",
+            "FUNCTION STATS",
+            "ACL GETUSER u",
+            "ACL LIST",
+            "GEOSEARCH k WITHDIST",
+            "XINFO STREAM k FULL",
+            "XINFO STREAM k",
+            "PUBSUB NUMSUB",
+            "FT.AGGREGATE i *",
+            "FT.SEARCH i *",
+            "FT.PROFILE i SEARCH",
+            "FT.PROFILE i AGGREGATE",
+            "FT.INFO i",
+            "TTL k",
+            "PTTL k",
+            "TIME",
+            "LASTSAVE",
+            "OBJECT IDLETIME k",
+        ]
+        .iter()
+        .map(|command| {
+            let mut args = command.split(' ');
+            let mut cmd = redis::cmd(args.next().unwrap());
+            for arg in args {
+                cmd.arg(arg);
+            }
+            cmd
+        })
+        .collect()
+    }
+
+    quickcheck! {
+        fn convert_to_expected_type_never_panics(reply: ArbitraryReply) -> bool {
+            for cmd in commands_with_expected_types() {
+                let expected_type = expected_type_for_client_cmd(&cmd, true, true);
+                assert!(expected_type.is_some(), "{:?}", cmd.command());
+                let _ = convert_to_expected_type(reply.0.clone(), expected_type);
+            }
+            true
+        }
+    }
 }
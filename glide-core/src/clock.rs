@@ -0,0 +1,98 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! The time source of the core's time-dependent features, such as adaptive timeouts, scan time budgets and the
+//! suppression of duplicate pub/sub messages. They read the time from a process-wide clock rather than from
+//! `Instant::now`, so that embedders can replace it, e.g. to test time-dependent behavior without waiting.
+
+use once_cell::sync::Lazy;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// A source of the current time.
+pub trait Clock: Send + Sync {
+    /// Returns the current time. Consecutive calls must never go backwards.
+    fn now(&self) -> Instant;
+}
+
+/// The clock of the system, which is used unless another one was set.
+#[derive(Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when it's advanced.
+#[derive(Debug)]
+pub struct ManualClock {
+    now: Mutex<Instant>,
+}
+
+impl ManualClock {
+    /// Creates a clock that is stopped at the current time of the system.
+    pub fn new() -> Self {
+        ManualClock {
+            now: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+}
+
+static CLOCK: Lazy<RwLock<Arc<dyn Clock>>> = Lazy::new(|| RwLock::new(Arc::new(SystemClock)));
+
+/// Sets the clock that the core reads the time from. `None` restores the system clock.
+///
+/// Times that were already read from the previous clock are compared with times of the new one, so the new clock
+/// shouldn't be behind the previous one.
+pub fn set_clock(clock: Option<Arc<dyn Clock>>) {
+    *CLOCK.write().unwrap() = clock.unwrap_or_else(|| Arc::new(SystemClock));
+}
+
+/// Returns the current time of the clock.
+pub fn now() -> Instant {
+    CLOCK.read().unwrap().now()
+}
+
+/// Returns the time that passed since `start` by the clock, or zero if `start` is in the future.
+pub fn elapsed(start: Instant) -> Duration {
+    now().saturating_duration_since(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_manual_clock_replaces_the_system_clock() {
+        let clock = Arc::new(ManualClock::new());
+        set_clock(Some(clock.clone()));
+        let start = now();
+        assert_eq!(elapsed(start), Duration::ZERO);
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(elapsed(start), Duration::from_secs(60));
+        assert_eq!(now(), start + Duration::from_secs(60));
+
+        set_clock(None);
+        assert!(elapsed(start) < Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,324 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Process-wide limit of the connections held by clients, which protects the host from running out of file
+//! descriptors when an application creates a client per request by mistake.
+//!
+//! Each client reserves its connections from a budget that is shared by all the clients of the process, and a client
+//! whose connections can't be reserved isn't created. The budget is shared fairly: every client is entitled to an equal
+//! share of the limit, and a client can only grow beyond its share with connections that the other clients aren't
+//! entitled to. A cluster client reserves the connections of each topology it discovers before connecting to its nodes,
+//! and a topology that would exceed the client's share isn't connected to. Connections that a client opens for its
+//! own use, such as those of optimistic transactions, are reserved from its share while they are open.
+
+use once_cell::sync::Lazy;
+use redis::{ErrorKind, RedisError};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// The description of the errors of cluster clients whose topology couldn't be reserved.
+pub(crate) const BUDGET_EXHAUSTED: &str = "Connection budget exhausted";
+
+/// The connections held by a client.
+#[derive(Clone, Copy, Default)]
+struct Held {
+    /// The connections that the client keeps open to its nodes.
+    pooled: usize,
+    /// The connections that the client opened for its own use, see [`ConnectionShare::reserve_dedicated`].
+    dedicated: usize,
+}
+
+impl Held {
+    fn total(&self) -> usize {
+        self.pooled + self.dedicated
+    }
+}
+
+#[derive(Default)]
+struct Budget {
+    limit: Option<usize>,
+    /// The connections held by each client.
+    clients: HashMap<u64, Held>,
+    next_client_id: u64,
+}
+
+impl Budget {
+    fn in_use(&self) -> usize {
+        self.clients.values().map(Held::total).sum()
+    }
+
+    fn held(&self, client_id: u64) -> Held {
+        self.clients.get(&client_id).copied().unwrap_or_default()
+    }
+
+    /// Whether the client may hold `count` connections in total instead of those it holds.
+    fn allows(&self, client_id: u64, count: usize) -> bool {
+        let Some(limit) = self.limit else {
+            return true;
+        };
+        let held = self.held(client_id).total();
+        if count <= held {
+            return true;
+        }
+        let available = limit.saturating_sub(self.in_use());
+        let additional = count - held;
+        if additional > available {
+            return false;
+        }
+        let fair_share = limit / self.clients.len().max(1);
+        if count <= fair_share {
+            return true;
+        }
+        // The connections that the other clients may still reserve without exceeding their shares are kept for them.
+        let entitled_to_others: usize = self
+            .clients
+            .iter()
+            .filter(|(id, _)| **id != client_id)
+            .map(|(_, held)| fair_share.saturating_sub(held.total()))
+            .sum();
+        available - additional >= entitled_to_others
+    }
+
+    fn exhausted_message(&self, count: usize) -> String {
+        format!(
+            "{count} connections couldn't be reserved, {} of the limit of {} are held by {} clients",
+            self.in_use(),
+            self.limit.unwrap_or(usize::MAX),
+            self.clients
+                .values()
+                .filter(|held| held.total() > 0)
+                .count(),
+        )
+    }
+}
+
+static BUDGET: Lazy<Mutex<Budget>> = Lazy::new(|| Mutex::new(Budget::default()));
+
+/// Sets the number of connections that all the clients of the process may hold together. `None`, the default, removes
+/// the limit. Clients that were already created keep their connections.
+pub fn set_connection_limit(limit: Option<usize>) {
+    BUDGET.lock().unwrap().limit = limit;
+}
+
+/// Sets the limit requested by a client's configuration. Since the limit is shared by all the clients in the process, a
+/// limit that conflicts with the one that is already set is refused, and the current limit is returned.
+pub(crate) fn request_connection_limit(limit: usize) -> Result<(), usize> {
+    let mut budget = BUDGET.lock().unwrap();
+    match budget.limit {
+        None => {
+            budget.limit = Some(limit);
+            Ok(())
+        }
+        Some(current) if current == limit => Ok(()),
+        Some(current) => Err(current),
+    }
+}
+
+/// Returns the limit of connections, if one was set.
+pub fn connection_limit() -> Option<usize> {
+    BUDGET.lock().unwrap().limit
+}
+
+/// Returns the number of connections that are currently held by clients.
+pub fn connections_in_use() -> usize {
+    BUDGET.lock().unwrap().in_use()
+}
+
+/// The connections held by a client. They are returned to the budget when it is dropped.
+#[derive(Debug)]
+pub(crate) struct ConnectionShare {
+    client_id: u64,
+}
+
+impl ConnectionShare {
+    /// Reserves `count` connections for a new client, or returns an error that describes the budget if they can't be
+    /// reserved.
+    pub(crate) fn reserve(count: usize) -> Result<Self, String> {
+        let mut budget = BUDGET.lock().unwrap();
+        let client_id = budget.next_client_id;
+        budget.next_client_id += 1;
+        // The new client counts when computing the fair share.
+        budget.clients.insert(client_id, Held::default());
+        if !budget.allows(client_id, count) {
+            let message = budget.exhausted_message(count);
+            budget.clients.remove(&client_id);
+            return Err(message);
+        }
+        budget.clients.insert(
+            client_id,
+            Held {
+                pooled: count,
+                dedicated: 0,
+            },
+        );
+        Ok(ConnectionShare { client_id })
+    }
+
+    /// Changes the number of connections that the client keeps open to its nodes to `count`, e.g. once the connections
+    /// of a cluster client were counted. Shrinking always succeeds.
+    pub(crate) fn resize(&self, count: usize) -> Result<(), String> {
+        let mut budget = BUDGET.lock().unwrap();
+        let held = budget.held(self.client_id);
+        if !budget.allows(self.client_id, count + held.dedicated) {
+            return Err(budget.exhausted_message(count));
+        }
+        budget.clients.insert(
+            self.client_id,
+            Held {
+                pooled: count,
+                ..held
+            },
+        );
+        Ok(())
+    }
+
+    /// Reserves a connection that the client opens for its own use, until the returned reservation is dropped.
+    pub(crate) fn reserve_dedicated(
+        self: &Arc<Self>,
+    ) -> Result<DedicatedConnectionReservation, String> {
+        let mut budget = BUDGET.lock().unwrap();
+        let held = budget.held(self.client_id);
+        if !budget.allows(self.client_id, held.total() + 1) {
+            return Err(budget.exhausted_message(1));
+        }
+        budget.clients.insert(
+            self.client_id,
+            Held {
+                dedicated: held.dedicated + 1,
+                ..held
+            },
+        );
+        Ok(DedicatedConnectionReservation {
+            share: self.clone(),
+        })
+    }
+
+    /// Returns a check that resizes the share to the connections of each topology that a cluster client discovers,
+    /// before the client connects to its nodes.
+    pub(crate) fn cluster_connections_check(self: &Arc<Self>) -> redis::cluster::ConnectionsCheck {
+        let share = self.clone();
+        Arc::new(move |count| {
+            share.resize(count).map_err(|message| {
+                RedisError::from((ErrorKind::InvalidClientConfig, BUDGET_EXHAUSTED, message))
+            })
+        })
+    }
+}
+
+impl Drop for ConnectionShare {
+    fn drop(&mut self) {
+        BUDGET.lock().unwrap().clients.remove(&self.client_id);
+    }
+}
+
+/// A connection reserved by [`ConnectionShare::reserve_dedicated`]. It is returned to the budget when it is dropped.
+#[derive(Debug)]
+pub(crate) struct DedicatedConnectionReservation {
+    share: Arc<ConnectionShare>,
+}
+
+impl Drop for DedicatedConnectionReservation {
+    fn drop(&mut self) {
+        if let Some(held) = BUDGET
+            .lock()
+            .unwrap()
+            .clients
+            .get_mut(&self.share.client_id)
+        {
+            held.dedicated = held.dedicated.saturating_sub(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_connections_are_returned_when_the_share_is_dropped() {
+        set_connection_limit(Some(4));
+        let first = ConnectionShare::reserve(2).unwrap();
+        let second = ConnectionShare::reserve(2).unwrap();
+        let err = ConnectionShare::reserve(1).unwrap_err();
+        assert!(err.contains("couldn't be reserved"), "{err}");
+        drop(first);
+        let third = ConnectionShare::reserve(2).unwrap();
+        assert!(third.resize(3).is_err());
+        third.resize(1).unwrap();
+        // The third client is still entitled to another connection.
+        assert!(second.resize(3).is_err());
+        drop(second);
+        drop(third);
+
+        set_connection_limit(None);
+        let unlimited = ConnectionShare::reserve(1_000_000).unwrap();
+        drop(unlimited);
+    }
+
+    #[test]
+    #[serial]
+    fn test_clients_cant_grow_into_the_shares_of_others() {
+        set_connection_limit(Some(12));
+        let small = ConnectionShare::reserve(1).unwrap();
+        let large = ConnectionShare::reserve(6).unwrap();
+        // The share of each of the clients is 6, so the remaining 5 connections are kept for the small client.
+        assert!(large.resize(7).is_err());
+        small.resize(6).unwrap();
+        // Without other clients, the large client is entitled to the whole limit.
+        drop(small);
+        large.resize(12).unwrap();
+        assert!(ConnectionShare::reserve(1).is_err());
+        drop(large);
+        set_connection_limit(None);
+    }
+
+    #[test]
+    #[serial]
+    fn test_cluster_connections_check_resizes_the_share() {
+        set_connection_limit(Some(6));
+        let share = Arc::new(ConnectionShare::reserve(2).unwrap());
+        let check = share.cluster_connections_check();
+        check(6).unwrap();
+        assert_eq!(connections_in_use(), 6);
+        let err = check(8).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidClientConfig);
+        assert!(err.to_string().starts_with(BUDGET_EXHAUSTED), "{err}");
+        assert_eq!(connections_in_use(), 6);
+        drop(check);
+        drop(share);
+        assert_eq!(connections_in_use(), 0);
+        set_connection_limit(None);
+    }
+
+    #[test]
+    #[serial]
+    fn test_dedicated_connections_are_reserved_from_the_share() {
+        set_connection_limit(Some(4));
+        let share = Arc::new(ConnectionShare::reserve(2).unwrap());
+        let first = share.reserve_dedicated().unwrap();
+        let second = share.reserve_dedicated().unwrap();
+        assert_eq!(connections_in_use(), 4);
+        assert!(share.reserve_dedicated().is_err());
+        // Resizing the pooled connections keeps the dedicated ones.
+        assert!(share.resize(3).is_err());
+        share.resize(1).unwrap();
+        assert_eq!(connections_in_use(), 3);
+        drop(first);
+        drop(second);
+        assert_eq!(connections_in_use(), 1);
+        drop(share);
+        set_connection_limit(None);
+    }
+
+    #[test]
+    #[serial]
+    fn test_requested_limit_must_match_the_current_limit() {
+        set_connection_limit(None);
+        assert_eq!(request_connection_limit(10), Ok(()));
+        assert_eq!(request_connection_limit(10), Ok(()));
+        assert_eq!(request_connection_limit(20), Err(10));
+        assert_eq!(connection_limit(), Some(10));
+        set_connection_limit(None);
+    }
+}
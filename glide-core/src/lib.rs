@@ -3,6 +3,7 @@
 #[cfg(feature = "socket-layer")]
 include!(concat!(env!("OUT_DIR"), "/protobuf/mod.rs"));
 pub mod client;
+pub mod clock;
 mod retry_strategies;
 #[cfg(feature = "socket-layer")]
 pub mod rotating_buffer;
@@ -14,5 +15,8 @@ pub mod errors;
 pub mod scripts_container;
 pub use client::ConnectionRequest;
 pub mod cluster_scan_container;
+pub mod connection_budget;
+pub mod memory_budget;
+pub mod pubsub_delivery;
 pub mod request_type;
 pub use telemetrylib::Telemetry;
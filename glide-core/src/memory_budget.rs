@@ -0,0 +1,156 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Process-wide accounting of the memory held by the core - in-flight requests, buffered responses and cached scripts.
+//! The accounting is approximate, and only covers the payloads, not the bookkeeping around them.
+
+use redis::{Cmd, ErrorKind, Pipeline, RedisError};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static MEMORY_USAGE: AtomicUsize = AtomicUsize::new(0);
+/// 0 means that no cap was set.
+static MEMORY_CAP: AtomicUsize = AtomicUsize::new(0);
+
+/// Sets the approximate number of bytes that the core may hold before new requests are rejected.
+/// `None` removes the cap.
+pub fn set_memory_cap(cap: Option<usize>) {
+    MEMORY_CAP.store(cap.unwrap_or(0), Ordering::Relaxed);
+}
+
+/// Sets the memory cap requested by a client's configuration. Since the cap is shared by all the clients in the
+/// process, a cap that conflicts with the one that is already set is refused, and the current cap is returned.
+pub(crate) fn request_memory_cap(cap: usize) -> Result<(), usize> {
+    let cap = cap.max(1);
+    match MEMORY_CAP.compare_exchange(0, cap, Ordering::Relaxed, Ordering::Relaxed) {
+        Ok(_) => Ok(()),
+        Err(current_cap) if current_cap == cap => Ok(()),
+        Err(current_cap) => Err(current_cap),
+    }
+}
+
+/// Returns the memory cap, if one was set.
+pub fn memory_cap() -> Option<usize> {
+    match MEMORY_CAP.load(Ordering::Relaxed) {
+        0 => None,
+        cap => Some(cap),
+    }
+}
+
+/// Returns the approximate number of bytes currently held by the core.
+pub fn memory_usage() -> usize {
+    MEMORY_USAGE.load(Ordering::Relaxed)
+}
+
+/// Accounts for a number of bytes until it is dropped.
+#[derive(Debug)]
+#[must_use]
+pub(crate) struct MemoryReservation {
+    bytes: usize,
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        MEMORY_USAGE.fetch_sub(self.bytes, Ordering::Relaxed);
+    }
+}
+
+/// Accounts for `bytes` regardless of the cap. Used for memory that was already allocated, such as buffered responses.
+pub(crate) fn reserve(bytes: usize) -> MemoryReservation {
+    MEMORY_USAGE.fetch_add(bytes, Ordering::Relaxed);
+    MemoryReservation { bytes }
+}
+
+/// Accounts for `bytes` if this doesn't exceed the cap, and returns `None` otherwise.
+/// Used to apply backpressure on new requests before the process runs out of memory.
+pub(crate) fn try_reserve(bytes: usize) -> Option<MemoryReservation> {
+    let Some(cap) = memory_cap() else {
+        return Some(reserve(bytes));
+    };
+    let previous_usage = MEMORY_USAGE.fetch_add(bytes, Ordering::Relaxed);
+    // A single request that is larger than the cap is still allowed when nothing else is held, so that it won't be starved.
+    if previous_usage > 0 && previous_usage.saturating_add(bytes) > cap {
+        MEMORY_USAGE.fetch_sub(bytes, Ordering::Relaxed);
+        return None;
+    }
+    Some(MemoryReservation { bytes })
+}
+
+pub(crate) fn cap_reached_error() -> RedisError {
+    RedisError::from((
+        ErrorKind::ClientError,
+        "Reached the memory cap, the request was rejected",
+    ))
+}
+
+/// Returns the approximate number of bytes held by the arguments of `cmd`.
+pub(crate) fn cmd_size(cmd: &Cmd) -> usize {
+    cmd.args_iter()
+        .map(|arg| match arg {
+            redis::Arg::Simple(arg) => arg.len(),
+            redis::Arg::Cursor => 0,
+        })
+        .sum()
+}
+
+/// Returns the approximate number of bytes held by the arguments of all commands in `pipeline`.
+pub(crate) fn pipeline_size(pipeline: &Pipeline) -> usize {
+    pipeline.cmd_iter().map(cmd_size).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_reservations_are_released_on_drop() {
+        let usage_before = memory_usage();
+        let first = reserve(100);
+        let second = try_reserve(50).unwrap();
+        assert_eq!(memory_usage(), usage_before + 150);
+        drop(first);
+        assert_eq!(memory_usage(), usage_before + 50);
+        drop(second);
+        assert_eq!(memory_usage(), usage_before);
+    }
+
+    #[test]
+    #[serial]
+    fn test_try_reserve_respects_cap() {
+        let previous_cap = memory_cap();
+        set_memory_cap(Some(memory_usage() + 100));
+        let held = try_reserve(80).unwrap();
+        assert!(try_reserve(30).is_none());
+        let small = try_reserve(20).unwrap();
+        drop(held);
+        assert!(try_reserve(30).is_some());
+        drop(small);
+        set_memory_cap(None);
+        let large = try_reserve(1_000_000).unwrap();
+        drop(large);
+        set_memory_cap(previous_cap);
+    }
+
+    #[test]
+    #[serial]
+    fn test_requested_memory_cap_must_match_the_current_cap() {
+        let previous_cap = memory_cap();
+        set_memory_cap(None);
+        assert_eq!(request_memory_cap(1000), Ok(()));
+        assert_eq!(memory_cap(), Some(1000));
+        assert_eq!(request_memory_cap(1000), Ok(()));
+        assert_eq!(request_memory_cap(2000), Err(1000));
+        assert_eq!(memory_cap(), Some(1000));
+        set_memory_cap(previous_cap);
+    }
+
+    #[test]
+    fn test_cmd_size() {
+        let mut cmd = redis::cmd("SET");
+        cmd.arg("key").arg("value");
+        assert_eq!(cmd_size(&cmd), 11);
+        let mut pipeline = Pipeline::new();
+        pipeline.add_command(cmd.clone()).add_command(cmd);
+        assert_eq!(pipeline_size(&pipeline), 22);
+    }
+}
@@ -0,0 +1,221 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Tracks the delivery of pub/sub messages across reconnections.
+//!
+//! Messages that are published while a subscription is being restored are lost, and a message may be delivered
+//! twice if it was received both before and after the subscription was restored. Since the server doesn't number
+//! the messages, duplicates can't be told apart from messages that were legitimately published twice, so every
+//! message is delivered. Instead, each subscription counts the messages that it delivered, and reports a
+//! `GapDetected` notification when it's restored after the connection to its node was lost.
+
+use redis::{PushInfo, PushKind, Value};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum SubscriptionKind {
+    Exact,
+    Pattern,
+    Sharded,
+}
+
+#[derive(Default)]
+struct SubscriptionState {
+    /// The number of messages that were delivered from this subscription.
+    delivered: u64,
+    /// The address of the node that confirmed the subscription, if it's known.
+    address: Option<String>,
+    /// Set when the connection to the subscription's node was lost, until the subscription is restored.
+    disconnected: bool,
+}
+
+/// Tracks the push notifications of a single client before they're passed to the wrapper.
+#[derive(Default)]
+pub struct PubSubDelivery {
+    subscriptions: HashMap<(SubscriptionKind, Vec<u8>), SubscriptionState>,
+}
+
+impl PubSubDelivery {
+    /// Returns the notifications that should be delivered in place of `push`, in order - `push` itself, and an
+    /// additional `GapDetected` notification for a subscription that was restored.
+    pub fn process(&mut self, push: PushInfo) -> Vec<PushInfo> {
+        match push.kind {
+            PushKind::Disconnection => {
+                // A disconnection of an unknown node, or of a subscription whose node is unknown, may have
+                // interrupted the subscription.
+                for state in self.subscriptions.values_mut() {
+                    if push.address.is_none()
+                        || state.address.is_none()
+                        || state.address == push.address
+                    {
+                        state.disconnected = true;
+                    }
+                }
+                vec![push]
+            }
+            PushKind::Subscribe | PushKind::PSubscribe | PushKind::SSubscribe => {
+                let Some(key) = subscription_key(&push) else {
+                    return vec![push];
+                };
+                let state = self.subscriptions.entry(key.clone()).or_default();
+                state.address.clone_from(&push.address);
+                if !state.disconnected {
+                    return vec![push];
+                }
+                state.disconnected = false;
+                let gap = PushInfo {
+                    kind: PushKind::GapDetected,
+                    data: vec![Value::BulkString(key.1), Value::Int(state.delivered as i64)],
+                    address: push.address.clone(),
+                };
+                vec![push, gap]
+            }
+            PushKind::Unsubscribe | PushKind::PUnsubscribe | PushKind::SUnsubscribe => {
+                if let Some(key) = subscription_key(&push) {
+                    self.subscriptions.remove(&key);
+                }
+                vec![push]
+            }
+            PushKind::Message | PushKind::PMessage | PushKind::SMessage => {
+                if let Some(key) = subscription_key(&push) {
+                    self.subscriptions.entry(key).or_default().delivered += 1;
+                }
+                vec![push]
+            }
+            _ => vec![push],
+        }
+    }
+}
+
+/// Returns the subscription that `push` belongs to - the channel, or the pattern for pattern messages.
+fn subscription_key(push: &PushInfo) -> Option<(SubscriptionKind, Vec<u8>)> {
+    let kind = match push.kind {
+        PushKind::Subscribe | PushKind::Unsubscribe | PushKind::Message => SubscriptionKind::Exact,
+        PushKind::PSubscribe | PushKind::PUnsubscribe | PushKind::PMessage => {
+            SubscriptionKind::Pattern
+        }
+        PushKind::SSubscribe | PushKind::SUnsubscribe | PushKind::SMessage => {
+            SubscriptionKind::Sharded
+        }
+        _ => return None,
+    };
+    match push.data.first()? {
+        Value::BulkString(name) => Some((kind, name.clone())),
+        Value::SimpleString(name) => Some((kind, name.clone().into_bytes())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NODE: &str = "127.0.0.1:7000";
+    const OTHER_NODE: &str = "127.0.0.1:7001";
+
+    fn push(kind: PushKind, data: &[&str]) -> PushInfo {
+        push_from(None, kind, data)
+    }
+
+    fn push_from(address: Option<&str>, kind: PushKind, data: &[&str]) -> PushInfo {
+        PushInfo {
+            kind,
+            data: data
+                .iter()
+                .map(|item| Value::BulkString(item.as_bytes().to_vec()))
+                .collect(),
+            address: address.map(str::to_string),
+        }
+    }
+
+    fn kinds(pushes: &[PushInfo]) -> Vec<PushKind> {
+        pushes.iter().map(|push| push.kind.clone()).collect()
+    }
+
+    #[test]
+    fn test_messages_pass_through_without_reconnection() {
+        let mut delivery = PubSubDelivery::default();
+        delivery.process(push(PushKind::Subscribe, &["channel"]));
+        let message = push(PushKind::Message, &["channel", "payload"]);
+        assert_eq!(delivery.process(message.clone()).len(), 1);
+        // Repeated payloads are legitimate while the subscription is stable.
+        assert_eq!(delivery.process(message).len(), 1);
+    }
+
+    #[test]
+    fn test_gap_is_reported_and_every_message_is_delivered_after_resubscribe() {
+        let mut delivery = PubSubDelivery::default();
+        delivery.process(push(PushKind::Subscribe, &["channel"]));
+        delivery.process(push(PushKind::Message, &["channel", "first"]));
+        delivery.process(push(PushKind::Message, &["channel", "second"]));
+        delivery.process(push(PushKind::Disconnection, &[]));
+
+        let restored = delivery.process(push(PushKind::Subscribe, &["channel"]));
+        assert_eq!(
+            kinds(&restored),
+            vec![PushKind::Subscribe, PushKind::GapDetected]
+        );
+        assert_eq!(
+            restored[1].data,
+            vec![Value::BulkString(b"channel".to_vec()), Value::Int(2)]
+        );
+
+        // A message that was received both before and after the subscription was restored is delivered twice.
+        assert_eq!(
+            delivery
+                .process(push(PushKind::Message, &["channel", "second"]))
+                .len(),
+            1
+        );
+        assert_eq!(
+            kinds(&delivery.process(push(PushKind::Subscribe, &["channel"]))),
+            vec![PushKind::Subscribe]
+        );
+    }
+
+    #[test]
+    fn test_disconnection_of_a_node_only_interrupts_its_subscriptions() {
+        let mut delivery = PubSubDelivery::default();
+        delivery.process(push_from(Some(NODE), PushKind::SSubscribe, &["first"]));
+        delivery.process(push_from(
+            Some(OTHER_NODE),
+            PushKind::SSubscribe,
+            &["second"],
+        ));
+        delivery.process(push_from(Some(NODE), PushKind::Disconnection, &[]));
+
+        assert_eq!(
+            kinds(&delivery.process(push_from(
+                Some(OTHER_NODE),
+                PushKind::SSubscribe,
+                &["second"]
+            ))),
+            vec![PushKind::SSubscribe]
+        );
+        // The subscription may be restored on another node, e.g. after a failover.
+        assert_eq!(
+            kinds(&delivery.process(push_from(
+                Some(OTHER_NODE),
+                PushKind::SSubscribe,
+                &["first"]
+            ))),
+            vec![PushKind::SSubscribe, PushKind::GapDetected]
+        );
+    }
+
+    #[test]
+    fn test_subscriptions_are_tracked_separately() {
+        let mut delivery = PubSubDelivery::default();
+        delivery.process(push(PushKind::Subscribe, &["channel"]));
+        delivery.process(push(PushKind::SSubscribe, &["channel"]));
+        delivery.process(push(PushKind::Unsubscribe, &["channel"]));
+        delivery.process(push(PushKind::Disconnection, &[]));
+        assert_eq!(
+            kinds(&delivery.process(push(PushKind::SSubscribe, &["channel"]))),
+            vec![PushKind::SSubscribe, PushKind::GapDetected]
+        );
+        assert_eq!(
+            kinds(&delivery.process(push(PushKind::Subscribe, &["channel"]))),
+            vec![PushKind::Subscribe]
+        );
+    }
+}
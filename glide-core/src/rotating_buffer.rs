@@ -68,6 +68,7 @@ mod tests {
     use crate::command_request::{command, command_request};
     use crate::command_request::{Command, CommandRequest, RequestType};
     use bytes::BufMut;
+    use quickcheck::quickcheck;
     use rand::{distributions::Alphanumeric, Rng};
     use rstest::rstest;
 
@@ -365,4 +366,54 @@ mod tests {
             args_pointer,
         );
     }
+
+    quickcheck! {
+        fn get_requests_never_panics_on_arbitrary_input(chunks: Vec<Vec<u8>>) -> bool {
+            let mut rotating_buffer = RotatingBuffer::new(16);
+            for chunk in chunks {
+                rotating_buffer.current_buffer().extend_from_slice(&chunk);
+                // The socket is closed after the first malformed request, so there's nothing left to parse.
+                if rotating_buffer.get_requests::<CommandRequest>().is_err() {
+                    break;
+                }
+            }
+            true
+        }
+
+        fn get_requests_parses_requests_split_at_any_point(keys: Vec<String>, split_points: Vec<usize>) -> bool {
+            let mut written = BytesMut::new();
+            for (index, key) in keys.iter().enumerate() {
+                write_get(&mut written, index as u32, key, false);
+            }
+            let mut split_points: Vec<usize> = split_points
+                .into_iter()
+                .map(|point| point % (written.len() + 1))
+                .collect();
+            split_points.push(written.len());
+            split_points.sort_unstable();
+
+            let mut rotating_buffer = RotatingBuffer::new(16);
+            let mut requests = vec![];
+            let mut position = 0;
+            for point in split_points {
+                rotating_buffer
+                    .current_buffer()
+                    .extend_from_slice(&written[position..point]);
+                position = point;
+                requests.extend(rotating_buffer.get_requests::<CommandRequest>().unwrap());
+            }
+            requests.len() == keys.len()
+                && requests
+                    .iter()
+                    .enumerate()
+                    .all(|(index, request)| request.callback_idx == index as u32)
+        }
+
+        fn connection_request_conversion_never_panics(bytes: Vec<u8>) -> bool {
+            if let Ok(request) = crate::connection_request::ConnectionRequest::parse_from_bytes(&bytes) {
+                let _ = crate::client::ConnectionRequest::from(request);
+            }
+            true
+        }
+    }
 }
@@ -1,5 +1,6 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
+use crate::memory_budget::{self, MemoryReservation};
 use bytes::BytesMut;
 use logger_core::log_info;
 use once_cell::sync::Lazy;
@@ -7,7 +8,7 @@ use sha1_smol::Sha1;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
-static CONTAINER: Lazy<Mutex<HashMap<String, Arc<BytesMut>>>> =
+static CONTAINER: Lazy<Mutex<HashMap<String, (Arc<BytesMut>, MemoryReservation)>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
 pub fn add_script(script: &[u8]) -> String {
@@ -18,15 +19,22 @@ pub fn add_script(script: &[u8]) -> String {
         "script lifetime",
         format!("Added script with hash: `{hash}`"),
     );
-    CONTAINER
-        .lock()
-        .unwrap()
-        .insert(hash.clone(), Arc::new(script.into()));
+    CONTAINER.lock().unwrap().insert(
+        hash.clone(),
+        (
+            Arc::new(script.into()),
+            memory_budget::reserve(script.len()),
+        ),
+    );
     hash
 }
 
 pub fn get_script(hash: &str) -> Option<Arc<BytesMut>> {
-    CONTAINER.lock().unwrap().get(hash).cloned()
+    CONTAINER
+        .lock()
+        .unwrap()
+        .get(hash)
+        .map(|(script, _)| script.clone())
 }
 
 pub fn remove_script(hash: &str) {
@@ -1,13 +1,17 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
 use super::rotating_buffer::RotatingBuffer;
-use crate::client::Client;
+use crate::client::{
+    affinity_routing, float_arg, int_arg, Client, DeleteByPatternOptions, ExportedKey,
+};
 use crate::cluster_scan_container::get_cluster_scan_cursor;
 use crate::command_request::{
     command, command_request, ClusterScan, Command, CommandRequest, Routes, SlotTypes, Transaction,
 };
 use crate::connection_request::ConnectionRequest;
 use crate::errors::{error_message, error_type, RequestErrorType};
+use crate::memory_budget;
+use crate::pubsub_delivery::PubSubDelivery;
 use crate::response;
 use crate::response::Response;
 use bytes::Bytes;
@@ -25,8 +29,10 @@ use std::collections::HashSet;
 use std::ptr::from_mut;
 use std::rc::Rc;
 use std::sync::RwLock;
+use std::time::Duration;
 use std::{env, str};
 use std::{io, thread};
+use telemetrylib::Telemetry;
 use thiserror::Error;
 use tokio::net::{UnixListener, UnixStream};
 use tokio::runtime::Builder;
@@ -35,12 +41,16 @@ use tokio::sync::mpsc::{channel, Sender};
 use tokio::sync::Mutex;
 use tokio::task;
 use tokio_util::task::LocalPoolHandle;
+use versions::SemVer;
 use ClosingReason::*;
 use PipeListeningResult::*;
 
 /// The socket file name
 const SOCKET_FILE_NAME: &str = "glide-socket";
 
+/// The version of the core, which is reported to the wrappers.
+const CORE_VERSION: &str = env!("GLIDE_VERSION");
+
 /// The maximum length of a request's arguments to be passed as a vector of
 /// strings instead of a pointer
 pub const MAX_REQUEST_ARGS_LENGTH: usize = 2_i32.pow(12) as usize; // TODO: find the right number
@@ -135,6 +145,8 @@ async fn write_to_output(writer: &Rc<Writer>) {
         if output.is_empty() {
             return;
         }
+        // The output is held until the wrapper reads it, which might take a while if the wrapper is busy.
+        let _output_reservation = memory_budget::reserve(output.len());
         let mut total_written_bytes = 0;
         while total_written_bytes < output.len() {
             if let Err(err) = writer.socket.writable().await {
@@ -179,11 +191,13 @@ async fn write_closing_error(
 async fn write_result(
     resp_result: ClientUsageResult<Value>,
     callback_index: u32,
+    replication_count: Option<u32>,
     writer: &Rc<Writer>,
 ) -> Result<(), io::Error> {
     let mut response = Response::new();
     response.callback_idx = callback_index;
     response.is_push = false;
+    response.replication_count = replication_count;
     response.value = match resp_result {
         Ok(Value::Okay) => Some(response::response::Value::ConstantResponse(
             response::ConstantResponse::OK.into(),
@@ -282,6 +296,22 @@ fn get_redis_command(command: &Command) -> Result<Cmd, ClientUsageError> {
                 cmd.arg(arg.as_ref());
             }
         }
+        Some(command::Args::TypedArgsArray(typed_args)) => {
+            for arg in typed_args.args.iter() {
+                match &arg.value {
+                    Some(command::typed_arg::Value::BytesValue(bytes)) => cmd.arg(bytes.as_ref()),
+                    Some(command::typed_arg::Value::DoubleValue(value)) => {
+                        cmd.arg(float_arg(*value)?)
+                    }
+                    Some(command::typed_arg::Value::IntValue(value)) => cmd.arg(int_arg(*value)),
+                    None => {
+                        return Err(ClientUsageError::Internal(
+                            "Received a typed argument without a value".to_string(),
+                        ));
+                    }
+                };
+            }
+        }
         None => {
             return Err(ClientUsageError::Internal(
                 "Failed to get request arguments, no arguments are set".to_string(),
@@ -298,14 +328,31 @@ fn get_redis_command(command: &Command) -> Result<Cmd, ClientUsageError> {
     Ok(cmd)
 }
 
+/// Keys whose read failed are represented by a map with an `error` field, since a single response can't hold errors.
+fn per_key_results_to_value(results: Vec<Result<Value, RedisError>>) -> Value {
+    Value::Array(
+        results
+            .into_iter()
+            .map(|result| {
+                result.unwrap_or_else(|err| {
+                    Value::Map(vec![(
+                        Value::SimpleString("error".to_string()),
+                        Value::BulkString(err.to_string().into_bytes()),
+                    )])
+                })
+            })
+            .collect(),
+    )
+}
+
 async fn send_command(
     cmd: Cmd,
     mut client: Client,
     routing: Option<RoutingInfo>,
-) -> ClientUsageResult<Value> {
+) -> ClientUsageResult<(Value, Option<u32>)> {
     let child_span = cmd.span().map(|span| span.add_span("send_command"));
     let res = client
-        .send_command(&cmd, routing)
+        .send_command_with_write_durability(&cmd, routing)
         .await
         .map_err(|err| err.into());
     if let Some(child_span) = child_span {
@@ -460,8 +507,10 @@ fn get_route(
 }
 
 fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer>) {
+    client.set_audit_context(request.audit_context.as_deref());
     task::spawn_local(async move {
         let mut updated_inflight_counter = true;
+        let mut replication_count = None;
         let client_clone = client.clone();
 
         let result = match client.reserve_inflight_request() {
@@ -479,7 +528,17 @@ fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer
                     command_request::Command::SingleCommand(command) => {
                         match get_redis_command(&command) {
                             Ok(cmd) => match get_route(request.route.0, Some(&cmd)) {
-                                Ok(routes) => send_command(cmd, client, routes).await,
+                                Ok(routes) => {
+                                    let routes = routes.or_else(|| {
+                                        affinity_routing(&request.affinity_token, Some(&cmd))
+                                    });
+                                    send_command(cmd, client, routes)
+                                        .await
+                                        .map(|(value, count)| {
+                                            replication_count = count;
+                                            value
+                                        })
+                                }
                                 Err(e) => Err(e),
                             },
                             Err(e) => Err(e),
@@ -494,6 +553,13 @@ fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer
                     command_request::Command::ScriptInvocation(script) => {
                         match get_route(request.route.0, None) {
                             Ok(routes) => {
+                                let routes = routes.or_else(|| {
+                                    if script.keys.is_empty() {
+                                        affinity_routing(&request.affinity_token, None)
+                                    } else {
+                                        None
+                                    }
+                                });
                                 invoke_script(
                                     script.hash,
                                     Some(script.keys),
@@ -515,6 +581,13 @@ fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer
                             .map(|pointer| *unsafe { Box::from_raw(pointer as *mut Vec<Bytes>) });
                         match get_route(request.route.0, None) {
                             Ok(routes) => {
+                                let routes = routes.or_else(|| {
+                                    if keys.as_ref().is_some_and(|keys| !keys.is_empty()) {
+                                        None
+                                    } else {
+                                        affinity_routing(&request.affinity_token, None)
+                                    }
+                                });
                                 invoke_script(script.hash, keys, args, client, routes).await
                             }
                             Err(e) => Err(e),
@@ -531,6 +604,88 @@ fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer
                         )
                         .await
                         .map_err(|err| err.into()),
+                    command_request::Command::UpdateRoutingWeights(update_routing_weights) => {
+                        client.update_routing_weights(
+                            update_routing_weights
+                                .weights
+                                .into_iter()
+                                .map(|(address, weight)| (address.to_string(), weight))
+                                .collect(),
+                        );
+                        Ok(Value::Okay)
+                    }
+                    command_request::Command::DeleteByPattern(delete_by_pattern) => {
+                        let mut options = DeleteByPatternOptions {
+                            max_keys_per_second: delete_by_pattern.max_keys_per_second,
+                            ..Default::default()
+                        };
+                        if delete_by_pattern.batch_size > 0 {
+                            options.batch_size = delete_by_pattern.batch_size;
+                        }
+                        client
+                            .delete_by_pattern(&delete_by_pattern.pattern, options)
+                            .await
+                            .map(|result| result.to_value())
+                            .map_err(|err| err.into())
+                    }
+                    command_request::Command::KeyspaceSnapshot(keyspace_snapshot) => client
+                        .keyspace_snapshot(keyspace_snapshot.sample_size)
+                        .await
+                        .map(|snapshot| snapshot.to_value())
+                        .map_err(|err| err.into()),
+                    command_request::Command::SampleKeys(sample_keys) => client
+                        .sample_keys(sample_keys.sample_size)
+                        .await
+                        .map(|sample| sample.to_value())
+                        .map_err(|err| err.into()),
+                    command_request::Command::HottestKeys(hottest_keys) => client
+                        .hottest_keys(hottest_keys.count as usize)
+                        .map(|keys| {
+                            Value::Map(
+                                keys.into_iter()
+                                    .map(|(key, count)| {
+                                        (Value::BulkString(key), Value::Int(count as i64))
+                                    })
+                                    .collect(),
+                            )
+                        })
+                        .map_err(|err| err.into()),
+                    command_request::Command::ParallelMget(parallel_mget) => {
+                        let keys: Vec<&[u8]> =
+                            parallel_mget.keys.iter().map(|key| key.as_ref()).collect();
+                        let results = client
+                            .parallel_mget(&keys, parallel_mget.max_concurrency as usize)
+                            .await;
+                        Ok(per_key_results_to_value(results))
+                    }
+                    command_request::Command::ExportKeys(export_keys) => {
+                        let batch_size = match export_keys.batch_size {
+                            0 => DeleteByPatternOptions::default().batch_size,
+                            batch_size => batch_size,
+                        };
+                        client
+                            .export_keys_page(&export_keys.cursor, &export_keys.pattern, batch_size)
+                            .await
+                            .map_err(|err| err.into())
+                    }
+                    command_request::Command::ImportKeys(import_keys) => {
+                        let keys: Vec<ExportedKey> = import_keys
+                            .keys
+                            .into_iter()
+                            .map(|key| ExportedKey {
+                                key: key.key.to_vec(),
+                                // The type is implied by the payload.
+                                key_type: String::new(),
+                                ttl: key.ttl_ms.map(Duration::from_millis),
+                                payload: key.payload.to_vec(),
+                            })
+                            .collect();
+                        client
+                            .import_keys(&keys, import_keys.replace)
+                            .await
+                            .map(|imported| Value::Int(imported as i64))
+                            .map_err(|err| err.into())
+                    }
                 },
                 None => {
                     log_debug(
@@ -551,7 +706,7 @@ fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer
             client_clone.release_inflight_request();
         }
 
-        let _res = write_result(result, request.callback_idx, &writer).await;
+        let _res = write_result(result, request.callback_idx, replication_count, &writer).await;
     });
 }
 
@@ -572,24 +727,82 @@ pub fn close_socket(socket_path: &String) {
     let _ = std::fs::remove_file(socket_path);
 }
 
+/// Returns whether a wrapper of `wrapper_version` is known to be incompatible with a core of `core_version`. The
+/// wrappers are released along with the core, so versions whose major or minor parts differ are incompatible. Versions
+/// that can't be parsed, such as those of development builds, aren't known to be incompatible.
+fn is_incompatible_wrapper(core_version: &str, wrapper_version: &str) -> bool {
+    match (
+        SemVer::new(core_version.trim_start_matches('v')),
+        SemVer::new(wrapper_version.trim_start_matches('v')),
+    ) {
+        (Some(core), Some(wrapper)) => (core.major, core.minor) != (wrapper.major, wrapper.minor),
+        _ => false,
+    }
+}
+
+/// Checks the wrapper's version against `core_version`, and reports whether it is incompatible.
+fn check_wrapper_version(request: &ConnectionRequest, core_version: &str) -> bool {
+    let wrapper_name: &str = &request.wrapper_name;
+    let wrapper_version: &str = &request.wrapper_version;
+    if wrapper_version.is_empty() {
+        return false;
+    }
+    if is_incompatible_wrapper(core_version, wrapper_version) {
+        Telemetry::incr_incompatible_wrappers();
+        log_warn(
+            "version handshake",
+            format!(
+                "The wrapper `{wrapper_name}` of version {wrapper_version} is incompatible with the core of version {core_version}, so requests might fail or be misinterpreted. Use the core that was released with the wrapper."
+            ),
+        );
+        true
+    } else {
+        log_debug(
+            "version handshake",
+            format!("Wrapper `{wrapper_name}` of version {wrapper_version}, core of version {core_version}"),
+        );
+        false
+    }
+}
+
+/// Creates the client, and replies to the connection request. If the request has readiness checks, the reply is their
+/// report, and no client is returned if it couldn't connect or the checks ask to disconnect.
 async fn create_client(
     writer: &Rc<Writer>,
     request: ConnectionRequest,
     push_tx: Option<mpsc::UnboundedSender<PushInfo>>,
-) -> Result<Client, ClientCreationError> {
+) -> Result<Option<Client>, ClientCreationError> {
+    check_wrapper_version(&request, CORE_VERSION);
+    if request.readiness_checks.is_some() {
+        let (report, client) = Client::validate(request.into(), push_tx).await;
+        let mut response = Response::new();
+        response.callback_idx = 0;
+        let reference = Box::leak(Box::new(report.to_value()));
+        let raw_pointer = from_mut(reference);
+        response.value = Some(response::response::Value::RespPointer(raw_pointer as u64));
+        response.core_version = Some(CORE_VERSION.into());
+        write_to_writer(response, writer).await?;
+        return Ok(client);
+    }
     let client = match Client::new(request.into(), push_tx).await {
         Ok(client) => client,
         Err(err) => return Err(ClientCreationError::ConnectionError(err)),
     };
-    write_result(Ok(Value::Okay), 0, writer).await?;
-    Ok(client)
+    let mut response = Response::new();
+    response.callback_idx = 0;
+    response.value = Some(response::response::Value::ConstantResponse(
+        response::ConstantResponse::OK.into(),
+    ));
+    response.core_version = Some(CORE_VERSION.into());
+    write_to_writer(response, writer).await?;
+    Ok(Some(client))
 }
 
 async fn wait_for_connection_configuration_and_create_client(
     client_listener: &mut UnixStreamListener,
     writer: &Rc<Writer>,
     push_tx: Option<mpsc::UnboundedSender<PushInfo>>,
-) -> Result<Client, ClientCreationError> {
+) -> Result<Option<Client>, ClientCreationError> {
     // Wait for the server's address
     match client_listener.next_values::<ConnectionRequest>().await {
         Closed(reason) => Err(ClientCreationError::SocketListenerClosed(reason)),
@@ -622,7 +835,28 @@ async fn read_values_loop(
     }
 }
 
-async fn push_manager_loop(mut push_rx: mpsc::UnboundedReceiver<PushInfo>, writer: Rc<Writer>) {
+async fn write_push_notification(push_msg: PushInfo, writer: &Rc<Writer>) {
+    let mut response = Response::new();
+    response.callback_idx = 0; // callback_idx is not used with push notifications
+    response.is_push = true;
+    response.value = {
+        let push_val = Value::Push {
+            kind: (push_msg.kind),
+            data: (push_msg.data),
+        };
+        let reference = Box::leak(Box::new(push_val));
+        let raw_pointer = from_mut(reference);
+        Some(response::response::Value::RespPointer(raw_pointer as u64))
+    };
+
+    _ = write_to_writer(response, writer).await;
+}
+
+async fn push_manager_loop(
+    push_rx: &mut mpsc::UnboundedReceiver<PushInfo>,
+    pubsub_delivery: &mut PubSubDelivery,
+    writer: Rc<Writer>,
+) {
     loop {
         let result = push_rx.recv().await;
         match result {
@@ -632,25 +866,46 @@ async fn push_manager_loop(mut push_rx: mpsc::UnboundedReceiver<PushInfo>, write
             }
             Some(push_msg) => {
                 log_debug("push manager loop", format!("got PushInfo: {:?}", push_msg));
-                let mut response = Response::new();
-                response.callback_idx = 0; // callback_idx is not used with push notifications
-                response.is_push = true;
-                response.value = {
-                    let push_val = Value::Push {
-                        kind: (push_msg.kind),
-                        data: (push_msg.data),
-                    };
-                    let reference = Box::leak(Box::new(push_val));
-                    let raw_pointer = from_mut(reference);
-                    Some(response::response::Value::RespPointer(raw_pointer as u64))
-                };
-
-                _ = write_to_writer(response, &writer).await;
+                for push_msg in pubsub_delivery.process(push_msg) {
+                    write_push_notification(push_msg, &writer).await;
+                }
             }
         }
     }
 }
 
+/// Runs `push_manager_loop`, and restarts it if it panics. Each panic is reported to the wrapper as a `FatalClientError` push notification.
+/// The delivery state of the subscriptions is kept across restarts, so that gaps are still detected after a restart.
+async fn supervised_push_manager_loop(
+    push_rx: mpsc::UnboundedReceiver<PushInfo>,
+    writer: Rc<Writer>,
+) {
+    let push_rx = Rc::new(Mutex::new(push_rx));
+    let pubsub_delivery = Rc::new(Mutex::new(PubSubDelivery::default()));
+    let loop_writer = writer.clone();
+    redis::run_supervised(
+        "push manager",
+        move || {
+            let push_rx = push_rx.clone();
+            let pubsub_delivery = pubsub_delivery.clone();
+            let writer = loop_writer.clone();
+            async move {
+                push_manager_loop(
+                    &mut *push_rx.lock().await,
+                    &mut *pubsub_delivery.lock().await,
+                    writer,
+                )
+                .await
+            }
+        },
+        move |push_info| {
+            let writer = writer.clone();
+            async move { write_push_notification(push_info, &writer).await }
+        },
+    )
+    .await
+}
+
 async fn listen_on_client_stream(socket: UnixStream) {
     let socket = Rc::new(socket);
     // Spawn a new task to listen on this client's stream
@@ -671,7 +926,14 @@ async fn listen_on_client_stream(socket: UnixStream) {
         Some(push_tx),
     );
     let client = match client_creation.await {
-        Ok(conn) => conn,
+        Ok(Some(conn)) => conn,
+        Ok(None) => {
+            log_debug(
+                "client creation",
+                "client closed after its readiness checks were reported.",
+            );
+            return;
+        }
         Err(ClientCreationError::SocketListenerClosed(ClosingReason::ReadSocketClosed)) => {
             // This isn't an error - it can happen when a new wrapper-client creates a connection in order to check whether something already listens on the socket.
             log_debug(
@@ -721,7 +983,7 @@ async fn listen_on_client_stream(socket: UnixStream) {
                     log_trace("client closing", "writer closed");
                 }
             },
-            _ = push_manager_loop(push_rx, writer.clone()) => {
+            _ = supervised_push_manager_loop(push_rx, writer.clone()) => {
                 log_trace("client closing", "push manager closed");
             }
     }
@@ -936,3 +1198,30 @@ where
 {
     start_socket_listener_internal(init_callback, None);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{check_wrapper_version, is_incompatible_wrapper};
+    use crate::connection_request::ConnectionRequest;
+
+    #[test]
+    fn test_wrappers_of_other_minor_versions_are_incompatible() {
+        assert!(!is_incompatible_wrapper("1.2.0", "1.2.3"));
+        assert!(!is_incompatible_wrapper("1.2.0", "v1.2.0-rc1"));
+        assert!(is_incompatible_wrapper("1.2.0", "1.3.0"));
+        assert!(is_incompatible_wrapper("2.0.0", "1.2.0"));
+        assert!(!is_incompatible_wrapper("unknown", "1.2.0"));
+        assert!(!is_incompatible_wrapper("1.2.0", "main"));
+    }
+
+    #[test]
+    fn test_check_wrapper_version_reports_incompatible_wrappers() {
+        let mut request = ConnectionRequest::new();
+        request.wrapper_name = "test".into();
+        assert!(!check_wrapper_version(&request, "1.2.0"));
+        request.wrapper_version = "1.2.5".into();
+        assert!(!check_wrapper_version(&request, "1.2.0"));
+        request.wrapper_version = "1.3.0".into();
+        assert!(check_wrapper_version(&request, "1.2.0"));
+    }
+}
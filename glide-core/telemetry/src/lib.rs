@@ -1,12 +1,196 @@
 use lazy_static::lazy_static;
 use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::RwLock as StdRwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
 mod open_telemetry;
 mod open_telemetry_exporter_file;
 
 pub use open_telemetry::{GlideOpenTelemetry, GlideSpan};
 pub use open_telemetry_exporter_file::SpanExporterFile;
 
+/// Bytes sent to and received from the server
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ByteCounters {
+    pub written: usize,
+    pub read: usize,
+}
+
+/// The byte counters of a command or a node, which are updated without a write lock
+#[derive(Default)]
+struct AtomicByteCounters {
+    written: AtomicUsize,
+    read: AtomicUsize,
+}
+
+impl AtomicByteCounters {
+    fn add(&self, written: usize, read: usize) {
+        self.written.fetch_add(written, Ordering::Relaxed);
+        self.read.fetch_add(read, Ordering::Relaxed);
+    }
+
+    fn load(&self) -> ByteCounters {
+        ByteCounters {
+            written: self.written.load(Ordering::Relaxed),
+            read: self.read.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Replies that were larger than the big reply threshold of their client
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct BigReplyCounters {
+    pub count: usize,
+    /// The size of the largest reply, in bytes
+    pub largest: usize,
+    /// The first key of the last big reply's command, if it had keys
+    pub last_key: Option<String>,
+}
+
+/// Events on a node whose last occurrence is recorded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeEvent {
+    /// A request to the node succeeded
+    Success,
+    /// A request to the node failed, either with an error response or a connection error
+    Error,
+    /// The connections to the node were refreshed after a disconnect
+    Reconnect,
+    /// The node was part of a refreshed topology
+    TopologyRefresh,
+    /// The node was found to have switched between primary and replica
+    RoleChange,
+}
+
+impl NodeEvent {
+    /// Returns `Success` or `Error`, according to the outcome of a request
+    pub fn for_request(succeeded: bool) -> Self {
+        if succeeded {
+            NodeEvent::Success
+        } else {
+            NodeEvent::Error
+        }
+    }
+}
+
+/// The last time that each event happened on a node, in milliseconds since the Unix epoch
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct NodeEventTimestamps {
+    pub last_success: Option<u64>,
+    pub last_error: Option<u64>,
+    pub last_reconnect: Option<u64>,
+    pub last_topology_refresh: Option<u64>,
+    pub last_role_change: Option<u64>,
+}
+
+/// The event timestamps of a node, which are updated without a write lock. 0 stands for an event that didn't happen.
+#[derive(Default)]
+struct AtomicNodeEventTimestamps([AtomicU64; 5]);
+
+impl AtomicNodeEventTimestamps {
+    fn set(&self, event: NodeEvent, timestamp: u64) {
+        let index = match event {
+            NodeEvent::Success => 0,
+            NodeEvent::Error => 1,
+            NodeEvent::Reconnect => 2,
+            NodeEvent::TopologyRefresh => 3,
+            NodeEvent::RoleChange => 4,
+        };
+        self.0[index].store(timestamp, Ordering::Relaxed);
+    }
+
+    fn load(&self) -> NodeEventTimestamps {
+        let get = |index: usize| Some(self.0[index].load(Ordering::Relaxed)).filter(|ts| *ts != 0);
+        NodeEventTimestamps {
+            last_success: get(0),
+            last_error: get(1),
+            last_reconnect: get(2),
+            last_topology_refresh: get(3),
+            last_role_change: get(4),
+        }
+    }
+}
+
+const SHARDS: usize = 16;
+
+/// A map of counters that are updated through shared references, so that recording takes a read lock of one shard,
+/// unless the key is recorded for the first time. The keys are spread over shards to reduce the contention on the
+/// write locks.
+struct ShardedCounters<V> {
+    shards: [StdRwLock<HashMap<String, V>>; SHARDS],
+}
+
+impl<V: Default> Default for ShardedCounters<V> {
+    fn default() -> Self {
+        Self {
+            shards: std::array::from_fn(|_| StdRwLock::default()),
+        }
+    }
+}
+
+impl<V: Default> ShardedCounters<V> {
+    fn shard(&self, key: &str) -> &StdRwLock<HashMap<String, V>> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        &self.shards[hasher.finish() as usize % SHARDS]
+    }
+
+    fn update(&self, key: &str, update: impl FnOnce(&V)) {
+        let shard = self.shard(key);
+        if let Some(counters) = shard.read().expect(MUTEX_READ_ERR).get(key) {
+            update(counters);
+            return;
+        }
+        update(
+            shard
+                .write()
+                .expect(MUTEX_WRITE_ERR)
+                .entry(key.to_string())
+                .or_default(),
+        );
+    }
+
+    fn snapshot<T>(&self, load: impl Fn(&V) -> T) -> HashMap<String, T> {
+        self.shards
+            .iter()
+            .flat_map(|shard| {
+                shard
+                    .read()
+                    .expect(MUTEX_READ_ERR)
+                    .iter()
+                    .map(|(key, counters)| (key.clone(), load(counters)))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn clear(&self) {
+        for shard in self.shards.iter() {
+            shard.write().expect(MUTEX_WRITE_ERR).clear();
+        }
+    }
+}
+
+/// The counters that are updated on every request, which are kept out of [`Telemetry`] so that requests don't take its
+/// write lock
+#[derive(Default)]
+struct RequestCounters {
+    /// Number of reads that were routed by weight to a canary, default and preferred node
+    weighted_reads: [AtomicUsize; 3],
+    /// Approximate bytes sent and received, segmented by command name
+    bytes_by_command: ShardedCounters<AtomicByteCounters>,
+    /// Approximate bytes sent and received, segmented by node address
+    bytes_by_node: ShardedCounters<AtomicByteCounters>,
+    /// The last time that each event happened, segmented by node address
+    node_events: ShardedCounters<AtomicNodeEventTimestamps>,
+}
+
+/// The weight classes of [`RequestCounters::weighted_reads`]
+const WEIGHT_CLASSES: [&str; 3] = ["canary", "default", "preferred"];
+
 #[derive(Default, Serialize)]
 #[allow(dead_code)]
 pub struct Telemetry {
@@ -14,10 +198,19 @@ pub struct Telemetry {
     total_connections: usize,
     /// Total number of GLIDE clients
     total_clients: usize,
+    /// Number of reads that a replica rejected with a MOVED redirect
+    replica_moved_reads: usize,
+    /// Replies above the big reply threshold, segmented by command name
+    big_replies: HashMap<String, BigReplyCounters>,
+    /// Number of clients that were created by a wrapper whose version is incompatible with the core's version
+    incompatible_wrappers: usize,
+    /// Number of connection attempts that were delayed by the process-wide connection rate limit
+    throttled_connection_attempts: usize,
 }
 
 lazy_static! {
     static ref TELEMETRY: StdRwLock<Telemetry> = StdRwLock::<Telemetry>::default();
+    static ref REQUEST_COUNTERS: RequestCounters = RequestCounters::default();
 }
 
 const MUTEX_WRITE_ERR: &str = "Failed to obtain write lock for mutex. Poisoned mutex";
@@ -66,8 +259,168 @@ impl Telemetry {
         TELEMETRY.read().expect(MUTEX_READ_ERR).total_clients
     }
 
+    /// Increment the number of reads routed by weight to a node of `weight_class` by 1
+    /// Return the number of reads routed to this class after the increment
+    pub fn incr_weighted_reads(weight_class: &'static str) -> usize {
+        let Some(index) = WEIGHT_CLASSES
+            .iter()
+            .position(|class| *class == weight_class)
+        else {
+            return 0;
+        };
+        REQUEST_COUNTERS.weighted_reads[index].fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Return the number of reads routed by weight, per weight class
+    pub fn weighted_reads() -> HashMap<&'static str, usize> {
+        WEIGHT_CLASSES
+            .iter()
+            .zip(REQUEST_COUNTERS.weighted_reads.iter())
+            .map(|(class, count)| (*class, count.load(Ordering::Relaxed)))
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+
+    /// Return the number of reads routed by weight as a JSON object, keyed by weight class.
+    /// Used by the wrappers, whose statistics are flat maps of strings
+    pub fn weighted_reads_json() -> String {
+        serde_json::json!(Self::weighted_reads()).to_string()
+    }
+
+    /// Increment the number of reads that a replica rejected with a MOVED redirect by 1
+    /// Return the number of rejected reads after the increment
+    pub fn incr_replica_moved_reads() -> usize {
+        let mut t = TELEMETRY.write().expect(MUTEX_WRITE_ERR);
+        t.replica_moved_reads = t.replica_moved_reads.saturating_add(1);
+        t.replica_moved_reads
+    }
+
+    /// Return the number of reads that a replica rejected with a MOVED redirect
+    pub fn replica_moved_reads() -> usize {
+        TELEMETRY.read().expect(MUTEX_READ_ERR).replica_moved_reads
+    }
+
+    /// Add `written` and `read` bytes to the counters of `command` and of `node`
+    pub fn record_bytes(command: &str, node: &str, written: usize, read: usize) {
+        REQUEST_COUNTERS
+            .bytes_by_command
+            .update(command, |counters| counters.add(written, read));
+        REQUEST_COUNTERS
+            .bytes_by_node
+            .update(node, |counters| counters.add(written, read));
+    }
+
+    /// Return the bytes sent and received, per command name
+    pub fn bytes_by_command() -> HashMap<String, ByteCounters> {
+        REQUEST_COUNTERS
+            .bytes_by_command
+            .snapshot(AtomicByteCounters::load)
+    }
+
+    /// Return the bytes sent and received, per node address
+    pub fn bytes_by_node() -> HashMap<String, ByteCounters> {
+        REQUEST_COUNTERS
+            .bytes_by_node
+            .snapshot(AtomicByteCounters::load)
+    }
+
+    /// Return the byte counters as a JSON object, with `by_command` and `by_node` fields.
+    /// Used by the wrappers, whose statistics are flat maps of strings
+    pub fn byte_counters_json() -> String {
+        serde_json::json!({
+            "by_command": Self::bytes_by_command(),
+            "by_node": Self::bytes_by_node(),
+        })
+        .to_string()
+    }
+
+    /// Record that `event` happened on `node` now
+    pub fn record_node_event(node: &str, event: NodeEvent) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or_default();
+        REQUEST_COUNTERS
+            .node_events
+            .update(node, |timestamps| timestamps.set(event, timestamp));
+    }
+
+    /// Return the last time that each event happened, per node address
+    pub fn node_events() -> HashMap<String, NodeEventTimestamps> {
+        REQUEST_COUNTERS
+            .node_events
+            .snapshot(AtomicNodeEventTimestamps::load)
+    }
+
+    /// Return the node event timestamps as a JSON object, keyed by node address.
+    /// Used by the wrappers, whose statistics are flat maps of strings
+    pub fn node_events_json() -> String {
+        serde_json::json!(Self::node_events()).to_string()
+    }
+
+    /// Record a reply of `size` bytes to `command`, which exceeded the big reply threshold
+    pub fn record_big_reply(command: &str, key: Option<&str>, size: usize) {
+        let mut t = TELEMETRY.write().expect(MUTEX_WRITE_ERR);
+        let counters = match t.big_replies.get_mut(command) {
+            Some(counters) => counters,
+            None => t.big_replies.entry(command.to_string()).or_default(),
+        };
+        counters.count = counters.count.saturating_add(1);
+        counters.largest = counters.largest.max(size);
+        counters.last_key = key.map(str::to_string);
+    }
+
+    /// Return the counters of big replies, per command name
+    pub fn big_replies() -> HashMap<String, BigReplyCounters> {
+        TELEMETRY.read().expect(MUTEX_READ_ERR).big_replies.clone()
+    }
+
+    /// Return the counters of big replies as a JSON object, keyed by command name.
+    /// Used by the wrappers, whose statistics are flat maps of strings
+    pub fn big_replies_json() -> String {
+        serde_json::json!(TELEMETRY.read().expect(MUTEX_READ_ERR).big_replies).to_string()
+    }
+
+    /// Increment the number of clients created by an incompatible wrapper by 1
+    /// Return the number of such clients after the increment
+    pub fn incr_incompatible_wrappers() -> usize {
+        let mut t = TELEMETRY.write().expect(MUTEX_WRITE_ERR);
+        t.incompatible_wrappers = t.incompatible_wrappers.saturating_add(1);
+        t.incompatible_wrappers
+    }
+
+    /// Return the number of clients created by an incompatible wrapper
+    pub fn incompatible_wrappers() -> usize {
+        TELEMETRY
+            .read()
+            .expect(MUTEX_READ_ERR)
+            .incompatible_wrappers
+    }
+
+    /// Increment the number of connection attempts delayed by the connection rate limit by 1
+    /// Return the number of such attempts after the increment
+    pub fn incr_throttled_connection_attempts() -> usize {
+        let mut t = TELEMETRY.write().expect(MUTEX_WRITE_ERR);
+        t.throttled_connection_attempts = t.throttled_connection_attempts.saturating_add(1);
+        t.throttled_connection_attempts
+    }
+
+    /// Return the number of connection attempts delayed by the connection rate limit
+    pub fn throttled_connection_attempts() -> usize {
+        TELEMETRY
+            .read()
+            .expect(MUTEX_READ_ERR)
+            .throttled_connection_attempts
+    }
+
     /// Reset the telemetry collected thus far
     pub fn reset() {
         *TELEMETRY.write().expect(MUTEX_WRITE_ERR) = Telemetry::default();
+        for count in REQUEST_COUNTERS.weighted_reads.iter() {
+            count.store(0, Ordering::Relaxed);
+        }
+        REQUEST_COUNTERS.bytes_by_command.clear();
+        REQUEST_COUNTERS.bytes_by_node.clear();
+        REQUEST_COUNTERS.node_events.clear();
     }
 }
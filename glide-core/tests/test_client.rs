@@ -26,13 +26,16 @@ pub(crate) mod shared_client_tests {
     use std::collections::HashMap;
 
     use super::*;
-    use glide_core::client::{Client, DEFAULT_RESPONSE_TIMEOUT};
+    use glide_core::client::{
+        AuditOutcome, Client, ConnectionError, ReadFrom, ReadinessChecks, DEFAULT_RESPONSE_TIMEOUT,
+    };
     use redis::{
         cluster_routing::{MultipleNodeRoutingInfo, RoutingInfo},
         FromRedisValue, InfoDict, RedisConnectionInfo, Value,
     };
     use rstest::rstest;
     use utilities::cluster::*;
+    use utilities::topology::{setup_topology, ACL_TEST_USER};
     use utilities::BackingServer;
     use utilities::*;
 
@@ -105,6 +108,161 @@ pub(crate) mod shared_client_tests {
         });
     }
 
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_send_command_without_reply(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+            let key = generate_random_string(6);
+            let mut set = redis::cmd("SET");
+            set.arg(&key).arg("value");
+            test_basics
+                .client
+                .send_command_without_reply(&set, None)
+                .await
+                .unwrap();
+            // The error of INCR on a string isn't replied, so it doesn't take the place of GET's reply.
+            let mut incr = redis::cmd("INCR");
+            incr.arg(&key);
+            test_basics
+                .client
+                .send_command_without_reply(&incr, None)
+                .await
+                .unwrap();
+
+            // Both commands were written to the key's primary before GET, so they were applied in order.
+            let value = send_get(&mut test_basics.client, &key).await.unwrap();
+            assert_eq!(value, Value::BulkString(b"value".to_vec()));
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_send_command_without_reply_rejects_multi_node_commands() {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                true,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+            let err = test_basics
+                .client
+                .send_command_without_reply(&redis::cmd("FLUSHALL"), None)
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), redis::ErrorKind::ClientError);
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_publish_to_channels(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+            let (addresses, cluster_mode) = if use_cluster {
+                (get_shared_cluster_addresses(false), ClusterMode::Enabled)
+            } else {
+                (
+                    vec![get_shared_server_address(false)],
+                    ClusterMode::Disabled,
+                )
+            };
+            let subscribed_channel = generate_random_string(10);
+            let other_channel = generate_random_string(10);
+            let mut connection_request = create_connection_request(
+                &addresses,
+                &TestConfiguration {
+                    cluster_mode,
+                    connection_info: Some(RedisConnectionInfo {
+                        protocol: redis::ProtocolVersion::RESP3,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            );
+            let mut channels = glide_core::connection_request::PubSubChannelsOrPatterns::new();
+            channels
+                .channels_or_patterns
+                .push(subscribed_channel.as_bytes().into());
+            let mut subscriptions = glide_core::connection_request::PubSubSubscriptions::new();
+            subscriptions
+                .channels_or_patterns_by_type
+                .insert(0, channels);
+            connection_request.pubsub_subscriptions =
+                protobuf::MessageField::from_option(Some(subscriptions));
+            let (push_sender, _push_receiver) = tokio::sync::mpsc::unbounded_channel();
+            let _subscriber = Client::new(connection_request.into(), Some(push_sender))
+                .await
+                .unwrap();
+
+            // The subscriptions of a cluster client are established in the background.
+            let mut counts = vec![];
+            for _ in 0..100 {
+                counts = test_basics
+                    .client
+                    .publish_to_channels(
+                        &[subscribed_channel.as_bytes(), other_channel.as_bytes()],
+                        b"message",
+                        false,
+                    )
+                    .await
+                    .unwrap();
+                if counts[0] > 0 {
+                    break;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            }
+            assert_eq!(counts, vec![1, 0]);
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_connect_with_tls_server_name(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let (addresses, cluster_mode) = if use_cluster {
+                (get_shared_cluster_addresses(true), ClusterMode::Enabled)
+            } else {
+                (vec![get_shared_server_address(true)], ClusterMode::Disabled)
+            };
+            let mut connection_request = create_connection_request(
+                &addresses,
+                &TestConfiguration {
+                    use_tls: true,
+                    cluster_mode,
+                    shared_server: true,
+                    ..Default::default()
+                },
+            );
+            // The test servers' certificates aren't verified, so any name is sent as SNI.
+            connection_request.tls_server_name = Some("valkey.glide.test".into());
+            let client = Client::new(connection_request.into(), None).await.unwrap();
+            let key = generate_random_string(6);
+            send_set_and_get(client, key).await;
+        });
+    }
+
     #[rstest]
     #[serial_test::serial]
     #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
@@ -560,6 +718,226 @@ pub(crate) mod shared_client_tests {
         });
     }
 
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(LONG_CLUSTER_TEST_TIMEOUT)]
+    fn test_client_reconnects_with_its_credentials_after_its_connections_are_killed(
+        #[values(false, true)] use_cluster: bool,
+        #[values(false, true)] use_tls: bool,
+        #[values(false, true)] use_acl: bool,
+    ) {
+        block_on_all(async {
+            let cluster_mode = if use_cluster {
+                ClusterMode::Enabled
+            } else {
+                ClusterMode::Disabled
+            };
+            let topology = setup_topology(cluster_mode, use_tls, use_acl).await;
+            let mut client = topology.client.clone();
+            let mut whoami = redis::cmd("ACL");
+            whoami.arg("WHOAMI");
+            let expected_user = if use_acl { ACL_TEST_USER } else { "default" };
+
+            kill_connection(&mut client).await;
+            send_set_and_get(client.clone(), generate_random_string(10)).await;
+            let user = client.send_command(&whoami, None).await.unwrap();
+            assert_eq!(
+                redis::from_owned_redis_value::<String>(user).unwrap(),
+                expected_user
+            );
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_audit_sink_receives_commands_and_context(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async move {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+            let sink = std::sync::Arc::new(RecordingSink::default());
+            test_basics.client.set_audit_sink(Some(sink.clone()));
+            test_basics.client.set_audit_context(Some("user-1"));
+
+            let key = generate_random_string(6);
+            let mut set = redis::cmd("SET");
+            set.arg(&key).arg("value");
+            test_basics.client.send_command(&set, None).await.unwrap();
+            let mut incr = redis::cmd("INCR");
+            incr.arg(&key);
+            let _ = test_basics.client.send_command(&incr, None).await;
+            let mut transaction = redis::pipe();
+            transaction.atomic();
+            transaction.cmd("GET").arg(&key);
+            transaction.cmd("MGET").arg(&key).arg(&key);
+            test_basics
+                .client
+                .send_transaction(&transaction, None)
+                .await
+                .unwrap();
+
+            assert_eq!(
+                *sink.events.lock().unwrap(),
+                vec![
+                    (
+                        "SET".to_string(),
+                        vec![key.clone().into_bytes()],
+                        Some("user-1".to_string()),
+                        AuditOutcome::Success
+                    ),
+                    (
+                        "INCR".to_string(),
+                        vec![key.clone().into_bytes()],
+                        Some("user-1".to_string()),
+                        AuditOutcome::Error(redis::ErrorKind::ResponseError)
+                    ),
+                    (
+                        "GET".to_string(),
+                        vec![key.clone().into_bytes()],
+                        Some("user-1".to_string()),
+                        AuditOutcome::Success
+                    ),
+                    (
+                        "MGET".to_string(),
+                        vec![key.clone().into_bytes(), key.into_bytes()],
+                        Some("user-1".to_string()),
+                        AuditOutcome::Success
+                    ),
+                ]
+            );
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_client_flags_are_set_on_all_connections(#[values(false, true)] use_cluster: bool) {
+        let mut client_info_cmd = redis::Cmd::new();
+        client_info_cmd.arg("CLIENT").arg("INFO");
+        block_on_all(async move {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    connection_info: Some(RedisConnectionInfo {
+                        no_evict: true,
+                        no_touch: true,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            let variant_res = test_basics
+                .client
+                .send_command(
+                    &client_info_cmd,
+                    Some(RoutingInfo::MultiNode((
+                        MultipleNodeRoutingInfo::AllNodes,
+                        None,
+                    ))),
+                )
+                .await
+                .unwrap();
+            let client_infos: Vec<String> = if use_cluster {
+                let client_infos: HashMap<String, String> =
+                    redis::from_owned_redis_value(variant_res).unwrap();
+                client_infos.into_values().collect()
+            } else {
+                vec![redis::from_owned_redis_value(variant_res).unwrap()]
+            };
+
+            for client_info in client_infos {
+                let flags = client_info
+                    .split_whitespace()
+                    .find_map(|field| field.strip_prefix("flags="))
+                    .unwrap();
+                assert!(flags.contains('e'), "{client_info}");
+                assert!(flags.contains('T'), "{client_info}");
+            }
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_parallel_mget_returns_values_in_key_order(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async move {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+            let keys: Vec<String> = (0..20).map(|_| generate_random_string(8)).collect();
+            // Every other key is left missing.
+            for key in keys.iter().step_by(2) {
+                let mut set = redis::cmd("SET");
+                set.arg(key).arg(format!("value-{key}"));
+                test_basics.client.send_command(&set, None).await.unwrap();
+            }
+
+            let key_refs: Vec<&[u8]> = keys.iter().map(|key| key.as_bytes()).collect();
+            let results = test_basics.client.parallel_mget(&key_refs, 3).await;
+
+            assert_eq!(results.len(), keys.len());
+            for (index, (key, result)) in keys.iter().zip(results).enumerate() {
+                let expected = if index % 2 == 0 {
+                    Value::BulkString(format!("value-{key}").into_bytes())
+                } else {
+                    Value::Nil
+                };
+                assert_eq!(result.unwrap(), expected);
+            }
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_node_events_are_recorded(#[values(false, true)] use_cluster: bool) {
+        Telemetry::reset();
+        block_on_all(async move {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+            let key = generate_random_string(6);
+            let mut set = redis::cmd("SET");
+            set.arg(&key).arg("value");
+            test_basics.client.send_command(&set, None).await.unwrap();
+            let mut incr = redis::cmd("INCR");
+            incr.arg(&key);
+            let _ = test_basics.client.send_command(&incr, None).await;
+
+            // Both commands were sent to the node that holds the key.
+            let node_events = Telemetry::node_events();
+            let key_node_events = node_events
+                .values()
+                .find(|events| events.last_error.is_some())
+                .unwrap();
+            assert!(key_node_events.last_success.unwrap() <= key_node_events.last_error.unwrap());
+            if use_cluster {
+                assert!(node_events
+                    .values()
+                    .any(|events| events.last_topology_refresh.is_some()));
+            }
+        });
+    }
+
     #[test]
     #[serial_test::serial]
     fn test_client_telemetry_standalone() {
@@ -696,4 +1074,950 @@ pub(crate) mod shared_client_tests {
             );
         });
     }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_prepared_transaction_can_be_sent_repeatedly(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    protocol: glide_core::connection_request::ProtocolVersion::RESP2,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            let key = format!("{{{}}}", generate_random_string(10));
+            let counter = format!("{key}-counter");
+            let mut pipeline = redis::pipe();
+            pipeline.atomic();
+            pipeline.hset(&key, "bar", "vaz");
+            pipeline.hgetall(&key);
+            pipeline.cmd("INCRBYFLOAT").arg(&counter).arg("0.5");
+            let transaction = test_basics.client.prepare_transaction(pipeline);
+
+            for (added_fields, count) in [(1, 0.5), (0, 1.0)] {
+                let result = test_basics
+                    .client
+                    .send_prepared_transaction(&transaction, None)
+                    .await;
+                assert_eq!(
+                    result,
+                    Ok(Value::Array(vec![
+                        Value::Int(added_fields),
+                        Value::Map(vec![(
+                            Value::BulkString(b"bar".to_vec()),
+                            Value::BulkString(b"vaz".to_vec()),
+                        )]),
+                        Value::Double(count),
+                    ]))
+                );
+            }
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_sample_keys_reports_memory_usage(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            for _ in 0..10 {
+                let mut set = redis::cmd("SET");
+                set.arg(generate_random_string(10))
+                    .arg(generate_random_string(100));
+                test_basics.client.send_command(&set, None).await.unwrap();
+            }
+
+            let sample = test_basics.client.sample_keys(5).await.unwrap();
+            assert!((1..=5).contains(&sample.keys.len()), "{sample:?}");
+            assert!(sample
+                .keys
+                .windows(2)
+                .all(|keys| keys[0].memory_usage >= keys[1].memory_usage));
+            assert!(sample.keys.iter().all(|key| key.memory_usage > 0));
+            assert_eq!(
+                sample.size_distribution.values().sum::<u64>(),
+                sample.keys.len() as u64
+            );
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_wait_for_batch_reports_each_written_shard(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            let mut pipeline = redis::pipe();
+            let mut read_only = redis::pipe();
+            for _ in 0..3 {
+                let key = generate_random_string(10);
+                pipeline.set(&key, "value");
+                read_only.get(&key);
+            }
+            for cmd in pipeline.cmd_iter() {
+                test_basics.client.send_command(cmd, None).await.unwrap();
+            }
+
+            let acks = test_basics
+                .client
+                .wait_for_batch(&pipeline, 0, std::time::Duration::from_millis(100))
+                .await
+                .unwrap();
+            if use_cluster {
+                assert!((1..=3).contains(&acks.len()), "{acks:?}");
+            } else {
+                assert_eq!(acks.len(), 1, "{acks:?}");
+            }
+            let acks = test_basics
+                .client
+                .wait_for_batch(&read_only, 0, std::time::Duration::from_millis(100))
+                .await
+                .unwrap();
+            assert!(acks.is_empty());
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_optimistic_transaction_is_retried_after_watched_key_changed(
+        #[values(false, true)] use_cluster: bool,
+    ) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            let key = generate_random_string(10);
+            let mut attempts = 0;
+            let result = test_basics
+                .client
+                .run_optimistic_transaction(&[key.as_bytes()], None, 3, |mut client| {
+                    attempts += 1;
+                    let is_first_attempt = attempts == 1;
+                    let key = key.clone();
+                    async move {
+                        if is_first_attempt {
+                            // Modify the watched key, so that the first EXEC is aborted.
+                            let mut set = redis::cmd("SET");
+                            set.arg(&key).arg("conflict");
+                            client.send_command(&set, None).await?;
+                        }
+                        let mut pipeline = redis::pipe();
+                        pipeline.atomic();
+                        pipeline.set(&key, "committed");
+                        Ok(pipeline)
+                    }
+                })
+                .await;
+            assert_eq!(result, Ok(Value::Array(vec![Value::Okay])));
+            assert_eq!(attempts, 2);
+
+            let mut get = redis::cmd("GET");
+            get.arg(&key);
+            let value = test_basics.client.send_command(&get, None).await.unwrap();
+            assert_eq!(value, Value::BulkString(b"committed".to_vec()));
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_optimistic_transaction_keys_stay_watched_during_other_transactions(
+        #[values(false, true)] use_cluster: bool,
+    ) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            let key = generate_random_string(10);
+            let mut attempts = 0;
+            let result = test_basics
+                .client
+                .run_optimistic_transaction(&[key.as_bytes()], None, 3, |mut client| {
+                    attempts += 1;
+                    let is_first_attempt = attempts == 1;
+                    let key = key.clone();
+                    async move {
+                        if is_first_attempt {
+                            // Another transaction of the client must not clear the watched key.
+                            let mut other_transaction = redis::pipe();
+                            other_transaction.atomic();
+                            other_transaction.set(format!("{{{key}}}other"), "value");
+                            client.send_transaction(&other_transaction, None).await?;
+                            let mut set = redis::cmd("SET");
+                            set.arg(&key).arg("conflict");
+                            client.send_command(&set, None).await?;
+                        }
+                        let mut pipeline = redis::pipe();
+                        pipeline.atomic();
+                        pipeline.set(&key, "committed");
+                        Ok(pipeline)
+                    }
+                })
+                .await;
+            assert_eq!(result, Ok(Value::Array(vec![Value::Okay])));
+            assert_eq!(attempts, 2);
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_delete_by_pattern_removes_only_matching_keys(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            let prefix = generate_random_string(10);
+            for index in 0..25 {
+                let mut set = redis::cmd("SET");
+                set.arg(format!("{prefix}:{index}")).arg("value");
+                test_basics.client.send_command(&set, None).await.unwrap();
+            }
+            let other_key = generate_random_string(10);
+            let mut set = redis::cmd("SET");
+            set.arg(&other_key).arg("value");
+            test_basics.client.send_command(&set, None).await.unwrap();
+
+            let deleted = test_basics
+                .client
+                .delete_by_pattern(
+                    format!("{prefix}:*").as_bytes(),
+                    glide_core::client::DeleteByPatternOptions {
+                        batch_size: 10,
+                        max_keys_per_second: Some(1000),
+                    },
+                )
+                .await
+                .unwrap();
+            assert_eq!(deleted.deleted, 25);
+            assert_eq!(deleted.progress.last(), Some(&25));
+            assert!(deleted.progress.windows(2).all(|pair| pair[0] <= pair[1]));
+
+            let mut exists = redis::cmd("EXISTS");
+            exists.arg(format!("{prefix}:0")).arg(&other_key);
+            let value = test_basics
+                .client
+                .send_command(&exists, None)
+                .await
+                .unwrap();
+            assert_eq!(value, Value::Int(1));
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_scan_page_resumes_until_finished(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            let key = generate_random_string(10);
+            let mut hset = redis::cmd("HSET");
+            hset.arg(&key);
+            for index in 0..25 {
+                hset.arg(format!("field{index}")).arg(index);
+            }
+            test_basics.client.send_command(&hset, None).await.unwrap();
+
+            let options = glide_core::client::ScanPageOptions {
+                count: 5,
+                max_items: 10,
+                ..Default::default()
+            };
+            let target = glide_core::client::ScanTarget::Hash(key.into_bytes());
+            let mut cursor = glide_core::client::ScanCursor::default();
+            let mut fields = std::collections::HashSet::new();
+            while !cursor.is_finished() {
+                let page = test_basics
+                    .client
+                    .scan_page(&target, &mut cursor, &options)
+                    .await
+                    .unwrap();
+                assert!(page.len() <= options.max_items);
+                for item in page {
+                    let (field, _value): (String, String) =
+                        redis::from_owned_redis_value(item).unwrap();
+                    fields.insert(field);
+                }
+            }
+            assert_eq!(fields.len(), 25);
+
+            let mut scan = redis::cmd("SCAN");
+            scan.arg(0).arg("COUNT").arg(0);
+            let err = test_basics
+                .client
+                .send_command(&scan, None)
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), redis::ErrorKind::ClientError);
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_export_and_import_keys(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            let prefix = generate_random_string(10);
+            for index in 0..15 {
+                let mut set = redis::cmd("SET");
+                set.arg(format!("{prefix}:{index}")).arg("value");
+                test_basics.client.send_command(&set, None).await.unwrap();
+            }
+            let pattern = format!("{prefix}:*");
+            let mut cursor = glide_core::client::ScanCursor::default();
+            let mut exported_keys = std::collections::HashSet::new();
+            while !cursor.is_finished() {
+                for exported_key in test_basics
+                    .client
+                    .export_keys(&mut cursor, pattern.as_bytes(), 10)
+                    .await
+                    .unwrap()
+                {
+                    assert_eq!(exported_key.key_type, "string");
+                    exported_keys.insert(exported_key.key);
+                }
+            }
+            assert_eq!(exported_keys.len(), 15);
+
+            // The pages of the socket API pass the cursor as a string.
+            let mut cursor_id = String::new();
+            let mut exported_keys = std::collections::HashSet::new();
+            while cursor_id != glide_core::client::FINISHED_SCAN_CURSOR {
+                let page = test_basics
+                    .client
+                    .export_keys_page(&cursor_id, pattern.as_bytes(), 10)
+                    .await
+                    .unwrap();
+                let (next_cursor, keys): (String, Vec<(Vec<u8>, String, i64, Vec<u8>)>) =
+                    redis::from_owned_redis_value(page).unwrap();
+                exported_keys.extend(keys.into_iter().map(|(key, ..)| key));
+                cursor_id = next_cursor;
+            }
+            assert_eq!(exported_keys.len(), 15);
+
+            let mut dump = redis::cmd("DUMP");
+            dump.arg(format!("{prefix}:0"));
+            let payload: Vec<u8> = redis::from_owned_redis_value(
+                test_basics.client.send_command(&dump, None).await.unwrap(),
+            )
+            .unwrap();
+            let imported_key = format!("{prefix}:imported");
+            let exported_key = glide_core::client::ExportedKey {
+                key: imported_key.clone().into_bytes(),
+                key_type: "string".to_string(),
+                ttl: Some(std::time::Duration::from_secs(100)),
+                payload,
+            };
+            let imported = test_basics
+                .client
+                .import_keys(&[exported_key.clone()], false)
+                .await
+                .unwrap();
+            assert_eq!(imported, 1);
+            // The key already exists, so it's only imported again when replacing.
+            assert!(test_basics
+                .client
+                .import_keys(&[exported_key.clone()], false)
+                .await
+                .is_err());
+            assert_eq!(
+                test_basics
+                    .client
+                    .import_keys(&[exported_key], true)
+                    .await
+                    .unwrap(),
+                1
+            );
+
+            let mut get = redis::cmd("GET");
+            get.arg(&imported_key);
+            let value = test_basics.client.send_command(&get, None).await.unwrap();
+            assert_eq!(value, Value::BulkString(b"value".to_vec()));
+            let mut pttl = redis::cmd("PTTL");
+            pttl.arg(&imported_key);
+            let ttl: i64 = redis::from_owned_redis_value(
+                test_basics.client.send_command(&pttl, None).await.unwrap(),
+            )
+            .unwrap();
+            assert!(ttl > 0 && ttl <= 100_000, "{ttl}");
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_old_values_are_bulk_strings_or_nil(
+        #[values(false, true)] use_cluster: bool,
+        #[values(
+            glide_core::connection_request::ProtocolVersion::RESP2,
+            glide_core::connection_request::ProtocolVersion::RESP3
+        )]
+        protocol: glide_core::connection_request::ProtocolVersion,
+    ) {
+        block_on_all(async {
+            let test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    protocol,
+                    ..Default::default()
+                },
+            )
+            .await;
+            let key = generate_random_string(10);
+            let client = test_basics.client.clone();
+            let send = |args: Vec<&str>| {
+                let cmd = command(&args);
+                let mut client = client.clone();
+                async move { client.send_command(&cmd, None).await.unwrap() }
+            };
+
+            // Missing keys are nil.
+            assert_eq!(send(vec!["GETDEL", &key]).await, Value::Nil);
+            assert_eq!(send(vec!["GETEX", &key, "PERSIST"]).await, Value::Nil);
+            assert_eq!(send(vec!["SET", &key, "", "GET"]).await, Value::Nil);
+
+            // Empty values are empty bulk strings.
+            assert_eq!(
+                send(vec!["GETEX", &key, "EX", "100"]).await,
+                Value::BulkString(vec![])
+            );
+            assert_eq!(
+                send(vec!["SET", &key, "value", "GET"]).await,
+                Value::BulkString(vec![])
+            );
+            assert_eq!(
+                send(vec!["GETDEL", &key]).await,
+                Value::BulkString(b"value".to_vec())
+            );
+            assert_eq!(send(vec!["GETDEL", &key]).await, Value::Nil);
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_acl_users_are_parsed(
+        #[values(false, true)] use_cluster: bool,
+        #[values(
+            glide_core::connection_request::ProtocolVersion::RESP2,
+            glide_core::connection_request::ProtocolVersion::RESP3
+        )]
+        protocol: glide_core::connection_request::ProtocolVersion,
+    ) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    protocol,
+                    ..Default::default()
+                },
+            )
+            .await;
+            let user = generate_random_string(10);
+            let mut setuser = redis::cmd("ACL");
+            setuser
+                .arg("SETUSER")
+                .arg(&user)
+                .arg("on")
+                .arg(">password")
+                .arg("~cache:*")
+                .arg("-@all")
+                .arg("+get");
+            test_basics
+                .client
+                .send_command(&setuser, None)
+                .await
+                .unwrap();
+
+            let bulk = |value: &str| Value::BulkString(value.as_bytes().to_vec());
+            let field = |fields: &Value, name: &str| {
+                let Value::Map(fields) = fields else {
+                    panic!("Expected a map, got {fields:?}");
+                };
+                fields
+                    .iter()
+                    .find(|(field, _)| *field == bulk(name))
+                    .map(|(_, value)| value.clone())
+                    .unwrap()
+            };
+            // In cluster mode, the users are read from all the nodes only when they are routed to all the nodes, and
+            // the replies of the nodes are returned by their addresses.
+            let all_nodes = use_cluster
+                .then(|| RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllNodes, None)));
+            let replies = |reply: Value| match (use_cluster, reply) {
+                (true, Value::Map(replies)) => {
+                    replies.into_iter().map(|(_, reply)| reply).collect()
+                }
+                (false, reply) => vec![reply],
+                (_, reply) => panic!("Unexpected reply {reply:?}"),
+            };
+
+            let mut getuser = redis::cmd("ACL");
+            getuser.arg("GETUSER").arg(&user);
+            let fields = test_basics
+                .client
+                .send_command(&getuser, None)
+                .await
+                .unwrap();
+            assert_eq!(field(&fields, "flags"), Value::Array(vec![bulk("on")]));
+            let reply = test_basics
+                .client
+                .send_command(&getuser, all_nodes.clone())
+                .await
+                .unwrap();
+            let users: Vec<Value> = replies(reply);
+            assert!(!users.is_empty());
+            for fields in users {
+                assert_eq!(field(&fields, "flags"), Value::Array(vec![bulk("on")]));
+                assert_eq!(field(&fields, "keys"), bulk("~cache:*"));
+                assert_eq!(field(&fields, "commands"), bulk("-@all +get"));
+            }
+
+            let mut list = redis::cmd("ACL");
+            list.arg("LIST");
+            let reply = test_basics
+                .client
+                .send_command(&list, all_nodes.clone())
+                .await
+                .unwrap();
+            for users in replies(reply) {
+                let Value::Array(users) = users else {
+                    panic!("Expected an array, got {users:?}");
+                };
+                let fields = users
+                    .into_iter()
+                    .find(|fields| field(fields, "name") == bulk(&user))
+                    .unwrap();
+                assert_eq!(field(&fields, "keys"), bulk("~cache:*"));
+                assert_eq!(field(&fields, "commands"), bulk("-@all +get"));
+                assert_eq!(field(&fields, "selectors"), Value::Array(vec![]));
+            }
+
+            let mut deluser = redis::cmd("ACL");
+            deluser.arg("DELUSER").arg(&user);
+            test_basics
+                .client
+                .send_command(&deluser, None)
+                .await
+                .unwrap();
+            let reply = test_basics
+                .client
+                .send_command(&getuser, all_nodes)
+                .await
+                .unwrap();
+            assert!(replies(reply).iter().all(|user| *user == Value::Nil));
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_clients_beyond_the_connection_limit_are_rejected(
+        #[values(false, true)] use_cluster: bool,
+    ) {
+        block_on_all(async {
+            let (addresses, cluster_mode) = if use_cluster {
+                (get_shared_cluster_addresses(false), ClusterMode::Enabled)
+            } else {
+                (
+                    vec![get_shared_server_address(false)],
+                    ClusterMode::Disabled,
+                )
+            };
+            let connection_request = create_connection_request(
+                &addresses,
+                &TestConfiguration {
+                    cluster_mode,
+                    shared_server: true,
+                    ..Default::default()
+                },
+            );
+            let create = || Client::new(connection_request.clone().into(), None);
+            let client = create().await.unwrap();
+            // The budget is exhausted by the first client.
+            glide_core::connection_budget::set_connection_limit(Some(
+                glide_core::connection_budget::connections_in_use(),
+            ));
+
+            let err = create().await.err().unwrap();
+            assert!(
+                matches!(err, ConnectionError::ConnectionBudgetExhausted(_)),
+                "{err:?}"
+            );
+            assert!(err.to_string().contains("connection budget"), "{err}");
+
+            drop(client);
+            let client = create().await;
+            glide_core::connection_budget::set_connection_limit(None);
+            let key = generate_random_string(6);
+            send_set_and_get(client.unwrap(), key).await;
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_idle_client_closes_its_connections_until_the_next_command(
+        #[values(false, true)] use_cluster: bool,
+    ) {
+        block_on_all(async {
+            let (addresses, cluster_mode) = if use_cluster {
+                (get_shared_cluster_addresses(false), ClusterMode::Enabled)
+            } else {
+                (
+                    vec![get_shared_server_address(false)],
+                    ClusterMode::Disabled,
+                )
+            };
+            let client_name = format!("idle_{}", generate_random_string(6));
+            let connection_request = create_connection_request(
+                &addresses,
+                &TestConfiguration {
+                    cluster_mode,
+                    shared_server: true,
+                    ..Default::default()
+                },
+            );
+            let mut observer = Client::new(connection_request.clone().into(), None)
+                .await
+                .unwrap();
+            let mut idle_request = connection_request;
+            idle_request.client_name = client_name.as_str().into();
+            idle_request.idle_timeout = 200;
+            let mut client = Client::new(idle_request.into(), None).await.unwrap();
+
+            let mut client_list = redis::cmd("CLIENT");
+            client_list.arg("LIST");
+            let count_connections = || {
+                let client_list = client_list.clone();
+                let name = format!("name={client_name} ");
+                let mut observer = observer.clone();
+                async move {
+                    let reply = observer
+                        .send_command(
+                            &client_list,
+                            Some(RoutingInfo::MultiNode((
+                                MultipleNodeRoutingInfo::AllNodes,
+                                None,
+                            ))),
+                        )
+                        .await
+                        .unwrap();
+                    let lists: Vec<String> = if use_cluster {
+                        let lists: HashMap<String, String> =
+                            redis::from_owned_redis_value(reply).unwrap();
+                        lists.into_values().collect()
+                    } else {
+                        vec![redis::from_owned_redis_value(reply).unwrap()]
+                    };
+                    lists
+                        .iter()
+                        .map(|list| list.matches(name.as_str()).count())
+                        .sum::<usize>()
+                }
+            };
+
+            let key = generate_random_string(6);
+            let mut set = redis::cmd("SET");
+            set.arg(&key).arg("value");
+            client.send_command(&set, None).await.unwrap();
+            assert!(count_connections().await > 0);
+
+            let mut connections = count_connections().await;
+            for _ in 0..50 {
+                if connections == 0 {
+                    break;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                connections = count_connections().await;
+            }
+            assert_eq!(connections, 0);
+
+            let mut get = redis::cmd("GET");
+            get.arg(&key);
+            let value = client.send_command(&get, None).await.unwrap();
+            assert_eq!(value, Value::BulkString(b"value".to_vec()));
+            assert!(count_connections().await > 0);
+
+            let mut del = redis::cmd("DEL");
+            del.arg(&key);
+            observer.send_command(&del, None).await.unwrap();
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_validate_reports_each_check(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let (addresses, cluster_mode) = if use_cluster {
+                (get_shared_cluster_addresses(false), ClusterMode::Enabled)
+            } else {
+                (
+                    vec![get_shared_server_address(false)],
+                    ClusterMode::Disabled,
+                )
+            };
+            let mut request: glide_core::client::ConnectionRequest = create_connection_request(
+                &addresses,
+                &TestConfiguration {
+                    cluster_mode,
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .into();
+            request.read_from = Some(ReadFrom::PreferReplica);
+            request.readiness_checks = Some(ReadinessChecks {
+                required_modules: vec!["not_a_module".to_string()],
+                required_commands: vec!["SET".to_string()],
+                disconnect: false,
+            });
+
+            let (report, client) = Client::validate(request, None).await;
+            assert!(!report.is_ready());
+            let names: Vec<&str> = report
+                .checks
+                .iter()
+                .map(|check| check.name.as_str())
+                .collect();
+            assert_eq!(
+                names,
+                vec![
+                    "configuration",
+                    "connection",
+                    "read from replicas",
+                    "module not_a_module",
+                    "command SET",
+                ]
+            );
+            let passed = |name: &str| {
+                report
+                    .checks
+                    .iter()
+                    .find(|check| check.name == name)
+                    .unwrap()
+                    .passed
+            };
+            assert!(passed("connection"));
+            assert!(!passed("module not_a_module"));
+            assert!(passed("command SET"));
+            if !use_cluster {
+                // The only address is the primary's.
+                assert!(!passed("read from replicas"));
+            }
+
+            // The client is kept, since the checks didn't ask to disconnect.
+            let key = generate_random_string(6);
+            send_set_and_get(client.unwrap(), key).await;
+
+            let mut request: glide_core::client::ConnectionRequest = create_connection_request(
+                &addresses,
+                &TestConfiguration {
+                    cluster_mode,
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .into();
+            request.addresses.clear();
+            let (report, client) = Client::validate(request, None).await;
+            assert!(client.is_none());
+            assert_eq!(report.checks.len(), 1);
+            assert_eq!(report.checks[0].name, "configuration");
+            assert!(!report.checks[0].passed);
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_memory_cap_is_set_by_the_connection_request(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let (addresses, cluster_mode) = if use_cluster {
+                (get_shared_cluster_addresses(false), ClusterMode::Enabled)
+            } else {
+                (
+                    vec![get_shared_server_address(false)],
+                    ClusterMode::Disabled,
+                )
+            };
+            let mut connection_request = create_connection_request(
+                &addresses,
+                &TestConfiguration {
+                    cluster_mode,
+                    shared_server: true,
+                    ..Default::default()
+                },
+            );
+            connection_request.memory_cap = 64 * 1024 * 1024;
+            let mut client = Client::new(connection_request.clone().into(), None)
+                .await
+                .unwrap();
+            assert_eq!(
+                glide_core::memory_budget::memory_cap(),
+                Some(64 * 1024 * 1024)
+            );
+            let key = generate_random_string(10);
+            assert_eq!(send_get(&mut client, &key).await, Ok(Value::Nil));
+
+            connection_request.memory_cap = 32 * 1024 * 1024;
+            assert!(matches!(
+                Client::new(connection_request.into(), None).await,
+                Err(ConnectionError::InvalidConfiguration(_))
+            ));
+            glide_core::memory_budget::set_memory_cap(None);
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_connection_limit_is_set_by_the_connection_request(
+        #[values(false, true)] use_cluster: bool,
+    ) {
+        block_on_all(async {
+            let (address, cluster_mode) = if use_cluster {
+                (
+                    get_shared_cluster_addresses(false)[0].clone(),
+                    ClusterMode::Enabled,
+                )
+            } else {
+                (get_shared_server_address(false), ClusterMode::Disabled)
+            };
+            let mut connection_request = create_connection_request(
+                &[address],
+                &TestConfiguration {
+                    cluster_mode,
+                    shared_server: true,
+                    ..Default::default()
+                },
+            );
+            // The seed connections fit, but a cluster's topology has more nodes than the seed.
+            let in_use = glide_core::connection_budget::connections_in_use();
+            let limit = in_use + if use_cluster { 2 } else { 0 };
+            connection_request.connection_limit = limit as u32;
+
+            let err = Client::new(connection_request.into(), None)
+                .await
+                .err()
+                .unwrap();
+            assert_eq!(
+                glide_core::connection_budget::connection_limit(),
+                Some(limit)
+            );
+            assert!(
+                matches!(err, ConnectionError::ConnectionBudgetExhausted(_)),
+                "{err:?}"
+            );
+            assert_eq!(glide_core::connection_budget::connections_in_use(), in_use);
+            glide_core::connection_budget::set_connection_limit(None);
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_write_durability_without_a_timeout_waits_for_the_request_timeout(
+        #[values(false, true)] use_cluster: bool,
+    ) {
+        block_on_all(async {
+            let (addresses, cluster_mode) = if use_cluster {
+                (get_shared_cluster_addresses(false), ClusterMode::Enabled)
+            } else {
+                (
+                    vec![get_shared_server_address(false)],
+                    ClusterMode::Disabled,
+                )
+            };
+            let mut connection_request = create_connection_request(
+                &addresses,
+                &TestConfiguration {
+                    cluster_mode,
+                    shared_server: true,
+                    request_timeout: Some(500),
+                    ..Default::default()
+                },
+            );
+            // More replicas than the servers have, so WAIT only returns once its timeout passed.
+            let mut write_durability = glide_core::connection_request::WriteDurability::new();
+            write_durability.num_replicas = 10;
+            write_durability.timeout = 0;
+            connection_request.write_durability = protobuf::MessageField::some(write_durability);
+            let mut client = Client::new(connection_request.into(), None).await.unwrap();
+
+            let mut set = redis::cmd("SET");
+            set.arg(generate_random_string(10)).arg("value");
+            let start = std::time::Instant::now();
+            let (value, replication_count) = client
+                .send_command_with_write_durability(&set, None)
+                .await
+                .unwrap();
+            assert_eq!(value, Value::Okay);
+            assert!(
+                replication_count.is_some_and(|count| count < 10),
+                "{replication_count:?}"
+            );
+            assert!(start.elapsed() < std::time::Duration::from_secs(3));
+        });
+    }
 }
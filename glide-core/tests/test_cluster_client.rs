@@ -8,16 +8,17 @@ mod cluster_client_tests {
 
     use super::*;
     use cluster::{setup_cluster_with_replicas, LONG_CLUSTER_TEST_TIMEOUT};
-    use glide_core::client::Client;
+    use glide_core::client::{Client, KeylessRouting, TopologySource};
     use glide_core::connection_request::{
         self, PubSubChannelsOrPatterns, PubSubSubscriptions, ReadFrom,
     };
     use redis::cluster_routing::{
         MultipleNodeRoutingInfo, Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr,
     };
-    use redis::InfoDict;
+    use redis::{InfoDict, Value};
     use rstest::rstest;
     use utilities::cluster::{setup_test_basics_internal, SHORT_CLUSTER_TEST_TIMEOUT};
+    use utilities::topology::setup_topology;
     use utilities::*;
     use versions::Versioning;
 
@@ -123,6 +124,270 @@ mod cluster_client_tests {
         });
     }
 
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_send_keyless_command_with_set_routing() {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics_internal(TestConfiguration {
+                cluster_mode: ClusterMode::Enabled,
+                shared_server: true,
+                ..Default::default()
+            })
+            .await;
+
+            let mut cmd = redis::cmd("ECHO");
+            cmd.arg("hello");
+            test_basics
+                .client
+                .set_keyless_routing("echo", Some(KeylessRouting::AllPrimaries(None)))
+                .unwrap();
+            let replies = test_basics.client.send_command(&cmd, None).await.unwrap();
+            let replies =
+                redis::from_owned_redis_value::<HashMap<String, String>>(replies).unwrap();
+            assert_eq!(replies.len(), 3);
+            assert!(replies.values().all(|reply| reply == "hello"));
+
+            test_basics
+                .client
+                .set_keyless_routing("echo", None)
+                .unwrap();
+            let reply = test_basics.client.send_command(&cmd, None).await.unwrap();
+            assert_eq!(reply, redis::Value::BulkString(b"hello".to_vec()));
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_script_keys_must_share_a_slot() {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics_internal(TestConfiguration {
+                cluster_mode: ClusterMode::Enabled,
+                shared_server: true,
+                ..Default::default()
+            })
+            .await;
+
+            let mut eval = redis::cmd("EVAL");
+            eval.arg("return KEYS[2]")
+                .arg(2)
+                .arg("{user}:a")
+                .arg("{user}:b")
+                .arg("not-a-key");
+            let reply = test_basics.client.send_command(&eval, None).await.unwrap();
+            assert_eq!(reply, redis::Value::BulkString(b"{user}:b".to_vec()));
+
+            let mut eval = redis::cmd("EVAL");
+            eval.arg("return 1").arg(2).arg("foo").arg("bar");
+            let err = test_basics
+                .client
+                .send_command(&eval, None)
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), redis::ErrorKind::CrossSlot);
+            assert!(err.to_string().contains("`foo`"), "{err}");
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_topology_source_matches_engine_version() {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics_internal(TestConfiguration {
+                cluster_mode: ClusterMode::Enabled,
+                shared_server: true,
+                ..Default::default()
+            })
+            .await;
+
+            let info = test_basics
+                .client
+                .send_command(
+                    &redis::cmd("INFO"),
+                    Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)),
+                )
+                .await
+                .unwrap();
+            let info_dict: InfoDict = redis::from_owned_redis_value(info).unwrap();
+            let version = info_dict.get::<String>("redis_version").unwrap();
+            let supports_shards =
+                Versioning::new(version).unwrap() >= Versioning::new("7.0").unwrap();
+
+            let source = test_basics.client.topology_source().await.unwrap();
+            let expected = if supports_shards {
+                TopologySource::ClusterShards
+            } else {
+                TopologySource::ClusterSlots
+            };
+            assert_eq!(source, Some(expected));
+        });
+    }
+
+    #[rstest]
+    #[timeout(LONG_CLUSTER_TEST_TIMEOUT)]
+    fn test_pinned_slots_override_the_discovered_owner() {
+        block_on_all(async {
+            let cluster = cluster::setup_default_cluster().await;
+            let addresses = cluster.get_server_addresses();
+            let mut client = cluster::setup_default_client(&cluster).await;
+
+            let mut myid = redis::cmd("CLUSTER");
+            myid.arg("MYID");
+            let slot_0 = RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+                0,
+                SlotAddr::Master,
+            )));
+            let owner_id = client
+                .send_command(&myid, Some(slot_0.clone()))
+                .await
+                .unwrap();
+            let mut other_node = None;
+            for address in addresses.iter().map(get_address_info) {
+                let by_address = RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+                    host: address.host.to_string(),
+                    port: address.port as u16,
+                });
+                let node_id = client.send_command(&myid, Some(by_address)).await.unwrap();
+                if node_id != owner_id {
+                    other_node = Some((address, node_id));
+                    break;
+                }
+            }
+            let (other_address, other_id) = other_node.unwrap();
+
+            let mut connection_request = create_connection_request(
+                &addresses,
+                &TestConfiguration {
+                    cluster_mode: ClusterMode::Enabled,
+                    ..Default::default()
+                },
+            );
+            let mut pinned_slot = connection_request::PinnedSlotRange::new();
+            pinned_slot.start = 0;
+            pinned_slot.end = 0;
+            pinned_slot.address = protobuf::MessageField::some(other_address);
+            connection_request.pinned_slots.push(pinned_slot);
+
+            // The pins are ignored unless discovery is overridden.
+            let mut unpinned_client = Client::new(connection_request.clone().into(), None)
+                .await
+                .unwrap();
+            let node_id = unpinned_client
+                .send_command(&myid, Some(slot_0.clone()))
+                .await
+                .unwrap();
+            assert_eq!(node_id, owner_id);
+
+            connection_request.override_slot_discovery = true;
+            let mut pinned_client = Client::new(connection_request.into(), None).await.unwrap();
+            let node_id = pinned_client
+                .send_command(&myid, Some(slot_0))
+                .await
+                .unwrap();
+            assert_eq!(node_id, other_id);
+        });
+    }
+
+    fn slot_primary(slot: u16) -> Option<RoutingInfo> {
+        Some(RoutingInfo::SingleNode(
+            SingleNodeRoutingInfo::SpecificNode(Route::new(slot, SlotAddr::Master)),
+        ))
+    }
+
+    #[rstest]
+    #[timeout(LONG_CLUSTER_TEST_TIMEOUT)]
+    fn test_requests_follow_a_slot_that_moved(
+        #[values(false, true)] use_tls: bool,
+        #[values(false, true)] use_acl: bool,
+    ) {
+        block_on_all(async {
+            let topology = setup_topology(ClusterMode::Enabled, use_tls, use_acl).await;
+            let cluster = topology.cluster().unwrap();
+            let mut client = topology.client.clone();
+            let key = generate_random_string(10);
+            let slot = redis::cluster_topology::get_slot(key.as_bytes());
+
+            let mut myid = redis::cmd("CLUSTER");
+            myid.arg("MYID");
+            let owner_id: String = redis::from_owned_redis_value(
+                client
+                    .send_command(&myid, slot_primary(slot))
+                    .await
+                    .unwrap(),
+            )
+            .unwrap();
+            let mut target = None;
+            for primary in cluster.get_primary_addresses() {
+                let id = cluster.node_id(&primary).await;
+                if id != owner_id {
+                    target = Some((primary, id));
+                    break;
+                }
+            }
+            let (target, target_id) = target.unwrap();
+            cluster.move_slot(slot, &target).await;
+
+            send_set_and_get(client.clone(), key).await;
+            let owner_id: String = redis::from_owned_redis_value(
+                client
+                    .send_command(&myid, slot_primary(slot))
+                    .await
+                    .unwrap(),
+            )
+            .unwrap();
+            assert_eq!(owner_id, target_id);
+        });
+    }
+
+    #[rstest]
+    #[timeout(LONG_CLUSTER_TEST_TIMEOUT)]
+    fn test_requests_follow_a_failover(
+        #[values(false, true)] use_tls: bool,
+        #[values(false, true)] use_acl: bool,
+    ) {
+        block_on_all(async {
+            let topology = setup_topology(ClusterMode::Enabled, use_tls, use_acl).await;
+            let cluster = topology.cluster().unwrap();
+            let mut client = topology.client.clone();
+            let key = generate_random_string(10);
+            let slot = redis::cluster_topology::get_slot(key.as_bytes());
+            let mut set = redis::cmd("SET");
+            set.arg(&key).arg("value");
+            client.send_command(&set, None).await.unwrap();
+
+            // The nodes of a TLS cluster only listen on their TLS port.
+            let port_parameter = if use_tls { "tls-port" } else { "port" };
+            let mut config_get_port = redis::cmd("CONFIG");
+            config_get_port.arg("GET").arg(port_parameter);
+            let config: HashMap<String, u16> = redis::from_owned_redis_value(
+                client
+                    .send_command(&config_get_port, slot_primary(slot))
+                    .await
+                    .unwrap(),
+            )
+            .unwrap();
+            let primary_port = config[port_parameter];
+            let primary = cluster
+                .get_primary_addresses()
+                .into_iter()
+                .find(|address| cluster::node_port(address) == primary_port)
+                .unwrap();
+            let replica = cluster.replica_of(&primary).await;
+            cluster.failover(&replica).await;
+
+            let value = send_get(&mut client, &key).await.unwrap();
+            assert_eq!(value, Value::BulkString(b"value".to_vec()));
+            let role: Vec<Value> = redis::from_owned_redis_value(
+                client
+                    .send_command(&redis::cmd("ROLE"), slot_primary(slot))
+                    .await
+                    .unwrap(),
+            )
+            .unwrap();
+            assert_eq!(role.first(), Some(&Value::BulkString(b"master".to_vec())));
+            client.send_command(&set, None).await.unwrap();
+        });
+    }
+
     #[rstest]
     #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
     fn test_send_routing_by_slot_to_primary() {
@@ -22,7 +22,9 @@ mod socket_listener {
 
     use super::*;
     use command_request::{CommandRequest, RequestType};
-    use glide_core::command_request::command::{Args, ArgsArray};
+    use glide_core::command_request::command::{
+        typed_arg, Args, ArgsArray, TypedArg, TypedArgsArray,
+    };
     use glide_core::command_request::{Command, Transaction};
     use glide_core::response::{response, ConstantResponse, Response};
     use glide_core::scripts_container::add_script;
@@ -373,7 +375,7 @@ mod socket_listener {
     ) {
         // Send the server address
         const CALLBACK_INDEX: u32 = 0;
-        let connection_request = create_connection_request(
+        let mut connection_request = create_connection_request(
             addresses,
             &TestConfiguration {
                 use_tls: use_tls.to_bool(),
@@ -382,21 +384,26 @@ mod socket_listener {
                 ..Default::default()
             },
         );
+        connection_request.wrapper_name = "test".into();
+        connection_request.wrapper_version = "0.0.0".into();
         let approx_message_length =
             APPROX_RESP_HEADER_LEN + connection_request.compute_size() as usize;
         let mut buffer = Vec::with_capacity(approx_message_length);
         write_message(&mut buffer, connection_request);
         let mut socket = socket.try_clone().unwrap();
         socket.write_all(&buffer).unwrap();
-        assert_ok_response(&mut buffer, &mut socket, CALLBACK_INDEX);
+        let response = assert_response(
+            &mut buffer,
+            Some(&mut socket),
+            CALLBACK_INDEX,
+            Some(Value::Okay),
+            ResponseType::Value,
+        );
+        assert!(response.core_version.is_some());
     }
 
-    fn setup_socket(
-        use_tls: Tls,
-        socket_path: Option<String>,
-        addresses: &[ConnectionAddr],
-        cluster_mode: ClusterMode,
-    ) -> UnixStream {
+    /// Starts the socket listener, and returns a socket that is connected to it, before a connection request was sent.
+    fn connect_to_socket_listener(socket_path: Option<String>) -> UnixStream {
         let socket_listener_state: Arc<ManualResetEvent> =
             Arc::new(ManualResetEvent::new(EventState::Unset));
         let cloned_state = socket_listener_state.clone();
@@ -414,7 +421,16 @@ mod socket_listener {
         socket_listener_state.wait();
         let path = path_arc.lock().unwrap();
         let path = path.as_ref().expect("Didn't get any socket path");
-        let socket = std::os::unix::net::UnixStream::connect(path).unwrap();
+        std::os::unix::net::UnixStream::connect(path).unwrap()
+    }
+
+    fn setup_socket(
+        use_tls: Tls,
+        socket_path: Option<String>,
+        addresses: &[ConnectionAddr],
+        cluster_mode: ClusterMode,
+    ) -> UnixStream {
+        let socket = connect_to_socket_listener(socket_path);
         connect_to_redis(addresses, &socket, use_tls, cluster_mode);
         socket
     }
@@ -821,6 +837,68 @@ mod socket_listener {
         assert_null_response(&mut buffer, &mut test_basics.socket, CALLBACK_INDEX);
     }
 
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_socket_typed_args_are_formatted_canonically() {
+        const CALLBACK_INDEX: u32 = 99;
+        let key = generate_random_string(KEY_LENGTH);
+        let mut expected_command = Cmd::new();
+        expected_command
+            .arg("ZADD")
+            .arg(key.clone())
+            .arg("+inf")
+            .arg("member1")
+            .arg("1000000000000000000000")
+            .arg("member2")
+            .arg("-7")
+            .arg("member3");
+        let mut test_basics = setup_mocked_test_basics(None);
+        test_basics
+            .server_mock
+            .add_response(&expected_command, ":3\r\n".to_string());
+
+        let bytes_arg = |bytes: &str| {
+            let mut arg = TypedArg::new();
+            arg.value = Some(typed_arg::Value::BytesValue(bytes.to_string().into()));
+            arg
+        };
+        let double_arg = |value: f64| {
+            let mut arg = TypedArg::new();
+            arg.value = Some(typed_arg::Value::DoubleValue(value));
+            arg
+        };
+        let mut int_arg = TypedArg::new();
+        int_arg.value = Some(typed_arg::Value::IntValue(-7));
+        let mut typed_args = TypedArgsArray::new();
+        typed_args.args = vec![
+            bytes_arg(&key),
+            double_arg(f64::INFINITY),
+            bytes_arg("member1"),
+            double_arg(1e21),
+            bytes_arg("member2"),
+            int_arg,
+            bytes_arg("member3"),
+        ];
+        let mut command = Command::new();
+        command.request_type = RequestType::ZAdd.into();
+        command.args = Some(Args::TypedArgsArray(typed_args));
+        let mut request = CommandRequest::new();
+        request.callback_idx = CALLBACK_INDEX;
+        request.command = Some(command_request::command_request::Command::SingleCommand(
+            command,
+        ));
+
+        let mut buffer = Vec::with_capacity(key.len() * 4);
+        write_request(&mut buffer, &mut test_basics.socket, request);
+
+        assert_value_response(
+            &mut buffer,
+            Some(&mut test_basics.socket),
+            CALLBACK_INDEX,
+            Value::Int(3),
+        );
+    }
+
     #[rstest]
     #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
     fn test_socket_report_error() {
@@ -1211,6 +1289,153 @@ mod socket_listener {
         );
     }
 
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_nils_keep_their_positions_in_array_responses(
+        #[values(RedisType::Cluster, RedisType::Standalone)] use_cluster: RedisType,
+    ) {
+        let test_basics = setup_test_basics(Tls::NoTls, TestServer::Shared, use_cluster);
+        let mut socket = test_basics.socket;
+
+        const CALLBACK_INDEX: u32 = 0;
+        // The keys share a slot, so the transaction can be sent in cluster mode.
+        let tag = generate_random_string(KEY_LENGTH);
+        let key = |name: &str| format!("{{{tag}}}{name}");
+        let command = |args: Vec<String>| CommandComponents {
+            args: args.into_iter().map(Into::into).collect(),
+            args_pointer: false,
+            request_type: RequestType::CustomCommand.into(),
+        };
+        let args =
+            |args: &[&str]| -> Vec<String> { args.iter().map(|arg| arg.to_string()).collect() };
+        let commands = vec![
+            command(vec![
+                "ZADD".into(),
+                key("zset"),
+                "1.5".into(),
+                "member".into(),
+            ]),
+            command(
+                [
+                    vec!["ZMSCORE".into(), key("zset")],
+                    args(&["missing", "member", "missing"]),
+                ]
+                .concat(),
+            ),
+            command(vec!["SADD".into(), key("set"), "member".into()]),
+            command(
+                [
+                    vec!["SMISMEMBER".into(), key("set")],
+                    args(&["missing", "member"]),
+                ]
+                .concat(),
+            ),
+            command([vec!["RPUSH".into(), key("list")], args(&["a", "b", "a"])].concat()),
+            command([vec!["LPOS".into(), key("list")], args(&["a", "COUNT", "0"])].concat()),
+            command(vec!["LPOS".into(), key("list"), "missing".into()]),
+        ];
+        let mut buffer = Vec::with_capacity(200);
+        write_transaction_request(&mut buffer, &mut socket, CALLBACK_INDEX, commands);
+
+        assert_value_response(
+            &mut buffer,
+            Some(&mut socket),
+            CALLBACK_INDEX,
+            Value::Array(vec![
+                Value::Int(1),
+                Value::Array(vec![Value::Nil, Value::Double(1.5), Value::Nil]),
+                Value::Int(1),
+                Value::Array(vec![Value::Boolean(false), Value::Boolean(true)]),
+                Value::Int(3),
+                Value::Array(vec![Value::Int(0), Value::Int(2)]),
+                Value::Nil,
+            ]),
+        );
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_readiness_report_replies_to_the_connection_request(
+        #[values(RedisType::Cluster, RedisType::Standalone)] use_cluster: RedisType,
+    ) {
+        let mut socket = connect_to_socket_listener(None);
+        let (addresses, cluster_mode) = match use_cluster {
+            RedisType::Cluster => (get_shared_cluster_addresses(false), ClusterMode::Enabled),
+            RedisType::Standalone => (
+                vec![get_shared_server_address(false)],
+                ClusterMode::Disabled,
+            ),
+        };
+        let mut connection_request = create_connection_request(
+            &addresses,
+            &TestConfiguration {
+                cluster_mode,
+                request_timeout: Some(REQUEST_TIMEOUT_MS),
+                ..Default::default()
+            },
+        );
+        connection_request.readiness_checks = Some(connection_request::ReadinessChecks {
+            required_commands: vec!["GET".into(), "NOT.A.COMMAND".into()],
+            disconnect: true,
+            ..Default::default()
+        })
+        .into();
+        let mut buffer =
+            Vec::with_capacity(APPROX_RESP_HEADER_LEN + connection_request.compute_size() as usize);
+        write_message(&mut buffer, connection_request);
+        socket.write_all(&buffer).unwrap();
+
+        let response = get_response(&mut buffer, Some(&mut socket));
+        assert_eq!(response.callback_idx, 0);
+        let Some(response::Value::RespPointer(pointer)) = response.value else {
+            panic!("Expected a report, got {:?}", response.value);
+        };
+        let Value::Map(report) = *pointer_to_value(pointer) else {
+            panic!("Expected the report to be a map");
+        };
+        let field = |fields: &[(Value, Value)], name: &str| {
+            fields
+                .iter()
+                .find(|(field, _)| *field == Value::SimpleString(name.to_string()))
+                .map(|(_, value)| value.clone())
+                .unwrap()
+        };
+        assert_eq!(field(&report, "ready"), Value::Boolean(false));
+        let Value::Array(checks) = field(&report, "checks") else {
+            panic!("Expected the checks to be an array");
+        };
+        let outcomes: Vec<(Value, Value)> = checks
+            .iter()
+            .map(|check| {
+                let Value::Map(check) = check else {
+                    panic!("Expected a check to be a map, got {check:?}");
+                };
+                (field(check, "name"), field(check, "passed"))
+            })
+            .collect();
+        let outcome = |name: &str, passed: bool| {
+            (
+                Value::SimpleString(name.to_string()),
+                Value::Boolean(passed),
+            )
+        };
+        assert_eq!(
+            outcomes,
+            vec![
+                outcome("configuration", true),
+                outcome("connection", true),
+                outcome("command GET", true),
+                outcome("command NOT.A.COMMAND", false),
+            ]
+        );
+
+        // The client was closed, since the checks asked to disconnect.
+        let mut closed = [0; 1];
+        assert_eq!(socket.read(&mut closed).unwrap(), 0);
+    }
+
     #[rstest]
     #[serial_test::serial]
     #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
@@ -4,13 +4,20 @@ mod utilities;
 
 #[cfg(test)]
 mod standalone_client_tests {
-    use crate::utilities::mocks::{Mock, ServerMock};
+    use crate::utilities::mocks::{replay_recording, Mock, ServerMock};
+    use crate::utilities::proxy::{Fault, NetworkProxy};
     use std::collections::HashMap;
+    use std::sync::Arc;
 
     use super::*;
     use glide_core::{
-        client::{ConnectionError, StandaloneClient},
-        connection_request::ReadFrom,
+        client::{
+            Client, ConnectionError, SessionRecorder, StandaloneClient, SyncClient, REDACTED,
+        },
+        connection_request::{
+            AdaptiveTimeout, PeriodicChecksManualInterval, PinnedSlotRange, ReadFrom,
+        },
+        Telemetry,
     };
     use redis::{FromRedisValue, Value};
     use rstest::rstest;
@@ -167,6 +174,68 @@ mod standalone_client_tests {
         mocks
     }
 
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_writes_move_to_the_new_primary_after_a_role_change() {
+        let role = "*1\r\n$4\r\nROLE\r\n".to_string();
+        // The primary reports that it's a replica once the client is connected, e.g. after a manual REPLICAOF.
+        let mut old_primary_responses = create_primary_responses();
+        old_primary_responses.insert(
+            role.clone(),
+            Value::Array(vec![
+                Value::BulkString(b"slave".to_vec()),
+                Value::BulkString(b"127.0.0.1".to_vec()),
+                Value::Int(6379),
+                Value::BulkString(b"connected".to_vec()),
+                Value::Int(0),
+            ]),
+        );
+        let mut new_primary_responses = create_replica_response();
+        new_primary_responses.insert(
+            role,
+            Value::Array(vec![
+                Value::BulkString(b"master".to_vec()),
+                Value::Int(0),
+                Value::Array(vec![]),
+            ]),
+        );
+        let mut listeners: Vec<std::net::TcpListener> =
+            (0..2).map(|_| get_listener_on_available_port()).collect();
+        let mocks = vec![
+            ServerMock::new_with_listener(old_primary_responses, listeners.pop().unwrap()),
+            ServerMock::new_with_listener(new_primary_responses, listeners.pop().unwrap()),
+        ];
+        let mut set = redis::cmd("SET");
+        set.arg("key").arg("value");
+        mocks[1].add_response(&set, "+OK\r\n".to_string());
+
+        let mut connection_request =
+            create_connection_request(&get_mock_addresses(&mocks), &Default::default());
+        connection_request.set_periodic_checks_manual_interval(PeriodicChecksManualInterval {
+            duration_in_sec: 1,
+            ..Default::default()
+        });
+
+        block_on_all(async {
+            let mut client = StandaloneClient::create_client(connection_request.into(), None)
+                .await
+                .unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+            let result = client.send_command(&set).await.unwrap();
+            assert_eq!(result, Value::Okay);
+        });
+
+        assert_eq!(mocks[0].get_number_of_received_commands(), 0);
+        assert_eq!(mocks[1].get_number_of_received_commands(), 1);
+        let node_events = Telemetry::node_events();
+        for address in get_mock_addresses(&mocks) {
+            assert!(node_events
+                .get(&address.to_string())
+                .is_some_and(|events| events.last_role_change.is_some()));
+        }
+    }
+
     struct ReadFromReplicaTestConfig {
         read_from: ReadFrom,
         expected_primary_reads: u16,
@@ -441,4 +510,339 @@ mod standalone_client_tests {
             assert!(client_info.contains("db=4"));
         });
     }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_recorded_session_replays_against_mock() {
+        let mut commands = vec![];
+        let mut config_set = redis::cmd("CONFIG");
+        config_set.arg("SET").arg("requirepass").arg("secret");
+        commands.push((config_set, "+OK\r\n"));
+        let mut hgetall = redis::cmd("HGETALL");
+        hgetall.arg("hash");
+        commands.push((
+            hgetall,
+            "*4\r\n$2\r\nf1\r\n$2\r\nv1\r\n$2\r\nf2\r\n$2\r\nv2\r\n",
+        ));
+        let mut incr = redis::cmd("INCR");
+        incr.arg("hash");
+        commands.push((
+            incr,
+            "-WRONGTYPE Operation against a key holding the wrong kind of value\r\n",
+        ));
+
+        let recorded_mock = ServerMock::new(create_primary_responses());
+        for (cmd, response) in commands.iter() {
+            recorded_mock.add_response(cmd, response.to_string());
+        }
+        let recorder = Arc::new(SessionRecorder::new());
+        let recorded_results = block_on_all(async {
+            let connection_request =
+                create_connection_request(&recorded_mock.get_addresses(), &Default::default());
+            let mut client = Client::new(connection_request.into(), None).await.unwrap();
+            client.set_session_recorder(Some(recorder.clone()));
+            let mut results = vec![];
+            for (cmd, _) in commands.iter() {
+                results.push(client.send_command(cmd, None).await);
+            }
+            results
+        });
+        assert!(recorded_results[2].is_err());
+        let recording = recorder.to_json_lines();
+        assert!(!recording.contains("secret"));
+        assert!(recording.contains(REDACTED));
+
+        let replay_mock = ServerMock::new(create_primary_responses());
+        let recorded_commands = replay_recording(&replay_mock, &recording);
+        assert_eq!(recorded_commands.len(), commands.len());
+        let replayed_results = block_on_all(async {
+            let connection_request =
+                create_connection_request(&replay_mock.get_addresses(), &Default::default());
+            let mut client = Client::new(connection_request.into(), None).await.unwrap();
+            let mut results = vec![];
+            for cmd in recorded_commands.iter() {
+                results.push(client.send_command(cmd, None).await);
+            }
+            results
+        });
+
+        assert_eq!(replayed_results.len(), recorded_results.len());
+        for (replayed, recorded) in replayed_results.iter().zip(recorded_results.iter()) {
+            match (replayed, recorded) {
+                (Ok(replayed), Ok(recorded)) => assert_eq!(replayed, recorded),
+                (Err(replayed), Err(recorded)) => {
+                    assert_eq!(replayed.to_string(), recorded.to_string())
+                }
+                _ => panic!("Replayed {replayed:?}, but recorded {recorded:?}"),
+            }
+        }
+        assert_eq!(replay_mock.get_number_of_received_commands(), 3);
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_hottest_keys_are_reported_by_access_count() {
+        let mock = ServerMock::new(create_primary_responses());
+        let mut commands = vec![];
+        for (key, accesses) in [("hot", 3), ("warm", 2), ("cold", 1)] {
+            let mut get = redis::cmd("GET");
+            get.arg(key);
+            for _ in 0..accesses {
+                mock.add_response(&get, "$5\r\nvalue\r\n".to_string());
+                commands.push(get.clone());
+            }
+        }
+        block_on_all(async {
+            let mut connection_request =
+                create_connection_request(&mock.get_addresses(), &Default::default());
+            connection_request.hot_keys_tracked = 2;
+            let mut client = Client::new(connection_request.into(), None).await.unwrap();
+            for cmd in commands.iter() {
+                client.send_command(cmd, None).await.unwrap();
+            }
+            assert_eq!(
+                client.hottest_keys(10).unwrap(),
+                vec![(b"hot".to_vec(), 3), (b"warm".to_vec(), 2)]
+            );
+
+            let connection_request =
+                create_connection_request(&mock.get_addresses(), &Default::default());
+            let client = Client::new(connection_request.into(), None).await.unwrap();
+            assert!(client.hottest_keys(10).is_err());
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_invalid_connection_request_is_rejected_before_connecting() {
+        let mock = ServerMock::new(create_primary_responses());
+        let mut connection_request =
+            create_connection_request(&mock.get_addresses(), &Default::default());
+        connection_request.cluster_mode_enabled = true;
+        connection_request.database_id = 2;
+        connection_request.client_name = "my client".into();
+        connection_request.read_from = protobuf::EnumOrUnknown::from_i32(100);
+
+        let result = block_on_all(Client::new(connection_request.into(), None));
+        let Err(ConnectionError::InvalidConfiguration(message)) = result else {
+            panic!("Expected an invalid configuration error");
+        };
+        assert!(message.contains("database_id 2"), "{message}");
+        assert!(message.contains("client_name `my client`"), "{message}");
+        assert!(message.contains("read_from value 100"), "{message}");
+        assert_eq!(mock.get_number_of_received_commands(), 0);
+
+        let mut connection_request = create_connection_request(&[], &Default::default());
+        connection_request.cluster_mode_enabled = false;
+        let result = block_on_all(Client::new(connection_request.into(), None));
+        assert!(
+            matches!(result, Err(ConnectionError::InvalidConfiguration(message)) if message == "no addresses were provided")
+        );
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_tls_server_name_requires_tls() {
+        let mock = ServerMock::new(create_primary_responses());
+        let mut connection_request =
+            create_connection_request(&mock.get_addresses(), &Default::default());
+        connection_request.tls_server_name = Some("valkey.glide.test".into());
+
+        let result = block_on_all(Client::new(connection_request.into(), None));
+        let Err(ConnectionError::InvalidConfiguration(message)) = result else {
+            panic!("Expected an invalid configuration error");
+        };
+        assert!(message.contains("TLS is disabled"), "{message}");
+        assert_eq!(mock.get_number_of_received_commands(), 0);
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_invalid_adaptive_timeout_is_rejected() {
+        let mock = ServerMock::new(create_primary_responses());
+        let mut connection_request =
+            create_connection_request(&mock.get_addresses(), &Default::default());
+        let mut adaptive_timeout = AdaptiveTimeout::new();
+        adaptive_timeout.max_timeout = 500;
+        connection_request.adaptive_timeout = protobuf::MessageField::some(adaptive_timeout);
+
+        let result = block_on_all(Client::new(connection_request.clone().into(), None));
+        let Err(ConnectionError::InvalidConfiguration(message)) = result else {
+            panic!("Expected an invalid configuration error");
+        };
+        assert!(
+            message.contains("min_timeout of adaptive_timeout must be positive"),
+            "{message}"
+        );
+
+        connection_request
+            .adaptive_timeout
+            .as_mut()
+            .unwrap()
+            .min_timeout = 1000;
+        let result = block_on_all(Client::new(connection_request.into(), None));
+        let Err(ConnectionError::InvalidConfiguration(message)) = result else {
+            panic!("Expected an invalid configuration error");
+        };
+        assert!(
+            message.contains("(500ms) is lower than its min_timeout (1000ms)"),
+            "{message}"
+        );
+        assert_eq!(mock.get_number_of_received_commands(), 0);
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_invalid_pinned_slots_are_rejected() {
+        let mock = ServerMock::new(create_primary_responses());
+        let mut connection_request =
+            create_connection_request(&mock.get_addresses(), &Default::default());
+        for (start, end) in [(0, 100), (50, 200), (300, 20000)] {
+            let mut pinned_slot = PinnedSlotRange::new();
+            pinned_slot.start = start;
+            pinned_slot.end = end;
+            pinned_slot.address =
+                protobuf::MessageField::some(get_address_info(&mock.get_addresses()[0]));
+            connection_request.pinned_slots.push(pinned_slot);
+        }
+        let mut pinned_slot = PinnedSlotRange::new();
+        pinned_slot.start = 400;
+        pinned_slot.end = 500;
+        let mut address = get_address_info(&mock.get_addresses()[0]);
+        address.port = 70000;
+        pinned_slot.address = protobuf::MessageField::some(address);
+        connection_request.pinned_slots.push(pinned_slot);
+        connection_request.override_slot_discovery = true;
+
+        let result = block_on_all(Client::new(connection_request.into(), None));
+        let Err(ConnectionError::InvalidConfiguration(message)) = result else {
+            panic!("Expected an invalid configuration error");
+        };
+        assert!(message.contains("requires cluster mode"), "{message}");
+        assert!(message.contains("0-100 and 50-200 overlap"), "{message}");
+        assert!(message.contains("300-20000"), "{message}");
+        assert!(
+            message.contains("the port 70000 of the pinned slot range 400-500 is out of range"),
+            "{message}"
+        );
+        assert_eq!(mock.get_number_of_received_commands(), 0);
+    }
+
+    #[rstest]
+    #[timeout(LONG_STANDALONE_TEST_TIMEOUT)]
+    fn test_requests_time_out_and_recover_through_a_faulty_network() {
+        block_on_all(async {
+            let proxy = NetworkProxy::new(&get_shared_server_address(false));
+            let connection_request = create_connection_request(
+                &[proxy.address()],
+                &TestConfiguration {
+                    request_timeout: Some(250),
+                    ..Default::default()
+                },
+            );
+            let mut client = Client::new(connection_request.into(), None).await.unwrap();
+            let key = generate_random_string(10);
+            send_set_and_get(client.clone(), key.clone()).await;
+
+            proxy.set_fault(Fault::Delay(std::time::Duration::from_millis(500)));
+            let error = send_get(&mut client, &key).await.unwrap_err();
+            assert!(error.is_timeout(), "{error}");
+
+            proxy.set_fault(Fault::None);
+            let connections = proxy.connection_count();
+            proxy.sever_all();
+            let mut recovered = false;
+            for _ in 0..100 {
+                if send_get(&mut client, &key).await.is_ok() {
+                    recovered = true;
+                    break;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            }
+            assert!(recovered);
+            assert!(proxy.connection_count() > connections);
+        });
+    }
+
+    struct TokenAuthenticator {
+        token: String,
+    }
+
+    #[async_trait::async_trait]
+    impl redis::aio::Authenticator for TokenAuthenticator {
+        async fn authenticate(
+            &self,
+            con: &mut (dyn redis::aio::ConnectionLike + Send),
+        ) -> redis::RedisResult<()> {
+            redis::aio::authenticate_with_password(con, Some("token-user"), &self.token).await
+        }
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_connections_are_authenticated_by_the_authenticator() {
+        let mock = ServerMock::new(create_primary_responses());
+        let mut auth = redis::cmd("AUTH");
+        auth.arg("token-user").arg("fresh-token");
+        mock.add_response(&auth, "+OK\r\n".to_string());
+        let mut get = redis::cmd("GET");
+        get.arg("key");
+        mock.add_response(&get, "$5\r\nvalue\r\n".to_string());
+
+        block_on_all(async {
+            let connection_request =
+                create_connection_request(&mock.get_addresses(), &Default::default());
+            let mut connection_request: glide_core::client::ConnectionRequest =
+                connection_request.into();
+            connection_request.authenticator = Some(Arc::new(TokenAuthenticator {
+                token: "fresh-token".to_string(),
+            }));
+            let mut client = Client::new(connection_request, None).await.unwrap();
+            let value = client.send_command(&get, None).await.unwrap();
+            assert_eq!(value, Value::BulkString(b"value".to_vec()));
+        });
+        assert_eq!(mock.get_number_of_received_commands(), 2);
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_sync_client_can_be_shared_between_threads() {
+        const THREADS: usize = 4;
+        let connection_request = create_connection_request(
+            &[get_shared_server_address(false)],
+            &TestConfiguration {
+                shared_server: true,
+                ..Default::default()
+            },
+        );
+        let client = SyncClient::new(connection_request.into(), None).unwrap();
+
+        // All the threads are started before any of them is joined, so that their requests are sent concurrently.
+        std::thread::scope(|scope| {
+            let threads: Vec<_> = (0..THREADS)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let key = generate_random_string(6);
+                        let mut hset = redis::cmd("HSET");
+                        hset.arg(&key).arg("field").arg("value");
+                        client.send_command(&hset, None).unwrap();
+                        let mut hgetall = redis::cmd("HGETALL");
+                        hgetall.arg(&key);
+                        // The reply is converted to a map, as by the async client.
+                        let result = client.send_command(&hgetall, None).unwrap();
+                        assert_eq!(
+                            result,
+                            Value::Map(vec![(
+                                Value::BulkString(b"field".to_vec()),
+                                Value::BulkString(b"value".to_vec()),
+                            )])
+                        );
+                    })
+                })
+                .collect();
+            for thread in threads {
+                thread.join().unwrap();
+            }
+        });
+    }
 }
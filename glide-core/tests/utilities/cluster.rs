@@ -1,11 +1,12 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
-use super::{create_connection_request, ClusterMode, TestConfiguration};
+use super::{create_connection_request, repeat_try_create, ClusterMode, TestConfiguration};
 use futures::future::{join_all, BoxFuture};
 use futures::FutureExt;
 use glide_core::client::Client;
 use once_cell::sync::Lazy;
-use redis::{ConnectionAddr, RedisConnectionInfo};
+use redis::aio::MultiplexedConnection;
+use redis::{ConnectionAddr, GlideConnectionOptions, RedisConnectionInfo, Value};
 use serde::Deserialize;
 use std::process::Command;
 use std::sync::Mutex;
@@ -49,6 +50,7 @@ pub struct RedisCluster {
     cluster_folder: String,
     use_tls: bool,
     password: Option<String>,
+    username: Option<String>,
     servers: Vec<ValkeyServerInfo>,
 }
 
@@ -140,13 +142,16 @@ impl RedisCluster {
         let (stdout, stderr) = Self::execute_cluster_script(script_args, use_tls, None);
         let (cluster_folder, servers) = Self::parse_start_script_output(&stdout, &stderr);
         let mut password: Option<String> = None;
+        let mut username: Option<String> = None;
         if let Some(info) = conn_info {
             password.clone_from(&info.password);
+            username.clone_from(&info.username);
         };
         RedisCluster {
             cluster_folder,
             use_tls,
             password,
+            username,
             servers,
         }
     }
@@ -231,6 +236,120 @@ impl RedisCluster {
             .map(|server| ClusterType::build_addr(self.use_tls, &server.host, server.port as u16))
             .collect()
     }
+
+    fn addresses_by_role(&self, is_primary: bool) -> Vec<ConnectionAddr> {
+        self.servers
+            .iter()
+            .filter(|server| server.is_primary == is_primary)
+            .map(|server| ClusterType::build_addr(self.use_tls, &server.host, server.port as u16))
+            .collect()
+    }
+
+    /// The addresses of the nodes that were primaries when the cluster was started.
+    pub fn get_primary_addresses(&self) -> Vec<ConnectionAddr> {
+        self.addresses_by_role(true)
+    }
+
+    /// The addresses of the nodes that were replicas when the cluster was started.
+    pub fn get_replica_addresses(&self) -> Vec<ConnectionAddr> {
+        self.addresses_by_role(false)
+    }
+
+    /// Opens a connection to a single node, bypassing the client under test. It authenticates with the credentials
+    /// that the cluster was created with, so it works after the ACL was set up.
+    pub async fn connect_to_node(&self, address: &ConnectionAddr) -> MultiplexedConnection {
+        let client = redis::Client::open(redis::ConnectionInfo {
+            addr: address.clone(),
+            redis: RedisConnectionInfo {
+                username: self.username.clone(),
+                password: self.password.clone(),
+                ..Default::default()
+            },
+        })
+        .unwrap();
+        repeat_try_create(|| async {
+            client
+                .get_multiplexed_async_connection(GlideConnectionOptions::default())
+                .await
+                .ok()
+        })
+        .await
+    }
+
+    /// Returns the ID of the node at `address`.
+    pub async fn node_id(&self, address: &ConnectionAddr) -> String {
+        let mut connection = self.connect_to_node(address).await;
+        let mut cmd = redis::cmd("CLUSTER");
+        cmd.arg("MYID");
+        let id = connection.send_packed_command(&cmd).await.unwrap();
+        redis::from_owned_redis_value(id).unwrap()
+    }
+
+    /// Returns the address of a replica of the primary at `primary`.
+    pub async fn replica_of(&self, primary: &ConnectionAddr) -> ConnectionAddr {
+        let primary_port = node_port(primary);
+        for replica in self.get_replica_addresses() {
+            let mut connection = self.connect_to_node(&replica).await;
+            // A replica's role is `slave`, followed by the host and the port of its primary.
+            let role = connection
+                .send_packed_command(&redis::cmd("ROLE"))
+                .await
+                .unwrap();
+            let role: Vec<Value> = redis::from_owned_redis_value(role).unwrap();
+            let port = role
+                .get(2)
+                .and_then(|port| redis::from_redis_value::<u16>(port).ok());
+            if port == Some(primary_port) {
+                return replica;
+            }
+        }
+        panic!("No replica of {primary} was found");
+    }
+
+    /// Promotes `replica` with a manual failover, and waits until it's a primary. Clients that are connected to the
+    /// cluster find out about the failover only through the replies of the nodes.
+    pub async fn failover(&self, replica: &ConnectionAddr) {
+        let mut connection = self.connect_to_node(replica).await;
+        let mut cmd = redis::cmd("CLUSTER");
+        cmd.arg("FAILOVER");
+        connection.send_packed_command(&cmd).await.unwrap();
+        for _ in 0..500 {
+            let role = connection
+                .send_packed_command(&redis::cmd("ROLE"))
+                .await
+                .unwrap();
+            let role: Vec<Value> = redis::from_owned_redis_value(role).unwrap();
+            if role.first() == Some(&Value::BulkString(b"master".to_vec())) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("{replica} wasn't promoted");
+    }
+
+    /// Assigns `slot` to the primary at `target` on all the primaries, without migrating keys, so the slot must be
+    /// empty. Requests for the slot that are sent to its previous owner are then answered with MOVED.
+    ///
+    /// The slot is assigned on the target first, so that it's served by one of the primaries at any time.
+    pub async fn move_slot(&self, slot: u16, target: &ConnectionAddr) {
+        let target_id = self.node_id(target).await;
+        let primaries = self.get_primary_addresses();
+        let others = primaries.iter().filter(|primary| *primary != target);
+        for primary in std::iter::once(target).chain(others) {
+            let mut connection = self.connect_to_node(primary).await;
+            let mut cmd = redis::cmd("CLUSTER");
+            cmd.arg("SETSLOT").arg(slot).arg("NODE").arg(&target_id);
+            connection.send_packed_command(&cmd).await.unwrap();
+        }
+    }
+}
+
+/// Returns the port of a cluster node, whether it's reached with TLS or not.
+pub fn node_port(address: &ConnectionAddr) -> u16 {
+    match address {
+        ConnectionAddr::Tcp(_, port) | ConnectionAddr::TcpTls { port, .. } => *port,
+        ConnectionAddr::Unix(_) => panic!("Cluster nodes can't be reached by unix sockets"),
+    }
 }
 
 pub struct ClusterTestBasics {
@@ -17,7 +17,7 @@ use tokio::sync::mpsc::UnboundedSender;
 
 pub struct MockedRequest {
     pub expected_message: String,
-    pub response: String,
+    pub response: Vec<u8>,
 }
 
 pub struct ServerMock {
@@ -97,6 +97,14 @@ fn receive_and_respond_to_next_message(
         return true;
     }
 
+    // The connection setup checks whether replies can be skipped.
+    if message == "*3\r\n$6\r\nCLIENT\r\n$5\r\nREPLY\r\n$2\r\nON\r\n" {
+        let mut buffer = Vec::new();
+        super::encode_value(&Value::Okay, &mut buffer).unwrap();
+        socket.write_all(&buffer).unwrap();
+        return true;
+    }
+
     if let Some(response) = constant_responses.get(&message) {
         let mut buffer = Vec::new();
         super::encode_value(response, &mut buffer).unwrap();
@@ -108,15 +116,32 @@ fn receive_and_respond_to_next_message(
     };
     received_commands.fetch_add(1, Ordering::AcqRel);
     assert_eq!(message, request.expected_message);
-    socket.write_all(request.response.as_bytes()).unwrap();
+    socket.write_all(&request.response).unwrap();
     true
 }
 
+/// Queues the replies of a session recording on `mock`, and returns the recorded commands, which should be sent in
+/// order to replay the session. Commands that failed without a reply from the server are skipped.
+pub fn replay_recording(mock: &ServerMock, recording: &str) -> Vec<Cmd> {
+    glide_core::client::parse_recording(recording)
+        .unwrap()
+        .into_iter()
+        .filter_map(|exchange| {
+            let cmd = exchange.to_cmd();
+            mock.add_binary_response(&cmd, exchange.response?);
+            Some(cmd)
+        })
+        .collect()
+}
+
 pub trait Mock {
     fn get_addresses(&self) -> Vec<ConnectionAddr>;
 
     fn add_response(&self, request: &Cmd, response: String);
 
+    /// Like [`Mock::add_response`], for replies that aren't valid UTF-8.
+    fn add_binary_response(&self, request: &Cmd, response: Vec<u8>);
+
     fn get_number_of_received_commands(&self) -> u16;
 }
 
@@ -189,6 +214,10 @@ impl Mock for ServerMock {
     }
 
     fn add_response(&self, request: &Cmd, response: String) {
+        self.add_binary_response(request, response.into_bytes());
+    }
+
+    fn add_binary_response(&self, request: &Cmd, response: Vec<u8>) {
         let expected_message = String::from_utf8(request.get_packed_command()).unwrap();
         let _ = self.request_sender.send(MockedRequest {
             expected_message,
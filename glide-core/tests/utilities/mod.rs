@@ -3,7 +3,7 @@
 #![allow(dead_code)]
 use futures::Future;
 use glide_core::{
-    client::{Client, StandaloneClient},
+    client::{AuditEvent, AuditOutcome, AuditSink, Client, StandaloneClient},
     connection_request::{self, AuthenticationInfo, NodeAddress, ProtocolVersion},
 };
 use once_cell::sync::Lazy;
@@ -22,6 +22,8 @@ use tokio::sync::mpsc;
 
 pub mod cluster;
 pub mod mocks;
+pub mod proxy;
+pub mod topology;
 
 pub(crate) const SHORT_STANDALONE_TEST_TIMEOUT: Duration = Duration::from_millis(10_000);
 pub(crate) const LONG_STANDALONE_TEST_TIMEOUT: Duration = Duration::from_millis(20_000);
@@ -481,6 +483,23 @@ pub async fn wait_for_server_to_become_ready(server_address: &ConnectionAddr) {
     }
 }
 
+/// An audit sink that keeps the command, keys, context and outcome of every event it receives.
+#[derive(Default)]
+pub struct RecordingSink {
+    pub events: Mutex<Vec<(String, Vec<Vec<u8>>, Option<String>, AuditOutcome)>>,
+}
+
+impl AuditSink for RecordingSink {
+    fn record(&self, event: &AuditEvent<'_>) {
+        self.events.lock().unwrap().push((
+            event.command.clone(),
+            event.keys.iter().map(|key| key.to_vec()).collect(),
+            event.context.map(str::to_string),
+            event.outcome,
+        ));
+    }
+}
+
 pub fn current_thread_runtime() -> tokio::runtime::Runtime {
     tokio::runtime::Builder::new_current_thread()
         .enable_all()
@@ -524,6 +543,15 @@ pub fn generate_random_string(length: usize) -> String {
         .collect()
 }
 
+/// Builds a command from its name and arguments, e.g. `command(&["SET", "key", "value"])`.
+pub fn command(args: &[&str]) -> redis::Cmd {
+    let mut cmd = redis::cmd(args[0]);
+    for arg in &args[1..] {
+        cmd.arg(*arg);
+    }
+    cmd
+}
+
 pub async fn send_get(client: &mut Client, key: &str) -> RedisResult<Value> {
     let mut get_command = redis::Cmd::new();
     get_command.arg("GET").arg(key);
@@ -565,6 +593,8 @@ fn set_connection_info_to_connection_request(
     connection_request: &mut connection_request::ConnectionRequest,
 ) {
     connection_request.protocol = convert_to_protobuf_protocol(connection_info.protocol).into();
+    connection_request.no_evict = connection_info.no_evict;
+    connection_request.no_touch = connection_info.no_touch;
     if connection_info.password.is_some() {
         connection_request.authentication_info =
             protobuf::MessageField(Some(Box::new(AuthenticationInfo {
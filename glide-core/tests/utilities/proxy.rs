@@ -0,0 +1,232 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! A TCP proxy that is placed between the client and a server, so tests can inject network faults into the
+//! connections that pass through it. The bytes are forwarded as is, so TLS connections can be proxied as well.
+
+use super::get_listener_on_available_port;
+use redis::ConnectionAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+
+/// A fault that is applied to the data that passes through a connection, in both directions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Fault {
+    /// The data is forwarded as soon as it's read.
+    #[default]
+    None,
+    /// Each chunk of data is forwarded after the delay.
+    Delay(Duration),
+    /// The data is discarded, as if the network stopped delivering it, while the connection stays open.
+    Drop,
+    /// Each chunk of data is held until the next one is read, and then forwarded after it. A chunk that isn't followed
+    /// by another within the duration is forwarded alone.
+    Reorder(Duration),
+}
+
+struct ProxiedConnection {
+    /// Overrides the proxy's fault for this connection.
+    fault: Option<Fault>,
+    severed: watch::Sender<bool>,
+}
+
+#[derive(Default)]
+struct ProxyState {
+    fault: Fault,
+    /// The connections in the order they were accepted, including those that were closed since.
+    connections: Vec<ProxiedConnection>,
+}
+
+impl ProxyState {
+    fn fault_of(&self, connection: usize) -> Fault {
+        self.connections[connection].fault.unwrap_or(self.fault)
+    }
+}
+
+/// Forwards the connections to its address to a target server. It runs on its own thread, so faults are applied even
+/// while the test blocks, and it closes all the connections when dropped.
+pub struct NetworkProxy {
+    address: ConnectionAddr,
+    state: Arc<Mutex<ProxyState>>,
+    shutdown: watch::Sender<bool>,
+}
+
+impl NetworkProxy {
+    pub fn new(target: &ConnectionAddr) -> Self {
+        let (target_host, target_port) = match target {
+            ConnectionAddr::Tcp(host, port) | ConnectionAddr::TcpTls { host, port, .. } => {
+                (host.clone(), *port)
+            }
+            ConnectionAddr::Unix(_) => panic!("Unix sockets can't be proxied"),
+        };
+        let listener = get_listener_on_available_port();
+        listener.set_nonblocking(true).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let address = match target {
+            ConnectionAddr::TcpTls {
+                insecure,
+                tls_params,
+                ..
+            } => ConnectionAddr::TcpTls {
+                host: "127.0.0.1".to_string(),
+                port,
+                insecure: *insecure,
+                tls_params: tls_params.clone(),
+            },
+            _ => ConnectionAddr::Tcp("127.0.0.1".to_string(), port),
+        };
+        let state = Arc::new(Mutex::new(ProxyState::default()));
+        let (shutdown, mut shutdown_receiver) = watch::channel(false);
+
+        let state_clone = state.clone();
+        std::thread::spawn(move || {
+            super::current_thread_runtime().block_on(async move {
+                let listener = TcpListener::from_std(listener).unwrap();
+                loop {
+                    let client = tokio::select! {
+                        accepted = listener.accept() => match accepted {
+                            Ok((client, _)) => client,
+                            Err(_) => continue,
+                        },
+                        _ = shutdown_receiver.changed() => return,
+                    };
+                    let Ok(server) = TcpStream::connect((target_host.as_str(), target_port)).await
+                    else {
+                        continue;
+                    };
+                    let _ = client.set_nodelay(true);
+                    let _ = server.set_nodelay(true);
+                    let (severed, severed_receiver) = watch::channel(false);
+                    let index = {
+                        let mut state = state_clone.lock().unwrap();
+                        state.connections.push(ProxiedConnection {
+                            fault: None,
+                            severed,
+                        });
+                        state.connections.len() - 1
+                    };
+                    let (client_reader, client_writer) = client.into_split();
+                    let (server_reader, server_writer) = server.into_split();
+                    for (reader, writer) in [
+                        (client_reader, server_writer),
+                        (server_reader, client_writer),
+                    ] {
+                        let state = state_clone.clone();
+                        let mut severed = severed_receiver.clone();
+                        tokio::spawn(async move {
+                            tokio::select! {
+                                _ = forward(reader, writer, state, index) => {}
+                                _ = severed.wait_for(|severed| *severed) => {}
+                            }
+                        });
+                    }
+                }
+            });
+        });
+
+        NetworkProxy {
+            address,
+            state,
+            shutdown,
+        }
+    }
+
+    /// The address that the client should connect to instead of the target.
+    pub fn address(&self) -> ConnectionAddr {
+        self.address.clone()
+    }
+
+    /// The number of connections that were accepted so far. Connections are identified by the order they were
+    /// accepted in, starting from 0.
+    pub fn connection_count(&self) -> usize {
+        self.state.lock().unwrap().connections.len()
+    }
+
+    /// Applies `fault` to all the connections, including those that will be accepted later. Faults that were set for
+    /// specific connections are cleared.
+    pub fn set_fault(&self, fault: Fault) {
+        let mut state = self.state.lock().unwrap();
+        state.fault = fault;
+        for connection in state.connections.iter_mut() {
+            connection.fault = None;
+        }
+    }
+
+    /// Applies `fault` to the connection with the given index only.
+    pub fn set_connection_fault(&self, connection: usize, fault: Fault) {
+        self.state.lock().unwrap().connections[connection].fault = Some(fault);
+    }
+
+    /// Closes the connection with the given index on both sides, as if it was reset by the network.
+    pub fn sever(&self, connection: usize) {
+        let _ = self.state.lock().unwrap().connections[connection]
+            .severed
+            .send(true);
+    }
+
+    /// Closes all the current connections. Connections that are made later are forwarded as usual.
+    pub fn sever_all(&self) {
+        for connection in self.state.lock().unwrap().connections.iter() {
+            let _ = connection.severed.send(true);
+        }
+    }
+}
+
+impl Drop for NetworkProxy {
+    fn drop(&mut self) {
+        self.sever_all();
+        let _ = self.shutdown.send(true);
+    }
+}
+
+async fn forward(
+    mut reader: OwnedReadHalf,
+    mut writer: OwnedWriteHalf,
+    state: Arc<Mutex<ProxyState>>,
+    connection: usize,
+) {
+    let fault_of = || state.lock().unwrap().fault_of(connection);
+    let mut buffer = vec![0; 16 * 1024];
+    let mut held: Option<Vec<u8>> = None;
+    loop {
+        let read = match (&held, fault_of()) {
+            (Some(_), Fault::Reorder(hold)) => tokio::time::timeout(hold, reader.read(&mut buffer))
+                .await
+                .ok(),
+            _ => Some(reader.read(&mut buffer).await),
+        };
+        let chunk = match read {
+            // The held chunk wasn't followed by another in time.
+            None => {
+                let previous = held.take().unwrap();
+                if writer.write_all(&previous).await.is_err() {
+                    return;
+                }
+                continue;
+            }
+            Some(Ok(0)) | Some(Err(_)) => return,
+            Some(Ok(size)) => buffer[..size].to_vec(),
+        };
+        let mut chunks = vec![];
+        match fault_of() {
+            Fault::None => chunks.extend(held.take().into_iter().chain([chunk])),
+            Fault::Delay(delay) => {
+                tokio::time::sleep(delay).await;
+                chunks.extend(held.take().into_iter().chain([chunk]));
+            }
+            Fault::Drop => held = None,
+            Fault::Reorder(_) => match held.take() {
+                Some(previous) => chunks.extend([chunk, previous]),
+                None => held = Some(chunk),
+            },
+        }
+        for chunk in chunks {
+            if writer.write_all(&chunk).await.is_err() {
+                return;
+            }
+        }
+    }
+}
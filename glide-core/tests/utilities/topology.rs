@@ -0,0 +1,79 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use super::{
+    cluster, create_connection_request, generate_random_string, repeat_try_create, setup_acl,
+    BackingServer, ClusterMode, RedisServer, ServerType, TestConfiguration,
+};
+use glide_core::client::Client;
+use redis::RedisConnectionInfo;
+
+/// The user that clients of topologies with ACL authenticate as.
+pub const ACL_TEST_USER: &str = "glide-test-user";
+
+/// A dedicated standalone server or cluster, which is torn down once dropped, with a client that is connected to it.
+pub struct Topology {
+    pub server: BackingServer,
+    pub client: Client,
+    pub configuration: TestConfiguration,
+}
+
+impl Topology {
+    /// The cluster of the topology, if it was launched in cluster mode.
+    pub fn cluster(&self) -> Option<&cluster::RedisCluster> {
+        match &self.server {
+            BackingServer::Cluster(cluster) => cluster.as_ref(),
+            BackingServer::Standalone(_) => None,
+        }
+    }
+}
+
+/// Returns the credentials of a topology with ACL, or `None` for a topology whose default user has no password.
+pub fn acl_connection_info(use_acl: bool) -> Option<RedisConnectionInfo> {
+    use_acl.then(|| RedisConnectionInfo {
+        username: Some(ACL_TEST_USER.to_string()),
+        password: Some(generate_random_string(10)),
+        ..Default::default()
+    })
+}
+
+/// Launches a standalone server, or a cluster of 3 primaries with a replica each, which uses TLS if `use_tls` is set,
+/// and authenticates its clients with [`ACL_TEST_USER`] if `use_acl` is set.
+pub async fn setup_topology(cluster_mode: ClusterMode, use_tls: bool, use_acl: bool) -> Topology {
+    let configuration = TestConfiguration {
+        use_tls,
+        connection_info: acl_connection_info(use_acl),
+        cluster_mode: cluster_mode.clone(),
+        shared_server: false,
+        ..Default::default()
+    };
+    match cluster_mode {
+        ClusterMode::Enabled => {
+            let test_basics =
+                cluster::setup_cluster_with_replicas(configuration.clone(), 1, 3).await;
+            Topology {
+                server: BackingServer::Cluster(test_basics.cluster),
+                client: test_basics.client,
+                configuration,
+            }
+        }
+        ClusterMode::Disabled => {
+            let server = RedisServer::new(ServerType::Tcp { tls: use_tls });
+            let address = server.get_client_addr();
+            if let Some(connection_info) = &configuration.connection_info {
+                setup_acl(&address, connection_info).await;
+            }
+            let connection_request = create_connection_request(&[address], &configuration);
+            let client = repeat_try_create(|| async {
+                Client::new(connection_request.clone().into(), None)
+                    .await
+                    .ok()
+            })
+            .await;
+            Topology {
+                server: BackingServer::Standalone(Some(server)),
+                client,
+                configuration,
+            }
+        }
+    }
+}
@@ -2,26 +2,19 @@
 
 #![deny(unsafe_op_in_unsafe_fn)]
 use glide_core::client::Client as GlideClient;
-use glide_core::command_request::SimpleRoutes;
-use glide_core::command_request::{Routes, SlotTypes};
-use glide_core::connection_request;
+use glide_core::command_request::Routes;
 use glide_core::errors;
 use glide_core::errors::RequestErrorType;
 use glide_core::request_type::RequestType;
-use glide_core::ConnectionRequest;
+use glide_ffi::conversions::{create_client, get_route};
 use protobuf::Message;
-use redis::cluster_routing::{
-    MultipleNodeRoutingInfo, Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr,
-};
-use redis::cluster_routing::{ResponsePolicy, Routable};
-use redis::{Cmd, RedisResult, Value};
+use redis::{ErrorKind, RedisError, RedisResult, Value};
 use std::slice::from_raw_parts;
 use std::{
     ffi::{c_void, CString},
     mem,
     os::raw::{c_char, c_double, c_long, c_ulong},
 };
-use tokio::runtime::Builder;
 use tokio::runtime::Runtime;
 
 /// The struct represents the response of the command.
@@ -144,21 +137,8 @@ fn create_client_internal(
     success_callback: SuccessCallback,
     failure_callback: FailureCallback,
 ) -> Result<ClientAdapter, String> {
-    let request = connection_request::ConnectionRequest::parse_from_bytes(connection_request_bytes)
-        .map_err(|err| err.to_string())?;
     // TODO: optimize this using multiple threads instead of a single worker thread (e.g. by pinning each go thread to a rust thread)
-    let runtime = Builder::new_multi_thread()
-        .enable_all()
-        .worker_threads(1)
-        .thread_name("Valkey-GLIDE Go thread")
-        .build()
-        .map_err(|err| {
-            let redis_error = err.into();
-            errors::error_message(&redis_error)
-        })?;
-    let client = runtime
-        .block_on(GlideClient::new(ConnectionRequest::from(request), None))
-        .map_err(|err| err.to_string())?;
+    let (client, runtime) = create_client(connection_request_bytes, "Valkey-GLIDE Go thread")?;
     Ok(ClientAdapter {
         client,
         success_callback,
@@ -536,12 +516,19 @@ pub unsafe extern "C" fn command(
 
     let r_bytes = unsafe { std::slice::from_raw_parts(route_bytes, route_bytes_len) };
 
-    let route = Routes::parse_from_bytes(r_bytes).unwrap();
+    let routing = Routes::parse_from_bytes(r_bytes)
+        .map_err(|err| err.to_string())
+        .and_then(|route| get_route(route, &cmd));
 
     client_adapter.runtime.spawn(async move {
-        let result = client_clone
-            .send_command(&cmd, get_route(route, Some(&cmd)))
-            .await;
+        let result = match routing {
+            Ok(routing) => client_clone.send_command(&cmd, routing).await,
+            Err(message) => Err(RedisError::from((
+                ErrorKind::ClientError,
+                "Received invalid route",
+                message,
+            ))),
+        };
         let client_adapter = unsafe { Box::leak(Box::from_raw(ptr_address as *mut ClientAdapter)) };
         let value = match result {
             Ok(value) => value,
@@ -577,65 +564,3 @@ pub unsafe extern "C" fn command(
         }
     });
 }
-
-fn get_route(route: Routes, cmd: Option<&Cmd>) -> Option<RoutingInfo> {
-    use glide_core::command_request::routes::Value;
-    let route = route.value?;
-    let get_response_policy = |cmd: Option<&Cmd>| {
-        cmd.and_then(|cmd| {
-            cmd.command()
-                .and_then(|cmd| ResponsePolicy::for_command(&cmd))
-        })
-    };
-    match route {
-        Value::SimpleRoutes(simple_route) => {
-            let simple_route = simple_route.enum_value().unwrap();
-            match simple_route {
-                SimpleRoutes::AllNodes => Some(RoutingInfo::MultiNode((
-                    MultipleNodeRoutingInfo::AllNodes,
-                    get_response_policy(cmd),
-                ))),
-                SimpleRoutes::AllPrimaries => Some(RoutingInfo::MultiNode((
-                    MultipleNodeRoutingInfo::AllMasters,
-                    get_response_policy(cmd),
-                ))),
-                SimpleRoutes::Random => {
-                    Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random))
-                }
-            }
-        }
-        Value::SlotKeyRoute(slot_key_route) => Some(RoutingInfo::SingleNode(
-            SingleNodeRoutingInfo::SpecificNode(Route::new(
-                redis::cluster_topology::get_slot(slot_key_route.slot_key.as_bytes()),
-                get_slot_addr(&slot_key_route.slot_type),
-            )),
-        )),
-        Value::SlotIdRoute(slot_id_route) => Some(RoutingInfo::SingleNode(
-            SingleNodeRoutingInfo::SpecificNode(Route::new(
-                slot_id_route.slot_id as u16,
-                get_slot_addr(&slot_id_route.slot_type),
-            )),
-        )),
-        Value::ByAddressRoute(by_address_route) => match u16::try_from(by_address_route.port) {
-            Ok(port) => Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
-                host: by_address_route.host.to_string(),
-                port,
-            })),
-            Err(_) => {
-                // TODO: Handle error propagation.
-                None
-            }
-        },
-        _ => panic!("unknown route type"),
-    }
-}
-
-fn get_slot_addr(slot_type: &protobuf::EnumOrUnknown<SlotTypes>) -> SlotAddr {
-    slot_type
-        .enum_value()
-        .map(|slot_type| match slot_type {
-            SlotTypes::Primary => SlotAddr::Master,
-            SlotTypes::Replica => SlotAddr::ReplicaRequired,
-        })
-        .expect("Received unexpected slot id type")
-}
@@ -137,6 +137,13 @@ public class ConnectionManager {
             connectionRequestBuilder.setProtocolValue(configuration.getProtocol().ordinal());
         }
 
+        // The version is only known when running from the jar, otherwise the core doesn't check it.
+        String wrapperVersion = ConnectionManager.class.getPackage().getImplementationVersion();
+        connectionRequestBuilder.setWrapperName("java");
+        if (wrapperVersion != null) {
+            connectionRequestBuilder.setWrapperVersion(wrapperVersion);
+        }
+
         return connectionRequestBuilder;
     }
 
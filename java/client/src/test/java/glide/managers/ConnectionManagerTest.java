@@ -87,6 +87,7 @@ public class ConnectionManagerTest {
                         .setTlsMode(TlsMode.NoTls)
                         .setClusterModeEnabled(false)
                         .setReadFrom(ConnectionRequestOuterClass.ReadFrom.Primary)
+                        .setWrapperName("java")
                         .build();
         CompletableFuture<Response> completedFuture = new CompletableFuture<>();
         Response response = Response.newBuilder().setConstantResponse(ConstantResponse.OK).build();
@@ -112,6 +113,7 @@ public class ConnectionManagerTest {
                         .setTlsMode(TlsMode.NoTls)
                         .setClusterModeEnabled(true)
                         .setReadFrom(ConnectionRequestOuterClass.ReadFrom.Primary)
+                        .setWrapperName("java")
                         .build();
         CompletableFuture<Response> completedFuture = new CompletableFuture<>();
         Response response = Response.newBuilder().setConstantResponse(ConstantResponse.OK).build();
@@ -201,6 +203,7 @@ public class ConnectionManagerTest {
                                                                         .build()))
                                         .build())
                         .setInflightRequestsLimit(INFLIGHT_REQUESTS_LIMIT)
+                        .setWrapperName("java")
                         .build();
         CompletableFuture<Response> completedFuture = new CompletableFuture<>();
         Response response = Response.newBuilder().setConstantResponse(ConstantResponse.OK).build();
@@ -296,6 +299,7 @@ public class ConnectionManagerTest {
                         .setTlsMode(TlsMode.SecureTls)
                         .setReadFrom(ConnectionRequestOuterClass.ReadFrom.AZAffinity)
                         .setClientAz(az)
+                        .setWrapperName("java")
                         .build();
 
         CompletableFuture<Response> completedFuture = new CompletableFuture<>();
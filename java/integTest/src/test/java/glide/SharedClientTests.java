@@ -56,8 +56,9 @@ public class SharedClientTests {
     @MethodSource("getClients")
     public void validate_statistics(BaseClient client) {
         assertFalse(client.getStatistics().isEmpty());
-        // we expect 2 items in the statistics map
-        assertEquals(2, client.getStatistics().size());
+        assertTrue(client.getStatistics().containsKey("big_replies"));
+        // we expect 10 items in the statistics map
+        assertEquals(10, client.getStatistics().size());
     }
 
     @AfterAll
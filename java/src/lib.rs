@@ -612,6 +612,62 @@ pub extern "system" fn Java_glide_ffi_resolvers_StatisticsResolver_getStatistics
         &format!("{}", Telemetry::total_clients()),
     );
 
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "memory_usage",
+        &format!("{}", glide_core::memory_budget::memory_usage()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "byte_counters",
+        &Telemetry::byte_counters_json(),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "replica_moved_reads",
+        &format!("{}", Telemetry::replica_moved_reads()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "weighted_reads",
+        &Telemetry::weighted_reads_json(),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "node_events",
+        &Telemetry::node_events_json(),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "throttled_connection_attempts",
+        &format!("{}", Telemetry::throttled_connection_attempts()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "incompatible_wrappers",
+        &format!("{}", Telemetry::incompatible_wrappers()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "big_replies",
+        &Telemetry::big_replies_json(),
+    );
+
     map
 }
 
@@ -492,13 +492,42 @@ impl Drop for ClusterScanCursor {
     }
 }
 
+/// Returns the version that the package was built for, which is set by the release build through `GLIDE_VERSION`, or
+/// an empty string for development builds, in which case the core doesn't check the wrapper's version.
+#[napi]
+pub fn get_wrapper_version() -> String {
+    match env!("GLIDE_VERSION") {
+        "unknown" => String::new(),
+        version => version.to_string(),
+    }
+}
+
 #[napi]
 pub fn get_statistics(env: Env) -> Result<JsObject> {
     let total_connections = Telemetry::total_connections().to_string();
     let total_clients = Telemetry::total_clients().to_string();
+    let memory_usage = glide_core::memory_budget::memory_usage().to_string();
+    let byte_counters = Telemetry::byte_counters_json();
+    let replica_moved_reads = Telemetry::replica_moved_reads().to_string();
+    let weighted_reads = Telemetry::weighted_reads_json();
+    let node_events = Telemetry::node_events_json();
+    let big_replies = Telemetry::big_replies_json();
+    let incompatible_wrappers = Telemetry::incompatible_wrappers().to_string();
+    let throttled_connection_attempts = Telemetry::throttled_connection_attempts().to_string();
     let mut stats: JsObject = env.create_object()?;
     stats.set_named_property("total_connections", total_connections)?;
     stats.set_named_property("total_clients", total_clients)?;
+    stats.set_named_property("memory_usage", memory_usage)?;
+    stats.set_named_property("byte_counters", byte_counters)?;
+    stats.set_named_property("replica_moved_reads", replica_moved_reads)?;
+    stats.set_named_property("weighted_reads", weighted_reads)?;
+    stats.set_named_property("node_events", node_events)?;
+    stats.set_named_property("big_replies", big_replies)?;
+    stats.set_named_property("incompatible_wrappers", incompatible_wrappers)?;
+    stats.set_named_property(
+        "throttled_connection_attempts",
+        throttled_connection_attempts,
+    )?;
 
     Ok(stats)
 }
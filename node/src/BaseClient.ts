@@ -8,6 +8,7 @@ import {
     Script,
     StartSocketConnection,
     getStatistics,
+    getWrapperVersion,
     valueFromSplitPointer,
 } from "glide-rs";
 import * as net from "net";
@@ -7651,6 +7652,8 @@ export class BaseClient {
             authenticationInfo,
             inflightRequestsLimit: options.inflightRequestsLimit,
             clientAz: options.clientAz ?? null,
+            wrapperName: "node",
+            wrapperVersion: getWrapperVersion(),
         };
     }
 
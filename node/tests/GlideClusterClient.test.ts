@@ -2153,7 +2153,9 @@ describe("GlideClusterClient", () => {
                 expect(typeof stats).toBe("object");
                 expect(stats).toHaveProperty("total_connections");
                 expect(stats).toHaveProperty("total_clients");
-                expect(Object.keys(stats)).toHaveLength(2);
+                expect(stats).toHaveProperty("weighted_reads");
+                expect(stats).toHaveProperty("big_replies");
+                expect(Object.keys(stats)).toHaveLength(10);
             } finally {
                 // Ensure the client is properly closed
                 glideClientForTesting?.close();
@@ -4,6 +4,7 @@ from __future__ import annotations
 
 from dataclasses import dataclass
 from enum import Enum, IntEnum
+from importlib import metadata
 from typing import Any, Callable, Dict, List, Optional, Set, Tuple, Union
 
 from glide.async_commands.core import CoreCommands
@@ -14,6 +15,17 @@ from glide.protobuf.connection_request_pb2 import ReadFrom as ProtobufReadFrom
 from glide.protobuf.connection_request_pb2 import TlsMode
 
 
+def _wrapper_version() -> str:
+    """
+    Returns the version of the installed package, which is sent to the core so that it can warn about an incompatible
+    core. Returns an empty string if the package isn't installed, in which case the core doesn't check it.
+    """
+    try:
+        return metadata.version("valkey-glide")
+    except metadata.PackageNotFoundError:
+        return ""
+
+
 class NodeAddress:
     def __init__(self, host: str = "localhost", port: int = 6379):
         """
@@ -245,6 +257,8 @@ class BaseClientConfiguration:
             request.inflight_requests_limit = self.inflight_requests_limit
         if self.client_az:
             request.client_az = self.client_az
+        request.wrapper_name = "python"
+        request.wrapper_version = _wrapper_version()
         if self.advanced_config:
             self.advanced_config._create_a_protobuf_conn_request(request)
 
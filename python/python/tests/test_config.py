@@ -41,6 +41,7 @@ def test_convert_to_protobuf():
     assert request.tls_mode is TlsMode.SecureTls
     assert request.read_from == ProtobufReadFrom.PreferReplica
     assert request.client_name == "TEST_CLIENT_NAME"
+    assert request.wrapper_name == "python"
 
 
 def test_periodic_checks_interval_to_protobuf():
@@ -140,6 +140,29 @@ fn glide(_py: Python, m: &Bound<PyModule>) -> PyResult<()> {
             "total_clients".to_string(),
             Telemetry::total_clients().to_string(),
         );
+        stats_map.insert(
+            "memory_usage".to_string(),
+            glide_core::memory_budget::memory_usage().to_string(),
+        );
+        stats_map.insert("byte_counters".to_string(), Telemetry::byte_counters_json());
+        stats_map.insert(
+            "replica_moved_reads".to_string(),
+            Telemetry::replica_moved_reads().to_string(),
+        );
+        stats_map.insert(
+            "weighted_reads".to_string(),
+            Telemetry::weighted_reads_json(),
+        );
+        stats_map.insert("node_events".to_string(), Telemetry::node_events_json());
+        stats_map.insert("big_replies".to_string(), Telemetry::big_replies_json());
+        stats_map.insert(
+            "incompatible_wrappers".to_string(),
+            Telemetry::incompatible_wrappers().to_string(),
+        );
+        stats_map.insert(
+            "throttled_connection_attempts".to_string(),
+            Telemetry::throttled_connection_attempts().to_string(),
+        );
 
         Python::with_gil(|py| {
             let py_dict = PyDict::new_bound(py);